@@ -0,0 +1,602 @@
+//! Signed offline bundles of wasmCloud components and capability providers
+//!
+//! A bundle is a tarball in the [OCI image layout] containing one image per artifact, so it can be
+//! consumed by any tool supporting the layout. Next to the layout, it contains a bundle manifest
+//! listing every artifact with its OCI reference, digest and claims, and a signature of the
+//! manifest made with an issuer key. As the manifest covers the digests of all artifacts, verifying
+//! the signature and the digests verifies the whole bundle.
+//!
+//! [OCI image layout]: https://github.com/opencontainers/image-spec/blob/main/image-layout.md
+
+#![warn(missing_docs)]
+#![forbid(clippy::unwrap_used)]
+
+use core::str::FromStr;
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::Cursor;
+use std::path::Path;
+
+use anyhow::{anyhow, ensure, Context as _};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
+use futures::StreamExt as _;
+use nkeys::KeyPair;
+use oci_distribution::Reference;
+use provider_archive::ProviderArchive;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest as _, Sha256};
+use tokio::fs;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite};
+
+/// Path of the bundle manifest within a bundle
+pub const MANIFEST_FILE: &str = "wasmcloud-bundle.json";
+/// Path of the bundle manifest signature within a bundle
+pub const SIGNATURE_FILE: &str = "wasmcloud-bundle.sig";
+/// Version of the bundle format written by this crate
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// Media type of component layers
+pub const WASM_MEDIA_TYPE: &str = "application/vnd.module.wasm.content.layer.v1+wasm";
+/// Media type of component image configs
+pub const WASM_CONFIG_MEDIA_TYPE: &str = "application/vnd.wasmcloud.actor.archive.config";
+/// Media type of provider archive layers
+pub const PROVIDER_ARCHIVE_MEDIA_TYPE: &str =
+    "application/vnd.wasmcloud.provider.archive.layer.v1+par";
+/// Media type of provider archive image configs
+pub const PROVIDER_ARCHIVE_CONFIG_MEDIA_TYPE: &str =
+    "application/vnd.wasmcloud.provider.archive.config";
+
+const OCI_LAYOUT_FILE: &str = "oci-layout";
+const OCI_INDEX_FILE: &str = "index.json";
+const OCI_BLOBS_DIR: &str = "blobs/sha256";
+const OCI_INDEX_MEDIA_TYPE: &str = "application/vnd.oci.image.index.v1+json";
+const OCI_MANIFEST_MEDIA_TYPE: &str = "application/vnd.oci.image.manifest.v1+json";
+const OCI_REF_NAME_ANNOTATION: &str = "org.opencontainers.image.ref.name";
+
+/// Maximum size of the bundle manifest and its signature in bytes
+const MAX_METADATA_SIZE: u64 = 16 << 20;
+
+/// Kind of an artifact contained in a bundle
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArtifactKind {
+    /// WebAssembly component or module
+    Component,
+    /// Capability provider archive
+    Provider,
+}
+
+impl ArtifactKind {
+    /// Returns the media types of the layer and image config of artifacts of this kind
+    #[must_use]
+    pub fn media_types(self) -> (&'static str, &'static str) {
+        match self {
+            Self::Component => (WASM_MEDIA_TYPE, WASM_CONFIG_MEDIA_TYPE),
+            Self::Provider => (
+                PROVIDER_ARCHIVE_MEDIA_TYPE,
+                PROVIDER_ARCHIVE_CONFIG_MEDIA_TYPE,
+            ),
+        }
+    }
+}
+
+/// An artifact listed in a bundle [`Manifest`]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Artifact {
+    /// Normalized OCI reference the artifact is resolved by
+    pub reference: String,
+    /// Kind of the artifact
+    pub kind: ArtifactKind,
+    /// Digest of the artifact contents in the form `sha256:<hex>`
+    pub digest: String,
+    /// Size of the artifact in bytes
+    pub size: u64,
+    /// Claims embedded in the artifact, if it is signed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claims: Option<serde_json::Value>,
+}
+
+/// Manifest describing the contents of a bundle
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Manifest {
+    /// Version of the bundle format
+    pub version: u32,
+    /// Artifacts contained in the bundle
+    pub artifacts: Vec<Artifact>,
+}
+
+/// Signature of a bundle [`Manifest`]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+struct Signature {
+    /// Public key of the issuer that signed the manifest
+    issuer: String,
+    /// Base64url-encoded Ed25519 signature of the manifest
+    signature: String,
+}
+
+/// Normalizes an OCI reference, e.g. `oci://Registry/Repo:Tag` to `registry/repo:Tag`, so it can
+/// be compared to the references listed in a bundle. Tags are case-sensitive and kept as-is
+///
+/// # Errors
+///
+/// Returns an error if `reference` is not a valid OCI reference
+pub fn normalize_reference(reference: &str) -> anyhow::Result<String> {
+    let reference = reference.strip_prefix("oci://").unwrap_or(reference);
+    // Only the registry and repository are case-insensitive, the tag or digest follows the last
+    // `:` or `@` after the last `/`
+    let name_end = reference
+        .rfind(['@', ':'])
+        .filter(|i| !reference[*i..].contains('/'))
+        .unwrap_or(reference.len());
+    let (name, suffix) = reference.split_at(name_end);
+    let reference = format!("{}{suffix}", name.to_lowercase());
+    let reference = Reference::from_str(&reference)
+        .with_context(|| format!("invalid OCI reference `{reference}`"))?;
+    Ok(reference.whole())
+}
+
+/// Returns the digest of `data` in the form `sha256:<hex>`, as listed for artifacts in a bundle
+/// manifest
+#[must_use]
+pub fn digest(data: &[u8]) -> String {
+    format!("sha256:{}", hex::encode(Sha256::digest(data)))
+}
+
+/// Returns the path of the blob with the given `digest` within a bundle
+fn blob_path(digest: &str) -> String {
+    let hex = digest.strip_prefix("sha256:").unwrap_or(digest);
+    format!("{OCI_BLOBS_DIR}/{hex}")
+}
+
+/// Verifies the bundle `signature` of `manifest`, returning the parsed manifest and the public key
+/// of its issuer
+fn verify_manifest(manifest: &[u8], signature: &[u8]) -> anyhow::Result<(Manifest, String)> {
+    let Signature { issuer, signature } =
+        serde_json::from_slice(signature).context("failed to parse bundle signature")?;
+    let signature = URL_SAFE_NO_PAD
+        .decode(signature)
+        .context("failed to decode bundle signature")?;
+    KeyPair::from_public_key(&issuer)
+        .with_context(|| format!("invalid bundle issuer `{issuer}`"))?
+        .verify(manifest, &signature)
+        .map_err(|e| anyhow!(e).context("bundle signature verification failed"))?;
+    let manifest: Manifest =
+        serde_json::from_slice(manifest).context("failed to parse bundle manifest")?;
+    ensure!(
+        manifest.version <= BUNDLE_FORMAT_VERSION,
+        "bundle format version {} is newer than the supported version {BUNDLE_FORMAT_VERSION}",
+        manifest.version
+    );
+    Ok((manifest, issuer))
+}
+
+/// A verified bundle read into memory
+#[derive(Clone, Debug)]
+pub struct Bundle {
+    manifest: Manifest,
+    issuer: String,
+    blobs: HashMap<String, Vec<u8>>,
+}
+
+impl Bundle {
+    /// Returns a [`BundleBuilder`] to create a new bundle
+    #[must_use]
+    pub fn builder() -> BundleBuilder {
+        BundleBuilder::default()
+    }
+
+    /// Reads a bundle from `reader`, verifying the manifest signature and the digests of all
+    /// artifacts
+    ///
+    /// The tarball is streamed: the manifest and its signature precede all blobs and are verified
+    /// first, so that only the contents of listed artifacts are read into memory, while hashing
+    /// them, and all other entries are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bundle cannot be read, is incomplete or fails verification
+    pub async fn read(reader: impl AsyncRead + Unpin + Send) -> anyhow::Result<Self> {
+        let mut archive = tokio_tar::Archive::new(reader);
+        let mut entries = archive.entries().context("failed to read bundle entries")?;
+        let mut manifest = None;
+        let mut signature = None;
+        let mut verified = None;
+        let mut blobs = HashMap::new();
+        while let Some(entry) = entries.next().await {
+            let mut entry = entry.context("failed to read bundle entry")?;
+            let path = entry
+                .path()
+                .context("failed to read bundle entry path")?
+                .to_string_lossy()
+                .into_owned();
+            if path == MANIFEST_FILE || path == SIGNATURE_FILE {
+                ensure!(
+                    verified.is_none(),
+                    "bundle contains `{path}` more than once"
+                );
+                let mut buf = Vec::new();
+                (&mut entry)
+                    .take(MAX_METADATA_SIZE + 1)
+                    .read_to_end(&mut buf)
+                    .await
+                    .with_context(|| format!("failed to read `{path}`"))?;
+                ensure!(
+                    buf.len() as u64 <= MAX_METADATA_SIZE,
+                    "`{path}` exceeds {MAX_METADATA_SIZE} bytes"
+                );
+                if path == MANIFEST_FILE {
+                    manifest = Some(buf);
+                } else {
+                    signature = Some(buf);
+                }
+                if let (Some(manifest), Some(signature)) = (&manifest, &signature) {
+                    verified = Some(verify_manifest(manifest, signature)?);
+                }
+            } else if let Some(hex) = path
+                .strip_prefix(OCI_BLOBS_DIR)
+                .and_then(|p| p.strip_prefix('/'))
+            {
+                let (manifest, _) = verified.as_ref().with_context(|| {
+                    format!("bundle blobs must follow `{MANIFEST_FILE}` and `{SIGNATURE_FILE}`")
+                })?;
+                let digest = format!("sha256:{hex}");
+                // OCI manifests and configs are not needed once the bundle manifest is verified
+                let Some(artifact) = manifest
+                    .artifacts
+                    .iter()
+                    .find(|artifact| artifact.digest == digest)
+                else {
+                    continue;
+                };
+                let size = entry
+                    .header()
+                    .entry_size()
+                    .with_context(|| format!("failed to read size of `{path}`"))?;
+                ensure!(
+                    size == artifact.size,
+                    "size of artifact `{}` does not match the manifest",
+                    artifact.reference
+                );
+                let mut buf = Vec::with_capacity(size.try_into().unwrap_or_default());
+                let mut hasher = Sha256::new();
+                let mut chunk = vec![0; 1 << 16];
+                loop {
+                    let n = entry
+                        .read(&mut chunk)
+                        .await
+                        .with_context(|| format!("failed to read `{path}`"))?;
+                    if n == 0 {
+                        break;
+                    }
+                    hasher.update(&chunk[..n]);
+                    buf.extend_from_slice(&chunk[..n]);
+                }
+                ensure!(
+                    format!("sha256:{}", hex::encode(hasher.finalize())) == artifact.digest,
+                    "digest of artifact `{}` does not match the manifest",
+                    artifact.reference
+                );
+                blobs.insert(digest, buf);
+            }
+        }
+        ensure!(manifest.is_some(), "bundle is missing `{MANIFEST_FILE}`");
+        let (manifest, issuer) =
+            verified.with_context(|| format!("bundle is missing `{SIGNATURE_FILE}`"))?;
+        for artifact in &manifest.artifacts {
+            ensure!(
+                blobs.contains_key(&artifact.digest),
+                "bundle is missing artifact `{}`",
+                artifact.reference
+            );
+        }
+        Ok(Self {
+            manifest,
+            issuer,
+            blobs,
+        })
+    }
+
+    /// Reads a bundle from the file at `path`, see [`Bundle::read`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened or the bundle cannot be read
+    pub async fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let file = fs::File::open(path)
+            .await
+            .with_context(|| format!("failed to open `{}`", path.display()))?;
+        Self::read(file)
+            .await
+            .with_context(|| format!("failed to read bundle `{}`", path.display()))
+    }
+
+    /// Returns the manifest of the bundle
+    #[must_use]
+    pub fn manifest(&self) -> &Manifest {
+        &self.manifest
+    }
+
+    /// Returns the public key of the issuer that signed the bundle
+    #[must_use]
+    pub fn issuer(&self) -> &str {
+        &self.issuer
+    }
+
+    /// Returns all artifacts in the bundle along with their contents
+    pub fn artifacts(&self) -> impl Iterator<Item = (&Artifact, &[u8])> {
+        self.manifest.artifacts.iter().filter_map(|artifact| {
+            let blob = self.blobs.get(&artifact.digest)?;
+            Some((artifact, blob.as_slice()))
+        })
+    }
+
+    /// Returns the artifact resolved by the OCI `reference` along with its contents, if the bundle
+    /// contains it
+    #[must_use]
+    pub fn artifact(&self, reference: &str) -> Option<(&Artifact, &[u8])> {
+        let reference = normalize_reference(reference).ok()?;
+        self.artifacts()
+            .find(|(artifact, _)| artifact.reference == reference)
+    }
+}
+
+/// Builder of a new [`Bundle`]
+#[derive(Clone, Debug, Default)]
+pub struct BundleBuilder {
+    artifacts: Vec<Artifact>,
+    blobs: HashMap<String, Vec<u8>>,
+}
+
+impl BundleBuilder {
+    fn add(
+        &mut self,
+        reference: &str,
+        kind: ArtifactKind,
+        data: Vec<u8>,
+        claims: Option<serde_json::Value>,
+    ) -> anyhow::Result<&mut Self> {
+        let reference = normalize_reference(reference)?;
+        ensure!(
+            !self
+                .artifacts
+                .iter()
+                .any(|artifact| artifact.reference == reference),
+            "bundle already contains `{reference}`"
+        );
+        let digest = digest(&data);
+        self.artifacts.push(Artifact {
+            reference,
+            kind,
+            digest: digest.clone(),
+            size: data.len() as u64,
+            claims,
+        });
+        self.blobs.insert(digest, data);
+        Ok(self)
+    }
+
+    /// Adds a component, resolved by the OCI `reference`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `wasm` is not a WebAssembly binary, its claims cannot be read or the
+    /// bundle already contains `reference`
+    pub fn component(&mut self, reference: &str, wasm: Vec<u8>) -> anyhow::Result<&mut Self> {
+        ensure!(
+            wasm.starts_with(b"\0asm"),
+            "`{reference}` is not a WebAssembly binary"
+        );
+        let claims = wascap::wasm::extract_claims(&wasm)
+            .with_context(|| format!("failed to extract claims of `{reference}`"))?
+            .map(|token| serde_json::to_value(token.claims))
+            .transpose()
+            .context("failed to encode claims")?;
+        self.add(reference, ArtifactKind::Component, wasm, claims)
+    }
+
+    /// Adds a capability provider archive, resolved by the OCI `reference`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `par` is not a valid provider archive or the bundle already contains
+    /// `reference`
+    pub async fn provider(&mut self, reference: &str, par: Vec<u8>) -> anyhow::Result<&mut Self> {
+        let archive = ProviderArchive::try_load(&par)
+            .await
+            .map_err(|e| anyhow!("`{reference}` is not a valid provider archive: {e}"))?;
+        let claims = archive
+            .claims()
+            .map(serde_json::to_value)
+            .transpose()
+            .context("failed to encode claims")?;
+        self.add(reference, ArtifactKind::Provider, par, claims)
+    }
+
+    /// Writes the bundle to `writer`, signing it with `issuer`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if signing or writing the bundle fails
+    pub async fn write(
+        &self,
+        writer: impl AsyncWrite + Unpin + Send + 'static,
+        issuer: &KeyPair,
+    ) -> anyhow::Result<()> {
+        ensure!(!self.artifacts.is_empty(), "bundle must contain artifacts");
+
+        let manifest = serde_json::to_vec_pretty(&Manifest {
+            version: BUNDLE_FORMAT_VERSION,
+            artifacts: self.artifacts.clone(),
+        })
+        .context("failed to encode bundle manifest")?;
+        let signature = issuer
+            .sign(&manifest)
+            .map_err(|e| anyhow!(e).context("failed to sign bundle manifest"))?;
+        let signature = serde_json::to_vec_pretty(&Signature {
+            issuer: issuer.public_key(),
+            signature: URL_SAFE_NO_PAD.encode(signature),
+        })
+        .context("failed to encode bundle signature")?;
+
+        // Lay out every artifact as an OCI image with an empty config and a single layer
+        let mut files = vec![
+            (MANIFEST_FILE.to_string(), manifest),
+            (SIGNATURE_FILE.to_string(), signature),
+            (
+                OCI_LAYOUT_FILE.to_string(),
+                br#"{"imageLayoutVersion":"1.0.0"}"#.to_vec(),
+            ),
+        ];
+        let config = b"{}".to_vec();
+        let config_digest = digest(&config);
+        files.push((blob_path(&config_digest), config.clone()));
+        let mut index = Vec::with_capacity(self.artifacts.len());
+        for artifact in &self.artifacts {
+            let data = self
+                .blobs
+                .get(&artifact.digest)
+                .context("artifact contents missing")?;
+            let (media_type, config_media_type) = artifact.kind.media_types();
+            let image_manifest = serde_json::to_vec(&json!({
+                "schemaVersion": 2,
+                "mediaType": OCI_MANIFEST_MEDIA_TYPE,
+                "config": {
+                    "mediaType": config_media_type,
+                    "digest": config_digest,
+                    "size": config.len(),
+                },
+                "layers": [{
+                    "mediaType": media_type,
+                    "digest": artifact.digest,
+                    "size": artifact.size,
+                }],
+            }))
+            .context("failed to encode OCI image manifest")?;
+            let image_manifest_digest = digest(&image_manifest);
+            index.push(json!({
+                "mediaType": OCI_MANIFEST_MEDIA_TYPE,
+                "digest": image_manifest_digest,
+                "size": image_manifest.len(),
+                "annotations": BTreeMap::from([(OCI_REF_NAME_ANNOTATION, &artifact.reference)]),
+            }));
+            files.push((blob_path(&image_manifest_digest), image_manifest));
+            files.push((blob_path(&artifact.digest), data.clone()));
+        }
+        let index = serde_json::to_vec_pretty(&json!({
+            "schemaVersion": 2,
+            "mediaType": OCI_INDEX_MEDIA_TYPE,
+            "manifests": index,
+        }))
+        .context("failed to encode OCI index")?;
+        files.push((OCI_INDEX_FILE.to_string(), index));
+
+        let mut builder = tokio_tar::Builder::new(writer);
+        for (path, data) in files {
+            let mut header = tokio_tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, &path, Cursor::new(data))
+                .await
+                .with_context(|| format!("failed to write `{path}`"))?;
+        }
+        builder
+            .into_inner()
+            .await
+            .context("failed to finish bundle")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The smallest valid WebAssembly module
+    const WASM: &[u8] = b"\0asm\x01\0\0\0";
+
+    async fn write(builder: &BundleBuilder, issuer: &KeyPair) -> Vec<u8> {
+        let (writer, mut reader) = tokio::io::duplex(1 << 20);
+        let (res, buf) = tokio::join!(builder.write(writer, issuer), async {
+            let mut buf = Vec::new();
+            reader
+                .read_to_end(&mut buf)
+                .await
+                .expect("failed to read bundle");
+            buf
+        });
+        res.expect("failed to write bundle");
+        buf
+    }
+
+    #[test]
+    fn test_normalize_reference() {
+        assert_eq!(
+            normalize_reference("oci://GHCR.io/wasmcloud/echo:0.1.0").expect("failed to normalize"),
+            "ghcr.io/wasmcloud/echo:0.1.0"
+        );
+        assert_eq!(
+            normalize_reference("localhost:5000/echo:0.1.0").expect("failed to normalize"),
+            "localhost:5000/echo:0.1.0"
+        );
+        assert_eq!(
+            normalize_reference("GHCR.io/wasmCloud/Echo:V0.1.0-RC").expect("failed to normalize"),
+            "ghcr.io/wasmcloud/echo:V0.1.0-RC"
+        );
+        assert!(normalize_reference("not a reference").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_roundtrip() {
+        let issuer = KeyPair::new_account();
+        let mut builder = Bundle::builder();
+        builder
+            .component("oci://ghcr.io/wasmcloud/echo:0.1.0", WASM.to_vec())
+            .expect("failed to add component");
+        assert!(builder
+            .component("ghcr.io/wasmcloud/echo:0.1.0", WASM.to_vec())
+            .is_err());
+        assert!(builder
+            .component("ghcr.io/wasmcloud/other:0.1.0", b"not wasm".to_vec())
+            .is_err());
+
+        let buf = write(&builder, &issuer).await;
+        let bundle = Bundle::read(buf.as_slice())
+            .await
+            .expect("failed to read bundle");
+        assert_eq!(bundle.issuer(), issuer.public_key());
+        assert_eq!(bundle.manifest().version, BUNDLE_FORMAT_VERSION);
+        let (artifact, data) = bundle
+            .artifact("ghcr.io/wasmcloud/echo:0.1.0")
+            .expect("artifact missing");
+        assert_eq!(artifact.kind, ArtifactKind::Component);
+        assert_eq!(artifact.claims, None);
+        assert_eq!(data, WASM);
+        assert!(bundle.artifact("ghcr.io/wasmcloud/echo:0.2.0").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_tampered() {
+        let issuer = KeyPair::new_account();
+        let mut builder = Bundle::builder();
+        builder
+            .component("ghcr.io/wasmcloud/echo:0.1.0", WASM.to_vec())
+            .expect("failed to add component");
+        let mut buf = write(&builder, &issuer).await;
+
+        // Flip the last byte of the module
+        let pos = buf
+            .windows(WASM.len())
+            .position(|w| w == WASM)
+            .expect("module missing");
+        buf[pos + WASM.len() - 1] ^= 1;
+        let err = Bundle::read(buf.as_slice())
+            .await
+            .expect_err("tampered bundle was accepted");
+        assert!(err.to_string().contains("does not match"), "{err:#}");
+    }
+}
@@ -26,6 +26,10 @@ pub fn delete_link(topic_prefix: &Option<String>, lattice: &str) -> String {
     format!("{}.link.del", prefix(topic_prefix, lattice))
 }
 
+pub fn validate_link(topic_prefix: &Option<String>, lattice: &str) -> String {
+    format!("{}.link.validate", prefix(topic_prefix, lattice))
+}
+
 pub fn publish_registries(topic_prefix: &Option<String>, lattice: &str) -> String {
     format!("{}.registry.put", prefix(topic_prefix, lattice))
 }
@@ -92,4 +96,8 @@ pub mod queries {
     pub fn config(topic_prefix: &Option<String>, lattice: &str, config_name: &str) -> String {
         format!("{}.config.get.{config_name}", prefix(topic_prefix, lattice),)
     }
+
+    pub fn provider_logs(topic_prefix: &Option<String>, lattice: &str, host_id: &str) -> String {
+        format!("{}.provider.logs.{host_id}", prefix(topic_prefix, lattice))
+    }
 }
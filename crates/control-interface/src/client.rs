@@ -11,13 +11,15 @@ use serde::de::DeserializeOwned;
 use tokio::sync::mpsc::Receiver;
 use tracing::{debug, error, instrument, trace};
 
+use crate::types::actor::ComponentLimits;
 use crate::types::link::InterfaceLinkDefinition;
 
 use crate::types::ctl::{
-    CtlResponse, ScaleActorCommand, StartProviderCommand, StopHostCommand, StopProviderCommand,
-    UpdateActorCommand,
+    CtlResponse, HotUpdateOptions, ProviderLogsCommand, ScaleActorCommand, StartProviderCommand,
+    StopHostCommand, StopProviderCommand, UpdateActorCommand,
 };
 use crate::types::host::{Host, HostInventory, HostLabel};
+use crate::types::provider::ProviderLogLine;
 use crate::types::registry::RegistryCredential;
 use crate::types::rpc::{
     ActorAuctionAck, ActorAuctionRequest, DeleteInterfaceLinkDefinitionRequest, ProviderAuctionAck,
@@ -186,6 +188,31 @@ impl Client {
         }
     }
 
+    /// Retrieves the `tail` most recent lines of output of a provider retained by a running host,
+    /// or all retained lines if `tail` is not set
+    #[instrument(level = "debug", skip_all)]
+    pub async fn get_provider_logs(
+        &self,
+        host_id: &str,
+        provider_id: &str,
+        tail: Option<usize>,
+    ) -> Result<CtlResponse<Vec<ProviderLogLine>>> {
+        let subject = broker::queries::provider_logs(
+            &self.topic_prefix,
+            &self.lattice,
+            parse_identifier(&IdentifierKind::HostId, host_id)?.as_str(),
+        );
+        debug!("get_provider_logs:request {}", &subject);
+        let bytes = json_serialize(ProviderLogsCommand {
+            provider_id: parse_identifier(&IdentifierKind::ComponentId, provider_id)?,
+            tail,
+        })?;
+        match self.request_timeout(subject, bytes, self.timeout).await {
+            Ok(msg) => Ok(json_deserialize(&msg.payload)?),
+            Err(e) => Err(format!("Did not receive provider logs from target host: {e}").into()),
+        }
+    }
+
     /// Retrieves the full set of all cached claims in the lattice.
     #[instrument(level = "debug", skip_all)]
     pub async fn get_claims(&self) -> Result<CtlResponse<Vec<HashMap<String, String>>>> {
@@ -254,6 +281,9 @@ impl Client {
     /// `actor_ref`: The OCI reference of the actor to scale
     /// `max_instances`: The maximum number of instances this actor can run concurrently. Specifying `0` will stop the actor.
     /// `annotations`: Optional annotations to apply to the actor
+    /// `config`: Names of the configs to supply to the actor
+    /// `limits`: Optional resource limits to enforce on each instance of the actor
+    #[allow(clippy::too_many_arguments)]
     #[instrument(level = "debug", skip_all)]
     pub async fn scale_actor(
         &self,
@@ -263,6 +293,7 @@ impl Client {
         max_instances: u32,
         annotations: Option<HashMap<String, String>>,
         config: Vec<String>,
+        limits: Option<ComponentLimits>,
     ) -> Result<CtlResponse<()>> {
         let host_id = parse_identifier(&IdentifierKind::HostId, host_id)?;
         let subject =
@@ -275,6 +306,7 @@ impl Client {
             host_id,
             annotations,
             config,
+            limits,
         })?;
         match self.request_timeout(subject, bytes, self.timeout).await {
             Ok(msg) => Ok(json_deserialize(&msg.payload)?),
@@ -330,6 +362,26 @@ impl Client {
         }
     }
 
+    /// Validates the configuration of a link against the config schemas of the providers it
+    /// connects without putting the link. The response is unsuccessful and describes every
+    /// violation if the configuration does not match a schema. Otherwise, its message describes
+    /// the schemas which are invalid and were skipped, if any
+    #[instrument(level = "debug", skip_all)]
+    pub async fn validate_link(&self, link: InterfaceLinkDefinition) -> Result<CtlResponse<()>> {
+        parse_identifier(&IdentifierKind::ComponentId, &link.source_id)?;
+        parse_identifier(&IdentifierKind::ComponentId, &link.target)?;
+        parse_identifier(&IdentifierKind::LinkName, &link.name)?;
+
+        let subject = broker::validate_link(&self.topic_prefix, &self.lattice);
+        debug!("validate_link:request {}", &subject);
+
+        let bytes = crate::json_serialize(&link)?;
+        match self.request_timeout(subject, bytes, self.timeout).await {
+            Ok(msg) => Ok(json_deserialize(&msg.payload)?),
+            Err(e) => Err(format!("Did not receive validate link response: {e}").into()),
+        }
+    }
+
     /// Deletes a link from the lattice metadata keyvalue bucket. Returns an error if it was unable
     /// to delete. This is an idempotent operation.
     #[instrument(level = "debug", skip_all)]
@@ -470,7 +522,11 @@ impl Client {
     /// and control clients cannot block waiting for a reply that could come several seconds later.
     /// If you need to verify that the actor has been updated, you will want to set up a listener
     /// for the appropriate **PublishedEvent** which will be published on the control events channel
-    /// in JSON
+    /// in JSON.
+    ///
+    /// If `hot_update` is set, the host keeps the old actor around until the new one has proven
+    /// itself and rolls back to it automatically if the new actor regresses, publishing an
+    /// `actor_update_failed` and an `actor_rolled_back` event
     #[instrument(level = "debug", skip_all)]
     pub async fn update_actor(
         &self,
@@ -478,6 +534,7 @@ impl Client {
         existing_actor_id: &str,
         new_actor_ref: &str,
         annotations: Option<HashMap<String, String>>,
+        hot_update: Option<HotUpdateOptions>,
     ) -> Result<CtlResponse<()>> {
         let host_id = parse_identifier(&IdentifierKind::HostId, host_id)?;
         let subject =
@@ -488,6 +545,7 @@ impl Client {
             actor_id: parse_identifier(&IdentifierKind::ComponentId, existing_actor_id)?,
             new_actor_ref: parse_identifier(&IdentifierKind::ActorRef, new_actor_ref)?,
             annotations,
+            hot_update,
         })?;
         match self.request_timeout(subject, bytes, self.timeout).await {
             Ok(msg) => Ok(json_deserialize(&msg.payload)?),
@@ -786,6 +844,7 @@ mod tests {
                 1,
                 None,
                 Vec::with_capacity(0),
+                None,
             )
             .await
             .expect("should be able to scale actor");
@@ -799,6 +858,7 @@ mod tests {
                 "nonexistantactorID",
                 "wasmcloud.azurecr.io/kvcounter:0.4.0",
                 None,
+                None,
             )
             .await
             .expect("should be able to issue update actor request");
@@ -859,6 +919,7 @@ mod tests {
                 interfaces: vec!["incoming-handler".to_string()],
                 source_config: vec![],
                 target_config: vec![],
+                limits: None,
             })
             .await
             .expect("should be able to put link");
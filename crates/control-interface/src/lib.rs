@@ -22,7 +22,7 @@ mod types;
 pub use types::actor::*;
 pub use types::ctl::*;
 pub use types::host::*;
-pub use types::link::InterfaceLinkDefinition;
+pub use types::link::{InterfaceLinkDefinition, LinkLimits};
 pub use types::provider::*;
 pub use types::registry::*;
 pub use types::rpc::*;
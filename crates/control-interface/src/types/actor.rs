@@ -28,6 +28,21 @@ pub struct ActorDescription {
     /// The maximum number of concurrent requests this instance can handle
     #[serde(default)]
     pub max_instances: u32,
+    /// The resource limits enforced on each instance of this actor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limits: Option<ComponentLimits>,
+}
+
+/// Resource limits enforced by the host on each instance of a component. Limits that are not set
+/// are bounded only by the host defaults
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ComponentLimits {
+    /// The maximum size, in bytes, any single linear memory of an instance may grow to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_memory_bytes: Option<u64>,
+    /// The maximum number of elements any single table of an instance may grow to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_table_elements: Option<u32>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
@@ -4,7 +4,7 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::ComponentId;
+use crate::{ComponentId, ComponentLimits};
 
 /// A control interface response that wraps a response payload, a success flag, and a message
 /// with additional context if necessary.
@@ -78,6 +78,10 @@ pub struct ScaleActorCommand {
     /// 6}
     #[serde(default)]
     pub config: Vec<String>,
+    /// Optional resource limits to enforce on each instance of this actor. Scaling a running actor
+    /// with different limits restarts it with the new limits applied
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limits: Option<ComponentLimits>,
 }
 
 /// A command sent to a host requesting a capability provider be started with the
@@ -127,6 +131,17 @@ pub struct StopProviderCommand {
     pub provider_id: ComponentId,
 }
 
+/// A request for the recent output of the given provider retained by the indicated host
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProviderLogsCommand {
+    /// Unique identifier for the provider
+    #[serde(default)]
+    pub provider_id: ComponentId,
+    /// Maximum number of most recent lines to return, all retained lines if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tail: Option<usize>,
+}
+
 /// A command instructing a specific host to perform a live update
 /// on the indicated actor by supplying a new image reference. Note that
 /// live updates are only possible through image references
@@ -146,4 +161,28 @@ pub struct UpdateActorCommand {
     /// The new image reference of the upgraded version of this actor
     #[serde(default)]
     pub new_actor_ref: String,
+    /// Optional options for a hot update. If set, the new version is verified before the old
+    /// version is discarded and the old version is restored if the new one regresses
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hot_update: Option<HotUpdateOptions>,
+}
+
+/// Options for a hot update of an actor. The new version starts serving invocations while the
+/// in-flight invocations of the old version are drained. The new version is then watched for
+/// invocation errors and rolled back to the old version if it produces too many of them.
+/// Options that are not set use the host defaults
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct HotUpdateOptions {
+    /// The maximum time, in milliseconds, to wait for in-flight invocations of the old version to
+    /// complete
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drain_timeout_ms: Option<u64>,
+    /// The time, in milliseconds, the new version is watched for invocation errors after it
+    /// replaced the old version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watch_window_ms: Option<u64>,
+    /// The maximum number of invocation errors the new version may produce during the watch
+    /// window without being rolled back
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_errors: Option<u64>,
 }
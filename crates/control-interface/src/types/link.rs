@@ -31,6 +31,24 @@ pub struct InterfaceLinkDefinition {
     /// List of named configurations to provide to the target upon request
     #[serde(default)]
     pub target_config: Vec<KnownConfigName>,
+    /// Limits enforced by the host of the source on invocations made over the link
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limits: Option<LinkLimits>,
+}
+
+/// Admission limits enforced on the invocations a source makes over a link. Invocations exceeding
+/// any of the limits fail immediately instead of being sent to the target.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize, Hash)]
+pub struct LinkLimits {
+    /// Maximum sustained number of invocations per second, enforced with a token bucket
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_per_second: Option<u32>,
+    /// Maximum number of invocations that may be made in a burst, defaults to `rate_per_second`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burst: Option<u32>,
+    /// Maximum number of invocations that may be in flight at the same time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_in_flight: Option<u32>,
 }
 
 /// Helper function to provide a default link name
@@ -26,3 +26,24 @@ pub struct ProviderDescription {
     #[serde(default)]
     pub revision: i32,
 }
+
+/// A line of output of a capability provider, as captured by the host running it
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProviderLogLine {
+    /// ID of the host running the provider
+    pub host_id: String,
+    /// ID of the provider
+    pub provider_id: ComponentId,
+    /// Output stream the line was written to, either `stdout` or `stderr`
+    pub stream: String,
+    /// RFC 3339 timestamp of when the line was read by the host
+    pub timestamp: String,
+    /// Contents of the line, without the trailing newline
+    pub line: String,
+}
+
+impl std::fmt::Display for ProviderLogLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} [{}] {}", self.timestamp, self.stream, self.line)
+    }
+}
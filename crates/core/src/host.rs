@@ -49,6 +49,9 @@ pub struct HostData {
     /// Host-wide default RPC timeout for rpc messages, in milliseconds.  Defaults to 2000.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub default_rpc_timeout_ms: Option<u64>,
+    /// Prefix of the inboxes to receive RPC responses on, if the host scopes them to the lattice
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rpc_inbox_prefix: Option<String>,
     /// True if structured logging is enabled for the host. Providers should use the same setting as the host.
     #[serde(default)]
     pub structured_logging: bool,
@@ -3,6 +3,7 @@
 pub mod chunking;
 pub mod logging;
 pub mod nats;
+pub mod rate_limit;
 
 pub mod host;
 pub use host::*;
@@ -14,6 +14,13 @@ pub fn convert_header_map_to_hashmap(map: &HeaderMap) -> HashMap<String, String>
         .collect::<HashMap<String, String>>()
 }
 
+/// Returns the prefix of the inboxes hosts and providers in `lattice` receive RPC responses on, if
+/// the host is configured to scope them to the lattice. Scoping inboxes to the lattice allows
+/// responses of a single lattice to be observed, e.g. when capturing them with `wash capture`
+pub fn rpc_inbox_prefix(lattice: &str) -> String {
+    format!("_INBOX.{lattice}")
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
@@ -0,0 +1,79 @@
+//! Token bucket rate limiting shared by the host and providers
+
+use std::time::Instant;
+
+/// A token bucket holding up to `capacity` tokens, refilled continuously at `refill_per_sec`
+/// tokens per second. Every admitted event takes one token.
+#[derive(Clone, Copy, Debug)]
+pub struct TokenBucket {
+    capacity: f64,
+    refill_per_sec: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    /// Construct a full bucket of `capacity` tokens refilled at `refill_per_sec` tokens per second
+    #[must_use]
+    pub fn new(capacity: f64, refill_per_sec: f64, now: Instant) -> Self {
+        Self {
+            capacity,
+            refill_per_sec,
+            tokens: capacity,
+            updated: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.updated = now;
+    }
+
+    /// Takes a token at time `now`, returning whether one was available
+    pub fn try_acquire(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns whether the bucket is full at time `now`, in which case it is indistinguishable
+    /// from a new bucket and can be dropped
+    #[must_use]
+    pub fn is_full(&self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens + elapsed * self.refill_per_sec >= self.capacity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::TokenBucket;
+
+    #[test]
+    fn test_token_bucket() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(2.0, 2.0, now);
+        assert!(bucket.is_full(now));
+        assert!(bucket.try_acquire(now));
+        assert!(!bucket.is_full(now));
+        assert!(bucket.try_acquire(now));
+        assert!(!bucket.try_acquire(now));
+        // Half a second refills one token
+        let later = now + Duration::from_millis(500);
+        assert!(bucket.try_acquire(later));
+        assert!(!bucket.try_acquire(later));
+        // The bucket never holds more than its capacity
+        let later = now + Duration::from_secs(60);
+        assert!(bucket.is_full(later));
+        assert!(bucket.try_acquire(later));
+        assert!(bucket.try_acquire(later));
+        assert!(!bucket.try_acquire(later));
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{ensure, Context as _};
+use tempfile::TempDir;
+use tokio::fs::{self, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tracing::{info, instrument, warn};
+use wasmcloud_bundle::{digest, normalize_reference, Bundle};
+
+/// Artifacts preloaded from signed offline bundles, resolved by OCI reference without network
+/// access
+#[derive(Debug, Default)]
+pub struct Cache {
+    /// Directory private to this cache holding the artifacts, removed once the cache is dropped
+    dir: Option<TempDir>,
+    /// Digests of cached artifacts keyed by normalized OCI reference
+    artifacts: HashMap<String, String>,
+}
+
+/// Returns the path of the artifact with the given `digest` within the cache directory `dir`
+fn artifact_path(dir: &Path, digest: &str) -> PathBuf {
+    dir.join(digest.replace(':', "_"))
+}
+
+/// Writes `data` to a new file at `path`. The data is written to a newly created file next to
+/// `path` first, which is then renamed, so that a partially written file is never observed at
+/// `path`
+async fn write_new(path: &Path, data: &[u8]) -> anyhow::Result<()> {
+    let tmp = path.with_extension("partial");
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&tmp)
+        .await
+        .with_context(|| format!("failed to create `{}`", tmp.display()))?;
+    file.write_all(data)
+        .await
+        .with_context(|| format!("failed to write `{}`", tmp.display()))?;
+    file.sync_all()
+        .await
+        .with_context(|| format!("failed to sync `{}`", tmp.display()))?;
+    fs::rename(&tmp, path)
+        .await
+        .with_context(|| format!("failed to rename `{}`", tmp.display()))
+}
+
+impl Cache {
+    /// Verifies the bundles at `paths` and stores their artifacts in the cache. Bundles must be
+    /// signed by one of `issuers`, unless `allow_any_issuer` is set and `issuers` is empty
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no issuers are trusted, a bundle cannot be read, fails verification,
+    /// is signed by an untrusted issuer or its artifacts cannot be stored
+    #[instrument(level = "debug", skip_all)]
+    pub async fn preload(
+        paths: &[PathBuf],
+        issuers: &[String],
+        allow_any_issuer: bool,
+    ) -> anyhow::Result<Self> {
+        if paths.is_empty() {
+            return Ok(Self::default());
+        }
+        ensure!(
+            !issuers.is_empty() || allow_any_issuer,
+            "no bundle issuers configured, bundles must be signed by a trusted issuer unless bundles signed by any issuer are explicitly allowed"
+        );
+        if issuers.is_empty() {
+            warn!("accepting bundles signed by any issuer");
+        }
+
+        let mut dir = tempfile::Builder::new();
+        dir.prefix("wasmcloud_bundlecache_");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            dir.permissions(std::fs::Permissions::from_mode(0o700));
+        }
+        let dir = dir
+            .tempdir()
+            .context("failed to create bundle cache directory")?;
+        let mut artifacts = HashMap::new();
+        for path in paths {
+            let bundle = Bundle::load(path).await?;
+            ensure!(
+                issuers.is_empty() || issuers.iter().any(|issuer| issuer == bundle.issuer()),
+                "bundle `{}` is signed by untrusted issuer `{}`",
+                path.display(),
+                bundle.issuer()
+            );
+            for (artifact, data) in bundle.artifacts() {
+                // Artifacts are stored by digest, so identical contents are only stored once
+                let file = artifact_path(dir.path(), &artifact.digest);
+                if fs::metadata(&file).await.is_err() {
+                    write_new(&file, data).await?;
+                }
+                artifacts.insert(artifact.reference.clone(), artifact.digest.clone());
+            }
+            info!(
+                path = %path.display(),
+                issuer = bundle.issuer(),
+                artifacts = bundle.manifest().artifacts.len(),
+                "preloaded bundle"
+            );
+        }
+        Ok(Self {
+            dir: Some(dir),
+            artifacts,
+        })
+    }
+
+    /// Reads the cached artifact resolved by the OCI reference `oci_ref`, if any. The contents are
+    /// verified against the digest listed in the bundle on every read
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cached artifact cannot be read or does not match its digest
+    pub async fn read(&self, oci_ref: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        let (Some(dir), Some(expected)) = (
+            &self.dir,
+            normalize_reference(oci_ref)
+                .ok()
+                .and_then(|oci_ref| self.artifacts.get(&oci_ref)),
+        ) else {
+            return Ok(None);
+        };
+        let path = artifact_path(dir.path(), expected);
+        let data = fs::read(&path)
+            .await
+            .with_context(|| format!("failed to read `{}`", path.display()))?;
+        ensure!(
+            digest(&data) == *expected,
+            "cached artifact `{}` does not match digest `{expected}`",
+            path.display()
+        );
+        Ok(Some(data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use anyhow::Context as _;
+    use nkeys::KeyPair;
+    use tokio::fs;
+    use wasmcloud_bundle::Bundle;
+
+    use super::{artifact_path, Cache};
+
+    const WASM: &[u8] = b"\0asm\x01\0\0\0";
+    const REFERENCE: &str = "wasmcloud.azurecr.io/echo:0.1.0";
+
+    async fn write_bundle(dir: &tempfile::TempDir, issuer: &KeyPair) -> anyhow::Result<PathBuf> {
+        let path = dir.path().join("bundle.tar");
+        let file = fs::File::create(&path)
+            .await
+            .context("failed to create bundle")?;
+        Bundle::builder()
+            .component(REFERENCE, WASM.to_vec())?
+            .write(file, issuer)
+            .await?;
+        Ok(path)
+    }
+
+    #[tokio::test]
+    async fn preload() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let issuer = KeyPair::new_account();
+        let path = write_bundle(&dir, &issuer).await?;
+        let paths = [path];
+
+        Cache::preload(&paths, &[], false)
+            .await
+            .expect_err("bundles should be rejected if no issuers are trusted");
+        Cache::preload(&paths, &[KeyPair::new_account().public_key()], false)
+            .await
+            .expect_err("bundles signed by an untrusted issuer should be rejected");
+        Cache::preload(&paths, &[], true)
+            .await
+            .context("bundles signed by any issuer should be accepted if allowed")?;
+
+        let cache = Cache::preload(&paths, &[issuer.public_key()], false).await?;
+        assert_eq!(cache.read(REFERENCE).await?.as_deref(), Some(WASM));
+        assert_eq!(
+            cache.read(&format!("oci://{REFERENCE}")).await?.as_deref(),
+            Some(WASM)
+        );
+        assert_eq!(cache.read("wasmcloud.azurecr.io/other:0.1.0").await?, None);
+
+        // Tampering with a cached artifact is detected on read
+        let digest = cache
+            .artifacts
+            .values()
+            .next()
+            .context("artifact not cached")?;
+        let dir = cache.dir.as_ref().context("cache directory missing")?;
+        fs::write(artifact_path(dir.path(), digest), b"\0asm\x01\0\0\0\0").await?;
+        cache
+            .read(REFERENCE)
+            .await
+            .expect_err("tampered artifact should fail verification");
+        Ok(())
+    }
+}
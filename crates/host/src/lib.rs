@@ -6,6 +6,9 @@
 /// wasmbus host
 pub mod wasmbus;
 
+/// Offline artifact bundles
+pub mod bundle;
+
 /// OCI artifact fetching
 pub mod oci;
 
@@ -21,6 +24,10 @@ mod par;
 /// wasmCloud host metrics
 pub(crate) mod metrics;
 
+/// Prometheus metrics endpoint
+pub(crate) mod prometheus;
+
+pub use bundle::Cache as BundleCache;
 pub use metrics::HostMetrics;
 pub use oci::{Config as OciConfig, Fetcher as OciFetcher};
 pub use policy::{
@@ -101,12 +108,14 @@ impl ResourceRef<'_> {
     }
 }
 
-/// Fetch an actor from a reference.
-#[instrument(level = "debug", skip(allow_file_load, registry_config))]
+/// Fetch an actor from a reference. OCI references are resolved from `bundles` first, if they
+/// contain the actor.
+#[instrument(level = "debug", skip(allow_file_load, registry_config, bundles))]
 pub async fn fetch_actor(
     actor_ref: &str,
     allow_file_load: bool,
     registry_config: &HashMap<String, RegistryConfig>,
+    bundles: &BundleCache,
 ) -> anyhow::Result<Vec<u8>> {
     match ResourceRef::try_from(actor_ref)? {
         ResourceRef::File(actor_ref) => {
@@ -116,24 +125,32 @@ pub async fn fetch_actor(
             );
             fs::read(actor_ref).await.context("failed to read actor")
         }
-        ref oci_ref @ ResourceRef::Oci(actor_ref) => oci_ref
-            .authority()
-            .and_then(|authority| registry_config.get(authority))
-            .map(oci::Fetcher::from)
-            .unwrap_or_default()
-            .fetch_actor(actor_ref)
-            .await
-            .with_context(|| format!("failed to fetch actor under OCI reference `{actor_ref}`")),
+        ref oci_ref @ ResourceRef::Oci(actor_ref) => {
+            if let Some(actor) = bundles.read(actor_ref).await? {
+                debug!(actor_ref, "using actor preloaded from bundle");
+                return Ok(actor);
+            }
+            oci_ref
+                .authority()
+                .and_then(|authority| registry_config.get(authority))
+                .map(oci::Fetcher::from)
+                .unwrap_or_default()
+                .fetch_actor(actor_ref)
+                .await
+                .with_context(|| format!("failed to fetch actor under OCI reference `{actor_ref}`"))
+        }
     }
 }
 
-/// Fetch a provider from a reference.
-#[instrument(skip(registry_config, host_id), fields(provider_ref = %provider_ref.as_ref()))]
+/// Fetch a provider from a reference. OCI references are resolved from `bundles` first, if they
+/// contain the provider.
+#[instrument(skip(registry_config, host_id, bundles), fields(provider_ref = %provider_ref.as_ref()))]
 pub async fn fetch_provider(
     provider_ref: impl AsRef<str>,
     host_id: impl AsRef<str>,
     allow_file_load: bool,
     registry_config: &HashMap<String, RegistryConfig>,
+    bundles: &BundleCache,
 ) -> anyhow::Result<(PathBuf, Option<jwt::Claims<jwt::CapabilityProvider>>)> {
     match ResourceRef::try_from(provider_ref.as_ref())? {
         ResourceRef::File(provider_path) => {
@@ -141,20 +158,28 @@ pub async fn fetch_provider(
                 allow_file_load,
                 "unable to start provider from file, file loading is disabled"
             );
-            par::read(provider_path, host_id, provider_ref)
+            par::read_file(provider_path, host_id, provider_ref)
                 .await
                 .context("failed to read provider")
         }
-        ref oci_ref @ ResourceRef::Oci(provider_ref) => oci_ref
-            .authority()
-            .and_then(|authority| registry_config.get(authority))
-            .map(oci::Fetcher::from)
-            .unwrap_or_default()
-            .fetch_provider(&provider_ref, host_id)
-            .await
-            .with_context(|| {
-                format!("failed to fetch provider under OCI reference `{provider_ref}`")
-            }),
+        ref oci_ref @ ResourceRef::Oci(provider_ref) => {
+            if let Some(provider) = bundles.read(provider_ref).await? {
+                debug!(provider_ref, "using provider preloaded from bundle");
+                return par::read_bytes(&provider, host_id, provider_ref)
+                    .await
+                    .context("failed to read provider");
+            }
+            oci_ref
+                .authority()
+                .and_then(|authority| registry_config.get(authority))
+                .map(oci::Fetcher::from)
+                .unwrap_or_default()
+                .fetch_provider(&provider_ref, host_id)
+                .await
+                .with_context(|| {
+                    format!("failed to fetch provider under OCI reference `{provider_ref}`")
+                })
+        }
     }
 }
 
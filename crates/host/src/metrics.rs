@@ -1,5 +1,181 @@
+use std::collections::{hash_map, HashMap};
+use std::sync::{Arc, Mutex};
+
+use prometheus::core::Collector;
+use prometheus::proto::{LabelPair, MetricFamily};
+use prometheus::{
+    HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
+};
 use wasmcloud_tracing::{Counter, Histogram, KeyValue, Meter, Unit};
 
+/// Labels of the metrics recorded per component
+const COMPONENT_LABELS: [&str; 2] = ["component_id", "image_ref"];
+
+/// Labels of the metrics recorded per provider
+const PROVIDER_LABELS: [&str; 2] = ["provider_id", "image_ref"];
+
+/// Metrics the host serves in the Prometheus format and reads back, e.g. to detect regressions
+/// after an update
+#[derive(Clone, Debug)]
+pub(crate) struct PrometheusMetrics {
+    pub registry: Registry,
+    pub host_info: IntGaugeVec,
+    pub uptime_seconds: IntGauge,
+    pub actor_invocations: IntCounterVec,
+    pub actor_errors: IntCounterVec,
+    pub actor_interruptions: IntCounterVec,
+    pub actor_duration_seconds: HistogramVec,
+    pub actor_active_instances: IntGaugeVec,
+    pub actor_max_instances: IntGaugeVec,
+    pub provider_healthy: IntGaugeVec,
+    pub policy_cache_hits: IntCounter,
+    pub policy_cache_misses: IntCounter,
+    pub config_watches: IntGauge,
+}
+
+impl PrometheusMetrics {
+    fn new() -> prometheus::Result<Self> {
+        let host_info = IntGaugeVec::new(
+            Opts::new(
+                "wasmcloud_host_info",
+                "Information about the wasmCloud host",
+            ),
+            &["host_id", "lattice", "version"],
+        )?;
+        let uptime_seconds = IntGauge::new(
+            "wasmcloud_host_uptime_seconds",
+            "Number of seconds since the host started",
+        )?;
+        let actor_invocations = IntCounterVec::new(
+            Opts::new(
+                "wasmcloud_host_actor_invocations_total",
+                "Number of actor invocations",
+            ),
+            &COMPONENT_LABELS,
+        )?;
+        let actor_errors = IntCounterVec::new(
+            Opts::new(
+                "wasmcloud_host_actor_invocation_errors_total",
+                "Number of actor invocations resulting in an error",
+            ),
+            &COMPONENT_LABELS,
+        )?;
+        let actor_interruptions = IntCounterVec::new(
+            Opts::new(
+                "wasmcloud_host_actor_invocation_interruptions_total",
+                "Number of actor invocations interrupted for exceeding execution limits",
+            ),
+            &COMPONENT_LABELS,
+        )?;
+        let actor_duration_seconds = HistogramVec::new(
+            HistogramOpts::new(
+                "wasmcloud_host_actor_invocation_duration_seconds",
+                "Duration of actor invocations in seconds",
+            ),
+            &COMPONENT_LABELS,
+        )?;
+        let actor_active_instances = IntGaugeVec::new(
+            Opts::new(
+                "wasmcloud_host_actor_active_instances",
+                "Number of actor instances currently handling an invocation",
+            ),
+            &COMPONENT_LABELS,
+        )?;
+        let actor_max_instances = IntGaugeVec::new(
+            Opts::new(
+                "wasmcloud_host_actor_max_instances",
+                "Maximum number of concurrently running instances of each actor",
+            ),
+            &COMPONENT_LABELS,
+        )?;
+        let provider_healthy = IntGaugeVec::new(
+            Opts::new(
+                "wasmcloud_host_provider_healthy",
+                "Whether the last health check of each provider succeeded",
+            ),
+            &PROVIDER_LABELS,
+        )?;
+        let policy_cache_hits = IntCounter::new(
+            "wasmcloud_host_policy_cache_hits_total",
+            "Number of policy decisions served from the decision cache",
+        )?;
+        let policy_cache_misses = IntCounter::new(
+            "wasmcloud_host_policy_cache_misses_total",
+            "Number of policy decisions that were not cached",
+        )?;
+        let config_watches = IntGauge::new(
+            "wasmcloud_host_config_watches",
+            "Number of named configs being watched for changes",
+        )?;
+
+        let registry = Registry::new();
+        let collectors: [Box<dyn Collector>; 12] = [
+            Box::new(host_info.clone()),
+            Box::new(uptime_seconds.clone()),
+            Box::new(actor_invocations.clone()),
+            Box::new(actor_errors.clone()),
+            Box::new(actor_interruptions.clone()),
+            Box::new(actor_duration_seconds.clone()),
+            Box::new(actor_active_instances.clone()),
+            Box::new(actor_max_instances.clone()),
+            Box::new(provider_healthy.clone()),
+            Box::new(policy_cache_hits.clone()),
+            Box::new(policy_cache_misses.clone()),
+            Box::new(config_watches.clone()),
+        ];
+        for collector in collectors {
+            registry.register(collector)?;
+        }
+        Ok(Self {
+            registry,
+            host_info,
+            uptime_seconds,
+            actor_invocations,
+            actor_errors,
+            actor_interruptions,
+            actor_duration_seconds,
+            actor_active_instances,
+            actor_max_instances,
+            provider_healthy,
+            policy_cache_hits,
+            policy_cache_misses,
+            config_watches,
+        })
+    }
+
+    /// Removes the series of all per-component metrics of `component_id` running `image_ref`
+    fn remove_component(&self, component_id: &str, image_ref: &str) {
+        let labels = [component_id, image_ref];
+        // Series that were never recorded cannot be removed, which is fine
+        let _ = self.actor_invocations.remove_label_values(&labels);
+        let _ = self.actor_errors.remove_label_values(&labels);
+        let _ = self.actor_interruptions.remove_label_values(&labels);
+        let _ = self.actor_duration_seconds.remove_label_values(&labels);
+        let _ = self.actor_active_instances.remove_label_values(&labels);
+    }
+}
+
+/// Returns the value of the series of `counter` with the given label values, without creating it
+fn counter_value(counter: &IntCounterVec, labels: &[&str]) -> Option<u64> {
+    counter
+        .collect()
+        .iter()
+        .flat_map(MetricFamily::get_metric)
+        .find(|metric| {
+            metric
+                .get_label()
+                .iter()
+                .map(LabelPair::get_value)
+                .eq(labels.iter().copied())
+        })
+        .map(|metric| {
+            // Series of an `IntCounterVec` only ever hold non-negative integers
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let value = metric.get_counter().get_value() as u64;
+            value
+        })
+}
+
 /// `HostMetrics` encapsulates the set of metrics emitted by the wasmcloud host
 #[derive(Clone, Debug)]
 #[allow(clippy::module_name_repetitions)]
@@ -10,6 +186,14 @@ pub struct HostMetrics {
     pub actor_invocations: Counter<u64>,
     /// The count of the number of times an actor invocation resulted in an error.
     pub actor_errors: Counter<u64>,
+    /// The count of the number of times an actor invocation was interrupted for exceeding its execution limits.
+    pub actor_interruptions: Counter<u64>,
+    /// The count of the number of times an outgoing invocation was rejected for exceeding the limits of its link.
+    pub link_rejections: Counter<u64>,
+    /// Metrics served in the Prometheus format, which the host can read back as well.
+    pub(crate) prometheus: PrometheusMetrics,
+    /// Number of live [`ComponentMetricsGuard`]s keyed by component ID and image reference
+    component_guards: Arc<Mutex<HashMap<(String, String), usize>>>,
 
     /// The host's ID.
     // TODO this is actually configured as an InstrumentationScope attribute on the global meter,
@@ -22,6 +206,39 @@ pub struct HostMetrics {
     pub lattice_id: String,
 }
 
+/// Marks a component instance as active for as long as it is alive
+pub(crate) struct ActiveInstanceGuard(IntGauge);
+
+impl Drop for ActiveInstanceGuard {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
+
+/// Retains the recorded metrics of a component ID and image reference for as long as any guard
+/// for them is alive. Metrics are pruned once the last guard is dropped
+#[derive(Debug)]
+pub(crate) struct ComponentMetricsGuard {
+    guards: Arc<Mutex<HashMap<(String, String), usize>>>,
+    prometheus: PrometheusMetrics,
+    key: (String, String),
+}
+
+impl Drop for ComponentMetricsGuard {
+    fn drop(&mut self) {
+        if let Ok(mut guards) = self.guards.lock() {
+            if let hash_map::Entry::Occupied(mut count) = guards.entry(self.key.clone()) {
+                *count.get_mut() = count.get().saturating_sub(1);
+                if *count.get() == 0 {
+                    count.remove();
+                    let (component_id, image_ref) = &self.key;
+                    self.prometheus.remove_component(component_id, image_ref);
+                }
+            }
+        }
+    }
+}
+
 impl HostMetrics {
     /// Construct a new [`HostMetrics`] instance for accessing the various wasmcloud host metrics linked to the provided meter.
     #[must_use]
@@ -42,10 +259,34 @@ impl HostMetrics {
             .with_description("Number of actor errors")
             .init();
 
+        let actor_interruption_count = meter
+            .u64_counter("wasmcloud_host.actor.invocation.interruptions")
+            .with_description(
+                "Number of actor invocations interrupted for exceeding execution limits",
+            )
+            .init();
+
+        let link_rejection_count = meter
+            .u64_counter("wasmcloud_host.link.invocation.rejections")
+            .with_description("Number of outgoing invocations rejected for exceeding link limits")
+            .init();
+
+        // The Prometheus metrics are static and registered in a fresh registry, so this can only
+        // fail if their definitions are broken
+        let prometheus = PrometheusMetrics::new().expect("invalid Prometheus metric definitions");
+        prometheus
+            .host_info
+            .with_label_values(&[&host_id, &lattice_id, env!("CARGO_PKG_VERSION")])
+            .set(1);
+
         Self {
             handle_rpc_message_duration_ns: wasmcloud_host_handle_rpc_message_duration_ns,
             actor_invocations: actor_invocation_count,
             actor_errors: actor_error_count,
+            actor_interruptions: actor_interruption_count,
+            link_rejections: link_rejection_count,
+            prometheus,
+            component_guards: Arc::default(),
             host_id,
             lattice_id,
         }
@@ -54,6 +295,8 @@ impl HostMetrics {
     /// Record the result of invoking a component, including the elapsed time, any attributes, and whether the invocation resulted in an error.
     pub(crate) fn record_component_invocation(
         &self,
+        component_id: &str,
+        image_ref: &str,
         elapsed: u64,
         attributes: &[KeyValue],
         error: bool,
@@ -64,5 +307,150 @@ impl HostMetrics {
         if error {
             self.actor_errors.add(1, attributes);
         }
+        let labels = [component_id, image_ref];
+        self.prometheus
+            .actor_invocations
+            .with_label_values(&labels)
+            .inc();
+        #[allow(clippy::cast_precision_loss)]
+        self.prometheus
+            .actor_duration_seconds
+            .with_label_values(&labels)
+            .observe(elapsed as f64 / 1e9);
+        if error {
+            self.prometheus
+                .actor_errors
+                .with_label_values(&labels)
+                .inc();
+        }
+    }
+
+    /// Returns the number of errors recorded for invocations of the component with the given ID
+    /// running the given image reference.
+    pub(crate) fn component_errors(&self, component_id: &str, image_ref: &str) -> u64 {
+        counter_value(&self.prometheus.actor_errors, &[component_id, image_ref]).unwrap_or_default()
+    }
+
+    /// Record that a component invocation was interrupted for exceeding its execution limits.
+    pub(crate) fn record_component_interruption(
+        &self,
+        component_id: &str,
+        image_ref: &str,
+        attributes: &[KeyValue],
+    ) {
+        self.actor_interruptions.add(1, attributes);
+        self.prometheus
+            .actor_interruptions
+            .with_label_values(&[component_id, image_ref])
+            .inc();
+    }
+
+    /// Record that an outgoing invocation was rejected for exceeding the limits of its link.
+    pub(crate) fn record_link_rejection(&self, attributes: &[KeyValue]) {
+        self.link_rejections.add(1, attributes);
+    }
+
+    /// Record that an instance of the component with the given ID running the given image
+    /// reference is active until the returned guard is dropped.
+    pub(crate) fn component_instance_active(
+        &self,
+        component_id: &str,
+        image_ref: &str,
+    ) -> ActiveInstanceGuard {
+        let active = self
+            .prometheus
+            .actor_active_instances
+            .with_label_values(&[component_id, image_ref]);
+        active.inc();
+        ActiveInstanceGuard(active)
+    }
+
+    /// Retains the recorded metrics of the component with the given ID running the given image
+    /// reference until the returned guard and all other guards for them are dropped.
+    pub(crate) fn retain_component(
+        &self,
+        component_id: &str,
+        image_ref: &str,
+    ) -> ComponentMetricsGuard {
+        let key = (component_id.to_string(), image_ref.to_string());
+        if let Ok(mut guards) = self.component_guards.lock() {
+            *guards.entry(key.clone()).or_default() += 1;
+        }
+        ComponentMetricsGuard {
+            guards: Arc::clone(&self.component_guards),
+            prometheus: self.prometheus.clone(),
+            key,
+        }
+    }
+
+    /// Record the result of a health check of the provider with the given ID running the given
+    /// image reference.
+    pub(crate) fn record_provider_health(&self, provider_id: &str, image_ref: &str, healthy: bool) {
+        self.prometheus
+            .provider_healthy
+            .with_label_values(&[provider_id, image_ref])
+            .set(healthy.into());
+    }
+
+    /// Forget the health of the provider with the given ID and image reference, once it is stopped.
+    pub(crate) fn remove_provider_health(&self, provider_id: &str, image_ref: &str) {
+        // Providers stopped before their first health check have no health recorded
+        let _ = self
+            .prometheus
+            .provider_healthy
+            .remove_label_values(&[provider_id, image_ref]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prometheus::{Encoder as _, TextEncoder};
+    use wasmcloud_tracing::global;
+
+    use super::HostMetrics;
+
+    fn encode(metrics: &HostMetrics) -> String {
+        let mut buf = Vec::new();
+        TextEncoder::new()
+            .encode(&metrics.prometheus.registry.gather(), &mut buf)
+            .expect("failed to encode metrics");
+        String::from_utf8(buf).expect("metrics are not valid UTF-8")
+    }
+
+    #[test]
+    fn prune_recorded() {
+        let metrics = HostMetrics::new(
+            &global::meter("test"),
+            "host".to_string(),
+            "lattice".to_string(),
+        );
+
+        // Metrics outlive a replaced instance of the same component and image reference
+        let old = metrics.retain_component("echo", "v1");
+        let new = metrics.retain_component("echo", "v1");
+        let updated = metrics.retain_component("echo", "v2");
+        metrics.record_component_invocation("echo", "v1", 1, &[], true);
+        metrics.record_component_invocation("echo", "v2", 1, &[], true);
+        drop(old);
+        assert_eq!(metrics.component_errors("echo", "v1"), 1);
+        assert!(encode(&metrics).contains(
+            r#"wasmcloud_host_actor_invocation_errors_total{component_id="echo",image_ref="v1"} 1"#
+        ));
+
+        // Metrics are pruned once the last instance is dropped
+        drop(new);
+        assert_eq!(metrics.component_errors("echo", "v1"), 0);
+        let encoded = encode(&metrics);
+        assert!(!encoded.contains(r#"image_ref="v1""#));
+        assert!(encoded.contains(r#"image_ref="v2""#));
+        drop(updated);
+        assert!(!encode(&metrics).contains("component_id="));
+
+        metrics.record_provider_health("provider", "ref", true);
+        assert!(encode(&metrics).contains(
+            r#"wasmcloud_host_provider_healthy{image_ref="ref",provider_id="provider"} 1"#
+        ));
+        metrics.remove_provider_health("provider", "ref");
+        assert!(!encode(&metrics).contains("provider_id="));
     }
 }
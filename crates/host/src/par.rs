@@ -10,6 +10,7 @@ use anyhow::{anyhow, Context};
 use provider_archive::ProviderArchive;
 use tokio::fs::{self, File, OpenOptions};
 use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
 use wascap::jwt;
 
 fn normalize_for_filename(input: &str) -> String {
@@ -77,17 +78,118 @@ pub async fn read(
     let par = ProviderArchive::try_load_target_from_file(path, &native_target())
         .await
         .map_err(|e| anyhow!(e).context("failed to load provider archive"))?;
+    write_cache(par, host_id, provider_ref, false).await
+}
+
+/// Reads a provider archive from a local file and writes it to the cache, see [`read`].
+///
+/// Unlike [`read`], a cached executable is replaced if the archive has changed, since local files
+/// are rebuilt under the same reference (e.g. by `wash dev`)
+pub async fn read_file(
+    path: impl AsRef<Path>,
+    host_id: impl AsRef<str>,
+    provider_ref: impl AsRef<str>,
+) -> anyhow::Result<(PathBuf, Option<jwt::Claims<jwt::CapabilityProvider>>)> {
+    let par = ProviderArchive::try_load_target_from_file(path, &native_target())
+        .await
+        .map_err(|e| anyhow!(e).context("failed to load provider archive"))?;
+    write_cache(par, host_id, provider_ref, true).await
+}
+
+/// Reads a provider archive from the given bytes and writes it to the cache, see [`read`]
+pub async fn read_bytes(
+    par: &[u8],
+    host_id: impl AsRef<str>,
+    provider_ref: impl AsRef<str>,
+) -> anyhow::Result<(PathBuf, Option<jwt::Claims<jwt::CapabilityProvider>>)> {
+    let par = ProviderArchive::try_load_target(par, &native_target())
+        .await
+        .map_err(|e| anyhow!(e).context("failed to load provider archive"))?;
+    write_cache(par, host_id, provider_ref, false).await
+}
+
+/// Writes the native target of `par` to the cache, unless it is already cached. If `replace` is
+/// set, a cached executable which differs from the one in `par` is replaced.
+async fn write_cache(
+    par: ProviderArchive,
+    host_id: impl AsRef<str>,
+    provider_ref: impl AsRef<str>,
+    replace: bool,
+) -> anyhow::Result<(PathBuf, Option<jwt::Claims<jwt::CapabilityProvider>>)> {
     let claims = par.claims();
 
     let exe = cache_path(host_id, provider_ref);
+    let target = native_target();
+    let buf = || {
+        par.target_bytes(&target)
+            .with_context(|| format!("target `{target}` not found"))
+    };
     // Only write the file if it doesn't exist
     if let Some(mut file) = create(&exe).await? {
-        let target = native_target();
-        let buf = par
-            .target_bytes(&target)
-            .with_context(|| format!("target `{target}` not found"))?;
-        file.write_all(&buf).await.context("failed to write")?;
+        file.write_all(&buf()?).await.context("failed to write")?;
         file.flush().await.context("failed to flush")?;
+    } else if replace {
+        let buf = buf()?;
+        if fs::read(&exe).await.ok().as_deref() != Some(buf.as_slice()) {
+            // Write a new file and move it in place, since the cached executable may be running
+            let mut tmp = exe.clone().into_os_string();
+            tmp.push(format!(".{}.tmp", Uuid::new_v4()));
+            let mut file = create(&tmp)
+                .await?
+                .context("temporary cache file already exists")?;
+            file.write_all(&buf).await.context("failed to write")?;
+            file.flush().await.context("failed to flush")?;
+            drop(file);
+            fs::rename(&tmp, &exe)
+                .await
+                .context("failed to replace cached executable")?;
+        }
     }
     Ok((exe, claims))
 }
+
+#[cfg(test)]
+mod tests {
+    use nkeys::KeyPair;
+    use provider_archive::ProviderArchive;
+    use tokio::fs;
+
+    use super::{native_target, read, read_file};
+
+    async fn write_par(path: &std::path::Path, exe: &[u8], issuer: &KeyPair, subject: &KeyPair) {
+        let mut par = ProviderArchive::new("wasmcloud:test", "test", "test", None, None);
+        par.add_library(&native_target(), exe)
+            .expect("failed to add library");
+        par.write(path, issuer, subject, false)
+            .await
+            .expect("failed to write provider archive");
+    }
+
+    #[tokio::test]
+    async fn replace_rebuilt_file() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("provider.par");
+        let provider_ref = format!("file://{}", path.display());
+        let host_id = format!("test-{}", uuid::Uuid::new_v4());
+        let issuer = KeyPair::new_account();
+        let subject = KeyPair::new_service();
+
+        write_par(&path, b"first build", &issuer, &subject).await;
+        let (exe, _) = read_file(&path, &host_id, &provider_ref).await?;
+        assert_eq!(fs::read(&exe).await?, b"first build");
+
+        // A rebuilt archive under the same reference replaces the cached executable
+        write_par(&path, b"second build", &issuer, &subject).await;
+        let (rebuilt, _) = read_file(&path, &host_id, &provider_ref).await?;
+        assert_eq!(rebuilt, exe);
+        assert_eq!(fs::read(&exe).await?, b"second build");
+
+        // Other references keep the executable cached first
+        write_par(&path, b"third build", &issuer, &subject).await;
+        read(&path, &host_id, &provider_ref).await?;
+        assert_eq!(fs::read(&exe).await?, b"second build");
+
+        fs::remove_dir_all(exe.parent().expect("cache directory")).await?;
+        Ok(())
+    }
+}
@@ -2,6 +2,8 @@ use core::time::Duration;
 
 use std::collections::{BTreeMap, HashMap};
 use std::hash::Hash;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -18,6 +20,11 @@ use ulid::Ulid;
 use uuid::Uuid;
 use wascap::jwt;
 
+/// Declarative policy rules evaluated by the host
+pub mod rules;
+
+pub use rules::{Effect as RuleEffect, Rule, Rules};
+
 // NOTE: All requests will be v1 until the schema changes, at which point we can change the version
 // per-request type
 const POLICY_TYPE_VERSION: &str = "v1";
@@ -98,7 +105,7 @@ pub struct HostInfo {
 }
 
 /// The action being requested
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Hash)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Hash)]
 pub enum RequestKind {
     /// The host is checking whether it may invoke the target component
     #[serde(rename = "performInvocation")]
@@ -182,6 +189,27 @@ struct RequestKey {
     cache_key: String,
 }
 
+impl RequestKey {
+    /// Constructs the key of a decision made by local [`Rules`]. Rules also match on the
+    /// annotations of the target and on the issuer and expiry of its claims, so these are part of
+    /// the key and a change in any of them leads to a new decision
+    fn for_rules(request: &RequestBody) -> Self {
+        let mut key = Self::from(request);
+        let (annotations, claims) = match request {
+            RequestBody::StartComponent(req) => (&req.annotations, req.claims.as_ref()),
+            RequestBody::StartProvider(req) => (&req.annotations, req.claims.as_ref()),
+            RequestBody::PerformInvocation(req) => {
+                (&req.target.annotations, req.target.claims.as_ref())
+            }
+            RequestBody::Unknown => return key,
+        };
+        let claims = claims.map(|claims| (&claims.issuer, claims.expired));
+        key.cache_key
+            .push_str(&format!("_{annotations:?}_{claims:?}"));
+        key
+    }
+}
+
 /// A policy decision response
 #[derive(Clone, Debug, Deserialize)]
 pub struct Response {
@@ -227,40 +255,64 @@ impl From<&jwt::Claims<jwt::CapabilityProvider>> for PolicyClaims {
     }
 }
 
+/// Usage statistics of the policy decision cache
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CacheStats {
+    /// Number of decisions served from the cache
+    pub hits: u64,
+    /// Number of decisions that were not cached and had to be evaluated
+    pub misses: u64,
+}
+
 /// Encapsulates making requests for policy decisions, and receiving updated decisions
 #[derive(Debug)]
 pub struct Manager {
     nats: async_nats::Client,
     host_info: HostInfo,
     policy_topic: Option<String>,
+    policy_rules: Option<Rules>,
     policy_timeout: Duration,
     decision_cache: Arc<RwLock<HashMap<RequestKey, Response>>>,
     request_to_key: Arc<RwLock<HashMap<String, RequestKey>>>,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
     /// An abort handle for the policy changes subscription
     pub policy_changes: AbortHandle,
 }
 
 impl Manager {
-    /// Construct a new policy manager. Can fail if policy_changes_topic is set but we fail to subscribe to it
+    /// Construct a new policy manager. If `policy_rules_file` is set, decisions are made locally using
+    /// the [`Rules`] it contains instead of being requested on `policy_topic`. Can fail if the rules
+    /// cannot be loaded or if policy_changes_topic is set but we fail to subscribe to it
     #[instrument(skip(nats))]
     pub async fn new(
         nats: async_nats::Client,
         host_info: HostInfo,
         policy_topic: Option<String>,
+        policy_rules_file: Option<PathBuf>,
         policy_timeout: Option<Duration>,
         policy_changes_topic: Option<String>,
     ) -> anyhow::Result<Arc<Self>> {
         const DEFAULT_POLICY_TIMEOUT: Duration = Duration::from_secs(1);
 
+        let policy_rules = if let Some(path) = policy_rules_file {
+            Some(Rules::load(path).await?)
+        } else {
+            None
+        };
+
         let (policy_changes_abort, policy_changes_abort_reg) = AbortHandle::new_pair();
 
         let manager = Manager {
             nats: nats.clone(),
             host_info,
             policy_topic,
+            policy_rules,
             policy_timeout: policy_timeout.unwrap_or(DEFAULT_POLICY_TIMEOUT),
             decision_cache: Arc::default(),
             request_to_key: Arc::default(),
+            cache_hits: AtomicU64::default(),
+            cache_misses: AtomicU64::default(),
             policy_changes: policy_changes_abort,
         };
         let manager = Arc::new(manager);
@@ -353,17 +405,18 @@ impl Manager {
             .await
     }
 
-    /// Sends a policy request to the policy server and caches the response
+    /// Evaluates a policy request using the local rules, if configured, or sends it to the policy
+    /// server and caches the decision
     #[instrument(level = "trace", skip_all)]
     pub async fn evaluate_action(&self, request: RequestBody) -> anyhow::Result<Response> {
-        let Some(policy_topic) = self.policy_topic.clone() else {
-            // Ensure we short-circuit and allow the request if no policy topic is configured
+        if self.policy_topic.is_none() && self.policy_rules.is_none() {
+            // Ensure we short-circuit and allow the request if no policy service is configured
             return Ok(Response {
                 request_id: "".to_string(),
                 permitted: true,
                 message: None,
             });
-        };
+        }
 
         let kind = match request {
             RequestBody::StartComponent(_) => RequestKind::StartComponent,
@@ -371,16 +424,62 @@ impl Manager {
             RequestBody::PerformInvocation(_) => RequestKind::PerformInvocation,
             RequestBody::Unknown => RequestKind::Unknown,
         };
-        let cache_key = (&request).into();
+        let cache_key = if self.policy_rules.is_some() {
+            RequestKey::for_rules(&request)
+        } else {
+            (&request).into()
+        };
         if let Some(entry) = self.decision_cache.read().await.get(&cache_key) {
             trace!(?cache_key, ?entry, "using cached policy decision");
+            self.cache_hits.fetch_add(1, Ordering::Relaxed);
             return Ok(entry.clone());
         }
+        self.cache_misses.fetch_add(1, Ordering::Relaxed);
 
         let request_id = Uuid::from_u128(Ulid::new().into()).to_string();
-        trace!(?cache_key, "requesting policy decision");
+        let decision = if let Some(rules) = &self.policy_rules {
+            trace!(?kind, "evaluating policy rules");
+            rules.evaluate(request_id.clone(), kind, &request, &self.host_info)
+        } else {
+            self.request_decision(request_id.clone(), kind, request)
+                .await?
+        };
+
+        self.decision_cache
+            .write()
+            .await
+            .insert(cache_key.clone(), decision.clone()); // cache policy decision
+        self.request_to_key
+            .write()
+            .await
+            .insert(request_id, cache_key); // cache request id -> decision key
+        Ok(decision)
+    }
+
+    /// Returns the number of policy decisions served from and missing in the decision cache
+    #[must_use]
+    pub fn cache_stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.cache_hits.load(Ordering::Relaxed),
+            misses: self.cache_misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Requests a policy decision from the policy server
+    #[instrument(level = "trace", skip_all)]
+    async fn request_decision(
+        &self,
+        request_id: String,
+        kind: RequestKind,
+        request: RequestBody,
+    ) -> anyhow::Result<Response> {
+        let policy_topic = self
+            .policy_topic
+            .clone()
+            .context("policy topic not configured")?;
+        trace!(request_id, "requesting policy decision");
         let payload = serde_json::to_vec(&Request {
-            request_id: request_id.clone(),
+            request_id,
             request,
             kind,
             version: POLICY_TYPE_VERSION.to_string(),
@@ -395,18 +494,8 @@ impl Manager {
             .send_request(policy_topic, request)
             .await
             .context("policy request failed")?;
-        let decision = serde_json::from_slice::<Response>(&res.payload)
-            .context("failed to deserialize policy response")?;
-
-        self.decision_cache
-            .write()
-            .await
-            .insert(cache_key.clone(), decision.clone()); // cache policy decision
-        self.request_to_key
-            .write()
-            .await
-            .insert(request_id, cache_key); // cache request id -> decision key
-        Ok(decision)
+        serde_json::from_slice::<Response>(&res.payload)
+            .context("failed to deserialize policy response")
     }
 
     #[instrument(skip(self))]
@@ -442,3 +531,71 @@ impl Manager {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::collections::{BTreeMap, HashMap};
+    use std::sync::atomic::AtomicU64;
+    use std::sync::Arc;
+
+    use futures::stream::AbortHandle;
+
+    use super::{CacheStats, HostInfo, Manager, Rules};
+
+    #[tokio::test]
+    async fn test_local_rules_cached() {
+        // The client is never used by local rules, so it does not need a server
+        let nats = async_nats::ConnectOptions::new()
+            .retry_on_initial_connect()
+            .connect("127.0.0.1:4222")
+            .await
+            .expect("failed to create NATS client");
+        let rules: Rules = serde_json::from_value(serde_json::json!({
+            "rules": [
+                { "effect": "allow", "annotations": { "env": "prod" } }
+            ]
+        }))
+        .expect("failed to parse rules");
+        let manager = Manager {
+            nats,
+            host_info: HostInfo {
+                public_key: "NHOST".to_string(),
+                lattice: "default".to_string(),
+                labels: HashMap::default(),
+            },
+            policy_topic: None,
+            policy_rules: Some(rules),
+            policy_timeout: core::time::Duration::from_secs(1),
+            decision_cache: Arc::default(),
+            request_to_key: Arc::default(),
+            cache_hits: AtomicU64::default(),
+            cache_misses: AtomicU64::default(),
+            policy_changes: AbortHandle::new_pair().0,
+        };
+
+        let prod = BTreeMap::from([("env".to_string(), "prod".to_string())]);
+        let dev = BTreeMap::from([("env".to_string(), "dev".to_string())]);
+        let res = manager
+            .evaluate_start_component("component", "ghcr.io/wasmcloud/echo:0.1.0", 1, &prod, None)
+            .await
+            .expect("failed to evaluate policy");
+        assert!(res.permitted);
+        let res = manager
+            .evaluate_start_component("component", "ghcr.io/wasmcloud/echo:0.1.0", 1, &prod, None)
+            .await
+            .expect("failed to evaluate policy");
+        assert!(res.permitted);
+        assert_eq!(manager.cache_stats(), CacheStats { hits: 1, misses: 1 });
+
+        // The same component and image reference with other annotations is evaluated again
+        let res = manager
+            .evaluate_start_component("component", "ghcr.io/wasmcloud/echo:0.1.0", 1, &dev, None)
+            .await
+            .expect("failed to evaluate policy");
+        assert!(!res.permitted);
+        assert_eq!(manager.cache_stats(), CacheStats { hits: 1, misses: 2 });
+
+        // Decisions of the rules are recorded, so they can be overridden on the changes topic
+        assert_eq!(manager.request_to_key.read().await.len(), 2);
+    }
+}
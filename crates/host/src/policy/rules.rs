@@ -0,0 +1,288 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use anyhow::Context;
+use serde::Deserialize;
+use tokio::fs;
+
+use super::{HostInfo, PolicyClaims, RequestBody, RequestKind, Response};
+
+/// The decision made by a matching [`Rule`]
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub enum Effect {
+    /// The request is permitted
+    #[serde(rename = "allow")]
+    Allow,
+    /// The request is denied
+    #[default]
+    #[serde(rename = "deny")]
+    Deny,
+}
+
+/// A single policy rule. A rule matches a request if all of its conditions match, conditions that
+/// are not set always match
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Rule {
+    /// Optional name of the rule, used in decision messages
+    #[serde(default)]
+    pub name: Option<String>,
+    /// The decision made if this rule matches
+    pub effect: Effect,
+    /// The kinds of requests this rule applies to. Applies to all kinds if empty
+    #[serde(default)]
+    pub kinds: Vec<RequestKind>,
+    /// Issuers of which one must have signed the claims of the target
+    #[serde(default)]
+    pub issuers: Vec<String>,
+    /// Image reference patterns of which one must match the image reference of the target.
+    /// `*` matches any sequence of characters and `?` matches any single character
+    #[serde(default, rename = "imageRefs")]
+    pub image_refs: Vec<String>,
+    /// Whether the claims of the target must be expired. Targets without claims are never expired
+    #[serde(default)]
+    pub expired: Option<bool>,
+    /// Annotations that must all be set on the target to the given values
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+    /// Labels that must all be set on the host to the given values
+    #[serde(default, rename = "hostLabels")]
+    pub host_labels: HashMap<String, String>,
+}
+
+/// An ordered set of [`Rule`]s evaluated by the host. The first matching rule makes the decision,
+/// if no rule matches the `default` effect applies
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Rules {
+    /// The decision made if no rule matches, defaults to [`Effect::Deny`]
+    #[serde(default)]
+    pub default: Effect,
+    /// The rules to evaluate, in order
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+}
+
+/// The parts of a request rules can match on
+struct Target<'a> {
+    image_ref: &'a str,
+    annotations: &'a BTreeMap<String, String>,
+    claims: Option<&'a PolicyClaims>,
+}
+
+impl<'a> Target<'a> {
+    fn from_request(request: &'a RequestBody) -> Option<Self> {
+        let (image_ref, annotations, claims) = match request {
+            RequestBody::StartComponent(component) => (
+                &component.image_ref,
+                &component.annotations,
+                component.claims.as_ref(),
+            ),
+            RequestBody::StartProvider(provider) => (
+                &provider.image_ref,
+                &provider.annotations,
+                provider.claims.as_ref(),
+            ),
+            RequestBody::PerformInvocation(invocation) => (
+                &invocation.target.image_ref,
+                &invocation.target.annotations,
+                invocation.target.claims.as_ref(),
+            ),
+            RequestBody::Unknown => return None,
+        };
+        Some(Self {
+            image_ref,
+            annotations,
+            claims,
+        })
+    }
+}
+
+impl Rule {
+    fn matches(&self, kind: RequestKind, target: &Target<'_>, host: &HostInfo) -> bool {
+        (self.kinds.is_empty() || self.kinds.contains(&kind))
+            && (self.issuers.is_empty()
+                || target
+                    .claims
+                    .is_some_and(|claims| self.issuers.contains(&claims.issuer)))
+            && (self.image_refs.is_empty()
+                || self
+                    .image_refs
+                    .iter()
+                    .any(|pattern| glob_match(pattern, target.image_ref)))
+            && self.expired.map_or(true, |expired| {
+                target.claims.is_some_and(|claims| claims.expired) == expired
+            })
+            && self
+                .annotations
+                .iter()
+                .all(|(k, v)| target.annotations.get(k) == Some(v))
+            && self
+                .host_labels
+                .iter()
+                .all(|(k, v)| host.labels.get(k) == Some(v))
+    }
+}
+
+impl Rules {
+    /// Reads [`Rules`] from a JSON file at `path`
+    pub async fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let rules = fs::read(path)
+            .await
+            .with_context(|| format!("failed to read policy rules from `{}`", path.display()))?;
+        serde_json::from_slice(&rules)
+            .with_context(|| format!("failed to parse policy rules from `{}`", path.display()))
+    }
+
+    /// Evaluates `request` against the rules, producing a [`Response`] with the given `request_id`
+    pub(super) fn evaluate(
+        &self,
+        request_id: String,
+        kind: RequestKind,
+        request: &RequestBody,
+        host: &HostInfo,
+    ) -> Response {
+        let matched = Target::from_request(request).and_then(|target| {
+            self.rules
+                .iter()
+                .enumerate()
+                .find(|(_, rule)| rule.matches(kind, &target, host))
+        });
+        let (effect, message) = match matched {
+            Some((i, rule)) => {
+                let name = rule.name.clone().unwrap_or_else(|| format!("#{i}"));
+                (rule.effect, format!("denied by policy rule `{name}`"))
+            }
+            None => (self.default, "no policy rule matched".to_string()),
+        };
+        let permitted = effect == Effect::Allow;
+        Response {
+            request_id,
+            permitted,
+            message: (!permitted).then_some(message),
+        }
+    }
+}
+
+/// Matches `value` against `pattern`, where `*` matches any sequence of characters and `?`
+/// matches any single character
+fn glob_match(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+    let (mut p, mut v) = (0, 0);
+    // Position of the last `*` in the pattern and the position in the value it was matched at
+    let mut backtrack = None;
+    while v < value.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, v));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == value[v] => {
+                p += 1;
+                v += 1;
+            }
+            _ => {
+                let Some((star, matched)) = backtrack else {
+                    return false;
+                };
+                // Let the last `*` consume one more character and retry
+                backtrack = Some((star, matched + 1));
+                p = star + 1;
+                v = matched + 1;
+            }
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+
+    use super::*;
+    use crate::policy::ComponentInformation;
+
+    fn host() -> HostInfo {
+        HostInfo {
+            public_key: "NHOST".to_string(),
+            lattice: "default".to_string(),
+            labels: HashMap::from([("zone".to_string(), "edge".to_string())]),
+        }
+    }
+
+    fn start_component(image_ref: &str, issuer: Option<&str>) -> RequestBody {
+        RequestBody::StartComponent(ComponentInformation {
+            component_id: "component".to_string(),
+            image_ref: image_ref.to_string(),
+            max_instances: 1,
+            annotations: BTreeMap::default(),
+            claims: issuer.map(|issuer| PolicyClaims {
+                issuer: issuer.to_string(),
+                ..Default::default()
+            }),
+        })
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match(
+            "ghcr.io/wasmcloud/*",
+            "ghcr.io/wasmcloud/echo:0.1.0"
+        ));
+        assert!(glob_match("*:0.?.0", "ghcr.io/wasmcloud/echo:0.1.0"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match(
+            "ghcr.io/wasmcloud/*",
+            "docker.io/wasmcloud/echo"
+        ));
+        assert!(!glob_match("*:0.1", "ghcr.io/wasmcloud/echo:0.1.0"));
+    }
+
+    #[test]
+    fn test_rules_evaluate() {
+        let rules: Rules = serde_json::from_value(serde_json::json!({
+            "rules": [
+                { "name": "untrusted", "effect": "deny", "issuers": ["AEVIL"] },
+                {
+                    "effect": "allow",
+                    "kinds": ["startComponent"],
+                    "imageRefs": ["ghcr.io/wasmcloud/*"],
+                    "hostLabels": { "zone": "edge" }
+                }
+            ]
+        }))
+        .expect("failed to parse rules");
+        let host = host();
+
+        let res = rules.evaluate(
+            "1".to_string(),
+            RequestKind::StartComponent,
+            &start_component("ghcr.io/wasmcloud/echo:0.1.0", Some("AGOOD")),
+            &host,
+        );
+        assert!(res.permitted);
+        assert_eq!(res.request_id, "1");
+        assert_eq!(res.message, None);
+
+        let res = rules.evaluate(
+            "2".to_string(),
+            RequestKind::StartComponent,
+            &start_component("ghcr.io/wasmcloud/echo:0.1.0", Some("AEVIL")),
+            &host,
+        );
+        assert!(!res.permitted);
+        assert_eq!(
+            res.message.as_deref(),
+            Some("denied by policy rule `untrusted`")
+        );
+
+        let res = rules.evaluate(
+            "3".to_string(),
+            RequestKind::StartComponent,
+            &start_component("docker.io/wasmcloud/echo:0.1.0", None),
+            &host,
+        );
+        assert!(!res.permitted);
+        assert_eq!(res.message.as_deref(), Some("no policy rule matched"));
+    }
+}
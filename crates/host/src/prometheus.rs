@@ -0,0 +1,73 @@
+use core::future::Future;
+use core::time::Duration;
+
+use anyhow::Context as _;
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::service::service_fn;
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpListener;
+use tokio::spawn;
+use tokio::time::sleep;
+use tracing::{debug, instrument, warn};
+
+/// Path metrics are served on
+const METRICS_PATH: &str = "/metrics";
+
+/// Delay before accepting connections again after the first failure to accept one
+const ACCEPT_BACKOFF_MIN: Duration = Duration::from_millis(10);
+
+/// Maximum delay before accepting connections again after failures to accept them
+const ACCEPT_BACKOFF_MAX: Duration = Duration::from_secs(1);
+
+/// Serves the metrics produced by `render` on [`METRICS_PATH`] to connections accepted on `listener`
+#[instrument(level = "debug", skip_all)]
+pub(crate) async fn serve<F, Fut>(listener: TcpListener, render: F)
+where
+    F: Fn() -> Fut + Clone + Send + Sync + 'static,
+    Fut: Future<Output = String> + Send + 'static,
+{
+    let mut backoff = ACCEPT_BACKOFF_MIN;
+    loop {
+        let (stream, addr) = match listener.accept().await {
+            Ok(conn) => {
+                backoff = ACCEPT_BACKOFF_MIN;
+                conn
+            }
+            Err(err) => {
+                // Errors like running out of file descriptors persist for a while, so back off
+                // instead of retrying in a hot loop
+                warn!(?err, ?backoff, "failed to accept metrics connection");
+                sleep(backoff).await;
+                backoff = (backoff * 2).min(ACCEPT_BACKOFF_MAX);
+                continue;
+            }
+        };
+        let render = render.clone();
+        spawn(async move {
+            let service = service_fn(move |request: hyper::Request<hyper::body::Incoming>| {
+                let render = render.clone();
+                async move {
+                    let res = if request.method() == hyper::Method::GET
+                        && request.uri().path() == METRICS_PATH
+                    {
+                        hyper::Response::builder()
+                            .header(hyper::header::CONTENT_TYPE, prometheus::TEXT_FORMAT)
+                            .body(Full::new(Bytes::from(render().await)))
+                    } else {
+                        hyper::Response::builder()
+                            .status(hyper::StatusCode::NOT_FOUND)
+                            .body(Full::default())
+                    };
+                    res.context("failed to build response")
+                }
+            });
+            if let Err(err) = hyper::server::conn::http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                debug!(?err, ?addr, "failed to serve metrics connection");
+            }
+        });
+    }
+}
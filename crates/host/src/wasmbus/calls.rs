@@ -0,0 +1,244 @@
+use core::fmt;
+use core::future::Future;
+use core::num::NonZeroUsize;
+
+use futures::stream::FuturesUnordered;
+use futures::{Stream, StreamExt};
+use tokio::select;
+use tokio::sync::{oneshot, Mutex};
+use tracing::warn;
+
+type Stop<S> = oneshot::Sender<Option<oneshot::Sender<S>>>;
+
+/// Handle of the task serving a stream of invocations `S` using [`serve_invocations`]
+pub(crate) struct Calls<S>(Mutex<Option<Stop<S>>>);
+
+impl<S> fmt::Debug for Calls<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Calls").finish()
+    }
+}
+
+impl<S> Calls<S> {
+    /// Returns a new [`Calls`] handle and the receiver to pass to [`serve_invocations`]
+    pub(crate) fn new() -> (Self, oneshot::Receiver<Option<oneshot::Sender<S>>>) {
+        let (tx, rx) = oneshot::channel();
+        (Self(Mutex::new(Some(tx))), rx)
+    }
+
+    /// Stops accepting new invocations, in-flight invocations run to completion
+    pub(crate) async fn stop(&self) {
+        if let Some(stop) = self.0.lock().await.take() {
+            let _ = stop.send(None);
+        }
+    }
+
+    /// Stops accepting new invocations and returns the stream of invocations, so that it can be
+    /// served by another task without subscribing again. No invocation is accepted by both tasks
+    /// and none is lost in between, since pending invocations stay buffered in the stream.
+    /// In-flight invocations run to completion.
+    ///
+    /// Returns `None` if invocations are not being accepted anymore
+    pub(crate) async fn handover(&self) -> Option<S> {
+        let stop = self.0.lock().await.take()?;
+        let (tx, rx) = oneshot::channel();
+        stop.send(Some(tx)).ok()?;
+        rx.await.ok()
+    }
+}
+
+/// Accepts invocations from `invocations` and handles at most `max` of them at once using
+/// `handle`, until the stream ends or `stop` is triggered by the associated [`Calls`] handle.
+/// Returns once all accepted invocations completed
+pub(crate) async fn serve_invocations<S, F, Fut>(
+    mut invocations: S,
+    max: NonZeroUsize,
+    mut stop: oneshot::Receiver<Option<oneshot::Sender<S>>>,
+    handle: F,
+) where
+    S: Stream + Unpin,
+    F: Fn(S::Item) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let mut calls = FuturesUnordered::new();
+    loop {
+        select! {
+            handover = &mut stop => {
+                if let Ok(Some(handover)) = handover {
+                    if handover.send(invocations).is_err() {
+                        warn!("invocation stream handover was abandoned");
+                    }
+                }
+                break;
+            }
+            Some(()) = calls.next(), if !calls.is_empty() => {}
+            invocation = invocations.next(), if calls.len() < max.get() => {
+                let Some(invocation) = invocation else {
+                    break;
+                };
+                calls.push(handle(invocation));
+            }
+        }
+    }
+    while calls.next().await.is_some() {}
+}
+
+#[cfg(test)]
+mod tests {
+    use core::num::NonZeroUsize;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    use anyhow::Context as _;
+    use tokio::spawn;
+    use tokio::sync::{mpsc, Semaphore};
+    use tokio_stream::wrappers::UnboundedReceiverStream;
+
+    use super::{serve_invocations, Calls};
+
+    type Invocations = UnboundedReceiverStream<u32>;
+    type Handled = Arc<Mutex<Vec<(u32, u32)>>>;
+
+    /// Serves `invocations` on a new task, recording each handled invocation in `handled` as
+    /// `(version, invocation)`
+    fn serve(
+        version: u32,
+        invocations: Invocations,
+        handled: &Handled,
+    ) -> (Calls<Invocations>, tokio::task::JoinHandle<()>) {
+        let (calls, stop) = Calls::new();
+        let handled = Arc::clone(handled);
+        let task = spawn(serve_invocations(
+            invocations,
+            NonZeroUsize::MIN,
+            stop,
+            move |invocation| {
+                let handled = Arc::clone(&handled);
+                async move {
+                    handled
+                        .lock()
+                        .expect("failed to lock handled invocations")
+                        .push((version, invocation));
+                }
+            },
+        ));
+        (calls, task)
+    }
+
+    async fn wait_handled(handled: &Handled, n: usize) {
+        while handled
+            .lock()
+            .expect("failed to lock handled invocations")
+            .len()
+            < n
+        {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn handover_and_rollback() -> anyhow::Result<()> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let handled = Handled::default();
+        let (old, old_task) = serve(1, UnboundedReceiverStream::new(rx), &handled);
+        for i in 0..10 {
+            tx.send(i)?;
+        }
+        wait_handled(&handled, 10).await;
+
+        // Update: the new version takes over the stream of the old one
+        let invocations = old
+            .handover()
+            .await
+            .context("old version should hand over")?;
+        old_task.await?;
+        assert!(old.handover().await.is_none());
+        for i in 10..20 {
+            tx.send(i)?;
+        }
+        let (new, new_task) = serve(2, invocations, &handled);
+        wait_handled(&handled, 20).await;
+
+        // Rollback: the stream is handed back to a restored old version
+        let invocations = new
+            .handover()
+            .await
+            .context("new version should hand over")?;
+        new_task.await?;
+        for i in 20..30 {
+            tx.send(i)?;
+        }
+        let (restored, restored_task) = serve(1, invocations, &handled);
+        wait_handled(&handled, 30).await;
+        restored.stop().await;
+        restored_task.await?;
+
+        // Every invocation is handled exactly once and in order, invocations buffered during a
+        // handover are handled by the version taking over
+        let handled = handled.lock().expect("failed to lock handled invocations");
+        assert_eq!(
+            handled.iter().map(|(_, i)| *i).collect::<Vec<_>>(),
+            (0..30).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            handled.iter().map(|(v, _)| *v).collect::<Vec<_>>(),
+            [[1; 10], [2; 10], [1; 10]].concat()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn stop_completes_in_flight() -> anyhow::Result<()> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let permits = Arc::new(Semaphore::new(0));
+        let (started_tx, mut started) = mpsc::unbounded_channel();
+        let completed = Arc::new(AtomicUsize::new(0));
+        let (calls, stop) = Calls::new();
+        let task = spawn({
+            let permits = Arc::clone(&permits);
+            let completed = Arc::clone(&completed);
+            serve_invocations(
+                UnboundedReceiverStream::new(rx),
+                NonZeroUsize::new(2).context("zero")?,
+                stop,
+                move |()| {
+                    let permits = Arc::clone(&permits);
+                    let completed = Arc::clone(&completed);
+                    let started_tx = started_tx.clone();
+                    async move {
+                        let _ = started_tx.send(());
+                        if let Ok(permit) = permits.acquire().await {
+                            permit.forget();
+                        }
+                        completed.fetch_add(1, Ordering::Relaxed);
+                    }
+                },
+            )
+        });
+        for _ in 0..3 {
+            tx.send(())?;
+        }
+
+        // Only `max` invocations are accepted at once
+        started
+            .recv()
+            .await
+            .context("first invocation not started")?;
+        started
+            .recv()
+            .await
+            .context("second invocation not started")?;
+        tokio::task::yield_now().await;
+        assert!(started.try_recv().is_err());
+
+        // Stopping waits for accepted invocations, but does not accept the pending one
+        calls.stop().await;
+        permits.add_permits(2);
+        task.await?;
+        assert_eq!(completed.load(Ordering::Relaxed), 2);
+        assert!(started.try_recv().is_err());
+        assert!(calls.handover().await.is_none());
+        Ok(())
+    }
+}
@@ -203,6 +203,11 @@ impl BundleGenerator {
         Ok(ConfigBundle::new(receivers).await)
     }
 
+    /// Returns the number of named configs currently being watched
+    pub async fn watch_count(&self) -> usize {
+        self.watch_cache.read().await.len()
+    }
+
     async fn get_receiver(&self, name: String) -> anyhow::Result<ConfigReceiver> {
         // First check the cache to see if we already have a receiver for this config
         if let Some(receiver) = self.watch_cache.read().await.get(&name) {
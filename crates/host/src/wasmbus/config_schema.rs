@@ -0,0 +1,173 @@
+//! Validation of link configuration against the JSON schema a provider embeds in its claims
+use std::collections::HashMap;
+
+use jsonschema::JSONSchema;
+use serde_json::{Map, Value};
+
+/// Merges the named configs of one side of a link, which are looked up using `named`, into a
+/// single map. Configs are applied in order, so later configs override the values of earlier ones.
+///
+/// Returns the merged config and the names of the configs which were not found.
+pub(crate) fn merge_link_config<'a>(
+    names: &'a [String],
+    named: &HashMap<&str, HashMap<String, String>>,
+) -> (HashMap<String, String>, Vec<&'a str>) {
+    let mut config = HashMap::new();
+    let mut missing = Vec::new();
+    for name in names {
+        if let Some(values) = named.get(name.as_str()) {
+            config.extend(values.clone());
+        } else {
+            missing.push(name.as_str());
+        }
+    }
+    (config, missing)
+}
+
+/// Validates `config` against the provider config `schema`. The draft of the schema is
+/// determined from its `$schema` keyword, defaulting to draft 7.
+///
+/// Config values are always strings, so values of properties the schema declares with a
+/// non-string type are parsed as JSON before validation (e.g. `"8080"` for an `integer` port).
+///
+/// Returns a description of every violation, which is empty if the config is valid, or an error
+/// if the schema itself is invalid.
+pub(crate) fn validate(
+    schema: &Value,
+    config: &HashMap<String, String>,
+) -> anyhow::Result<Vec<String>> {
+    let compiled = JSONSchema::options()
+        .compile(schema)
+        .map_err(|e| anyhow::anyhow!("invalid provider config schema: {e}"))?;
+    let instance = Value::Object(
+        config
+            .iter()
+            .map(|(key, value)| (key.clone(), coerce(schema, key, value)))
+            .collect::<Map<_, _>>(),
+    );
+    let violations = match compiled.validate(&instance) {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors
+            .map(|e| {
+                let path = e.instance_path.to_string();
+                if path.is_empty() {
+                    e.to_string()
+                } else {
+                    format!("{path}: {e}")
+                }
+            })
+            .collect(),
+    };
+    Ok(violations)
+}
+
+/// Converts a config value to the JSON type declared for `key` by the top-level `properties` of
+/// `schema`, falling back to a string if the schema allows strings or the value does not parse
+fn coerce(schema: &Value, key: &str, value: &str) -> Value {
+    let allows_string = match schema
+        .get("properties")
+        .and_then(|properties| properties.get(key))
+        .and_then(|property| property.get("type"))
+    {
+        Some(Value::String(ty)) => ty == "string",
+        Some(Value::Array(types)) => types.iter().any(|ty| ty == "string"),
+        _ => true,
+    };
+    if allows_string {
+        return Value::String(value.to_string());
+    }
+    serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use super::{merge_link_config, validate};
+
+    fn schema() -> serde_json::Value {
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "URL": { "type": "string", "format": "uri" },
+                "PORT": { "type": "integer", "minimum": 1 },
+                "TLS": { "type": "boolean" }
+            },
+            "required": ["URL"],
+            "additionalProperties": false
+        })
+    }
+
+    #[test]
+    fn merges_named_config_in_order() {
+        let named = HashMap::from([
+            (
+                "base",
+                HashMap::from([
+                    ("URL".to_string(), "redis://base".to_string()),
+                    ("PORT".to_string(), "6379".to_string()),
+                ]),
+            ),
+            (
+                "override",
+                HashMap::from([("URL".to_string(), "redis://override".to_string())]),
+            ),
+        ]);
+        let entries = vec![
+            "base".to_string(),
+            "override".to_string(),
+            "missing".to_string(),
+        ];
+        let (config, missing) = merge_link_config(&entries, &named);
+        assert_eq!(
+            config,
+            HashMap::from([
+                ("URL".to_string(), "redis://override".to_string()),
+                ("PORT".to_string(), "6379".to_string()),
+            ])
+        );
+        assert_eq!(missing, vec!["missing"]);
+    }
+
+    #[test]
+    fn accepts_valid_config() {
+        let config = HashMap::from([
+            ("URL".to_string(), "redis://127.0.0.1".to_string()),
+            ("PORT".to_string(), "6379".to_string()),
+            ("TLS".to_string(), "true".to_string()),
+        ]);
+        assert_eq!(
+            validate(&schema(), &config).expect("schema should compile"),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn reports_every_violation() {
+        let config = HashMap::from([
+            ("URl".to_string(), "redis://127.0.0.1".to_string()),
+            ("PORT".to_string(), "0".to_string()),
+        ]);
+        let mut violations = validate(&schema(), &config).expect("schema should compile");
+        violations.sort();
+        assert_eq!(violations.len(), 3, "{violations:?}");
+        assert!(
+            violations[0].contains("\"URL\" is a required property"),
+            "{violations:?}"
+        );
+        assert!(violations[1].starts_with("/PORT: "), "{violations:?}");
+        assert!(
+            violations[2].contains("'URl' was unexpected"),
+            "{violations:?}"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_schema() {
+        let schema = json!({ "type": "not-a-type" });
+        assert!(validate(&schema, &HashMap::new()).is_err());
+    }
+}
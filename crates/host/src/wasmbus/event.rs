@@ -1,6 +1,7 @@
 use core::num::NonZeroUsize;
 
 use std::collections::BTreeMap;
+use std::time::Duration;
 
 use anyhow::Context;
 use cloudevents::{EventBuilder, EventBuilderV10};
@@ -10,6 +11,7 @@ use tracing::instrument;
 use ulid::Ulid;
 use uuid::Uuid;
 use wascap::jwt;
+use wasmcloud_runtime::ExecutionLimitExceeded;
 
 fn format_actor_claims(claims: &jwt::Claims<jwt::Actor>) -> serde_json::Value {
     let issuer = &claims.issuer;
@@ -96,6 +98,54 @@ pub fn actor_scale_failed(
     }
 }
 
+pub fn actor_update_failed(
+    host_id: impl AsRef<str>,
+    actor_id: impl AsRef<str>,
+    image_ref: impl AsRef<str>,
+    new_image_ref: impl AsRef<str>,
+    error: &anyhow::Error,
+) -> serde_json::Value {
+    json!({
+        "actor_id": actor_id.as_ref(),
+        "host_id": host_id.as_ref(),
+        "image_ref": image_ref.as_ref(),
+        "new_image_ref": new_image_ref.as_ref(),
+        "error": format!("{error:#}"),
+    })
+}
+
+pub fn actor_rolled_back(
+    host_id: impl AsRef<str>,
+    actor_id: impl AsRef<str>,
+    image_ref: impl AsRef<str>,
+    failed_image_ref: impl AsRef<str>,
+) -> serde_json::Value {
+    json!({
+        "actor_id": actor_id.as_ref(),
+        "host_id": host_id.as_ref(),
+        "image_ref": image_ref.as_ref(),
+        "failed_image_ref": failed_image_ref.as_ref(),
+    })
+}
+
+pub fn actor_invocation_interrupted(
+    host_id: impl AsRef<str>,
+    image_ref: impl AsRef<str>,
+    actor_id: impl AsRef<str>,
+    limit: ExecutionLimitExceeded,
+) -> serde_json::Value {
+    let reason = match limit {
+        ExecutionLimitExceeded::Deadline => "deadline",
+        ExecutionLimitExceeded::Fuel => "fuel",
+    };
+    json!({
+        "host_id": host_id.as_ref(),
+        "image_ref": image_ref.as_ref(),
+        "actor_id": actor_id.as_ref(),
+        "reason": reason,
+    })
+}
+
 pub fn linkdef_set(
     link: &wasmcloud_control_interface::InterfaceLinkDefinition,
 ) -> serde_json::Value {
@@ -207,6 +257,36 @@ pub fn provider_stopped(
     }
 }
 
+pub fn provider_restarted(
+    host_id: impl AsRef<str>,
+    provider_id: impl AsRef<str>,
+    exit_status: impl AsRef<str>,
+    restarts: usize,
+) -> serde_json::Value {
+    json!({
+        "host_id": host_id.as_ref(),
+        "provider_id": provider_id.as_ref(),
+        "exit_status": exit_status.as_ref(),
+        "restarts": restarts,
+    })
+}
+
+pub fn provider_crash_loop(
+    host_id: impl AsRef<str>,
+    provider_id: impl AsRef<str>,
+    exit_status: impl AsRef<str>,
+    restarts: usize,
+    window: Duration,
+) -> serde_json::Value {
+    json!({
+        "host_id": host_id.as_ref(),
+        "provider_id": provider_id.as_ref(),
+        "exit_status": exit_status.as_ref(),
+        "restarts": restarts,
+        "window_ms": u64::try_from(window.as_millis()).unwrap_or(u64::MAX),
+    })
+}
+
 pub fn provider_health_check(
     host_id: impl AsRef<str>,
     provider_id: impl AsRef<str>,
@@ -217,6 +297,13 @@ pub fn provider_health_check(
     })
 }
 
+pub fn circuit_breaker(host_id: impl AsRef<str>, target: impl AsRef<str>) -> serde_json::Value {
+    json!({
+        "host_id": host_id.as_ref(),
+        "target": target.as_ref(),
+    })
+}
+
 pub fn config_set(config_name: impl AsRef<str>) -> serde_json::Value {
     json!({
         "config_name": config_name.as_ref(),
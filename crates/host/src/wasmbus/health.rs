@@ -0,0 +1,307 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{bail, Context as _};
+use serde::Deserialize;
+
+/// Annotation or config key of the interval between provider health checks, in milliseconds
+pub const INTERVAL_KEY: &str = "wasmcloud.dev/health-check-interval-ms";
+/// Annotation or config key of the delay before the first provider health check, in milliseconds
+pub const INITIAL_DELAY_KEY: &str = "wasmcloud.dev/health-check-initial-delay-ms";
+/// Annotation or config key of the timeout of a provider health check request, in milliseconds
+pub const TIMEOUT_KEY: &str = "wasmcloud.dev/health-check-timeout-ms";
+/// Annotation or config key of the number of consecutive failed health checks after which the
+/// unhealthy action is taken
+pub const FAILURE_THRESHOLD_KEY: &str = "wasmcloud.dev/health-check-failure-threshold";
+/// Annotation or config key of the [`UnhealthyAction`] taken for an unhealthy provider
+pub const UNHEALTHY_ACTION_KEY: &str = "wasmcloud.dev/health-check-unhealthy-action";
+
+/// Action taken once a provider failed its configured number of consecutive health checks
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnhealthyAction {
+    /// Only publish `health_check_failed` events
+    #[default]
+    None,
+    /// Restart the provider process
+    Restart,
+    /// Fail invocations targeting the provider immediately until it is healthy again
+    MarkUnavailable,
+}
+
+impl FromStr for UnhealthyAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "none" => Ok(Self::None),
+            "restart" => Ok(Self::Restart),
+            "mark-unavailable" => Ok(Self::MarkUnavailable),
+            _ => bail!(
+                "invalid unhealthy action `{s}`, expected one of `none`, `restart` or `mark-unavailable`"
+            ),
+        }
+    }
+}
+
+/// Health check settings of a provider
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    /// Interval between health checks
+    pub interval: Duration,
+    /// Delay before the first health check, allowing the provider to initialize
+    pub initial_delay: Duration,
+    /// Timeout of a health check request, the NATS client's request timeout if not set
+    pub timeout: Option<Duration>,
+    /// Number of consecutive failed health checks after which `unhealthy_action` is taken
+    pub failure_threshold: usize,
+    /// Action taken once the provider is considered unhealthy
+    pub unhealthy_action: UnhealthyAction,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(30),
+            initial_delay: Duration::from_secs(5),
+            timeout: None,
+            failure_threshold: 3,
+            unhealthy_action: UnhealthyAction::None,
+        }
+    }
+}
+
+impl Config {
+    /// Parses health check settings from the `annotations` of a provider, falling back to its
+    /// named `config` and finally the defaults
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a setting has an invalid value
+    pub fn new(
+        annotations: &BTreeMap<String, String>,
+        config: &HashMap<String, String>,
+    ) -> anyhow::Result<Self> {
+        fn parse<T: FromStr>(
+            annotations: &BTreeMap<String, String>,
+            config: &HashMap<String, String>,
+            key: &str,
+        ) -> anyhow::Result<Option<T>>
+        where
+            T::Err: Into<anyhow::Error>,
+        {
+            annotations
+                .get(key)
+                .or_else(|| config.get(key))
+                .map(|value| {
+                    value
+                        .parse()
+                        .map_err(Into::into)
+                        .with_context(|| format!("invalid value `{value}` for `{key}`"))
+                })
+                .transpose()
+        }
+
+        let defaults = Self::default();
+        let millis =
+            |key| parse::<u64>(annotations, config, key).map(|ms| ms.map(Duration::from_millis));
+        let interval = millis(INTERVAL_KEY)?.unwrap_or(defaults.interval);
+        if interval.is_zero() {
+            bail!("`{INTERVAL_KEY}` must be greater than zero");
+        }
+        let failure_threshold = parse(annotations, config, FAILURE_THRESHOLD_KEY)?
+            .unwrap_or(defaults.failure_threshold);
+        if failure_threshold == 0 {
+            bail!("`{FAILURE_THRESHOLD_KEY}` must be greater than zero");
+        }
+        Ok(Self {
+            interval,
+            initial_delay: millis(INITIAL_DELAY_KEY)?.unwrap_or(defaults.initial_delay),
+            timeout: millis(TIMEOUT_KEY)?.or(defaults.timeout),
+            failure_threshold,
+            unhealthy_action: parse(annotations, config, UNHEALTHY_ACTION_KEY)?
+                .unwrap_or(defaults.unhealthy_action),
+        })
+    }
+}
+
+/// Names of the lattice events [`Availability`] is derived from
+pub const AVAILABILITY_EVENTS: [&str; 5] = [
+    "provider_unavailable",
+    "provider_available",
+    "health_check_passed",
+    "provider_stopped",
+    "host_stopped",
+];
+
+/// Availability of providers across the lattice, derived from the events published by the hosts
+/// running them. A provider is unavailable as long as any host marked it unavailable, since
+/// invocations are routed to any of its instances
+#[derive(Debug, Default)]
+pub struct Availability {
+    /// IDs of the hosts on which a provider is marked unavailable, keyed by provider ID
+    unavailable: HashMap<String, HashSet<String>>,
+}
+
+#[derive(Deserialize)]
+struct ProviderEvent {
+    host_id: String,
+    provider_id: String,
+}
+
+impl Availability {
+    /// Returns whether `provider_id` is not marked unavailable on any host
+    pub fn is_available(&self, provider_id: &str) -> bool {
+        !self.unavailable.contains_key(provider_id)
+    }
+
+    /// Marks `provider_id` unavailable on `host_id`
+    pub fn mark_unavailable(&mut self, provider_id: &str, host_id: &str) {
+        self.unavailable
+            .entry(provider_id.to_string())
+            .or_default()
+            .insert(host_id.to_string());
+    }
+
+    /// Clears the mark of `provider_id` on `host_id`, returns whether it was marked
+    pub fn mark_available(&mut self, provider_id: &str, host_id: &str) -> bool {
+        let Some(hosts) = self.unavailable.get_mut(provider_id) else {
+            return false;
+        };
+        let marked = hosts.remove(host_id);
+        if hosts.is_empty() {
+            self.unavailable.remove(provider_id);
+        }
+        marked
+    }
+
+    /// Applies the lattice event `name` published by host `source` with `data`, ignoring events
+    /// not listed in [`AVAILABILITY_EVENTS`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `data` is not valid for the event
+    pub fn apply(
+        &mut self,
+        name: &str,
+        source: &str,
+        data: serde_json::Value,
+    ) -> anyhow::Result<()> {
+        match name {
+            "host_stopped" => {
+                self.unavailable.retain(|_, hosts| {
+                    hosts.remove(source);
+                    !hosts.is_empty()
+                });
+            }
+            "provider_unavailable"
+            | "provider_available"
+            | "health_check_passed"
+            | "provider_stopped" => {
+                let ProviderEvent {
+                    host_id,
+                    provider_id,
+                } = serde_json::from_value(data)
+                    .with_context(|| format!("invalid `{name}` event data"))?;
+                if name == "provider_unavailable" {
+                    self.mark_unavailable(&provider_id, &host_id);
+                } else {
+                    self.mark_available(&provider_id, &host_id);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+    use std::time::Duration;
+
+    use serde_json::json;
+
+    use super::{
+        Availability, Config, UnhealthyAction, FAILURE_THRESHOLD_KEY, INTERVAL_KEY, TIMEOUT_KEY,
+        UNHEALTHY_ACTION_KEY,
+    };
+
+    #[test]
+    fn test_availability() {
+        let mut availability = Availability::default();
+        let event = |host_id: &str| json!({ "host_id": host_id, "provider_id": "provider" });
+        assert!(availability.is_available("provider"));
+
+        // Marks of different hosts are tracked independently
+        availability
+            .apply("provider_unavailable", "host-a", event("host-a"))
+            .expect("failed to apply event");
+        availability
+            .apply("provider_unavailable", "host-b", event("host-b"))
+            .expect("failed to apply event");
+        assert!(!availability.is_available("provider"));
+        availability
+            .apply("health_check_passed", "host-a", event("host-a"))
+            .expect("failed to apply event");
+        assert!(!availability.is_available("provider"));
+        availability
+            .apply("host_stopped", "host-b", json!({ "labels": {} }))
+            .expect("failed to apply event");
+        assert!(availability.is_available("provider"));
+
+        availability
+            .apply("provider_unavailable", "host-a", event("host-a"))
+            .expect("failed to apply event");
+        availability
+            .apply("host_heartbeat", "host-a", json!({}))
+            .expect("unrelated events should be ignored");
+        assert!(!availability.is_available("provider"));
+        availability
+            .apply("provider_stopped", "host-a", event("host-a"))
+            .expect("failed to apply event");
+        assert!(availability.is_available("provider"));
+
+        assert!(availability
+            .apply("provider_unavailable", "host-a", json!({}))
+            .is_err());
+    }
+
+    #[test]
+    fn test_config() {
+        assert_eq!(
+            Config::new(&BTreeMap::default(), &HashMap::default()).expect("failed to parse"),
+            Config::default()
+        );
+
+        let annotations = BTreeMap::from([
+            (INTERVAL_KEY.to_string(), "1000".to_string()),
+            (UNHEALTHY_ACTION_KEY.to_string(), "restart".to_string()),
+        ]);
+        let config = HashMap::from([
+            (INTERVAL_KEY.to_string(), "2000".to_string()),
+            (TIMEOUT_KEY.to_string(), "500".to_string()),
+            (FAILURE_THRESHOLD_KEY.to_string(), "5".to_string()),
+        ]);
+        assert_eq!(
+            Config::new(&annotations, &config).expect("failed to parse"),
+            Config {
+                interval: Duration::from_secs(1),
+                initial_delay: Duration::from_secs(5),
+                timeout: Some(Duration::from_millis(500)),
+                failure_threshold: 5,
+                unhealthy_action: UnhealthyAction::Restart,
+            }
+        );
+
+        for (key, value) in [
+            (INTERVAL_KEY, "0"),
+            (INTERVAL_KEY, "soon"),
+            (FAILURE_THRESHOLD_KEY, "0"),
+            (UNHEALTHY_ACTION_KEY, "panic"),
+        ] {
+            let annotations = BTreeMap::from([(key.to_string(), value.to_string())]);
+            assert!(Config::new(&annotations, &HashMap::default()).is_err());
+        }
+    }
+}
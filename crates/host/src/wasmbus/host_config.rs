@@ -1,9 +1,13 @@
 use crate::OciConfig;
 
 use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 
+use anyhow::bail;
 use nkeys::KeyPair;
 use url::Url;
 use wasmcloud_core::{logging::Level as LogLevel, OtelConfig};
@@ -32,6 +36,9 @@ pub struct Host {
     pub rpc_key: Option<Arc<KeyPair>>,
     /// Whether to require TLS for RPC connection
     pub rpc_tls: bool,
+    /// Whether the host and its providers receive RPC responses on inboxes scoped to the lattice,
+    /// see [`wasmcloud_core::nats::rpc_inbox_prefix`]
+    pub rpc_lattice_inbox: bool,
     /// The lattice the host belongs to
     pub lattice: String,
     /// The domain to use for host Jetstream operations
@@ -46,10 +53,27 @@ pub struct Host {
     pub cluster_issuers: Option<Vec<String>>,
     /// The amount of time to wait for a provider to gracefully shut down before terminating it
     pub provider_shutdown_delay: Option<Duration>,
+    /// Whether to restart capability provider processes that exit without being stopped
+    pub provider_restart_policy: ProviderRestartPolicy,
+    /// Maximum number of times a provider may be restarted within `provider_restart_window`
+    /// before it is considered to be crash looping and is no longer restarted
+    pub provider_max_restarts: usize,
+    /// The window in which provider restarts are counted towards `provider_max_restarts`
+    pub provider_restart_window: Duration,
+    /// Whether to publish the output of capability providers on `wasmbus.logs.{lattice}.{provider_id}`
+    pub publish_provider_logs: bool,
     /// Configuration for downloading artifacts from OCI registries
     pub oci_opts: OciConfig,
     /// Whether to allow loading actor or provider components from the filesystem
     pub allow_file_load: bool,
+    /// Signed offline bundles to preload artifacts from, which are then resolved by OCI reference
+    /// without network access
+    pub bundles: Vec<PathBuf>,
+    /// The account public keys that offline bundles must be signed by
+    pub bundle_issuers: Vec<String>,
+    /// Whether to accept offline bundles signed by any key if `bundle_issuers` is empty. Bundles
+    /// are rejected in that case otherwise
+    pub allow_any_bundle_issuer: bool,
     /// Whether or not structured logging is enabled
     pub enable_structured_logging: bool,
     /// Log level to pass to capability providers to use. Should be parsed from a [`tracing::Level`]
@@ -60,6 +84,90 @@ pub struct Host {
     pub otel_config: OtelConfig,
     /// configuration for wasmCloud policy service
     pub policy_service_config: PolicyService,
+    /// Maximum wall-clock time a single component invocation may execute for, unlimited if not set
+    pub max_execution_time: Option<Duration>,
+    /// Maximum amount of fuel a single component invocation may consume, unlimited if not set
+    pub max_execution_fuel: Option<u64>,
+    /// Whether to serve `wasi:keyvalue` links targeting the built-in JetStream KV capability
+    pub builtin_keyvalue_enabled: bool,
+    /// Address to serve Prometheus metrics on at `/metrics`, disabled if not set
+    pub metrics_listen_address: Option<SocketAddr>,
+    /// Configuration of the circuit breakers guarding invocations of each lattice target
+    pub circuit_breaker: CircuitBreakerConfig,
+    /// Policy for retrying failed invocations of idempotent operations on lattice targets
+    pub retry_policy: RetryPolicy,
+}
+
+/// Configuration of the circuit breakers guarding invocations of each lattice target
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CircuitBreakerConfig {
+    /// Number of consecutive failed invocations of a target after which its circuit breaker opens
+    /// and invocations of it fail immediately. Circuit breakers are disabled if not set
+    pub failure_threshold: Option<u32>,
+    /// The amount of time an open circuit breaker rejects invocations for, before letting a single
+    /// trial invocation through to determine whether the target recovered
+    pub open_timeout: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: None,
+            open_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Policy for retrying failed invocations of idempotent operations on lattice targets
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Operations safe to retry, either interfaces of the form `<ns>:<pkg>/<interface>`, matching
+    /// all of their functions, or single functions of the form `<ns>:<pkg>/<interface>.<function>`
+    pub operations: Vec<String>,
+    /// Maximum number of attempts made to invoke a retried operation, including the first one
+    pub max_attempts: u32,
+    /// Backoff before the first retry, doubled for every following retry
+    pub initial_backoff: Duration,
+    /// Upper bound of the backoff between retries
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            operations: Vec::default(),
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(2),
+        }
+    }
+}
+
+/// Policy for restarting capability provider processes that exit without being stopped
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProviderRestartPolicy {
+    /// Never restart providers, they are removed from the host once they exit
+    Never,
+    /// Restart providers that exit with a non-zero status or are terminated by a signal
+    #[default]
+    OnFailure,
+    /// Restart providers regardless of how they exited
+    Always,
+}
+
+impl FromStr for ProviderRestartPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "never" => Ok(Self::Never),
+            "on-failure" => Ok(Self::OnFailure),
+            "always" => Ok(Self::Always),
+            _ => bail!(
+                "invalid provider restart policy `{s}`, expected one of `never`, `on-failure` or `always`"
+            ),
+        }
+    }
 }
 
 /// Configuration for wasmCloud policy service
@@ -67,6 +175,9 @@ pub struct Host {
 pub struct PolicyService {
     /// The topic to request policy decisions on
     pub policy_topic: Option<String>,
+    /// A JSON file containing policy rules evaluated by the host. Mutually exclusive with
+    /// `policy_topic`
+    pub policy_rules_file: Option<PathBuf>,
     /// An optional topic to receive updated policy decisions on
     pub policy_changes_topic: Option<String>,
     /// The timeout for policy requests
@@ -88,6 +199,7 @@ impl Default for Host {
             rpc_jwt: None,
             rpc_key: None,
             rpc_tls: false,
+            rpc_lattice_inbox: false,
             lattice: "default".to_string(),
             js_domain: None,
             labels: HashMap::default(),
@@ -95,13 +207,26 @@ impl Default for Host {
             cluster_key: None,
             cluster_issuers: None,
             provider_shutdown_delay: None,
+            provider_restart_policy: ProviderRestartPolicy::default(),
+            provider_max_restarts: 5,
+            provider_restart_window: Duration::from_secs(300),
+            publish_provider_logs: false,
             oci_opts: OciConfig::default(),
             allow_file_load: false,
+            bundles: Vec::default(),
+            bundle_issuers: Vec::default(),
+            allow_any_bundle_issuer: false,
             enable_structured_logging: false,
             log_level: LogLevel::Info,
             config_service_enabled: false,
             otel_config: OtelConfig::default(),
             policy_service_config: PolicyService::default(),
+            max_execution_time: None,
+            max_execution_fuel: None,
+            builtin_keyvalue_enabled: false,
+            metrics_listen_address: None,
+            circuit_breaker: CircuitBreakerConfig::default(),
+            retry_policy: RetryPolicy::default(),
         }
     }
 }
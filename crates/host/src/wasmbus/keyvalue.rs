@@ -0,0 +1,256 @@
+//! Built-in `wasi:keyvalue` capability backed by NATS JetStream KV
+
+use std::collections::HashMap;
+
+use anyhow::{bail, ensure, Context as _};
+use async_nats::jetstream::context::PublishErrorKind;
+use async_nats::jetstream::kv::{Operation, Store};
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::sync::RwLock;
+use tracing::{debug, instrument};
+use wasmcloud_runtime::capability::{KeyValueAtomic, KeyValueEventual};
+use wrpc_transport::IncomingInputStream;
+
+/// Link target selecting the built-in JetStream KV implementation of `wasi:keyvalue`
+pub const BUILTIN_KEYVALUE_TARGET: &str = "wasmcloud:builtin:keyvalue";
+
+/// Number of times a revision-checked update is attempted before giving up on concurrent writers
+const MAX_UPDATE_ATTEMPTS: usize = 10;
+
+/// [`KeyValueAtomic`] and [`KeyValueEventual`] implementation storing each `wasi:keyvalue`
+/// bucket in a JetStream KV bucket named `KEYVALUE_{lattice}_{bucket}`
+#[derive(Debug)]
+pub struct JetStreamKeyValue {
+    jetstream: async_nats::jetstream::Context,
+    lattice: String,
+    buckets: RwLock<HashMap<String, Store>>,
+}
+
+impl JetStreamKeyValue {
+    /// Construct a new [`JetStreamKeyValue`] storing buckets of `lattice` using `jetstream`
+    pub fn new(jetstream: async_nats::jetstream::Context, lattice: impl Into<String>) -> Self {
+        Self {
+            jetstream,
+            lattice: lattice.into(),
+            buckets: RwLock::default(),
+        }
+    }
+
+    /// Returns the JetStream KV store for `bucket`, creating it if `create` is set
+    async fn store(&self, bucket: &str, create: bool) -> anyhow::Result<Store> {
+        if let Some(store) = self.buckets.read().await.get(bucket) {
+            return Ok(store.clone());
+        }
+        let name = bucket_name(&self.lattice, bucket)?;
+        let store = match self.jetstream.get_key_value(&name).await {
+            Ok(store) => store,
+            Err(_) if create => {
+                debug!(bucket, name, "creating JetStream KV bucket");
+                self.jetstream
+                    .create_key_value(async_nats::jetstream::kv::Config {
+                        bucket: name.clone(),
+                        ..Default::default()
+                    })
+                    .await
+                    .with_context(|| format!("failed to create bucket `{name}`"))?
+            }
+            Err(_) => bail!("bucket not found"),
+        };
+        self.buckets
+            .write()
+            .await
+            .insert(bucket.to_string(), store.clone());
+        Ok(store)
+    }
+
+    /// Returns the current numeric value of `key` along with its revision, or `None` if the key
+    /// is not set. The revision of a deleted key is that of its delete marker
+    async fn load_number(store: &Store, key: &str) -> anyhow::Result<(Option<u64>, u64)> {
+        let Some(entry) = store.entry(key).await.context("failed to get entry")? else {
+            return Ok((None, 0));
+        };
+        if entry.operation != Operation::Put {
+            return Ok((None, entry.revision));
+        }
+        let value = std::str::from_utf8(&entry.value)
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .context("invalid entry type")?;
+        Ok((Some(value), entry.revision))
+    }
+
+    /// Sets `key` to `value` if the current revision of `key` is `revision`, returning `false` if
+    /// it was concurrently modified
+    async fn store_number(
+        &self,
+        store: &Store,
+        key: &str,
+        value: u64,
+        revision: u64,
+    ) -> anyhow::Result<bool> {
+        // `Store::update` reports revision mismatches as `UpdateErrorKind::Other`, like any other
+        // server error, so publish the revision-checked write directly to tell them apart. That
+        // skips the key validation of `Store::update`, so validate the key here instead
+        validate_key(key)?;
+        let subject = format!(
+            "{}{key}",
+            store.put_prefix.as_deref().unwrap_or(&store.prefix)
+        );
+        let mut headers = async_nats::HeaderMap::new();
+        headers.insert(
+            async_nats::header::NATS_EXPECTED_LAST_SUBJECT_SEQUENCE,
+            revision.to_string().as_str(),
+        );
+        let ack = self
+            .jetstream
+            .publish_with_headers(subject, headers, value.to_string().into())
+            .await
+            .context("failed to update entry")?;
+        match ack.await {
+            Ok(_) => Ok(true),
+            Err(err) if err.kind() == PublishErrorKind::WrongLastSequence => {
+                debug!(key, revision, "entry was concurrently modified");
+                Ok(false)
+            }
+            Err(err) => Err(anyhow::Error::new(err).context("failed to update entry")),
+        }
+    }
+}
+
+/// Returns the JetStream KV bucket name for `bucket` in `lattice`
+fn bucket_name(lattice: &str, bucket: &str) -> anyhow::Result<String> {
+    ensure!(!bucket.is_empty(), "bucket name must not be empty");
+    ensure!(
+        bucket
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'),
+        "bucket name `{bucket}` may only contain alphanumeric characters, `-` and `_`"
+    );
+    Ok(format!("KEYVALUE_{lattice}_{bucket}"))
+}
+
+/// Ensures `key` is a valid JetStream KV key, as `Store` operations require, so that it can be
+/// used in a subject
+fn validate_key(key: &str) -> anyhow::Result<()> {
+    ensure!(
+        !key.is_empty() && !key.starts_with('.') && !key.ends_with('.'),
+        "key cannot be empty or start/end with `.`"
+    );
+    ensure!(
+        key.chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '/' | '_' | '=' | '.')),
+        "key `{key}` may only contain alphanumeric characters, `-`, `/`, `_`, `=` and `.`"
+    );
+    Ok(())
+}
+
+#[async_trait]
+impl KeyValueAtomic for JetStreamKeyValue {
+    #[instrument(skip(self))]
+    async fn increment(&self, bucket: &str, key: String, delta: u64) -> anyhow::Result<u64> {
+        let store = self.store(bucket, true).await?;
+        for _ in 0..MAX_UPDATE_ATTEMPTS {
+            let (value, revision) = Self::load_number(&store, &key).await?;
+            let value = value.unwrap_or_default().wrapping_add(delta);
+            if self.store_number(&store, &key, value, revision).await? {
+                return Ok(value);
+            }
+        }
+        bail!("failed to increment `{key}`, it was concurrently modified")
+    }
+
+    #[instrument(skip(self))]
+    async fn compare_and_swap(
+        &self,
+        bucket: &str,
+        key: String,
+        old: u64,
+        new: u64,
+    ) -> anyhow::Result<bool> {
+        let store = self.store(bucket, false).await?;
+        for _ in 0..MAX_UPDATE_ATTEMPTS {
+            let (value, revision) = Self::load_number(&store, &key).await?;
+            if value.context("key not found")? != old {
+                return Ok(false);
+            }
+            if self.store_number(&store, &key, new, revision).await? {
+                return Ok(true);
+            }
+        }
+        bail!("failed to swap `{key}`, it was concurrently modified")
+    }
+}
+
+#[async_trait]
+impl KeyValueEventual for JetStreamKeyValue {
+    #[instrument(skip(self))]
+    async fn get(&self, bucket: &str, key: String) -> anyhow::Result<Option<IncomingInputStream>> {
+        let store = self.store(bucket, false).await?;
+        let value = store.get(key).await.context("failed to get entry")?;
+        Ok(value.map(|value| -> IncomingInputStream { Box::new(stream::iter([Ok(value)])) }))
+    }
+
+    #[instrument(skip(self, value))]
+    async fn set(
+        &self,
+        bucket: &str,
+        key: String,
+        mut value: Box<dyn AsyncRead + Sync + Send + Unpin>,
+    ) -> anyhow::Result<()> {
+        let mut buf = vec![];
+        value
+            .read_to_end(&mut buf)
+            .await
+            .context("failed to read value")?;
+        let store = self.store(bucket, true).await?;
+        store
+            .put(key, Bytes::from(buf))
+            .await
+            .context("failed to put entry")?;
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn delete(&self, bucket: &str, key: String) -> anyhow::Result<()> {
+        let store = self.store(bucket, false).await?;
+        store.delete(key).await.context("failed to delete entry")
+    }
+
+    #[instrument(skip(self))]
+    async fn exists(&self, bucket: &str, key: String) -> anyhow::Result<bool> {
+        let store = self.store(bucket, false).await?;
+        let value = store.get(key).await.context("failed to get entry")?;
+        Ok(value.is_some())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{bucket_name, validate_key};
+
+    #[test]
+    fn test_bucket_name() {
+        assert_eq!(
+            bucket_name("default", "my-bucket_1").expect("failed to map bucket name"),
+            "KEYVALUE_default_my-bucket_1"
+        );
+        assert!(bucket_name("default", "").is_err());
+        assert!(bucket_name("default", "my.bucket").is_err());
+        assert!(bucket_name("default", "data>").is_err());
+    }
+
+    #[test]
+    fn test_validate_key() {
+        validate_key("counter").expect("failed to validate key");
+        validate_key("a/b.c_d-e=f").expect("failed to validate key");
+        assert!(validate_key("").is_err());
+        assert!(validate_key(".counter").is_err());
+        assert!(validate_key("counter.").is_err());
+        assert!(validate_key("counter*").is_err());
+        assert!(validate_key("counter>").is_err());
+        assert!(validate_key("my counter").is_err());
+    }
+}
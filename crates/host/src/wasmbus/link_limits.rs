@@ -0,0 +1,308 @@
+use core::fmt;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use anyhow::ensure;
+use http_body::{Body, Frame, SizeHint};
+use wasmcloud_control_interface::{InterfaceLinkDefinition, LinkLimits};
+use wasmcloud_core::rate_limit::TokenBucket;
+use wasmcloud_core::LinkName;
+
+/// Error returned when an invocation over a link is rejected for exceeding the limits configured
+/// on the link. It can be retrieved from invocation errors using [`anyhow::Error::downcast_ref`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LinkLimitExceeded {
+    /// The link's rate limit is exhausted
+    Rate,
+    /// The link's maximum number of in-flight invocations is reached
+    InFlight,
+}
+
+impl LinkLimitExceeded {
+    /// Returns the reason recorded in metrics for rejecting the invocation
+    pub(crate) fn reason(self) -> &'static str {
+        match self {
+            Self::Rate => "rate",
+            Self::InFlight => "in_flight",
+        }
+    }
+}
+
+impl fmt::Display for LinkLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rate => write!(f, "invocation exceeded the rate limit of the link"),
+            Self::InFlight => write!(
+                f,
+                "invocation exceeded the maximum number of in-flight invocations of the link"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LinkLimitExceeded {}
+
+/// Enforces the [`LinkLimits`] of a single link
+#[derive(Debug)]
+pub(crate) struct LinkLimiter {
+    limits: LinkLimits,
+    bucket: Option<Mutex<TokenBucket>>,
+    in_flight: Arc<AtomicU32>,
+}
+
+/// Marks an invocation over a link as in flight until dropped
+#[derive(Debug, Default)]
+pub(crate) struct LinkPermit(Option<Arc<AtomicU32>>);
+
+impl Drop for LinkPermit {
+    fn drop(&mut self) {
+        if let Some(in_flight) = self.0.take() {
+            in_flight.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// A response body which holds a [`LinkPermit`] until it is streamed to the end or dropped, so
+/// that a streamed response counts as in flight for as long as it is being received
+pub(crate) struct PermitBody<B> {
+    body: B,
+    permit: Option<LinkPermit>,
+}
+
+impl<B> PermitBody<B> {
+    pub(crate) fn new(body: B, permit: LinkPermit) -> Self {
+        Self {
+            body,
+            permit: Some(permit),
+        }
+    }
+}
+
+impl<B: Body + Unpin> Body for PermitBody<B> {
+    type Data = B::Data;
+    type Error = B::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let frame = Pin::new(&mut self.body).poll_frame(cx);
+        if let Poll::Ready(None | Some(Err(_))) = frame {
+            self.permit = None;
+        }
+        frame
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.body.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.body.size_hint()
+    }
+}
+
+impl LinkLimiter {
+    pub(crate) fn new(limits: LinkLimits) -> Self {
+        Self {
+            limits,
+            bucket: limits.rate_per_second.map(|rate| {
+                Mutex::new(TokenBucket::new(
+                    f64::from(limits.burst.unwrap_or(rate)),
+                    f64::from(rate),
+                    Instant::now(),
+                ))
+            }),
+            in_flight: Arc::default(),
+        }
+    }
+
+    /// Admits an invocation at time `now`, returning a permit to hold for its duration
+    pub(crate) fn acquire(&self, now: Instant) -> Result<LinkPermit, LinkLimitExceeded> {
+        let permit = if let Some(max) = self.limits.max_in_flight {
+            self.in_flight
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+                    (n < max).then_some(n + 1)
+                })
+                .map_err(|_| LinkLimitExceeded::InFlight)?;
+            LinkPermit(Some(Arc::clone(&self.in_flight)))
+        } else {
+            LinkPermit::default()
+        };
+        if let Some(bucket) = &self.bucket {
+            let mut bucket = bucket.lock().map_err(|_| LinkLimitExceeded::Rate)?;
+            if !bucket.try_acquire(now) {
+                return Err(LinkLimitExceeded::Rate);
+            }
+        }
+        Ok(permit)
+    }
+}
+
+/// Validates `limits`, which must admit invocations and may only set a burst along with a rate
+pub(crate) fn validate_link_limits(limits: &LinkLimits) -> anyhow::Result<()> {
+    ensure!(
+        limits.rate_per_second != Some(0),
+        "`rate_per_second` of link limits must be greater than 0"
+    );
+    ensure!(
+        limits.burst != Some(0),
+        "`burst` of link limits must be greater than 0"
+    );
+    ensure!(
+        limits.burst.is_none() || limits.rate_per_second.is_some(),
+        "`burst` of link limits requires `rate_per_second` to be set"
+    );
+    ensure!(
+        limits.max_in_flight != Some(0),
+        "`max_in_flight` of link limits must be greater than 0"
+    );
+    Ok(())
+}
+
+/// Limiters of the links of a component keyed by link name and WIT namespace and package
+pub(crate) type LinkLimiters = HashMap<(LinkName, String), Arc<LinkLimiter>>;
+
+/// Builds the limiters of the links of a component, reusing the limiters in `previous` of links
+/// whose limits did not change, so that updating unrelated links does not reset their state
+pub(crate) fn link_limiters(
+    links: &[InterfaceLinkDefinition],
+    previous: &LinkLimiters,
+) -> LinkLimiters {
+    links
+        .iter()
+        .filter_map(|link| {
+            let limits = link.limits?;
+            let key = (
+                link.name.clone(),
+                format!("{}:{}", link.wit_namespace, link.wit_package),
+            );
+            let limiter = match previous.get(&key) {
+                Some(limiter) if limiter.limits == limits => Arc::clone(limiter),
+                _ => Arc::new(LinkLimiter::new(limits)),
+            };
+            Some((key, limiter))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use bytes::Bytes;
+    use http_body_util::{BodyExt as _, Full};
+    use wasmcloud_control_interface::LinkLimits;
+
+    use super::{validate_link_limits, LinkLimitExceeded, LinkLimiter, PermitBody};
+
+    #[test]
+    fn rate_limit() {
+        let limiter = LinkLimiter::new(LinkLimits {
+            rate_per_second: Some(2),
+            burst: Some(3),
+            max_in_flight: None,
+        });
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert!(limiter.acquire(now).is_ok());
+        }
+        assert_eq!(limiter.acquire(now).err(), Some(LinkLimitExceeded::Rate));
+        assert!(limiter.acquire(now + Duration::from_millis(500)).is_ok());
+        assert_eq!(
+            limiter.acquire(now + Duration::from_millis(500)).err(),
+            Some(LinkLimitExceeded::Rate)
+        );
+    }
+
+    #[test]
+    fn max_in_flight() {
+        let limiter = LinkLimiter::new(LinkLimits {
+            rate_per_second: None,
+            burst: None,
+            max_in_flight: Some(1),
+        });
+        let now = Instant::now();
+        let permit = limiter
+            .acquire(now)
+            .expect("first invocation should be admitted");
+        assert_eq!(
+            limiter.acquire(now).err(),
+            Some(LinkLimitExceeded::InFlight)
+        );
+        drop(permit);
+        assert!(limiter.acquire(now).is_ok());
+    }
+
+    #[tokio::test]
+    async fn permit_body() {
+        let limiter = LinkLimiter::new(LinkLimits {
+            rate_per_second: None,
+            burst: None,
+            max_in_flight: Some(1),
+        });
+        let now = Instant::now();
+
+        // The permit is held until the body is streamed to the end
+        let permit = limiter
+            .acquire(now)
+            .expect("first invocation should be admitted");
+        let mut body = PermitBody::new(Full::new(Bytes::from("body")), permit);
+        assert_eq!(
+            limiter.acquire(now).err(),
+            Some(LinkLimitExceeded::InFlight)
+        );
+        while body.frame().await.is_some() {}
+        assert!(limiter.acquire(now).is_ok());
+
+        // The permit is released if the body is dropped before it is done
+        let permit = limiter.acquire(now).expect("invocation should be admitted");
+        let body = PermitBody::new(Full::new(Bytes::from("body")), permit);
+        assert_eq!(
+            limiter.acquire(now).err(),
+            Some(LinkLimitExceeded::InFlight)
+        );
+        drop(body);
+        assert!(limiter.acquire(now).is_ok());
+    }
+
+    #[test]
+    fn validate() {
+        for limits in [
+            LinkLimits::default(),
+            LinkLimits {
+                rate_per_second: Some(1),
+                burst: Some(1),
+                max_in_flight: Some(1),
+            },
+        ] {
+            assert!(validate_link_limits(&limits).is_ok(), "{limits:?}");
+        }
+        for limits in [
+            LinkLimits {
+                rate_per_second: Some(0),
+                ..LinkLimits::default()
+            },
+            LinkLimits {
+                rate_per_second: Some(1),
+                burst: Some(0),
+                ..LinkLimits::default()
+            },
+            LinkLimits {
+                burst: Some(1),
+                ..LinkLimits::default()
+            },
+            LinkLimits {
+                max_in_flight: Some(0),
+                ..LinkLimits::default()
+            },
+        ] {
+            assert!(validate_link_limits(&limits).is_err(), "{limits:?}");
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::io;
+use std::sync::Mutex;
+
+use bytes::Bytes;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncRead, BufReader};
+use tracing::{info, instrument, warn};
+use wasmcloud_control_interface::ProviderLogLine;
+
+/// Number of lines of output retained per provider
+const HISTORY_LINES: usize = 1000;
+/// Maximum length of a line of provider output in bytes, longer lines are split
+const MAX_LINE_LEN: usize = 64 * 1024;
+/// Number of consecutive errors reading provider output after which capturing is stopped
+const MAX_READ_ERRORS: usize = 16;
+
+/// Output stream of a provider process
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stream {
+    /// Standard output
+    Stdout,
+    /// Standard error
+    Stderr,
+}
+
+impl fmt::Display for Stream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stdout => write!(f, "stdout"),
+            Self::Stderr => write!(f, "stderr"),
+        }
+    }
+}
+
+/// Most recent output of a provider, retained across restarts of its process
+#[derive(Debug, Default)]
+pub(crate) struct History(Mutex<VecDeque<ProviderLogLine>>);
+
+impl History {
+    fn push(&self, line: ProviderLogLine) {
+        if let Ok(mut lines) = self.0.lock() {
+            if lines.len() == HISTORY_LINES {
+                lines.pop_front();
+            }
+            lines.push_back(line);
+        }
+    }
+
+    /// Returns the `tail` most recent lines in the order they were written, or all retained lines
+    /// if `tail` is not set
+    pub(crate) fn tail(&self, tail: Option<usize>) -> Vec<ProviderLogLine> {
+        let Ok(lines) = self.0.lock() else {
+            return Vec::default();
+        };
+        let skip = tail.map_or(0, |tail| lines.len().saturating_sub(tail));
+        lines.iter().skip(skip).cloned().collect()
+    }
+}
+
+/// Returns `line` without the trailing `\n` or `\r\n`
+fn trim_newline(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+/// Reads bytes from `output` into `buf` until a newline is read or `buf` holds [`MAX_LINE_LEN`]
+/// bytes, returning the number of bytes read. Unlike [`AsyncBufReadExt::read_until`], memory use
+/// is bounded if the output never contains a newline
+async fn read_line(
+    output: &mut (impl AsyncBufRead + Unpin),
+    buf: &mut Vec<u8>,
+) -> io::Result<usize> {
+    let mut read = 0;
+    loop {
+        let available = output.fill_buf().await?;
+        if available.is_empty() {
+            return Ok(read);
+        }
+        let available = &available[..available.len().min(MAX_LINE_LEN - buf.len())];
+        let (n, done) = match available.iter().position(|b| *b == b'\n') {
+            Some(i) => (i + 1, true),
+            None => (available.len(), false),
+        };
+        buf.extend_from_slice(&available[..n]);
+        output.consume(n);
+        read += n;
+        if done || buf.len() >= MAX_LINE_LEN {
+            return Ok(read);
+        }
+    }
+}
+
+/// Reads `output` of provider `provider_id` line by line until it is closed. Every line is
+/// forwarded to the tracing subscriber, retained in `history` and, if `nats` is set, published on
+/// `subject`. Lines longer than [`MAX_LINE_LEN`] are split, invalid UTF-8 is replaced and reading
+/// continues after errors, so that the provider never blocks on a full pipe
+#[instrument(level = "debug", skip(output, history, nats))]
+pub(crate) async fn capture(
+    output: impl AsyncRead + Unpin,
+    stream: Stream,
+    host_id: String,
+    provider_id: String,
+    history: &History,
+    nats: Option<(async_nats::Client, String)>,
+) {
+    let mut output = BufReader::new(output);
+    let mut buf = Vec::new();
+    let mut errors = 0;
+    loop {
+        buf.clear();
+        match read_line(&mut output, &mut buf).await {
+            Ok(0) => return,
+            Ok(_) => errors = 0,
+            Err(e) if errors < MAX_READ_ERRORS => {
+                warn!(?e, "failed to read provider output");
+                errors += 1;
+                continue;
+            }
+            Err(e) => {
+                warn!(?e, "failed to read provider output, no longer capturing it");
+                return;
+            }
+        }
+        let line = String::from_utf8_lossy(trim_newline(&buf)).into_owned();
+        match stream {
+            Stream::Stdout => info!(provider_id, %stream, "{line}"),
+            Stream::Stderr => warn!(provider_id, %stream, "{line}"),
+        }
+        let line = ProviderLogLine {
+            host_id: host_id.clone(),
+            provider_id: provider_id.clone(),
+            stream: stream.to_string(),
+            timestamp: OffsetDateTime::now_utc()
+                .format(&Rfc3339)
+                .unwrap_or_default(),
+            line,
+        };
+        if let Some((nats, subject)) = &nats {
+            match serde_json::to_vec(&line) {
+                Ok(payload) => {
+                    if let Err(e) = nats.publish(subject.clone(), Bytes::from(payload)).await {
+                        warn!(?e, "failed to publish provider output");
+                    }
+                }
+                Err(e) => warn!(?e, "failed to encode provider output"),
+            }
+        }
+        history.push(line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wasmcloud_control_interface::ProviderLogLine;
+
+    use super::{capture, History, Stream, HISTORY_LINES, MAX_LINE_LEN};
+
+    fn line(n: usize) -> ProviderLogLine {
+        ProviderLogLine {
+            host_id: "host".to_string(),
+            provider_id: "provider".to_string(),
+            stream: Stream::Stdout.to_string(),
+            timestamp: String::default(),
+            line: n.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_capture() {
+        let history = History::default();
+        capture(
+            &b"first\r\ninvalid \xff utf-8\n\nlast"[..],
+            Stream::Stderr,
+            "host".to_string(),
+            "provider".to_string(),
+            &history,
+            None,
+        )
+        .await;
+        let lines = history
+            .tail(None)
+            .into_iter()
+            .map(|line| line.line)
+            .collect::<Vec<_>>();
+        assert_eq!(lines, ["first", "invalid \u{fffd} utf-8", "", "last"]);
+    }
+
+    #[tokio::test]
+    async fn test_capture_long_line() {
+        let history = History::default();
+        let mut output = vec![b'a'; MAX_LINE_LEN * 2 + 1];
+        output.extend_from_slice(b"\nnext\n");
+        capture(
+            output.as_slice(),
+            Stream::Stdout,
+            "host".to_string(),
+            "provider".to_string(),
+            &history,
+            None,
+        )
+        .await;
+        let lines = history
+            .tail(None)
+            .into_iter()
+            .map(|line| line.line)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [
+                "a".repeat(MAX_LINE_LEN),
+                "a".repeat(MAX_LINE_LEN),
+                "a".to_string(),
+                "next".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_history() {
+        let history = History::default();
+        assert!(history.tail(None).is_empty());
+        for n in 0..HISTORY_LINES + 10 {
+            history.push(line(n));
+        }
+        let lines = history.tail(None);
+        assert_eq!(lines.len(), HISTORY_LINES);
+        assert_eq!(lines.first(), Some(&line(10)));
+        assert_eq!(
+            history.tail(Some(2)),
+            [line(HISTORY_LINES + 8), line(HISTORY_LINES + 9)]
+        );
+        assert_eq!(history.tail(Some(0)), []);
+        assert_eq!(history.tail(Some(usize::MAX)).len(), HISTORY_LINES);
+    }
+}
@@ -1,10 +1,11 @@
 use std::collections::hash_map::{self, Entry};
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::env;
 use std::env::consts::{ARCH, FAMILY, OS};
 use std::future::Future;
 use std::num::NonZeroUsize;
 use std::ops::{Deref, RangeInclusive};
+use std::path::Path;
 use std::pin::Pin;
 use std::process::Stdio;
 use std::str::FromStr;
@@ -18,14 +19,16 @@ use async_trait::async_trait;
 use base64::engine::general_purpose::STANDARD;
 use base64::Engine;
 use bytes::{Buf, BufMut, Bytes, BytesMut};
-use cloudevents::{EventBuilder, EventBuilderV10};
-use futures::future::Either;
+use cloudevents::{AttributesReader, EventBuilder, EventBuilderV10};
+use futures::future::{try_join_all, Either};
 use futures::stream::{select_all, AbortHandle, Abortable, SelectAll};
 use futures::{join, stream, try_join, Stream, StreamExt, TryFutureExt, TryStreamExt};
+use http_body_util::BodyExt as _;
 use nkeys::{KeyPair, KeyPairType};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use tokio::io::{stderr, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpListener;
 use tokio::sync::{watch, RwLock};
 use tokio::task::JoinHandle;
 use tokio::time::{interval_at, Instant};
@@ -35,11 +38,12 @@ use tracing::{debug, error, info, instrument, trace, warn};
 use uuid::Uuid;
 use wascap::{jwt, prelude::ClaimsBuilder};
 use wasmcloud_control_interface::{
-    ActorAuctionAck, ActorAuctionRequest, ActorDescription, CtlResponse,
+    ActorAuctionAck, ActorAuctionRequest, ActorDescription, ComponentLimits, CtlResponse,
     DeleteInterfaceLinkDefinitionRequest, GetClaimsResponse, HostInventory, HostLabel,
-    InterfaceLinkDefinition, ProviderAuctionAck, ProviderAuctionRequest, ProviderDescription,
-    RegistryCredential, ScaleActorCommand, StartProviderCommand, StopHostCommand,
-    StopProviderCommand, UpdateActorCommand, WitInterface,
+    HotUpdateOptions, InterfaceLinkDefinition, ProviderAuctionAck, ProviderAuctionRequest,
+    ProviderDescription, ProviderLogLine, ProviderLogsCommand, RegistryCredential,
+    ScaleActorCommand, StartProviderCommand, StopHostCommand, StopProviderCommand,
+    UpdateActorCommand, WitInterface,
 };
 use wasmcloud_core::{HealthCheckResponse, HostData, LatticeTarget, LinkName, OtelConfig};
 use wasmcloud_runtime::capability::logging::logging;
@@ -48,7 +52,7 @@ use wasmcloud_runtime::capability::{
     KeyValueAtomic, KeyValueEventual, LatticeInterfaceTarget, Logging, Messaging, OutgoingHttp,
     TargetEntity,
 };
-use wasmcloud_runtime::Runtime;
+use wasmcloud_runtime::{ExecutionLimitExceeded, ResourceLimits, Runtime};
 use wasmcloud_tracing::context::TraceContextInjector;
 use wasmcloud_tracing::{global, KeyValue};
 use wasmtime_wasi_http::body::HyperIncomingBody;
@@ -58,20 +62,44 @@ use wrpc_transport::{
 use wrpc_transport::{AsyncSubscription, AsyncValue, Encode, Receive, Subscribe};
 use wrpc_types::DynamicFunction;
 
+use crate::metrics::ComponentMetricsGuard;
+use crate::prometheus;
 use crate::{
-    fetch_actor, HostMetrics, OciConfig, PolicyHostInfo, PolicyManager, PolicyResponse,
-    RegistryAuth, RegistryConfig, RegistryType,
+    fetch_actor, BundleCache, HostMetrics, OciConfig, PolicyHostInfo, PolicyManager,
+    PolicyResponse, RegistryAuth, RegistryConfig, RegistryType,
 };
 
 /// wasmCloud host configuration
 pub mod host_config;
 pub use host_config::Host as HostConfig;
+use host_config::{ProviderRestartPolicy, RetryPolicy};
 
 pub mod config;
 use config::{BundleGenerator, ConfigBundle};
 
+pub mod keyvalue;
+use keyvalue::{JetStreamKeyValue, BUILTIN_KEYVALUE_TARGET};
+
 mod event;
 
+mod health;
+use health::UnhealthyAction;
+
+mod logs;
+
+mod link_limits;
+pub use link_limits::LinkLimitExceeded;
+use link_limits::{link_limiters, validate_link_limits, LinkLimiters, LinkPermit, PermitBody};
+
+mod config_schema;
+
+mod calls;
+use calls::{serve_invocations, Calls};
+
+mod resilience;
+pub use resilience::CircuitBreakerOpen;
+use resilience::{retry_params, CircuitBreakers, Transition};
+
 #[derive(Debug)]
 struct Queue {
     all_streams: SelectAll<async_nats::Subscriber>,
@@ -198,6 +226,185 @@ struct Handler {
     /// result types of the function, which is required for the wRPC protocol to set up proper
     /// subscriptions for the return types.
     polyfilled_imports: HashMap<String, HashMap<String, Arc<[wrpc_types::Type]>>>,
+    /// Built-in `wasi:keyvalue` implementation, used for links targeting [`BUILTIN_KEYVALUE_TARGET`]
+    builtin_keyvalue: Option<Arc<JetStreamKeyValue>>,
+    /// Availability of providers across the lattice, derived from provider health events
+    unavailable_providers: Arc<RwLock<health::Availability>>,
+    /// Limiters of the links of the component with limits, keyed by link name and WIT namespace
+    /// and package
+    link_limiters: Arc<RwLock<LinkLimiters>>,
+    /// Host metrics, used to record invocations rejected for exceeding link limits
+    metrics: Arc<HostMetrics>,
+    /// Circuit breakers of the lattice targets invoked by components on the host
+    circuit_breakers: Arc<CircuitBreakers>,
+    /// Policy for retrying failed invocations of idempotent operations
+    retry_policy: Arc<RetryPolicy>,
+    /// NATS client and event builder used to publish circuit breaker state changes
+    ctl_nats: async_nats::Client,
+    event_builder: EventBuilderV10,
+}
+
+impl Handler {
+    /// Returns an error if `target` is a provider marked as unavailable, so invocations fail fast
+    /// instead of timing out
+    async fn ensure_available(&self, target: &str) -> anyhow::Result<()> {
+        ensure!(
+            self.unavailable_providers.read().await.is_available(target),
+            "target `{target}` is unavailable, since it failed its health checks"
+        );
+        Ok(())
+    }
+
+    /// Admits an invocation of `target` according to the limits of the link it was resolved with,
+    /// returning a permit to hold until the invocation completes.
+    ///
+    /// Rejected invocations fail with [`LinkLimitExceeded`] and are recorded in metrics.
+    async fn admit(&self, target: &LatticeInterfaceTarget) -> anyhow::Result<LinkPermit> {
+        let (namespace, package, _, _) = target.interface.as_parts();
+        let Some(limiter) = self
+            .link_limiters
+            .read()
+            .await
+            .get(&(target.link_name.clone(), format!("{namespace}:{package}")))
+            .cloned()
+        else {
+            return Ok(LinkPermit::default());
+        };
+        limiter.acquire(std::time::Instant::now()).map_err(|err| {
+            self.metrics.record_link_rejection(&[
+                KeyValue::new("lattice", self.metrics.lattice_id.clone()),
+                KeyValue::new("host", self.metrics.host_id.clone()),
+                KeyValue::new("source", self.component_id.clone()),
+                KeyValue::new("target", target.id.clone()),
+                KeyValue::new("link_name", target.link_name.clone()),
+                KeyValue::new("reason", err.reason()),
+            ]);
+            warn!(%target, %err, "rejecting invocation");
+            anyhow::Error::new(err)
+        })
+    }
+
+    /// Publishes the lattice event of a circuit breaker `transition` of `target`
+    async fn publish_breaker_transition(&self, target: &str, transition: Transition) {
+        info!(target, ?transition, "circuit breaker state changed");
+        if let Err(err) = event::publish(
+            &self.event_builder,
+            &self.ctl_nats,
+            &self.lattice,
+            transition.event_name(),
+            event::circuit_breaker(&self.metrics.host_id, target),
+        )
+        .await
+        {
+            warn!(?err, "failed to publish circuit breaker event");
+        }
+    }
+
+    /// Awaits the invocation `invoke` of `target`, guarded by the circuit breaker of `target`
+    async fn guarded<T>(
+        &self,
+        target: &str,
+        invoke: impl Future<Output = anyhow::Result<T>>,
+    ) -> anyhow::Result<T> {
+        if let Some(transition) = self
+            .circuit_breakers
+            .admit(target, std::time::Instant::now())?
+        {
+            self.publish_breaker_transition(target, transition).await;
+        }
+        let res = invoke.await;
+        if let Some(transition) =
+            self.circuit_breakers
+                .record(target, res.is_ok(), std::time::Instant::now())
+        {
+            self.publish_breaker_transition(target, transition).await;
+        }
+        res
+    }
+
+    /// Invokes `function` of `instance` on `target` using `invoke`, guarded by the circuit breaker
+    /// of `target` and retried according to the retry policy. Only failures to invoke the target
+    /// are retried, errors returned by the function itself are part of `T`
+    async fn guarded_retry<T, Fut>(
+        &self,
+        target: &str,
+        instance: &str,
+        function: &str,
+        mut invoke: impl FnMut() -> Fut,
+    ) -> anyhow::Result<T>
+    where
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        let attempts = self.retry_policy.attempts(instance, function);
+        let mut attempt = 1;
+        loop {
+            match self.guarded(target, invoke()).await {
+                Err(err)
+                    if attempt < attempts && err.downcast_ref::<CircuitBreakerOpen>().is_none() =>
+                {
+                    let backoff = self.retry_policy.backoff(attempt);
+                    warn!(?err, attempt, ?backoff, "invocation failed, retrying");
+                    tokio::time::sleep(backoff).await;
+                    attempt += 1;
+                }
+                res => return res,
+            }
+        }
+    }
+
+    /// Invokes `name` of `instance` on `target` once, guarded by the circuit breaker of `target`
+    async fn invoke_dynamic(
+        &self,
+        target: &str,
+        instance: &str,
+        name: &str,
+        params: Vec<wrpc_transport::Value>,
+        results: &Arc<[wrpc_types::Type]>,
+    ) -> anyhow::Result<Vec<wrpc_transport::Value>> {
+        let injector = TraceContextInjector::default_with_span();
+        let mut headers = injector_to_headers(&injector);
+        headers.insert("source-id", self.component_id.as_str());
+        self.guarded(target, async {
+            let (results, tx) = wasmcloud_core::wrpc::Client::new(
+                self.nats.clone(),
+                &self.lattice,
+                target,
+                headers,
+            )
+            .invoke_dynamic(instance, name, DynamicTuple(params), results)
+            .await?;
+            tx.await.context("failed to transmit parameters")?;
+            anyhow::Ok(results)
+        })
+        .await
+    }
+
+    /// Identifies the wRPC target of component interface invocation and admits the invocation,
+    /// failing if the target is unknown or unavailable or the limits of the link are exceeded
+    async fn wrpc_target(
+        &self,
+        interface: &CallTargetInterface,
+    ) -> anyhow::Result<(LatticeInterfaceTarget, LinkPermit)> {
+        let target = self
+            .identify_wrpc_target(interface)
+            .await
+            .context("unknown target")?;
+        self.ensure_available(&target.id).await?;
+        let permit = self.admit(&target).await?;
+        Ok((target, permit))
+    }
+
+    /// Returns the built-in `wasi:keyvalue` implementation if `target` refers to it
+    fn builtin_keyvalue(&self, target: &str) -> anyhow::Result<Option<&JetStreamKeyValue>> {
+        if target != BUILTIN_KEYVALUE_TARGET {
+            return Ok(None);
+        }
+        let kv = self
+            .builtin_keyvalue
+            .as_deref()
+            .context("built-in keyvalue capability is not enabled on this host")?;
+        Ok(Some(kv))
+    }
 }
 
 #[async_trait]
@@ -206,24 +413,33 @@ impl Blobstore for Handler {
     async fn create_container(&self, name: &str) -> anyhow::Result<()> {
         use wrpc_interface_blobstore::Blobstore;
 
-        let LatticeInterfaceTarget { id, .. } = self
-            .identify_wrpc_target(&CallTargetInterface::from_parts((
+        let (LatticeInterfaceTarget { id, .. }, _permit) = self
+            .wrpc_target(&CallTargetInterface::from_parts((
                 "wasi",
                 "blobstore",
                 "blobstore",
                 None,
             )))
-            .await
-            .context("unknown target")?;
+            .await?;
         let wrpc =
             wrpc_transport_nats::Client::new(self.nats.clone(), format!("{}.{id}", self.lattice));
-        let (res, tx) = wrpc
-            .invoke_create_container(name.to_string())
-            .await
-            .context("failed to invoke `wrpc:blobstore/blobstore.create-container`")?;
+        let res = self
+            .guarded_retry(
+                &id,
+                "wasi:blobstore/blobstore",
+                "create-container",
+                || async {
+                    let (res, tx) = wrpc
+                        .invoke_create_container(name.to_string())
+                        .await
+                        .context("failed to invoke `wrpc:blobstore/blobstore.create-container`")?;
+                    tx.await.context("failed to transmit parameters")?;
+                    anyhow::Ok(res)
+                },
+            )
+            .await?;
         // TODO: return a result directly
         res.map_err(|err| anyhow!(err).context("function failed"))?;
-        tx.await.context("failed to transmit parameters")?;
         Ok(())
     }
 
@@ -231,24 +447,33 @@ impl Blobstore for Handler {
     async fn container_exists(&self, name: &str) -> anyhow::Result<bool> {
         use wrpc_interface_blobstore::Blobstore;
 
-        let LatticeInterfaceTarget { id, .. } = self
-            .identify_wrpc_target(&CallTargetInterface::from_parts((
+        let (LatticeInterfaceTarget { id, .. }, _permit) = self
+            .wrpc_target(&CallTargetInterface::from_parts((
                 "wasi",
                 "blobstore",
                 "blobstore",
                 None,
             )))
-            .await
-            .context("unknown target")?;
+            .await?;
         let wrpc =
             wrpc_transport_nats::Client::new(self.nats.clone(), format!("{}.{id}", self.lattice));
-        let (res, tx) = wrpc
-            .invoke_container_exists(name.to_string())
-            .await
-            .context("failed to invoke `wrpc:blobstore/blobstore.container-exists`")?;
+        let res = self
+            .guarded_retry(
+                &id,
+                "wasi:blobstore/blobstore",
+                "container-exists",
+                || async {
+                    let (res, tx) = wrpc
+                        .invoke_container_exists(name.to_string())
+                        .await
+                        .context("failed to invoke `wrpc:blobstore/blobstore.container-exists`")?;
+                    tx.await.context("failed to transmit parameters")?;
+                    anyhow::Ok(res)
+                },
+            )
+            .await?;
         // TODO: return a result directly
         let exists = res.map_err(|err| anyhow!(err).context("function failed"))?;
-        tx.await.context("failed to transmit parameters")?;
         Ok(exists)
     }
 
@@ -256,24 +481,33 @@ impl Blobstore for Handler {
     async fn delete_container(&self, name: &str) -> anyhow::Result<()> {
         use wrpc_interface_blobstore::Blobstore;
 
-        let LatticeInterfaceTarget { id, .. } = self
-            .identify_wrpc_target(&CallTargetInterface::from_parts((
+        let (LatticeInterfaceTarget { id, .. }, _permit) = self
+            .wrpc_target(&CallTargetInterface::from_parts((
                 "wasi",
                 "blobstore",
                 "blobstore",
                 None,
             )))
-            .await
-            .context("unknown target")?;
+            .await?;
         let wrpc =
             wrpc_transport_nats::Client::new(self.nats.clone(), format!("{}.{id}", self.lattice));
-        let (res, tx) = wrpc
-            .invoke_delete_container(name.to_string())
-            .await
-            .context("failed to invoke `wrpc:blobstore/blobstore.delete-container`")?;
+        let res = self
+            .guarded_retry(
+                &id,
+                "wasi:blobstore/blobstore",
+                "delete-container",
+                || async {
+                    let (res, tx) = wrpc
+                        .invoke_delete_container(name.to_string())
+                        .await
+                        .context("failed to invoke `wrpc:blobstore/blobstore.delete-container`")?;
+                    tx.await.context("failed to transmit parameters")?;
+                    anyhow::Ok(res)
+                },
+            )
+            .await?;
         // TODO: return a result directly
         res.map_err(|err| anyhow!(err).context("function failed"))?;
-        tx.await.context("failed to transmit parameters")?;
         Ok(())
     }
 
@@ -284,25 +518,36 @@ impl Blobstore for Handler {
     ) -> anyhow::Result<blobstore::container::ContainerMetadata> {
         use wrpc_interface_blobstore::{Blobstore, ContainerMetadata};
 
-        let LatticeInterfaceTarget { id, .. } = self
-            .identify_wrpc_target(&CallTargetInterface::from_parts((
+        let (LatticeInterfaceTarget { id, .. }, _permit) = self
+            .wrpc_target(&CallTargetInterface::from_parts((
                 "wasi",
                 "blobstore",
                 "blobstore",
                 None,
             )))
-            .await
-            .context("unknown target")?;
+            .await?;
         let wrpc =
             wrpc_transport_nats::Client::new(self.nats.clone(), format!("{}.{id}", self.lattice));
-        let (res, tx) = wrpc
-            .invoke_get_container_info(name.to_string())
-            .await
-            .context("failed to invoke `wrpc:blobstore/blobstore.get-container-info`")?;
+        let res = self
+            .guarded_retry(
+                &id,
+                "wasi:blobstore/blobstore",
+                "get-container-info",
+                || async {
+                    let (res, tx) = wrpc
+                        .invoke_get_container_info(name.to_string())
+                        .await
+                        .context(
+                            "failed to invoke `wrpc:blobstore/blobstore.get-container-info`",
+                        )?;
+                    tx.await.context("failed to transmit parameters")?;
+                    anyhow::Ok(res)
+                },
+            )
+            .await?;
         // TODO: return a result directly
         let ContainerMetadata { name, created_at } =
             res.map_err(|err| anyhow!(err).context("function failed"))?;
-        tx.await.context("failed to transmit parameters")?;
         Ok(blobstore::container::ContainerMetadata { name, created_at })
     }
 
@@ -315,31 +560,42 @@ impl Blobstore for Handler {
     ) -> anyhow::Result<IncomingInputStream> {
         use wrpc_interface_blobstore::{Blobstore, ObjectId};
 
-        let LatticeInterfaceTarget { id, .. } = self
-            .identify_wrpc_target(&CallTargetInterface::from_parts((
+        let (LatticeInterfaceTarget { id, .. }, _permit) = self
+            .wrpc_target(&CallTargetInterface::from_parts((
                 "wasi",
                 "blobstore",
                 "blobstore",
                 None,
             )))
-            .await
-            .context("unknown target")?;
+            .await?;
         let wrpc =
             wrpc_transport_nats::Client::new(self.nats.clone(), format!("{}.{id}", self.lattice));
-        let (res, tx) = wrpc
-            .invoke_get_container_data(
-                ObjectId {
-                    container: container.to_string(),
-                    object: name,
+        let res = self
+            .guarded_retry(
+                &id,
+                "wasi:blobstore/blobstore",
+                "get-container-data",
+                || async {
+                    let (res, tx) = wrpc
+                        .invoke_get_container_data(
+                            ObjectId {
+                                container: container.to_string(),
+                                object: name.clone(),
+                            },
+                            *range.start(),
+                            *range.end(),
+                        )
+                        .await
+                        .context(
+                            "failed to invoke `wrpc:blobstore/blobstore.get-container-data`",
+                        )?;
+                    tx.await.context("failed to transmit parameters")?;
+                    anyhow::Ok(res)
                 },
-                *range.start(),
-                *range.end(),
             )
-            .await
-            .context("failed to invoke `wrpc:blobstore/blobstore.get-container-data`")?;
+            .await?;
         // TODO: return a result directly
         let data = res.map_err(|err| anyhow!(err).context("function failed"))?;
-        tx.await.context("failed to transmit parameters")?;
         Ok(data)
     }
 
@@ -347,27 +603,31 @@ impl Blobstore for Handler {
     async fn has_object(&self, container: &str, name: String) -> anyhow::Result<bool> {
         use wrpc_interface_blobstore::{Blobstore, ObjectId};
 
-        let LatticeInterfaceTarget { id, .. } = self
-            .identify_wrpc_target(&CallTargetInterface::from_parts((
+        let (LatticeInterfaceTarget { id, .. }, _permit) = self
+            .wrpc_target(&CallTargetInterface::from_parts((
                 "wasi",
                 "blobstore",
                 "blobstore",
                 None,
             )))
-            .await
-            .context("unknown target")?;
+            .await?;
         let wrpc =
             wrpc_transport_nats::Client::new(self.nats.clone(), format!("{}.{id}", self.lattice));
-        let (res, tx) = wrpc
-            .invoke_has_object(ObjectId {
-                container: container.to_string(),
-                object: name,
+        let res = self
+            .guarded_retry(&id, "wasi:blobstore/blobstore", "has-object", || async {
+                let (res, tx) = wrpc
+                    .invoke_has_object(ObjectId {
+                        container: container.to_string(),
+                        object: name.clone(),
+                    })
+                    .await
+                    .context("failed to invoke `wrpc:blobstore/blobstore.has-object`")?;
+                tx.await.context("failed to transmit parameters")?;
+                anyhow::Ok(res)
             })
-            .await
-            .context("failed to invoke `wrpc:blobstore/blobstore.has-object`")?;
+            .await?;
         // TODO: return a result directly
         let has = res.map_err(|err| anyhow!(err).context("function failed"))?;
-        tx.await.context("failed to transmit parameters")?;
         Ok(has)
     }
 
@@ -380,15 +640,14 @@ impl Blobstore for Handler {
     ) -> anyhow::Result<()> {
         use wrpc_interface_blobstore::{Blobstore, ObjectId};
 
-        let LatticeInterfaceTarget { id, .. } = self
-            .identify_wrpc_target(&CallTargetInterface::from_parts((
+        let (LatticeInterfaceTarget { id, .. }, _permit) = self
+            .wrpc_target(&CallTargetInterface::from_parts((
                 "wasi",
                 "blobstore",
                 "blobstore",
                 None,
             )))
-            .await
-            .context("unknown target")?;
+            .await?;
         let wrpc =
             wrpc_transport_nats::Client::new(self.nats.clone(), format!("{}.{id}", self.lattice));
         let mut buf = vec![];
@@ -396,19 +655,31 @@ impl Blobstore for Handler {
             .read_to_end(&mut buf)
             .await
             .context("failed to read value")?;
-        let (res, tx) = wrpc
-            .invoke_write_container_data(
-                ObjectId {
-                    container: container.to_string(),
-                    object: name,
+        let res = self
+            .guarded_retry(
+                &id,
+                "wasi:blobstore/blobstore",
+                "write-container-data",
+                || async {
+                    let (res, tx) = wrpc
+                        .invoke_write_container_data(
+                            ObjectId {
+                                container: container.to_string(),
+                                object: name.clone(),
+                            },
+                            stream::iter([buf.clone().into()]),
+                        )
+                        .await
+                        .context(
+                            "failed to invoke `wrpc:blobstore/blobstore.write-container-data`",
+                        )?;
+                    tx.await.context("failed to transmit parameters")?;
+                    anyhow::Ok(res)
                 },
-                stream::iter([buf.into()]),
             )
-            .await
-            .context("failed to invoke `wrpc:blobstore/blobstore.write-container-data`")?;
+            .await?;
         // TODO: return a result directly
         res.map_err(|err| anyhow!(err).context("function failed"))?;
-        tx.await.context("failed to transmit parameters")?;
         Ok(())
     }
 
@@ -416,25 +687,34 @@ impl Blobstore for Handler {
     async fn delete_objects(&self, container: &str, names: Vec<String>) -> anyhow::Result<()> {
         use wrpc_interface_blobstore::Blobstore;
 
-        let LatticeInterfaceTarget { id, .. } = self
-            .identify_wrpc_target(&CallTargetInterface::from_parts((
+        let (LatticeInterfaceTarget { id, .. }, _permit) = self
+            .wrpc_target(&CallTargetInterface::from_parts((
                 "wasi",
                 "blobstore",
                 "blobstore",
                 None,
             )))
-            .await
-            .context("unknown target")?;
+            .await?;
         let wrpc =
             wrpc_transport_nats::Client::new(self.nats.clone(), format!("{}.{id}", self.lattice));
         let container = container.to_string();
-        let (res, tx) = wrpc
-            .invoke_delete_objects(container.to_string(), names)
-            .await
-            .context("failed to invoke `wrpc:blobstore/blobstore.write-container-data`")?;
+        let res = self
+            .guarded_retry(
+                &id,
+                "wasi:blobstore/blobstore",
+                "delete-objects",
+                || async {
+                    let (res, tx) = wrpc
+                        .invoke_delete_objects(container.to_string(), names.clone())
+                        .await
+                        .context("failed to invoke `wrpc:blobstore/blobstore.delete-objects`")?;
+                    tx.await.context("failed to transmit parameters")?;
+                    anyhow::Ok(res)
+                },
+            )
+            .await?;
         // TODO: return a result directly
         res.map_err(|err| anyhow!(err).context("function failed"))?;
-        tx.await.context("failed to transmit parameters")?;
         Ok(())
     }
 
@@ -446,24 +726,35 @@ impl Blobstore for Handler {
     {
         use wrpc_interface_blobstore::Blobstore;
 
-        let LatticeInterfaceTarget { id, .. } = self
-            .identify_wrpc_target(&CallTargetInterface::from_parts((
+        let (LatticeInterfaceTarget { id, .. }, _permit) = self
+            .wrpc_target(&CallTargetInterface::from_parts((
                 "wasi",
                 "blobstore",
                 "blobstore",
                 None,
             )))
-            .await
-            .context("unknown target")?;
+            .await?;
         let wrpc =
             wrpc_transport_nats::Client::new(self.nats.clone(), format!("{}.{id}", self.lattice));
         // TODO: implement a stream with limit and offset
-        let (res, tx) = wrpc
-            .invoke_list_container_objects(container.to_string(), None, None)
-            .await
-            .context("failed to invoke `wrpc:blobstore/blobstore.list-container-objects`")?;
+        let res = self
+            .guarded_retry(
+                &id,
+                "wasi:blobstore/blobstore",
+                "list-container-objects",
+                || async {
+                    let (res, tx) = wrpc
+                        .invoke_list_container_objects(container.to_string(), None, None)
+                        .await
+                        .context(
+                            "failed to invoke `wrpc:blobstore/blobstore.list-container-objects`",
+                        )?;
+                    tx.await.context("failed to transmit parameters")?;
+                    anyhow::Ok(res)
+                },
+            )
+            .await?;
         let names = res.map_err(|err| anyhow!(err).context("function failed"))?;
-        tx.await.context("failed to transmit parameters")?;
         Ok(names)
     }
 
@@ -475,24 +766,34 @@ impl Blobstore for Handler {
     ) -> anyhow::Result<blobstore::container::ObjectMetadata> {
         use wrpc_interface_blobstore::{Blobstore, ObjectId, ObjectMetadata};
 
-        let LatticeInterfaceTarget { id, .. } = self
-            .identify_wrpc_target(&CallTargetInterface::from_parts((
+        let (LatticeInterfaceTarget { id, .. }, _permit) = self
+            .wrpc_target(&CallTargetInterface::from_parts((
                 "wasi",
                 "blobstore",
                 "blobstore",
                 None,
             )))
-            .await
-            .context("unknown target")?;
+            .await?;
         let wrpc =
             wrpc_transport_nats::Client::new(self.nats.clone(), format!("{}.{id}", self.lattice));
-        let (res, tx) = wrpc
-            .invoke_get_object_info(ObjectId {
-                container: container.to_string(),
-                object: name,
-            })
-            .await
-            .context("failed to invoke `wrpc:blobstore/blobstore.get-object-info`")?;
+        let res = self
+            .guarded_retry(
+                &id,
+                "wasi:blobstore/blobstore",
+                "get-object-info",
+                || async {
+                    let (res, tx) = wrpc
+                        .invoke_get_object_info(ObjectId {
+                            container: container.to_string(),
+                            object: name.clone(),
+                        })
+                        .await
+                        .context("failed to invoke `wrpc:blobstore/blobstore.get-object-info`")?;
+                    tx.await.context("failed to transmit parameters")?;
+                    anyhow::Ok(res)
+                },
+            )
+            .await?;
         // TODO: return a result directly
         let ObjectMetadata {
             name,
@@ -500,7 +801,6 @@ impl Blobstore for Handler {
             created_at,
             size,
         } = res.map_err(|err| anyhow!(err).context("function failed"))?;
-        tx.await.context("failed to transmit parameters")?;
         Ok(blobstore::container::ObjectMetadata {
             name,
             container,
@@ -513,25 +813,34 @@ impl Blobstore for Handler {
     async fn clear_container(&self, container: &str) -> anyhow::Result<()> {
         use wrpc_interface_blobstore::Blobstore;
 
-        let LatticeInterfaceTarget { id, .. } = self
-            .identify_wrpc_target(&CallTargetInterface::from_parts((
+        let (LatticeInterfaceTarget { id, .. }, _permit) = self
+            .wrpc_target(&CallTargetInterface::from_parts((
                 "wasi",
                 "blobstore",
                 "blobstore",
                 None,
             )))
-            .await
-            .context("unknown target")?;
+            .await?;
         let wrpc =
             wrpc_transport_nats::Client::new(self.nats.clone(), format!("{}.{id}", self.lattice));
         let container = container.to_string();
-        let (res, tx) = wrpc
-            .invoke_clear_container(container.to_string())
-            .await
-            .context("failed to invoke `wrpc:blobstore/blobstore.clear-container`")?;
+        let res = self
+            .guarded_retry(
+                &id,
+                "wasi:blobstore/blobstore",
+                "clear-container",
+                || async {
+                    let (res, tx) = wrpc
+                        .invoke_clear_container(container.to_string())
+                        .await
+                        .context("failed to invoke `wrpc:blobstore/blobstore.clear-container`")?;
+                    tx.await.context("failed to transmit parameters")?;
+                    anyhow::Ok(res)
+                },
+            )
+            .await?;
         // TODO: return a result directly
         res.map_err(|err| anyhow!(err).context("function failed"))?;
-        tx.await.context("failed to transmit parameters")?;
         Ok(())
     }
 }
@@ -630,21 +939,45 @@ impl Bus for Handler {
         name: &str,
         params: Vec<wrpc_transport::Value>,
     ) -> anyhow::Result<Vec<wrpc_transport::Value>> {
-        if let TargetEntity::Lattice(LatticeInterfaceTarget { id, .. }) = target {
+        if let TargetEntity::Lattice(target) = target {
+            self.ensure_available(&target.id).await?;
+            let _permit = self.admit(&target).await?;
+            let LatticeInterfaceTarget { id, .. } = target;
             let results = self
                 .polyfilled_imports
                 .get(instance)
                 .and_then(|functions| functions.get(name)).with_context(|| format!("polyfilled import {instance}/{name} not found, could not determine result types"))?;
 
-            let injector = TraceContextInjector::default_with_span();
-            let mut headers = injector_to_headers(&injector);
-            headers.insert("source-id", self.component_id.as_str());
-            let (results, tx) =
-                wasmcloud_core::wrpc::Client::new(self.nats.clone(), &self.lattice, &id, headers)
-                    .invoke_dynamic(instance, name, DynamicTuple(params), results)
-                    .await?;
-            tx.await.context("failed to transmit parameters")?;
-            Ok(results)
+            let attempts = self.retry_policy.attempts(instance, name);
+            let mut params = params;
+            let mut attempt = 1;
+            loop {
+                // Keep a copy of the parameters if the invocation may be retried on failure
+                let next_params = if attempt < attempts {
+                    retry_params(&params)
+                } else {
+                    None
+                };
+                match self
+                    .invoke_dynamic(&id, instance, name, params, results)
+                    .await
+                {
+                    Ok(results) => return Ok(results),
+                    Err(err) => {
+                        let Some(next_params) = next_params else {
+                            return Err(err);
+                        };
+                        if err.downcast_ref::<CircuitBreakerOpen>().is_some() {
+                            return Err(err);
+                        }
+                        let backoff = self.retry_policy.backoff(attempt);
+                        warn!(?err, attempt, ?backoff, "invocation failed, retrying");
+                        tokio::time::sleep(backoff).await;
+                        params = next_params;
+                        attempt += 1;
+                    }
+                }
+            }
         } else {
             bail!("component attempted to invoke a function on an unknown target")
         }
@@ -657,21 +990,32 @@ impl KeyValueAtomic for Handler {
     async fn increment(&self, bucket: &str, key: String, delta: u64) -> anyhow::Result<u64> {
         use wrpc_interface_keyvalue::Atomic;
 
-        let LatticeInterfaceTarget { id, .. } = self
-            .identify_wrpc_target(&CallTargetInterface::from_parts((
+        let (LatticeInterfaceTarget { id, .. }, _permit) = self
+            .wrpc_target(&CallTargetInterface::from_parts((
                 "wasi", "keyvalue", "atomic", None,
             )))
-            .await
-            .context("unknown target")?;
+            .await?;
+        if let Some(kv) = self.builtin_keyvalue(&id)? {
+            return self
+                .guarded_retry(&id, "wasi:keyvalue/atomic", "increment", || {
+                    kv.increment(bucket, key.clone(), delta)
+                })
+                .await;
+        }
         let wrpc =
             wrpc_transport_nats::Client::new(self.nats.clone(), format!("{}.{id}", self.lattice));
-        let (res, tx) = wrpc
-            .invoke_increment(bucket.to_string(), key, delta)
-            .await
-            .context("failed to invoke `wrpc:keyvalue/atomic.increment`")?;
+        let res = self
+            .guarded_retry(&id, "wasi:keyvalue/atomic", "increment", || async {
+                let (res, tx) = wrpc
+                    .invoke_increment(bucket.to_string(), key.clone(), delta)
+                    .await
+                    .context("failed to invoke `wrpc:keyvalue/atomic.increment`")?;
+                tx.await.context("failed to transmit parameters")?;
+                anyhow::Ok(res)
+            })
+            .await?;
         // TODO: return a result directly
         let value = res.map_err(|err| anyhow!(err).context("function failed"))?;
-        tx.await.context("failed to transmit parameters")?;
         Ok(value)
     }
 
@@ -686,21 +1030,32 @@ impl KeyValueAtomic for Handler {
     ) -> anyhow::Result<bool> {
         use wrpc_interface_keyvalue::Atomic;
 
-        let LatticeInterfaceTarget { id, .. } = self
-            .identify_wrpc_target(&CallTargetInterface::from_parts((
+        let (LatticeInterfaceTarget { id, .. }, _permit) = self
+            .wrpc_target(&CallTargetInterface::from_parts((
                 "wasi", "keyvalue", "atomic", None,
             )))
-            .await
-            .context("unknown target")?;
+            .await?;
+        if let Some(kv) = self.builtin_keyvalue(&id)? {
+            return self
+                .guarded_retry(&id, "wasi:keyvalue/atomic", "compare-and-swap", || {
+                    kv.compare_and_swap(bucket, key.clone(), old, new)
+                })
+                .await;
+        }
         let wrpc =
             wrpc_transport_nats::Client::new(self.nats.clone(), format!("{}.{id}", self.lattice));
-        let (res, tx) = wrpc
-            .invoke_compare_and_swap(bucket.to_string(), key, old, new)
-            .await
-            .context("failed to invoke `wrpc:keyvalue/atomic.compare-and-swap`")?;
+        let res = self
+            .guarded_retry(&id, "wasi:keyvalue/atomic", "compare-and-swap", || async {
+                let (res, tx) = wrpc
+                    .invoke_compare_and_swap(bucket.to_string(), key.clone(), old, new)
+                    .await
+                    .context("failed to invoke `wrpc:keyvalue/atomic.compare-and-swap`")?;
+                tx.await.context("failed to transmit parameters")?;
+                anyhow::Ok(res)
+            })
+            .await?;
         // TODO: return a result directly
         let value = res.map_err(|err| anyhow!(err).context("function failed"))?;
-        tx.await.context("failed to transmit parameters")?;
         Ok(value)
     }
 }
@@ -711,21 +1066,32 @@ impl KeyValueEventual for Handler {
     async fn get(&self, bucket: &str, key: String) -> anyhow::Result<Option<IncomingInputStream>> {
         use wrpc_interface_keyvalue::Eventual;
 
-        let LatticeInterfaceTarget { id, .. } = self
-            .identify_wrpc_target(&CallTargetInterface::from_parts((
+        let (LatticeInterfaceTarget { id, .. }, _permit) = self
+            .wrpc_target(&CallTargetInterface::from_parts((
                 "wasi", "keyvalue", "eventual", None,
             )))
-            .await
-            .context("unknown target")?;
+            .await?;
+        if let Some(kv) = self.builtin_keyvalue(&id)? {
+            return self
+                .guarded_retry(&id, "wasi:keyvalue/eventual", "get", || {
+                    kv.get(bucket, key.clone())
+                })
+                .await;
+        }
         let wrpc =
             wrpc_transport_nats::Client::new(self.nats.clone(), format!("{}.{id}", self.lattice));
-        let (res, tx) = wrpc
-            .invoke_get(bucket.to_string(), key)
-            .await
-            .context("failed to invoke `wrpc:keyvalue/eventual.get`")?;
+        let res = self
+            .guarded_retry(&id, "wasi:keyvalue/eventual", "get", || async {
+                let (res, tx) = wrpc
+                    .invoke_get(bucket.to_string(), key.clone())
+                    .await
+                    .context("failed to invoke `wrpc:keyvalue/eventual.get`")?;
+                tx.await.context("failed to transmit parameters")?;
+                anyhow::Ok(res)
+            })
+            .await?;
         // TODO: return a result directly
         let value = res.map_err(|err| anyhow!(err).context("function failed"))?;
-        tx.await.context("failed to transmit parameters")?;
         Ok(value)
     }
 
@@ -738,12 +1104,14 @@ impl KeyValueEventual for Handler {
     ) -> anyhow::Result<()> {
         use wrpc_interface_keyvalue::Eventual;
 
-        let LatticeInterfaceTarget { id, .. } = self
-            .identify_wrpc_target(&CallTargetInterface::from_parts((
+        let (LatticeInterfaceTarget { id, .. }, _permit) = self
+            .wrpc_target(&CallTargetInterface::from_parts((
                 "wasi", "keyvalue", "eventual", None,
             )))
-            .await
-            .context("unknown target")?;
+            .await?;
+        if let Some(kv) = self.builtin_keyvalue(&id)? {
+            return self.guarded(&id, kv.set(bucket, key, value)).await;
+        }
         let wrpc =
             wrpc_transport_nats::Client::new(self.nats.clone(), format!("{}.{id}", self.lattice));
         // TODO: Stream value (or not, depending on how `wasi:keyvalue` develops)
@@ -752,13 +1120,22 @@ impl KeyValueEventual for Handler {
             .read_to_end(&mut buf)
             .await
             .context("failed to read value")?;
-        let (res, tx) = wrpc
-            .invoke_set(bucket.to_string(), key, stream::iter([buf.into()]))
-            .await
-            .context("failed to invoke `wrpc:keyvalue/eventual.set`")?;
+        let res = self
+            .guarded_retry(&id, "wasi:keyvalue/eventual", "set", || async {
+                let (res, tx) = wrpc
+                    .invoke_set(
+                        bucket.to_string(),
+                        key.clone(),
+                        stream::iter([buf.clone().into()]),
+                    )
+                    .await
+                    .context("failed to invoke `wrpc:keyvalue/eventual.set`")?;
+                tx.await.context("failed to transmit parameters")?;
+                anyhow::Ok(res)
+            })
+            .await?;
         // TODO: return a result directly
         let value = res.map_err(|err| anyhow!(err).context("function failed"))?;
-        tx.await.context("failed to transmit parameters")?;
         Ok(value)
     }
 
@@ -766,22 +1143,33 @@ impl KeyValueEventual for Handler {
     async fn delete(&self, bucket: &str, key: String) -> anyhow::Result<()> {
         use wrpc_interface_keyvalue::Eventual;
 
-        let LatticeInterfaceTarget { id, .. } = self
-            .identify_wrpc_target(&CallTargetInterface::from_parts((
+        let (LatticeInterfaceTarget { id, .. }, _permit) = self
+            .wrpc_target(&CallTargetInterface::from_parts((
                 "wasi", "keyvalue", "eventual", None,
             )))
-            .await
-            .context("unknown target")?;
+            .await?;
+        if let Some(kv) = self.builtin_keyvalue(&id)? {
+            return self
+                .guarded_retry(&id, "wasi:keyvalue/eventual", "delete", || {
+                    kv.delete(bucket, key.clone())
+                })
+                .await;
+        }
         let wrpc =
             wrpc_transport_nats::Client::new(self.nats.clone(), format!("{}.{id}", self.lattice));
         // TODO: Stream value (or not, depending on how `wasi:keyvalue` develops)
-        let (res, tx) = wrpc
-            .invoke_delete(bucket.to_string(), key)
-            .await
-            .context("failed to invoke `wrpc:keyvalue/eventual.delete`")?;
+        let res = self
+            .guarded_retry(&id, "wasi:keyvalue/eventual", "delete", || async {
+                let (res, tx) = wrpc
+                    .invoke_delete(bucket.to_string(), key.clone())
+                    .await
+                    .context("failed to invoke `wrpc:keyvalue/eventual.delete`")?;
+                tx.await.context("failed to transmit parameters")?;
+                anyhow::Ok(res)
+            })
+            .await?;
         // TODO: return a result directly
         res.map_err(|err| anyhow!(err).context("function failed"))?;
-        tx.await.context("failed to transmit parameters")?;
         Ok(())
     }
 
@@ -789,21 +1177,32 @@ impl KeyValueEventual for Handler {
     async fn exists(&self, bucket: &str, key: String) -> anyhow::Result<bool> {
         use wrpc_interface_keyvalue::Eventual;
 
-        let LatticeInterfaceTarget { id, .. } = self
-            .identify_wrpc_target(&CallTargetInterface::from_parts((
+        let (LatticeInterfaceTarget { id, .. }, _permit) = self
+            .wrpc_target(&CallTargetInterface::from_parts((
                 "wasi", "keyvalue", "eventual", None,
             )))
-            .await
-            .context("unknown target")?;
+            .await?;
+        if let Some(kv) = self.builtin_keyvalue(&id)? {
+            return self
+                .guarded_retry(&id, "wasi:keyvalue/eventual", "exists", || {
+                    kv.exists(bucket, key.clone())
+                })
+                .await;
+        }
         let wrpc =
             wrpc_transport_nats::Client::new(self.nats.clone(), format!("{}.{id}", self.lattice));
-        let (res, tx) = wrpc
-            .invoke_exists(bucket.to_string(), key)
-            .await
-            .context("failed to invoke `wrpc:keyvalue/eventual.exists`")?;
+        let res = self
+            .guarded_retry(&id, "wasi:keyvalue/eventual", "exists", || async {
+                let (res, tx) = wrpc
+                    .invoke_exists(bucket.to_string(), key.clone())
+                    .await
+                    .context("failed to invoke `wrpc:keyvalue/eventual.exists`")?;
+                tx.await.context("failed to transmit parameters")?;
+                anyhow::Ok(res)
+            })
+            .await?;
         // TODO: return a result directly
         let exists = res.map_err(|err| anyhow!(err).context("function failed"))?;
-        tx.await.context("failed to transmit parameters")?;
         Ok(exists)
     }
 }
@@ -951,28 +1350,32 @@ impl Messaging for Handler {
         body: Option<Vec<u8>>,
         timeout: Duration,
     ) -> anyhow::Result<messaging::types::BrokerMessage> {
-        let LatticeInterfaceTarget { id, .. } = self
-            .identify_wrpc_target(&CallTargetInterface::from_parts((
+        let (LatticeInterfaceTarget { id, .. }, _permit) = self
+            .wrpc_target(&CallTargetInterface::from_parts((
                 "wasmcloud",
                 "messaging",
                 "consumer",
                 None,
             )))
-            .await
-            .context("unknown target")?;
+            .await?;
         let wrpc =
             wrpc_transport_nats::Client::new(self.nats.clone(), format!("{}.{id}", self.lattice));
-        let (res, tx) = wrpc
-            .invoke_static::<Result<_, String>>(
-                "wasmcloud:messaging/consumer",
-                "request",
-                (subject, body, timeout),
-            )
-            .await
-            .context("failed to invoke `wasmcloud:messaging/consumer.request`")?;
+        let res = self
+            .guarded_retry(&id, "wasmcloud:messaging/consumer", "request", || async {
+                let (res, tx) = wrpc
+                    .invoke_static::<Result<_, String>>(
+                        "wasmcloud:messaging/consumer",
+                        "request",
+                        (subject.clone(), body.clone(), timeout),
+                    )
+                    .await
+                    .context("failed to invoke `wasmcloud:messaging/consumer.request`")?;
+                tx.await.context("failed to transmit parameters")?;
+                anyhow::Ok(res)
+            })
+            .await?;
         // TODO: return a result directly
         let BrokerMessage(msg) = res.map_err(|err| anyhow!(err).context("function failed"))?;
-        tx.await.context("failed to transmit parameters")?;
         Ok(msg)
     }
 
@@ -984,56 +1387,69 @@ impl Messaging for Handler {
         timeout: Duration,
         max_results: u32,
     ) -> anyhow::Result<Vec<messaging::types::BrokerMessage>> {
-        let LatticeInterfaceTarget { id, .. } = self
-            .identify_wrpc_target(&CallTargetInterface::from_parts((
+        let (LatticeInterfaceTarget { id, .. }, _permit) = self
+            .wrpc_target(&CallTargetInterface::from_parts((
                 "wasmcloud",
                 "messaging",
                 "consumer",
                 None,
             )))
-            .await
-            .context("unknown target")?;
+            .await?;
         let wrpc =
             wrpc_transport_nats::Client::new(self.nats.clone(), format!("{}.{id}", self.lattice));
-        let (res, tx) = wrpc
-            .invoke_static::<Result<Vec<_>, String>>(
+        let res = self
+            .guarded_retry(
+                &id,
                 "wasmcloud:messaging/consumer",
                 "request-multi",
-                (subject, body, timeout, max_results),
+                || async {
+                    let (res, tx) = wrpc
+                        .invoke_static::<Result<Vec<_>, String>>(
+                            "wasmcloud:messaging/consumer",
+                            "request-multi",
+                            (subject.clone(), body.clone(), timeout, max_results),
+                        )
+                        .await
+                        .context("failed to invoke `wasmcloud:messaging/consumer.request-multi`")?;
+                    tx.await.context("failed to transmit parameters")?;
+                    anyhow::Ok(res)
+                },
             )
-            .await
-            .context("failed to invoke `wasmcloud:messaging/consumer.request`")?;
+            .await?;
         // TODO: return a result directly
         let msgs = res.map_err(|err| anyhow!(err).context("function failed"))?;
-        tx.await.context("failed to transmit parameters")?;
         let msgs = msgs.into_iter().map(|BrokerMessage(msg)| msg).collect();
         Ok(msgs)
     }
 
     #[instrument(skip_all)]
     async fn publish(&self, msg: messaging::types::BrokerMessage) -> anyhow::Result<()> {
-        let LatticeInterfaceTarget { id, .. } = self
-            .identify_wrpc_target(&CallTargetInterface::from_parts((
+        let (LatticeInterfaceTarget { id, .. }, _permit) = self
+            .wrpc_target(&CallTargetInterface::from_parts((
                 "wasmcloud",
                 "messaging",
                 "consumer",
                 None,
             )))
-            .await
-            .context("unknown target")?;
+            .await?;
         let wrpc =
             wrpc_transport_nats::Client::new(self.nats.clone(), format!("{}.{id}", self.lattice));
-        let (res, tx) = wrpc
-            .invoke_static::<Result<(), String>>(
-                "wasmcloud:messaging/consumer",
-                "publish",
-                BrokerMessage(msg),
-            )
-            .await
-            .context("failed to invoke `wasmcloud:messaging/consumer.publish`")?;
+        let res = self
+            .guarded_retry(&id, "wasmcloud:messaging/consumer", "publish", || async {
+                let (res, tx) = wrpc
+                    .invoke_static::<Result<(), String>>(
+                        "wasmcloud:messaging/consumer",
+                        "publish",
+                        BrokerMessage(msg.clone()),
+                    )
+                    .await
+                    .context("failed to invoke `wasmcloud:messaging/consumer.publish`")?;
+                tx.await.context("failed to transmit parameters")?;
+                anyhow::Ok(res)
+            })
+            .await?;
         // TODO: return a result directly
         res.map_err(|err| anyhow!(err).context("function failed"))?;
-        tx.await.context("failed to transmit parameters")?;
         Ok(())
     }
 }
@@ -1052,21 +1468,27 @@ impl OutgoingHttp for Handler {
     > {
         use wrpc_interface_http::OutgoingHandler;
 
-        let LatticeInterfaceTarget { id, .. } = self
-            .identify_wrpc_target(&CallTargetInterface::from_parts((
+        let (LatticeInterfaceTarget { id, .. }, permit) = self
+            .wrpc_target(&CallTargetInterface::from_parts((
                 "wasi",
                 "http",
                 "outgoing-handler",
                 None,
             )))
-            .await
-            .context("unknown target")?;
+            .await?;
+        // The source ID lets the provider apply the settings of the component's link
+        let mut headers = injector_to_headers(&TraceContextInjector::default_with_span());
+        headers.insert("source-id", self.component_id.as_str());
         let wrpc =
-            wrpc_transport_nats::Client::new(self.nats.clone(), format!("{}.{id}", self.lattice));
-        let (res, body_errors, tx) = wrpc
-            .invoke_handle_wasmtime(request)
-            .await
-            .context("failed to invoke `wrpc:http/outgoing-handler.handle`")?;
+            wasmcloud_core::wrpc::Client::new(self.nats.clone(), &self.lattice, &id, headers);
+        // The request body can only be sent once, so the request is never retried
+        let (res, body_errors, tx) = self
+            .guarded(&id, async {
+                wrpc.invoke_handle_wasmtime(request)
+                    .await
+                    .context("failed to invoke `wrpc:http/outgoing-handler.handle`")
+            })
+            .await?;
         spawn(async move {
             if let Err(err) = tx.await {
                 error!(?err, "failed to transmit parameter values")
@@ -1074,24 +1496,61 @@ impl OutgoingHttp for Handler {
         });
         // TODO: Do not ignore outgoing body errors
         let _ = body_errors;
-        Ok(res)
+        // The response body is streamed after returning, so it holds the permit until it is done
+        Ok(res.map(|res| res.map(|body| PermitBody::new(body, permit).boxed())))
     }
 }
 
 type Annotations = BTreeMap<String, String>;
 
+/// Default time to wait for in-flight invocations of the replaced version during a hot update
+const DEFAULT_UPDATE_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+/// Default time the new version is watched for invocation errors after a hot update
+const DEFAULT_UPDATE_WATCH_WINDOW: Duration = Duration::from_secs(60);
+/// Interval at which the invocation errors of the new version are checked during a hot update
+const UPDATE_WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Delay before the first restart of a provider process, doubled for every subsequent restart
+/// within the restart window
+const PROVIDER_RESTART_BACKOFF: Duration = Duration::from_secs(1);
+/// Maximum delay between restarts of a provider process
+const PROVIDER_RESTART_MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Stream of invocations accepted by an [Actor]
+type Invocations = SelectAll<
+    Box<
+        dyn Stream<
+                Item = anyhow::Result<
+                    AcceptedInvocation<
+                        Option<async_nats::HeaderMap>,
+                        InvocationParams,
+                        wrpc_transport_nats::Subject,
+                        wasmcloud_core::wrpc::TransmitterWithHeaders,
+                    >,
+                >,
+            > + Send
+            + Unpin,
+    >,
+>;
+
 #[derive(Debug)]
 struct Actor {
     component: wasmcloud_runtime::Component,
     /// Unique component identifier for this actor
     id: String,
-    calls: AbortHandle,
+    /// Handle of the stream of invocations accepted by this actor. Stopping it stops accepting
+    /// new invocations, in-flight invocations run to completion
+    calls: Calls<Invocations>,
+    /// Set to `true` once all accepted invocations completed after `calls` was stopped
+    calls_done: watch::Receiver<bool>,
     handler: Handler,
     annotations: Annotations,
     /// Maximum number of instances of this actor that can be running at once
     max_instances: NonZeroUsize,
     image_reference: String,
     metrics: Arc<HostMetrics>,
+    /// Retains the recorded statistics of this actor until all of its instances are dropped
+    _recorded: ComponentMetricsGuard,
     // TODO(#1220): implement issuer verification
     /// Cluster issuers that this actor should accept invocations from
     #[allow(unused)]
@@ -1133,6 +1592,19 @@ impl std::fmt::Debug for InvocationParams {
 }
 
 impl Actor {
+    /// Stops accepting new invocations and waits up to `timeout` for in-flight invocations to
+    /// complete. Returns `false` if invocations were still in flight when the timeout elapsed
+    async fn drain(&self, timeout: Duration) -> bool {
+        self.calls.stop().await;
+        let mut calls_done = self.calls_done.clone();
+        tokio::time::timeout(timeout, async move {
+            // The sender is only dropped once the invocation stream completed
+            let _ = calls_done.wait_for(|done| *done).await;
+        })
+        .await
+        .is_ok()
+    }
+
     /// Handle an incoming wRPC request to invoke an export on this actor instance.
     #[instrument(
         level = "info",
@@ -1195,6 +1667,10 @@ impl Actor {
             wasmcloud_tracing::context::attach_span_context(&trace_context);
         }
 
+        let _active = self
+            .metrics
+            .component_instance_active(&self.id, &self.image_reference);
+
         // Instantiate component with expected handlers
         let mut actor = self.instantiate().context("failed to instantiate actor")?;
         actor
@@ -1229,8 +1705,20 @@ impl Actor {
                     .context("failed to call actor");
                 let elapsed = u64::try_from(start_at.elapsed().as_nanos()).unwrap_or_default();
                 attributes.push(KeyValue::new("operation", format!("{instance}/{name}")));
-                self.metrics
-                    .record_component_invocation(elapsed, &attributes, res.is_err());
+                self.metrics.record_component_invocation(
+                    &self.id,
+                    &self.image_reference,
+                    elapsed,
+                    &attributes,
+                    res.is_err(),
+                );
+                if is_interrupted(&res) {
+                    self.metrics.record_component_interruption(
+                        &self.id,
+                        &self.image_reference,
+                        &attributes,
+                    );
+                }
                 Box::pin(async {
                     let results = res?;
                     transmitter
@@ -1252,8 +1740,20 @@ impl Actor {
                     "operation",
                     "wrpc:http/incoming-handler.handle",
                 ));
-                self.metrics
-                    .record_component_invocation(elapsed, &attributes, res.is_err());
+                self.metrics.record_component_invocation(
+                    &self.id,
+                    &self.image_reference,
+                    elapsed,
+                    &attributes,
+                    res.is_err(),
+                );
+                if is_interrupted(&res) {
+                    self.metrics.record_component_interruption(
+                        &self.id,
+                        &self.image_reference,
+                        &attributes,
+                    );
+                }
                 Box::pin(async {
                     let res = match res? {
                         Ok(resp) => {
@@ -1309,7 +1809,14 @@ pub struct Host {
     policy_manager: Arc<PolicyManager>,
     /// The provider map is a map of provider component ID to provider
     providers: RwLock<HashMap<String, Provider>>,
+    /// Recent output of providers, retained after their process exits until they are stopped
+    provider_logs: RwLock<HashMap<String, Arc<logs::History>>>,
+    /// Availability of providers across the lattice, derived from provider health events
+    unavailable_providers: Arc<RwLock<health::Availability>>,
+    availability: AbortHandle,
     registry_config: RwLock<HashMap<String, RegistryConfig>>,
+    /// Artifacts preloaded from offline bundles
+    bundles: BundleCache,
     runtime: Runtime,
     start_at: Instant,
     stop_tx: watch::Sender<Option<Instant>>,
@@ -1321,6 +1828,10 @@ pub struct Host {
     provider_claims: Arc<RwLock<HashMap<String, jwt::Claims<jwt::CapabilityProvider>>>>,
     config_data_cache: Arc<RwLock<ConfigCache>>,
     metrics: Arc<HostMetrics>,
+    /// Built-in `wasi:keyvalue` implementation, if enabled
+    builtin_keyvalue: Option<Arc<JetStreamKeyValue>>,
+    /// Circuit breakers of the lattice targets invoked by components on the host
+    circuit_breakers: Arc<CircuitBreakers>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -1333,6 +1844,9 @@ pub struct ComponentSpecification {
     url: String,
     /// All outbound links from this component to other components, used for routing when calling a component `import`
     links: Vec<InterfaceLinkDefinition>,
+    /// The JSON schema of the link configuration of the component, if it is a provider which embeds one in its claims
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    config_schema: Option<serde_json::Value>,
     ////
     // Possible additions in the future, left in as comments to facilitate discussion
     ////
@@ -1350,6 +1864,7 @@ impl ComponentSpecification {
         Self {
             url: url.as_ref().to_string(),
             links: Vec::new(),
+            config_schema: None,
         }
     }
 }
@@ -1447,8 +1962,8 @@ async fn create_bucket(
     }
 }
 
-/// Given the NATS address, authentication jwt, seed, tls requirement and optional request timeout,
-/// attempt to establish connection.
+/// Given the NATS address, authentication jwt, seed, tls requirement, optional request timeout and
+/// optional inbox prefix, attempt to establish connection.
 ///
 ///
 /// # Errors
@@ -1462,6 +1977,7 @@ async fn connect_nats(
     key: Option<Arc<KeyPair>>,
     require_tls: bool,
     request_timeout: Option<Duration>,
+    inbox_prefix: Option<String>,
 ) -> anyhow::Result<async_nats::Client> {
     let opts = async_nats::ConnectOptions::new().require_tls(require_tls);
     let opts = match (jwt, key) {
@@ -1481,11 +1997,25 @@ async fn connect_nats(
     } else {
         opts
     };
+    let opts = if let Some(inbox_prefix) = inbox_prefix {
+        opts.custom_inbox_prefix(inbox_prefix)
+    } else {
+        opts
+    };
     opts.connect(addr)
         .await
         .context("failed to connect to NATS")
 }
 
+/// Result of validating the config of a link against config schemas
+#[derive(Debug, Default)]
+struct LinkConfigValidation {
+    /// Description of all violations, if the config is invalid
+    violations: Option<String>,
+    /// Descriptions of the sides of the link, which were not validated due to an invalid schema
+    invalid_schemas: Vec<String>,
+}
+
 #[derive(Debug, Default)]
 struct SupplementalConfig {
     registry_config: Option<HashMap<String, RegistryConfig>>,
@@ -1683,6 +2213,7 @@ impl Host {
                     config.ctl_key.clone(),
                     config.ctl_tls,
                     None,
+                    None,
                 )
                 .await
                 .context("failed to establish NATS control server connection")?;
@@ -1709,6 +2240,9 @@ impl Host {
                     config.rpc_key.clone(),
                     config.rpc_tls,
                     Some(config.rpc_timeout),
+                    config
+                        .rpc_lattice_inbox
+                        .then(|| wasmcloud_core::nats::rpc_inbox_prefix(&config.lattice)),
                 )
                 .await
                 .context("failed to establish NATS RPC server connection")
@@ -1726,12 +2260,16 @@ impl Host {
         let (stop_tx, stop_rx) = watch::channel(None);
 
         // TODO: Configure
-        let runtime = Runtime::builder()
-            .actor_config(wasmcloud_runtime::ActorConfig {
-                require_signature: true,
-            })
-            .build()
-            .context("failed to build runtime")?;
+        let mut runtime = Runtime::builder().actor_config(wasmcloud_runtime::ActorConfig {
+            require_signature: true,
+        });
+        if let Some(max_execution_time) = config.max_execution_time {
+            runtime = runtime.max_execution_time(max_execution_time);
+        }
+        if let Some(max_execution_fuel) = config.max_execution_fuel {
+            runtime = runtime.max_execution_fuel(max_execution_fuel);
+        }
+        let runtime = runtime.build().context("failed to build runtime")?;
         let event_builder = EventBuilderV10::new().source(host_key.public_key());
 
         let ctl_jetstream = if let Some(domain) = config.js_domain.as_ref() {
@@ -1745,9 +2283,25 @@ impl Host {
         let config_bucket = format!("CONFIGDATA_{}", config.lattice);
         let config_data = create_bucket(&ctl_jetstream, &config_bucket).await?;
 
+        let builtin_keyvalue = config.builtin_keyvalue_enabled.then(|| {
+            Arc::new(JetStreamKeyValue::new(
+                ctl_jetstream.clone(),
+                config.lattice.clone(),
+            ))
+        });
+
         let (queue_abort, queue_abort_reg) = AbortHandle::new_pair();
         let (heartbeat_abort, heartbeat_abort_reg) = AbortHandle::new_pair();
         let (data_watch_abort, data_watch_abort_reg) = AbortHandle::new_pair();
+        let (availability_abort, availability_abort_reg) = AbortHandle::new_pair();
+
+        // Subscribe before the host starts, so that no event affecting availability is missed
+        let availability_events = try_join_all(
+            health::AVAILABILITY_EVENTS
+                .map(|name| ctl_nats.subscribe(format!("wasmbus.evt.{}.{name}", config.lattice))),
+        )
+        .await
+        .context("failed to subscribe to provider availability events")?;
 
         let supplemental_config = if config.config_service_enabled {
             load_supplemental_config(&ctl_nats, &config.lattice, &labels).await?
@@ -1758,6 +2312,14 @@ impl Host {
         let registry_config = RwLock::new(supplemental_config.registry_config.unwrap_or_default());
         merge_registry_config(&registry_config, config.oci_opts.clone()).await;
 
+        let bundles = BundleCache::preload(
+            &config.bundles,
+            &config.bundle_issuers,
+            config.allow_any_bundle_issuer,
+        )
+        .await
+        .context("failed to preload bundles")?;
+
         let policy_manager = PolicyManager::new(
             ctl_nats.clone(),
             PolicyHostInfo {
@@ -1766,6 +2328,7 @@ impl Host {
                 labels: labels.clone(),
             },
             config.policy_service_config.policy_topic.clone(),
+            config.policy_service_config.policy_rules_file.clone(),
             config.policy_service_config.policy_timeout_ms,
             config.policy_service_config.policy_changes_topic.clone(),
         )
@@ -1787,6 +2350,7 @@ impl Host {
         );
 
         let config_generator = BundleGenerator::new(config_data.clone());
+        let circuit_breakers = Arc::new(CircuitBreakers::new(config.circuit_breaker.clone()));
 
         let host = Host {
             actors: RwLock::default(),
@@ -1807,7 +2371,11 @@ impl Host {
             config_generator,
             policy_manager,
             providers: RwLock::default(),
+            provider_logs: RwLock::default(),
+            unavailable_providers: Arc::default(),
+            availability: availability_abort.clone(),
             registry_config,
+            bundles,
             runtime,
             start_at,
             stop_rx,
@@ -1818,9 +2386,24 @@ impl Host {
             provider_claims: Arc::default(),
             config_data_cache: Arc::default(),
             metrics: Arc::new(metrics),
+            builtin_keyvalue,
+            circuit_breakers,
+        };
+
+        let host = Arc::new(host);
+        let metrics_server = if let Some(addr) = host.host_config.metrics_listen_address {
+            let listener = TcpListener::bind(addr)
+                .await
+                .with_context(|| format!("failed to bind metrics listener on `{addr}`"))?;
+            info!(%addr, "serving Prometheus metrics");
+            let host = Arc::clone(&host);
+            Some(spawn(prometheus::serve(listener, move || {
+                let host = Arc::clone(&host);
+                async move { host.prometheus_metrics().await }
+            })))
+        } else {
+            None
         };
-
-        let host = Arc::new(host);
         let queue = spawn({
             let host = Arc::clone(&host);
             async move {
@@ -1882,6 +2465,28 @@ impl Host {
             }
         });
 
+        let availability = spawn({
+            let host = Arc::clone(&host);
+            async move {
+                let mut events =
+                    Abortable::new(select_all(availability_events), availability_abort_reg);
+                events
+                    .by_ref()
+                    .for_each(|msg| {
+                        let host = Arc::clone(&host);
+                        async move { host.process_availability_event(&msg.payload).await }
+                    })
+                    .await;
+                let deadline = { *host.stop_rx.borrow() };
+                host.stop_tx.send_replace(deadline);
+                if events.is_aborted() {
+                    info!("provider availability task gracefully stopped");
+                } else {
+                    error!("provider availability task unexpectedly stopped");
+                }
+            }
+        });
+
         let heartbeat = spawn({
             let host = Arc::clone(&host);
             async move {
@@ -1969,8 +2574,13 @@ impl Host {
             heartbeat_abort.abort();
             queue_abort.abort();
             data_watch_abort.abort();
+            availability_abort.abort();
+            if let Some(metrics_server) = metrics_server {
+                metrics_server.abort();
+            }
             host.policy_manager.policy_changes.abort();
-            let _ = try_join!(queue, data_watch, heartbeat).context("failed to await tasks")?;
+            let _ = try_join!(queue, data_watch, heartbeat, availability)
+                .context("failed to await tasks")?;
             host.publish_event(
                 "host_stopped",
                 json!({
@@ -2025,6 +2635,7 @@ impl Host {
                         .and_then(|jwt::Actor { rev, .. }| *rev)
                         .unwrap_or_default(),
                     name,
+                    limits: component_limits(actor.resource_limits()),
                 })
             })
             .collect()
@@ -2084,6 +2695,53 @@ impl Host {
         Ok(serde_json::to_value(self.inventory().await)?)
     }
 
+    /// Renders the host metrics in the Prometheus text exposition format
+    #[instrument(level = "debug", skip_all)]
+    async fn prometheus_metrics(&self) -> String {
+        use ::prometheus::Encoder as _;
+
+        let metrics = &self.metrics.prometheus;
+        metrics.uptime_seconds.set(
+            self.start_at
+                .elapsed()
+                .as_secs()
+                .try_into()
+                .unwrap_or(i64::MAX),
+        );
+
+        metrics.actor_max_instances.reset();
+        for actor in self.actors.read().await.values() {
+            metrics
+                .actor_max_instances
+                .with_label_values(&[&actor.id, &actor.image_reference])
+                .set(actor.max_instances.get().try_into().unwrap_or(i64::MAX));
+        }
+
+        let policy = self.policy_manager.cache_stats();
+        for (counter, value) in [
+            (&metrics.policy_cache_hits, policy.hits),
+            (&metrics.policy_cache_misses, policy.misses),
+        ] {
+            counter.inc_by(value.saturating_sub(counter.get()));
+        }
+
+        metrics.config_watches.set(
+            self.config_generator
+                .watch_count()
+                .await
+                .try_into()
+                .unwrap_or(i64::MAX),
+        );
+
+        let mut buf = Vec::new();
+        if let Err(err) =
+            ::prometheus::TextEncoder::new().encode(&metrics.registry.gather(), &mut buf)
+        {
+            error!(?err, "failed to encode metrics");
+        }
+        String::from_utf8(buf).unwrap_or_default()
+    }
+
     #[instrument(level = "debug", skip(self))]
     async fn publish_event(&self, name: &str, data: serde_json::Value) -> anyhow::Result<()> {
         event::publish(
@@ -2096,42 +2754,21 @@ impl Host {
         .await
     }
 
-    /// Instantiate an actor
-    #[allow(clippy::too_many_arguments)] // TODO: refactor into a config struct
+    /// Subscribes to invocations of the functions exported by `component` on behalf of `actor_id`
     #[instrument(level = "debug", skip_all)]
-    async fn instantiate_actor(
+    async fn serve_actor_exports(
         &self,
-        annotations: &Annotations,
-        actor_ref: String,
-        actor_id: String,
-        max_instances: NonZeroUsize,
-        component: wasmcloud_runtime::Component,
-        handler: Handler,
-    ) -> anyhow::Result<Arc<Actor>> {
-        trace!(actor_ref, max_instances, "instantiating actor");
-
+        actor_id: &str,
+        component: &wasmcloud_runtime::Component,
+    ) -> anyhow::Result<Invocations> {
         let wrpc = wasmcloud_core::wrpc::Client::new(
             self.rpc_nats.clone(),
             &self.host_config.lattice,
-            &actor_id,
+            actor_id,
             // NOTE(brooksmtownsend): We only use this client for serving functions,
             // and the headers will be set by the incoming invocation.
             async_nats::HeaderMap::new(),
         );
-        let (calls_abort, calls_abort_reg) = AbortHandle::new_pair();
-        let actor = Arc::new(Actor {
-            component: component.clone(),
-            id: actor_id,
-            calls: calls_abort,
-            handler: handler.clone(),
-            annotations: annotations.clone(),
-            max_instances,
-            valid_issuers: self.cluster_issuers.clone(),
-            policy_manager: Arc::clone(&self.policy_manager),
-            image_reference: actor_ref,
-            metrics: Arc::clone(&self.metrics),
-        });
-
         let mut exports: Vec<Box<dyn Stream<Item = _> + Send + Unpin>> = Vec::new();
         for (instance, functions) in component.exports().iter() {
             match instance.as_str() {
@@ -2201,44 +2838,122 @@ impl Host {
             }
         }
 
+        Ok(select_all(exports))
+    }
+
+    /// Returns the invocations to be served by `component` replacing `actor`. If both export the
+    /// same functions, the invocations are handed over from `actor`, which stops accepting them, so
+    /// that no invocation is handled by both
+    async fn replacement_invocations(
+        &self,
+        actor: &Actor,
+        component: &wasmcloud_runtime::Component,
+    ) -> anyhow::Result<Invocations> {
+        if served_functions(&actor.component) == served_functions(component) {
+            if let Some(invocations) = actor.calls.handover().await {
+                return Ok(invocations);
+            }
+        }
+        self.serve_actor_exports(&actor.id, component).await
+    }
+
+    /// Instantiate an actor serving `invocations`
+    #[allow(clippy::too_many_arguments)] // TODO: refactor into a config struct
+    #[instrument(level = "debug", skip_all)]
+    fn instantiate_actor(
+        &self,
+        annotations: &Annotations,
+        actor_ref: String,
+        actor_id: String,
+        max_instances: NonZeroUsize,
+        component: wasmcloud_runtime::Component,
+        handler: Handler,
+        invocations: Invocations,
+    ) -> Arc<Actor> {
+        trace!(actor_ref, max_instances, "instantiating actor");
+
+        let (calls, stop) = Calls::new();
+        let (calls_done_tx, calls_done) = watch::channel(false);
+        let actor = Arc::new(Actor {
+            _recorded: self.metrics.retain_component(&actor_id, &actor_ref),
+            component,
+            id: actor_id,
+            calls,
+            calls_done,
+            handler,
+            annotations: annotations.clone(),
+            max_instances,
+            valid_issuers: self.cluster_issuers.clone(),
+            policy_manager: Arc::clone(&self.policy_manager),
+            image_reference: actor_ref,
+            metrics: Arc::clone(&self.metrics),
+        });
+
         let _calls = spawn({
             let actor = Arc::clone(&actor);
-            Abortable::new(select_all(exports), calls_abort_reg).for_each_concurrent(
-                max_instances.get(),
-                move |invocation| {
-                    let actor = Arc::clone(&actor);
-                    async move {
-                        let AcceptedInvocation {
-                            context,
-                            params,
-                            result_subject,
-                            error_subject,
-                            transmitter,
-                        } = match invocation {
-                            Ok(invocation) => invocation,
-                            Err(err) => {
-                                error!(?err, "failed to accept invocation");
-                                return;
-                            }
-                        };
-                        if let Err(err) = {
-                            actor
-                                .handle_invocation(context, params, result_subject, &transmitter)
-                                .await
-                        } {
-                            error!(?err, "failed to handle invocation");
-                            if let Err(err) = transmitter
-                                .transmit_static(error_subject, format!("{err:#}"))
-                                .await
+            let ctl_nats = self.ctl_nats.clone();
+            let event_builder = self.event_builder.clone();
+            let lattice = self.host_config.lattice.clone();
+            let host_id = self.host_key.public_key();
+            let calls = serve_invocations(invocations, max_instances, stop, move |invocation| {
+                let actor = Arc::clone(&actor);
+                let ctl_nats = ctl_nats.clone();
+                let event_builder = event_builder.clone();
+                let lattice = lattice.clone();
+                let host_id = host_id.clone();
+                async move {
+                    let AcceptedInvocation {
+                        context,
+                        params,
+                        result_subject,
+                        error_subject,
+                        transmitter,
+                    } = match invocation {
+                        Ok(invocation) => invocation,
+                        Err(err) => {
+                            error!(?err, "failed to accept invocation");
+                            return;
+                        }
+                    };
+                    if let Err(err) = {
+                        actor
+                            .handle_invocation(context, params, result_subject, &transmitter)
+                            .await
+                    } {
+                        error!(?err, "failed to handle invocation");
+                        if let Some(limit) = err.downcast_ref::<ExecutionLimitExceeded>() {
+                            if let Err(err) = event::publish(
+                                &event_builder,
+                                &ctl_nats,
+                                &lattice,
+                                "actor_invocation_interrupted",
+                                event::actor_invocation_interrupted(
+                                    &host_id,
+                                    &actor.image_reference,
+                                    &actor.id,
+                                    *limit,
+                                ),
+                            )
+                            .await
                             {
-                                error!(?err, "failed to transmit error to invoker");
+                                warn!(?err, "failed to publish actor invocation interrupted event");
                             }
                         }
+                        if let Err(err) = transmitter
+                            .transmit_static(error_subject, format!("{err:#}"))
+                            .await
+                        {
+                            error!(?err, "failed to transmit error to invoker");
+                        }
                     }
-                },
-            )
+                }
+            });
+            async move {
+                calls.await;
+                calls_done_tx.send_replace(true);
+            }
         });
-        Ok(actor)
+        actor
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -2314,19 +3029,32 @@ impl Host {
             interface_link_name: Arc::new(RwLock::new("default".to_string())),
             interface_links: Arc::new(RwLock::new(component_import_links(&component_spec.links))),
             polyfilled_imports: imports,
+            builtin_keyvalue: self.builtin_keyvalue.clone(),
+            unavailable_providers: Arc::clone(&self.unavailable_providers),
+            link_limiters: Arc::new(RwLock::new(link_limiters(
+                &component_spec.links,
+                &LinkLimiters::default(),
+            ))),
+            metrics: Arc::clone(&self.metrics),
+            circuit_breakers: Arc::clone(&self.circuit_breakers),
+            retry_policy: Arc::new(self.host_config.retry_policy.clone()),
+            ctl_nats: self.ctl_nats.clone(),
+            event_builder: self.event_builder.clone(),
         };
 
-        let actor = self
-            .instantiate_actor(
-                &annotations,
-                actor_ref.clone(),
-                actor_id.clone(),
-                max_instances,
-                component.clone(),
-                handler.clone(),
-            )
+        let invocations = self
+            .serve_actor_exports(&actor_id, &component)
             .await
             .context("failed to instantiate actor")?;
+        let actor = self.instantiate_actor(
+            &annotations,
+            actor_ref.clone(),
+            actor_id.clone(),
+            max_instances,
+            component.clone(),
+            handler.clone(),
+            invocations,
+        );
 
         info!(actor_ref, "actor started");
         self.publish_event(
@@ -2349,7 +3077,7 @@ impl Host {
     async fn stop_actor(&self, actor: &Actor, host_id: &str) -> anyhow::Result<()> {
         trace!(actor_id = %actor.id, "stopping actor");
 
-        actor.calls.abort();
+        actor.calls.stop().await;
 
         self.publish_event(
             "actor_scaled",
@@ -2449,6 +3177,7 @@ impl Host {
             actor_ref,
             self.host_config.allow_file_load,
             &registry_config,
+            &self.bundles,
         )
         .await
         .context("failed to fetch actor")?;
@@ -2478,6 +3207,7 @@ impl Host {
         self.heartbeat.abort();
         self.data_watch.abort();
         self.queue.abort();
+        self.availability.abort();
         self.policy_manager.policy_changes.abort();
         let deadline =
             timeout.and_then(|timeout| Instant::now().checked_add(Duration::from_millis(timeout)));
@@ -2497,6 +3227,7 @@ impl Host {
             annotations,
             max_instances,
             config,
+            limits,
             ..
         } = serde_json::from_slice(payload.as_ref())
             .context("failed to deserialize actor scale command")?;
@@ -2514,6 +3245,7 @@ impl Host {
                     max_instances,
                     annotations,
                     config,
+                    resource_limits(limits),
                 )
                 .await
             {
@@ -2523,6 +3255,7 @@ impl Host {
         Ok(CtlResponse::success())
     }
 
+    #[allow(clippy::too_many_arguments)]
     #[instrument(level = "debug", skip_all)]
     /// Handles scaling an actor to a supplied number of `max` concurrently executing instances.
     /// Supplying `0` will result in stopping that actor instance.
@@ -2534,10 +3267,12 @@ impl Host {
         max_instances: u32,
         annotations: Annotations,
         config: Vec<String>,
+        limits: ResourceLimits,
     ) -> anyhow::Result<()> {
-        trace!(actor_ref, max_instances, "scale actor task");
+        trace!(actor_ref, max_instances, ?limits, "scale actor task");
 
-        let actor = self.fetch_actor(actor_ref).await?;
+        let mut actor = self.fetch_actor(actor_ref).await?;
+        actor.set_resource_limits(limits);
         let claims = actor.claims();
         let resp = self
             .policy_manager
@@ -2636,24 +3371,29 @@ impl Host {
                 let actor = entry.get_mut();
                 let config_changed =
                     &config != actor.handler.config_data.read().await.config_names();
-                // Modify scale only if the requested max differs from the current max or if the configuration has changed
-                if actor.max_instances != max || config_changed {
+                let limits_changed = actor.resource_limits() != limits;
+                // Modify scale only if the requested max differs from the current max or if the configuration or limits have changed
+                if actor.max_instances != max || config_changed || limits_changed {
                     let handler = actor.handler.clone();
                     if config_changed {
                         let mut conf = handler.config_data.write().await;
                         *conf = self.config_generator.generate(config).await?;
                     }
-                    let instance = self
-                        .instantiate_actor(
-                            &annotations,
-                            actor_ref.to_string(),
-                            actor.id.to_string(),
-                            max,
-                            actor.component.clone(),
-                            handler,
-                        )
+                    let mut component = actor.component.clone();
+                    component.set_resource_limits(limits);
+                    let invocations = self
+                        .replacement_invocations(actor, &component)
                         .await
                         .context("failed to instantiate actor")?;
+                    let instance = self.instantiate_actor(
+                        &annotations,
+                        actor_ref.to_string(),
+                        actor.id.to_string(),
+                        max,
+                        component,
+                        handler,
+                        invocations,
+                    );
                     let publish_result = match actor.max_instances.cmp(&max) {
                         std::cmp::Ordering::Less | std::cmp::Ordering::Greater => {
                             self.publish_event(
@@ -2691,7 +3431,7 @@ impl Host {
     // Should it also update configuration, or is that separate? Should scaling be done via an update?
     #[instrument(level = "debug", skip_all)]
     async fn handle_update_actor(
-        &self,
+        self: Arc<Self>,
         payload: impl AsRef<[u8]>,
         host_id: &str,
     ) -> anyhow::Result<CtlResponse<()>> {
@@ -2699,6 +3439,7 @@ impl Host {
             actor_id,
             annotations,
             new_actor_ref,
+            hot_update,
             ..
         } = serde_json::from_slice(payload.as_ref())
             .context("failed to deserialize actor update command")?;
@@ -2707,14 +3448,40 @@ impl Host {
             actor_id,
             new_actor_ref,
             ?annotations,
+            ?hot_update,
             "handling update actor"
         );
 
-        let actors = self.actors.read().await;
-        let actor = actors.get(&actor_id).context("actor not found")?;
+        let actor = self
+            .actors
+            .read()
+            .await
+            .get(&actor_id)
+            .cloned()
+            .context("actor not found")?;
         let annotations = annotations.unwrap_or_default().into_iter().collect();
 
-        let new_actor = self.fetch_actor(&new_actor_ref).await?;
+        if let Some(options) = hot_update {
+            let host_id = host_id.to_string();
+            spawn(async move {
+                if let Err(err) = self
+                    .handle_hot_update_actor_task(
+                        actor,
+                        &annotations,
+                        &new_actor_ref,
+                        &host_id,
+                        options,
+                    )
+                    .await
+                {
+                    error!(%new_actor_ref, %actor_id, ?err, "failed to hot update actor");
+                }
+            });
+            return Ok(CtlResponse::success());
+        }
+
+        let mut new_actor = self.fetch_actor(&new_actor_ref).await?;
+        new_actor.set_resource_limits(actor.resource_limits());
         let new_claims = new_actor.claims();
         if let Some(claims) = new_claims.cloned() {
             self.store_claims(Claims::Actor(claims))
@@ -2723,19 +3490,19 @@ impl Host {
         }
 
         let max = actor.max_instances;
-        let Ok(new_actor) = self
-            .instantiate_actor(
-                &annotations,
-                new_actor_ref.clone(),
-                actor_id.clone(),
-                max,
-                new_actor.clone(),
-                actor.handler.clone(),
-            )
+        let invocations = self
+            .replacement_invocations(&actor, &new_actor)
             .await
-        else {
-            bail!("failed to instantiate actor from new reference");
-        };
+            .context("failed to instantiate actor from new reference")?;
+        let new_actor = self.instantiate_actor(
+            &annotations,
+            new_actor_ref.clone(),
+            actor_id.clone(),
+            max,
+            new_actor.clone(),
+            actor.handler.clone(),
+            invocations,
+        );
 
         info!(%new_actor_ref, "actor updated");
         self.publish_event(
@@ -2752,30 +3519,280 @@ impl Host {
         .await?;
 
         // TODO(#1548): If this errors, we need to rollback
-        self.stop_actor(actor, host_id)
+        self.stop_actor(&actor, host_id)
             .await
             .context("failed to stop old actor")?;
         self.publish_event(
             "actor_scaled",
             event::actor_scaled(
-                actor.claims(),
-                &actor.annotations,
+                actor.claims(),
+                &actor.annotations,
+                host_id,
+                0_usize,
+                &actor.image_reference,
+                &actor.id,
+            ),
+        )
+        .await?;
+
+        self.actors.write().await.insert(actor_id, new_actor);
+
+        Ok(CtlResponse::success())
+    }
+
+    /// Hot updates `actor` to `new_actor_ref`. The new version takes over serving invocations from
+    /// the old version, which is then drained, and is watched for invocation errors. If the new version
+    /// fails to start or produces more than the allowed number of errors, `actor` is restored
+    #[instrument(level = "debug", skip_all, fields(actor_id = %actor.id, new_actor_ref))]
+    async fn handle_hot_update_actor_task(
+        &self,
+        actor: Arc<Actor>,
+        annotations: &Annotations,
+        new_actor_ref: &str,
+        host_id: &str,
+        options: HotUpdateOptions,
+    ) -> anyhow::Result<()> {
+        let drain_timeout = options
+            .drain_timeout_ms
+            .map_or(DEFAULT_UPDATE_DRAIN_TIMEOUT, Duration::from_millis);
+        let watch_window = options
+            .watch_window_ms
+            .map_or(DEFAULT_UPDATE_WATCH_WINDOW, Duration::from_millis);
+        let max_errors = options.max_errors.unwrap_or_default();
+
+        let new_actor = match self
+            .start_actor_update(&actor, annotations, new_actor_ref)
+            .await
+        {
+            Ok(new_actor) => new_actor,
+            Err(err) => {
+                // The old version never stopped serving invocations, so there is nothing to restore
+                self.publish_event(
+                    "actor_update_failed",
+                    event::actor_update_failed(
+                        host_id,
+                        &actor.id,
+                        &actor.image_reference,
+                        new_actor_ref,
+                        &err,
+                    ),
+                )
+                .await?;
+                return Err(err);
+            }
+        };
+        let baseline = self
+            .metrics
+            .component_errors(&new_actor.id, &new_actor.image_reference);
+
+        if !actor.drain(drain_timeout).await {
+            warn!(
+                ?drain_timeout,
+                "in-flight invocations of the old version did not complete in time"
+            );
+        }
+        self.actors
+            .write()
+            .await
+            .insert(actor.id.clone(), Arc::clone(&new_actor));
+        self.publish_event(
+            "actor_scaled",
+            event::actor_scaled(
+                actor.claims(),
+                &actor.annotations,
+                host_id,
+                0_usize,
+                &actor.image_reference,
+                &actor.id,
+            ),
+        )
+        .await?;
+        self.publish_event(
+            "actor_scaled",
+            event::actor_scaled(
+                new_actor.claims(),
+                &new_actor.annotations,
+                host_id,
+                new_actor.max_instances,
+                &new_actor.image_reference,
+                &new_actor.id,
+            ),
+        )
+        .await?;
+        info!(
+            ?watch_window,
+            max_errors, "actor updated, watching new version"
+        );
+
+        let errors = match watch_update(
+            || self.is_current_actor(&new_actor),
+            || {
+                self.metrics
+                    .component_errors(&new_actor.id, &new_actor.image_reference)
+                    .saturating_sub(baseline)
+            },
+            max_errors,
+            watch_window,
+        )
+        .await
+        {
+            UpdateWatch::Completed => {
+                info!("hot update completed");
+                return Ok(());
+            }
+            UpdateWatch::Superseded => {
+                debug!("actor changed while watching new version, ending hot update");
+                return Ok(());
+            }
+            UpdateWatch::Failed(errors) => errors,
+        };
+
+        let err = anyhow!(
+            "new version produced {errors} invocation errors, exceeding the maximum of {max_errors}"
+        );
+        warn!(?err, "rolling back actor update");
+        self.publish_event(
+            "actor_update_failed",
+            event::actor_update_failed(
+                host_id,
+                &actor.id,
+                &actor.image_reference,
+                new_actor_ref,
+                &err,
+            ),
+        )
+        .await?;
+        self.rollback_actor_update(&actor, &new_actor, host_id, drain_timeout)
+            .await
+            .context("failed to roll back actor update")
+    }
+
+    /// Fetches and instantiates the new version of `actor` from `new_actor_ref`, which takes over
+    /// serving invocations from `actor`. The new version must export the same functions
+    async fn start_actor_update(
+        &self,
+        actor: &Actor,
+        annotations: &Annotations,
+        new_actor_ref: &str,
+    ) -> anyhow::Result<Arc<Actor>> {
+        let mut component = self.fetch_actor(new_actor_ref).await?;
+        component.set_resource_limits(actor.resource_limits());
+        if let Some(claims) = component.claims().cloned() {
+            self.store_claims(Claims::Actor(claims))
+                .await
+                .context("failed to store claims")?;
+        }
+        // Warm up the new version, so that it does not start serving if it cannot be instantiated
+        component
+            .warm_up()
+            .await
+            .context("failed to instantiate new version")?;
+        ensure!(
+            served_functions(&component) == served_functions(&actor.component),
+            "new version must export the same functions as the old version"
+        );
+        let invocations = actor
+            .calls
+            .handover()
+            .await
+            .context("old version is not serving invocations anymore")?;
+        Ok(self.instantiate_actor(
+            annotations,
+            new_actor_ref.to_string(),
+            actor.id.clone(),
+            actor.max_instances,
+            component,
+            actor.handler.clone(),
+            invocations,
+        ))
+    }
+
+    /// Restores `actor`, which was replaced by `new_actor` in a hot update
+    async fn rollback_actor_update(
+        &self,
+        actor: &Actor,
+        new_actor: &Arc<Actor>,
+        host_id: &str,
+        drain_timeout: Duration,
+    ) -> anyhow::Result<()> {
+        let restored = {
+            let mut actors = self.actors.write().await;
+            ensure!(
+                matches!(actors.get(&actor.id), Some(current) if Arc::ptr_eq(current, new_actor)),
+                "actor changed during the update, not rolling back"
+            );
+            let invocations = new_actor
+                .calls
+                .handover()
+                .await
+                .context("new version is not serving invocations anymore")?;
+            let restored = self.instantiate_actor(
+                &actor.annotations,
+                actor.image_reference.clone(),
+                actor.id.clone(),
+                actor.max_instances,
+                actor.component.clone(),
+                actor.handler.clone(),
+                invocations,
+            );
+            actors.insert(actor.id.clone(), Arc::clone(&restored));
+            restored
+        };
+        if !new_actor.drain(drain_timeout).await {
+            warn!(
+                ?drain_timeout,
+                "in-flight invocations of the new version did not complete in time"
+            );
+        }
+        self.publish_event(
+            "actor_scaled",
+            event::actor_scaled(
+                new_actor.claims(),
+                &new_actor.annotations,
+                host_id,
+                0_usize,
+                &new_actor.image_reference,
+                &new_actor.id,
+            ),
+        )
+        .await?;
+        self.publish_event(
+            "actor_scaled",
+            event::actor_scaled(
+                restored.claims(),
+                &restored.annotations,
                 host_id,
-                0_usize,
-                &actor.image_reference,
-                &actor.id,
+                restored.max_instances,
+                &restored.image_reference,
+                &restored.id,
             ),
         )
         .await?;
+        info!(image_ref = restored.image_reference, "actor rolled back");
+        self.publish_event(
+            "actor_rolled_back",
+            event::actor_rolled_back(
+                host_id,
+                &restored.id,
+                &restored.image_reference,
+                &new_actor.image_reference,
+            ),
+        )
+        .await
+    }
 
-        self.actors.write().await.insert(actor_id, new_actor);
-
-        Ok(CtlResponse::success())
+    /// Returns whether `actor` is the actor currently running under its ID
+    async fn is_current_actor(&self, actor: &Arc<Actor>) -> bool {
+        self.actors
+            .read()
+            .await
+            .get(&actor.id)
+            .is_some_and(|current| Arc::ptr_eq(current, actor))
     }
 
     #[instrument(level = "debug", skip_all)]
     async fn handle_start_provider_task(
-        &self,
+        self: Arc<Self>,
         config: ConfigBundle,
         provider_id: &str,
         provider_ref: &str,
@@ -2790,6 +3807,7 @@ impl Host {
             host_id,
             self.host_config.allow_file_load,
             &registry_config,
+            &self.bundles,
         )
         .await
         .context("failed to fetch provider")?;
@@ -2814,7 +3832,7 @@ impl Host {
             "policy denied request to start provider `{request_id}`: `{message:?}`",
         );
 
-        let component_specification =
+        let mut component_specification =
             if let Ok(Some(mut spec)) = self.get_component_spec(provider_id).await {
                 // If the component didn't start yet, the URL will be empty but the spec may contain links.
                 // Populate the URL and store the updated spec.
@@ -2832,121 +3850,25 @@ impl Host {
             } else {
                 ComponentSpecification::new(provider_ref)
             };
+        // Always take the schema of the provider being started, so that links put from now on are
+        // validated against the schema of the running version
+        component_specification.config_schema = claims
+            .as_ref()
+            .and_then(|claims| claims.metadata.as_ref())
+            .and_then(|metadata| metadata.config_schema.clone());
         self.store_component_spec(&provider_id, &component_specification)
             .await?;
 
         let mut providers = self.providers.write().await;
         if let hash_map::Entry::Vacant(entry) = providers.entry(provider_id.into()) {
-            let invocation_seed = self
-                .cluster_key
-                .seed()
-                .context("cluster key seed missing")?;
-            let lattice_rpc_user_seed = self
-                .host_config
-                .rpc_key
-                .as_ref()
-                .map(|key| key.seed())
-                .transpose()
-                .context("private key missing for provider RPC key")?;
-            let default_rpc_timeout_ms = Some(
-                self.host_config
-                    .rpc_timeout
-                    .as_millis()
-                    .try_into()
-                    .context("failed to convert rpc_timeout to u64")?,
-            );
-            let otel_config = OtelConfig {
-                enable_observability: self.host_config.otel_config.enable_observability,
-                enable_traces: self.host_config.otel_config.enable_traces,
-                enable_metrics: self.host_config.otel_config.enable_metrics,
-                enable_logs: self.host_config.otel_config.enable_logs,
-                observability_endpoint: self.host_config.otel_config.observability_endpoint.clone(),
-                traces_endpoint: self.host_config.otel_config.traces_endpoint.clone(),
-                metrics_endpoint: self.host_config.otel_config.metrics_endpoint.clone(),
-                logs_endpoint: self.host_config.otel_config.logs_endpoint.clone(),
-            };
-            let host_data = HostData {
-                host_id: self.host_key.public_key(),
-                lattice_rpc_prefix: self.host_config.lattice.clone(),
-                link_name: "default".to_string(),
-                lattice_rpc_user_jwt: self.host_config.rpc_jwt.clone().unwrap_or_default(),
-                lattice_rpc_user_seed: lattice_rpc_user_seed.unwrap_or_default(),
-                lattice_rpc_url: self.host_config.rpc_nats_url.to_string(),
-                env_values: vec![],
-                instance_id: Uuid::new_v4().to_string(),
-                provider_key: provider_id.to_string(),
-                link_definitions: self
-                    .links
-                    .read()
-                    .await
-                    .get(provider_id)
-                    .cloned()
-                    .map(|links| {
-                        links
-                            .into_iter()
-                            // TODO(named config): deliver actual source and target config to the provider
-                            .map(|link| wasmcloud_core::InterfaceLinkDefinition {
-                                source_id: link.source_id,
-                                target: link.target,
-                                name: link.name,
-                                wit_namespace: link.wit_namespace,
-                                wit_package: link.wit_package,
-                                interfaces: link.interfaces,
-                                source_config: link.source_config,
-                                target_config: link.target_config,
-                            })
-                            .collect()
-                    })
-                    .unwrap_or_default(),
-                config: config.get_config().await.clone(),
-                default_rpc_timeout_ms,
-                cluster_issuers: self.cluster_issuers.clone(),
-                invocation_seed,
-                log_level: Some(self.host_config.log_level.clone()),
-                structured_logging: self.host_config.enable_structured_logging,
-                otel_config,
-            };
-            let host_data =
-                serde_json::to_vec(&host_data).context("failed to serialize provider data")?;
-
-            trace!("spawn provider process");
-
-            let mut child_cmd = process::Command::new(&path);
-            // Prevent the provider from inheriting the host's environment, with the exception of
-            // the following variables we manually add back
-            child_cmd.env_clear();
-
-            if cfg!(windows) {
-                // Proxy SYSTEMROOT to providers. Without this, providers on Windows won't be able to start
-                child_cmd.env(
-                    "SYSTEMROOT",
-                    env::var("SYSTEMROOT")
-                        .context("SYSTEMROOT is not set. Providers cannot be started")?,
-                );
-            }
-
-            // Proxy RUST_LOG to (Rust) providers, so they can use the same module-level directives
-            if let Ok(rust_log) = env::var("RUST_LOG") {
-                let _ = child_cmd.env("RUST_LOG", rust_log);
-            }
-
-            let mut child = child_cmd
-                .stdin(Stdio::piped())
-                .kill_on_drop(true)
-                .spawn()
-                .context("failed to spawn provider process")?;
-            let mut stdin = child.stdin.take().context("failed to take stdin")?;
-            stdin
-                .write_all(STANDARD.encode(&host_data).as_bytes())
-                .await
-                .context("failed to write provider data")?;
-            stdin
-                .write_all(b"\r\n")
-                .await
-                .context("failed to write newline")?;
-            stdin.shutdown().await.context("failed to close stdin")?;
+            let health = health::Config::new(&annotations, &*config.get_config().await)
+                .context("invalid provider health check settings")?;
+            let mut child = self
+                .spawn_provider_process(&path, provider_id, &config)
+                .await?;
 
-            // TODO: Change method receiver to Arc<Self> and `move` into the closure
+            // NOTE: The supervisor only holds a weak reference to the host, which owns its handle
+            let host = Arc::downgrade(&self);
             let rpc_nats = self.rpc_nats.clone();
             let ctl_nats = self.ctl_nats.clone();
             let event_builder = self.event_builder.clone();
@@ -2954,91 +3876,113 @@ impl Host {
             let health_lattice = self.host_config.lattice.clone();
             let health_host_id = host_id.to_string();
             let health_provider_id = provider_id.to_string();
+            let health_image_ref = provider_ref.to_string();
+            let health_metrics = Arc::clone(&self.metrics);
+            let health_unavailable = Arc::clone(&self.unavailable_providers);
             let child = spawn(async move {
-                // Check the health of the provider every 30 seconds
-                let mut health_check = tokio::time::interval(Duration::from_secs(30));
-                let mut previous_healthy = false;
-                // Allow the provider 5 seconds to initialize
-                health_check.reset_after(Duration::from_secs(5));
                 let health_topic =
                     format!("wasmbus.rpc.{health_lattice}.{health_provider_id}.health");
-                // TODO: Refactor this logic to simplify nesting
+                let publish_health_event = |name| {
+                    event::publish(
+                        &event_builder,
+                        &ctl_nats,
+                        &health_lattice,
+                        name,
+                        event::provider_health_check(&health_host_id, &health_provider_id),
+                    )
+                };
+                // Restarts of the provider within the restart window, used to detect crash loops
+                let mut restarts = VecDeque::new();
                 loop {
-                    select! {
-                        _ = health_check.tick() => {
-                            trace!(provider_id=health_provider_id, "performing provider health check");
-                            let request = async_nats::Request::new()
-                                .payload(Bytes::new())
-                                .headers(injector_to_headers(&TraceContextInjector::default_with_span()));
-                            if let Ok(async_nats::Message { payload, ..}) = rpc_nats.send_request(
-                                health_topic.clone(),
-                                request,
-                                ).await {
-                                    match (serde_json::from_slice::<HealthCheckResponse>(&payload), previous_healthy) {
-                                        (Ok(HealthCheckResponse { healthy: true, ..}), false) => {
-                                            trace!(provider_id=health_provider_id, "provider health check succeeded");
-                                            previous_healthy = true;
-                                            if let Err(e) = event::publish(
-                                                &event_builder,
-                                                &ctl_nats,
-                                                &health_lattice,
-                                                "health_check_passed",
-                                                event::provider_health_check(
-                                                    &health_host_id,
-                                                    &health_provider_id,
-                                                )
-                                            ).await {
-                                                warn!(?e, "failed to publish provider health check succeeded event");
-                                            }
-                                        },
-                                        (Ok(HealthCheckResponse { healthy: false, ..}), true) => {
-                                            trace!(provider_id=health_provider_id, "provider health check failed");
-                                            previous_healthy = false;
-                                            if let Err(e) = event::publish(
-                                                &event_builder,
-                                                &ctl_nats,
-                                                &health_lattice,
-                                                "health_check_failed",
-                                                event::provider_health_check(
-                                                    &health_host_id,
-                                                    &health_provider_id,
-                                                )
-                                            ).await {
-                                                warn!(?e, "failed to publish provider health check failed event");
-                                            }
-                                        }
+                    let mut health_check = tokio::time::interval(health.interval);
+                    let mut previous_healthy = false;
+                    let mut consecutive_failures = 0;
+                    // Allow the provider to initialize
+                    health_check.reset_after(health.initial_delay);
+                    let (failed, exit_status, force_restart) = loop {
+                        select! {
+                            _ = health_check.tick() => {
+                                trace!(provider_id=health_provider_id, "performing provider health check");
+                                let healthy = if let Some(healthy) = request_provider_health(&rpc_nats, health_topic.clone(), health.timeout).await {
+                                    let name = match (healthy, previous_healthy) {
+                                        (true, false) => "health_check_passed",
+                                        (false, true) => "health_check_failed",
                                         // If the provider health status didn't change, we simply publish a health check status event
-                                        (Ok(_), _) => {
-                                            if let Err(e) = event::publish(
-                                                &event_builder,
-                                                &ctl_nats,
-                                                &health_lattice,
-                                                "health_check_status",
-                                                event::provider_health_check(
-                                                    &health_host_id,
-                                                    &health_provider_id,
-                                                )
-                                            ).await {
-                                                warn!(?e, "failed to publish provider health check status event");
-                                            }
-                                        },
-                                        _ => warn!("failed to deserialize provider health check response"),
+                                        _ => "health_check_status",
+                                    };
+                                    trace!(provider_id=health_provider_id, healthy, "provider health check completed");
+                                    previous_healthy = healthy;
+                                    if let Err(e) = publish_health_event(name).await {
+                                        warn!(?e, name, "failed to publish provider health check event");
+                                    }
+                                    healthy
+                                } else {
+                                    warn!(interval = ?health.interval, "failed to request provider health, retrying");
+                                    false
+                                };
+                                health_metrics.record_provider_health(&health_provider_id, &health_image_ref, healthy);
+                                if healthy {
+                                    consecutive_failures = 0;
+                                    if health_unavailable.write().await.mark_available(&health_provider_id, &health_host_id) {
+                                        info!(provider_id=health_provider_id, "provider is healthy again, marking it available");
+                                        if let Err(e) = publish_health_event("provider_available").await {
+                                            warn!(?e, "failed to publish provider available event");
+                                        }
                                     }
+                                    continue;
                                 }
-                                else {
-                                    warn!("failed to request provider health, retrying in 30 seconds");
+                                consecutive_failures += 1;
+                                if consecutive_failures != health.failure_threshold {
+                                    continue;
+                                }
+                                match health.unhealthy_action {
+                                    UnhealthyAction::None => {}
+                                    UnhealthyAction::MarkUnavailable => {
+                                        warn!(provider_id=health_provider_id, consecutive_failures, "provider is unhealthy, marking it unavailable");
+                                        health_unavailable.write().await.mark_unavailable(&health_provider_id, &health_host_id);
+                                        if let Err(e) = publish_health_event("provider_unavailable").await {
+                                            warn!(?e, "failed to publish provider unavailable event");
+                                        }
+                                    }
+                                    UnhealthyAction::Restart => {
+                                        warn!(provider_id=health_provider_id, consecutive_failures, "provider is unhealthy, restarting it");
+                                        if let Err(e) = child.kill().await {
+                                            warn!(?e, "failed to kill unhealthy provider");
+                                        }
+                                        break (true, format!("failed {consecutive_failures} consecutive health checks"), true);
+                                    }
                                 }
-                        }
-                        exit_status = child.wait() => match exit_status {
-                            Ok(status) => {
-                                debug!("`{}` exited with `{status:?}`", path.display());
-                                break;
-                            }
-                            Err(e) => {
-                                warn!("failed to wait for `{}` to execute: {e}", path.display());
-                                break;
                             }
+                            exit_status = child.wait() => match exit_status {
+                                Ok(status) => {
+                                    debug!("`{}` exited with `{status:?}`", path.display());
+                                    break (!status.success(), status.to_string(), false);
+                                }
+                                Err(e) => {
+                                    warn!("failed to wait for `{}` to execute: {e}", path.display());
+                                    break (true, e.to_string(), false);
+                                }
+                            },
                         }
+                    };
+                    let Some(host) = host.upgrade() else {
+                        return;
+                    };
+                    match host
+                        .handle_provider_exit(
+                            &path,
+                            &health_provider_id,
+                            &config,
+                            &health_host_id,
+                            failed,
+                            &exit_status,
+                            force_restart,
+                            &mut restarts,
+                        )
+                        .await
+                    {
+                        Some(restarted) => child = restarted,
+                        None => return,
                     }
                 }
             });
@@ -3066,6 +4010,289 @@ impl Host {
         Ok(())
     }
 
+    /// Spawns the process of provider `provider_id` from the binary at `path`, passing it the
+    /// [`HostData`] including the links currently targeting it on stdin
+    #[instrument(level = "debug", skip(self, path, config))]
+    async fn spawn_provider_process(
+        &self,
+        path: &Path,
+        provider_id: &str,
+        config: &ConfigBundle,
+    ) -> anyhow::Result<process::Child> {
+        let invocation_seed = self
+            .cluster_key
+            .seed()
+            .context("cluster key seed missing")?;
+        let lattice_rpc_user_seed = self
+            .host_config
+            .rpc_key
+            .as_ref()
+            .map(|key| key.seed())
+            .transpose()
+            .context("private key missing for provider RPC key")?;
+        let default_rpc_timeout_ms = Some(
+            self.host_config
+                .rpc_timeout
+                .as_millis()
+                .try_into()
+                .context("failed to convert rpc_timeout to u64")?,
+        );
+        let otel_config = OtelConfig {
+            enable_observability: self.host_config.otel_config.enable_observability,
+            enable_traces: self.host_config.otel_config.enable_traces,
+            enable_metrics: self.host_config.otel_config.enable_metrics,
+            enable_logs: self.host_config.otel_config.enable_logs,
+            observability_endpoint: self.host_config.otel_config.observability_endpoint.clone(),
+            traces_endpoint: self.host_config.otel_config.traces_endpoint.clone(),
+            metrics_endpoint: self.host_config.otel_config.metrics_endpoint.clone(),
+            logs_endpoint: self.host_config.otel_config.logs_endpoint.clone(),
+        };
+        let host_data = HostData {
+            host_id: self.host_key.public_key(),
+            lattice_rpc_prefix: self.host_config.lattice.clone(),
+            link_name: "default".to_string(),
+            lattice_rpc_user_jwt: self.host_config.rpc_jwt.clone().unwrap_or_default(),
+            lattice_rpc_user_seed: lattice_rpc_user_seed.unwrap_or_default(),
+            lattice_rpc_url: self.host_config.rpc_nats_url.to_string(),
+            env_values: vec![],
+            instance_id: Uuid::new_v4().to_string(),
+            provider_key: provider_id.to_string(),
+            link_definitions: self
+                .links
+                .read()
+                .await
+                .get(provider_id)
+                .cloned()
+                .map(|links| {
+                    links
+                        .into_iter()
+                        // TODO(named config): deliver actual source and target config to the provider
+                        .map(|link| wasmcloud_core::InterfaceLinkDefinition {
+                            source_id: link.source_id,
+                            target: link.target,
+                            name: link.name,
+                            wit_namespace: link.wit_namespace,
+                            wit_package: link.wit_package,
+                            interfaces: link.interfaces,
+                            source_config: link.source_config,
+                            target_config: link.target_config,
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            config: config.get_config().await.clone(),
+            default_rpc_timeout_ms,
+            rpc_inbox_prefix: self
+                .host_config
+                .rpc_lattice_inbox
+                .then(|| wasmcloud_core::nats::rpc_inbox_prefix(&self.host_config.lattice)),
+            cluster_issuers: self.cluster_issuers.clone(),
+            invocation_seed,
+            log_level: Some(self.host_config.log_level.clone()),
+            structured_logging: self.host_config.enable_structured_logging,
+            otel_config,
+        };
+        let host_data =
+            serde_json::to_vec(&host_data).context("failed to serialize provider data")?;
+
+        trace!("spawn provider process");
+
+        let mut child_cmd = process::Command::new(path);
+        // Prevent the provider from inheriting the host's environment, with the exception of
+        // the following variables we manually add back
+        child_cmd.env_clear();
+
+        if cfg!(windows) {
+            // Proxy SYSTEMROOT to providers. Without this, providers on Windows won't be able to start
+            child_cmd.env(
+                "SYSTEMROOT",
+                env::var("SYSTEMROOT")
+                    .context("SYSTEMROOT is not set. Providers cannot be started")?,
+            );
+        }
+
+        // Proxy RUST_LOG to (Rust) providers, so they can use the same module-level directives
+        if let Ok(rust_log) = env::var("RUST_LOG") {
+            let _ = child_cmd.env("RUST_LOG", rust_log);
+        }
+
+        let mut child = child_cmd
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .context("failed to spawn provider process")?;
+
+        let history = Arc::clone(
+            self.provider_logs
+                .write()
+                .await
+                .entry(provider_id.to_string())
+                .or_default(),
+        );
+        let subject = format!("wasmbus.logs.{}.{provider_id}", self.host_config.lattice);
+        let stdout = child.stdout.take().context("failed to take stdout")?;
+        let stderr = child.stderr.take().context("failed to take stderr")?;
+        for (output, stream) in [
+            (
+                Box::new(stdout) as Box<dyn AsyncRead + Send + Unpin>,
+                logs::Stream::Stdout,
+            ),
+            (Box::new(stderr), logs::Stream::Stderr),
+        ] {
+            let host_id = self.host_key.public_key();
+            let provider_id = provider_id.to_string();
+            let history = Arc::clone(&history);
+            let nats = self
+                .host_config
+                .publish_provider_logs
+                .then(|| (self.ctl_nats.clone(), subject.clone()));
+            spawn(async move {
+                logs::capture(output, stream, host_id, provider_id, &history, nats).await;
+            });
+        }
+        let mut stdin = child.stdin.take().context("failed to take stdin")?;
+        stdin
+            .write_all(STANDARD.encode(&host_data).as_bytes())
+            .await
+            .context("failed to write provider data")?;
+        stdin
+            .write_all(b"\r\n")
+            .await
+            .context("failed to write newline")?;
+        stdin.shutdown().await.context("failed to close stdin")?;
+        Ok(child)
+    }
+
+    /// Handles the exit of the process of provider `provider_id` according to the configured
+    /// [`ProviderRestartPolicy`], returning the restarted process, if any. Providers are always
+    /// restarted if `force_restart` is set, e.g. after failing their health checks. Once the
+    /// provider is no longer restarted, it is removed from the host and a `provider_stopped` event
+    /// is published
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(level = "debug", skip(self, path, config, restarts))]
+    async fn handle_provider_exit(
+        &self,
+        path: &Path,
+        provider_id: &str,
+        config: &ConfigBundle,
+        host_id: &str,
+        failed: bool,
+        exit_status: &str,
+        force_restart: bool,
+        restarts: &mut VecDeque<Instant>,
+    ) -> Option<process::Child> {
+        // Providers stopped via the control interface are removed before their process exits
+        if !self.providers.read().await.contains_key(provider_id) {
+            return None;
+        }
+        if self
+            .unavailable_providers
+            .write()
+            .await
+            .mark_available(provider_id, host_id)
+        {
+            if let Err(e) = self
+                .publish_event(
+                    "provider_available",
+                    event::provider_health_check(host_id, provider_id),
+                )
+                .await
+            {
+                warn!(?e, "failed to publish provider available event");
+            }
+        }
+        let restart = force_restart
+            || match self.host_config.provider_restart_policy {
+                ProviderRestartPolicy::Never => false,
+                ProviderRestartPolicy::OnFailure => failed,
+                ProviderRestartPolicy::Always => true,
+            };
+        let reason = if restart {
+            let window = self.host_config.provider_restart_window;
+            loop {
+                let ProviderRestart::After(backoff) = provider_restart(
+                    restarts,
+                    Instant::now(),
+                    window,
+                    self.host_config.provider_max_restarts,
+                ) else {
+                    error!(
+                        provider_id,
+                        exit_status,
+                        restarts = restarts.len(),
+                        ?window,
+                        "provider is crash looping, no longer restarting it"
+                    );
+                    if let Err(e) = self
+                        .publish_event(
+                            "provider_crash_loop",
+                            event::provider_crash_loop(
+                                host_id,
+                                provider_id,
+                                exit_status,
+                                restarts.len(),
+                                window,
+                            ),
+                        )
+                        .await
+                    {
+                        warn!(?e, "failed to publish provider crash loop event");
+                    }
+                    break "crash_loop";
+                };
+                warn!(provider_id, exit_status, ?backoff, "restarting provider");
+                tokio::time::sleep(backoff).await;
+                restarts.push_back(Instant::now());
+                match self.spawn_provider_process(path, provider_id, config).await {
+                    Ok(child) => {
+                        info!(provider_id, restarts = restarts.len(), "provider restarted");
+                        if let Err(e) = self
+                            .publish_event(
+                                "provider_restarted",
+                                event::provider_restarted(
+                                    host_id,
+                                    provider_id,
+                                    exit_status,
+                                    restarts.len(),
+                                ),
+                            )
+                            .await
+                        {
+                            warn!(?e, "failed to publish provider restarted event");
+                        }
+                        return Some(child);
+                    }
+                    // Failing to respawn the process counts towards crash loop detection
+                    Err(err) => error!(provider_id, ?err, "failed to restart provider"),
+                }
+            }
+        } else {
+            "exit"
+        };
+
+        let Provider {
+            annotations,
+            claims,
+            image_ref,
+            ..
+        } = self.providers.write().await.remove(provider_id)?;
+        self.metrics.remove_provider_health(provider_id, &image_ref);
+        self.provider_logs.write().await.remove(provider_id);
+        info!(provider_id, reason, "provider stopped");
+        if let Err(e) = self
+            .publish_event(
+                "provider_stopped",
+                event::provider_stopped(claims, &annotations, host_id, provider_id, reason),
+            )
+            .await
+        {
+            warn!(?e, "failed to publish provider stopped event");
+        }
+        None
+    }
+
     #[instrument(level = "debug", skip_all)]
     async fn handle_start_provider(
         self: Arc<Self>,
@@ -3098,7 +4325,7 @@ impl Host {
 
         let host_id = host_id.to_string();
         spawn(async move {
-            if let Err(err) = self
+            if let Err(err) = Arc::clone(&self)
                 .handle_start_provider_task(
                     config,
                     &provider_id,
@@ -3146,6 +4373,7 @@ impl Host {
             child,
             annotations,
             claims,
+            image_ref,
             ..
         } = entry.remove();
 
@@ -3175,6 +4403,13 @@ impl Host {
             );
         }
         child.abort();
+        self.metrics
+            .remove_provider_health(&provider_id, &image_ref);
+        self.provider_logs.write().await.remove(&provider_id);
+        self.unavailable_providers
+            .write()
+            .await
+            .mark_available(&provider_id, host_id);
         info!(provider_id, "provider stopped");
         self.publish_event(
             "provider_stopped",
@@ -3184,6 +4419,26 @@ impl Host {
         Ok(CtlResponse::success())
     }
 
+    #[instrument(level = "debug", skip_all)]
+    async fn handle_provider_logs(
+        &self,
+        payload: impl AsRef<[u8]>,
+    ) -> anyhow::Result<CtlResponse<Vec<ProviderLogLine>>> {
+        let ProviderLogsCommand { provider_id, tail } = serde_json::from_slice(payload.as_ref())
+            .context("failed to deserialize provider logs command")?;
+
+        trace!(provider_id, "handling provider logs");
+
+        let Some(history) = self.provider_logs.read().await.get(&provider_id).cloned() else {
+            return Ok(CtlResponse {
+                success: false,
+                message: "no logs retained for a provider with that ID".to_string(),
+                response: None,
+            });
+        };
+        Ok(CtlResponse::ok(history.tail(tail)))
+    }
+
     #[instrument(level = "debug", skip_all)]
     async fn handle_inventory(&self) -> anyhow::Result<CtlResponse<HostInventory>> {
         trace!("handling inventory");
@@ -3287,6 +4542,7 @@ impl Host {
             name,
             source_config: _,
             target_config: _,
+            limits,
         } = interface_link_definition.clone();
 
         let ns_and_package = format!("{}:{}", wit_namespace, wit_package);
@@ -3296,9 +4552,24 @@ impl Host {
             ns_and_package,
             name,
             ?interfaces,
+            ?limits,
             "handling put wrpc link definition"
         );
 
+        if let Some(limits) = &limits {
+            if let Err(e) = validate_link_limits(limits) {
+                return Ok(CtlResponse::error(&format!("invalid link limits: {e}")));
+            }
+        }
+
+        if let Some(message) = self
+            .validate_link_config(&interface_link_definition)
+            .await?
+            .violations
+        {
+            return Ok(CtlResponse::error(&message));
+        }
+
         // Note here that unwrapping to a default is intentional. If the component spec doesn't exist, we want to create it
         // so that when that component does start it can use pre-existing links.
         let mut component_spec = self
@@ -3335,6 +4606,108 @@ impl Host {
         Ok(CtlResponse::success())
     }
 
+    #[instrument(level = "debug", skip_all)]
+    /// Validate the configuration of an interface link without putting it
+    async fn handle_interface_link_validate(
+        &self,
+        payload: impl AsRef<[u8]>,
+    ) -> anyhow::Result<CtlResponse<()>> {
+        let interface_link_definition: InterfaceLinkDefinition =
+            serde_json::from_slice(payload.as_ref())
+                .context("failed to deserialize wrpc link definition")?;
+        debug!(
+            source_id = interface_link_definition.source_id,
+            target = interface_link_definition.target,
+            name = interface_link_definition.name,
+            "handling validate wrpc link definition"
+        );
+        let LinkConfigValidation {
+            violations,
+            invalid_schemas,
+        } = self
+            .validate_link_config(&interface_link_definition)
+            .await?;
+        // Invalid schemas do not reject links, but are reported when only validating
+        Ok(CtlResponse {
+            success: violations.is_none(),
+            message: violations
+                .into_iter()
+                .chain(invalid_schemas)
+                .collect::<Vec<_>>()
+                .join("\n"),
+            response: None,
+        })
+    }
+
+    /// Validates the source and target config of a link against the config schemas of the source
+    /// and target components. Only the sides of the link with a config schema are validated, and
+    /// for those, named configs which do not exist are violations on their own.
+    async fn validate_link_config(
+        &self,
+        link: &InterfaceLinkDefinition,
+    ) -> anyhow::Result<LinkConfigValidation> {
+        let mut violations = Vec::new();
+        let mut invalid_schemas = Vec::new();
+        for (side, id, names) in [
+            ("source", &link.source_id, &link.source_config),
+            ("target", &link.target, &link.target_config),
+        ] {
+            let Some(schema) = self
+                .get_component_spec(id)
+                .await?
+                .and_then(|spec| spec.config_schema)
+            else {
+                continue;
+            };
+            let mut named = HashMap::new();
+            for name in names {
+                if let Some(data) = self
+                    .config_data
+                    .get(name)
+                    .await
+                    .with_context(|| format!("failed to get config `{name}`"))?
+                {
+                    let values: HashMap<String, String> = serde_json::from_slice(&data)
+                        .with_context(|| format!("failed to deserialize config `{name}`"))?;
+                    named.insert(name.as_str(), values);
+                }
+            }
+            let (config, missing) = config_schema::merge_link_config(names, &named);
+            violations.extend(
+                missing
+                    .into_iter()
+                    .map(|name| format!("{side} config `{name}` was not found")),
+            );
+            let side_violations = match config_schema::validate(&schema, &config) {
+                Ok(side_violations) => side_violations,
+                Err(err) => {
+                    // A broken schema is a problem of the provider, so don't reject links over it
+                    warn!(?err, id, "skipping validation of link config");
+                    invalid_schemas.push(format!(
+                        "{side} config was not validated, the config schema of `{id}` is invalid: {err:#}"
+                    ));
+                    continue;
+                }
+            };
+            if side_violations.is_empty() {
+                continue;
+            }
+            violations.push(format!(
+                "{side} config does not match the config schema of `{id}`:"
+            ));
+            violations.extend(
+                side_violations
+                    .into_iter()
+                    .map(|violation| format!("  - {violation}")),
+            );
+        }
+        Ok(LinkConfigValidation {
+            violations: (!violations.is_empty())
+                .then(|| format!("invalid link configuration\n{}", violations.join("\n"))),
+            invalid_schemas,
+        })
+    }
+
     #[instrument(level = "debug", skip_all)]
     /// Remove an interface link on a source component for a specific package
     async fn handle_interface_link_del(
@@ -3525,7 +4898,7 @@ impl Host {
                 .await
                 .map(Some)
                 .map(serialize_ctl_response),
-            (Some("actor"), Some("update"), Some(host_id), None) => self
+            (Some("actor"), Some("update"), Some(host_id), None) => Arc::clone(&self)
                 .handle_update_actor(message.payload, host_id)
                 .await
                 .map(Some)
@@ -3545,6 +4918,11 @@ impl Host {
                 .await
                 .map(Some)
                 .map(serialize_ctl_response),
+            (Some("provider"), Some("logs"), Some(_host_id), None) => self
+                .handle_provider_logs(message.payload)
+                .await
+                .map(Some)
+                .map(serialize_ctl_response),
             // Host commands
             (Some("host"), Some("get"), Some(_host_id), None) => self
                 .handle_inventory()
@@ -3582,6 +4960,11 @@ impl Host {
                 .await
                 .map(Some)
                 .map(serialize_ctl_response),
+            (Some("link"), Some("validate"), None, None) => self
+                .handle_interface_link_validate(message.payload)
+                .await
+                .map(Some)
+                .map(serialize_ctl_response),
             // Label commands
             (Some("label"), Some("del"), Some(_host_id), None) => self
                 .handle_label_del(message.payload)
@@ -3824,6 +5207,8 @@ impl Host {
         // If the actor is already running, update the links
         if let Some(actor) = self.actors.write().await.get(id) {
             *actor.handler.interface_links.write().await = component_import_links(&spec.links);
+            let mut limiters = actor.handler.link_limiters.write().await;
+            *limiters = link_limiters(&spec.links, &limiters);
             // NOTE(brooksmtownsend): We can consider updating the actor if the image URL changes
         };
 
@@ -3952,6 +5337,32 @@ impl Host {
         }
     }
 
+    /// Applies a lattice event published by a host to the availability of providers
+    #[instrument(level = "trace", skip_all)]
+    async fn process_availability_event(&self, payload: &[u8]) {
+        let event = match serde_json::from_slice::<cloudevents::Event>(payload) {
+            Ok(event) => event,
+            Err(e) => {
+                warn!(?e, "failed to deserialize lattice event");
+                return;
+            }
+        };
+        let (Some(name), Some(cloudevents::Data::Json(data))) = (
+            event.ty().strip_prefix("com.wasmcloud.lattice."),
+            event.data(),
+        ) else {
+            return;
+        };
+        if let Err(e) =
+            self.unavailable_providers
+                .write()
+                .await
+                .apply(name, event.source(), data.clone())
+        {
+            warn!(?e, "failed to apply lattice event to provider availability");
+        }
+    }
+
     #[instrument(level = "trace", skip_all, fields(bucket = %entry.bucket, key = %entry.key, revision = %entry.revision, operation = ?entry.operation))]
     async fn process_config_entry(&self, entry: KvEntry) {
         match entry.operation {
@@ -3973,6 +5384,126 @@ impl Host {
     }
 }
 
+/// Requests the health of a provider on `topic`, returning `None` if the request fails or the
+/// response is invalid
+async fn request_provider_health(
+    rpc_nats: &async_nats::Client,
+    topic: String,
+    timeout: Option<Duration>,
+) -> Option<bool> {
+    let request = async_nats::Request::new()
+        .payload(Bytes::new())
+        .timeout(timeout)
+        .headers(injector_to_headers(
+            &TraceContextInjector::default_with_span(),
+        ));
+    let async_nats::Message { payload, .. } = rpc_nats.send_request(topic, request).await.ok()?;
+    match serde_json::from_slice::<HealthCheckResponse>(&payload) {
+        Ok(HealthCheckResponse { healthy, .. }) => Some(healthy),
+        Err(e) => {
+            warn!(?e, "failed to deserialize provider health check response");
+            None
+        }
+    }
+}
+
+/// Decision on restarting an exited provider process, see [`provider_restart`]
+#[derive(Debug, PartialEq, Eq)]
+enum ProviderRestart {
+    /// Restart the process after the contained backoff
+    After(Duration),
+    /// The provider is crash looping and must not be restarted anymore
+    CrashLoop,
+}
+
+/// Decides whether to restart a provider process exiting at `now`, given the times of its
+/// previous `restarts`. Restarts older than `window` are forgotten and the provider is crash
+/// looping once it was restarted `max_restarts` times within `window`. Otherwise the backoff starts
+/// at [`PROVIDER_RESTART_BACKOFF`] and doubles for every restart within `window`, up to
+/// [`PROVIDER_RESTART_MAX_BACKOFF`]
+fn provider_restart(
+    restarts: &mut VecDeque<Instant>,
+    now: Instant,
+    window: Duration,
+    max_restarts: usize,
+) -> ProviderRestart {
+    while restarts
+        .front()
+        .is_some_and(|restarted| now.duration_since(*restarted) > window)
+    {
+        restarts.pop_front();
+    }
+    if restarts.len() >= max_restarts {
+        return ProviderRestart::CrashLoop;
+    }
+    ProviderRestart::After(
+        restarts
+            .iter()
+            .fold(PROVIDER_RESTART_BACKOFF, |backoff, _| {
+                backoff.saturating_mul(2).min(PROVIDER_RESTART_MAX_BACKOFF)
+            }),
+    )
+}
+
+/// Outcome of watching a hot updated actor, see [`watch_update`]
+#[derive(Debug, PartialEq, Eq)]
+enum UpdateWatch {
+    /// The new version stayed within the error budget for the whole watch window
+    Completed,
+    /// The new version was replaced by another operation before the watch window elapsed
+    Superseded,
+    /// The new version produced the contained number of errors, exceeding the error budget
+    Failed(u64),
+}
+
+/// Watches a new version of an actor for `window`, polling `errors` for the number of invocation
+/// errors it produced every [`UPDATE_WATCH_INTERVAL`] while `is_current` holds
+async fn watch_update<F>(
+    mut is_current: impl FnMut() -> F,
+    mut errors: impl FnMut() -> u64,
+    max_errors: u64,
+    window: Duration,
+) -> UpdateWatch
+where
+    F: Future<Output = bool>,
+{
+    let deadline = Instant::now() + window;
+    loop {
+        if !is_current().await {
+            return UpdateWatch::Superseded;
+        }
+        let errors = errors();
+        if errors > max_errors {
+            return UpdateWatch::Failed(errors);
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return UpdateWatch::Completed;
+        }
+        tokio::time::sleep(UPDATE_WATCH_INTERVAL.min(deadline - now)).await;
+    }
+}
+
+/// Returns the parameter types of the functions served for `component` by instance and function
+/// name. Invocations can only be handed over between components serving the same functions
+fn served_functions(
+    component: &wasmcloud_runtime::Component,
+) -> BTreeMap<(&str, &str), &[wrpc_types::Type]> {
+    component
+        .exports()
+        .iter()
+        .flat_map(|(instance, functions)| {
+            functions.iter().filter_map(move |(name, function)| {
+                if let wrpc_types::DynamicFunction::Static { params, .. } = function {
+                    Some(((instance.as_str(), name.as_str()), &**params))
+                } else {
+                    None
+                }
+            })
+        })
+        .collect()
+}
+
 /// Helper function to transform a Vec of [InterfaceLinkDefinition]s into the structure components expect to be able
 /// to quickly look up the desired target for a given interface
 ///
@@ -4270,6 +5801,44 @@ impl<'de> Deserialize<'de> for MessyVec {
     }
 }
 
+/// Returns `true` if `res` is an error caused by the invocation exceeding its execution limits
+fn is_interrupted<T>(res: &anyhow::Result<T>) -> bool {
+    res.as_ref()
+        .is_err_and(|err| err.downcast_ref::<ExecutionLimitExceeded>().is_some())
+}
+
+/// Converts [`ComponentLimits`] received over the control interface into [`ResourceLimits`]
+/// enforced by the runtime
+fn resource_limits(limits: Option<ComponentLimits>) -> ResourceLimits {
+    let Some(ComponentLimits {
+        max_memory_bytes,
+        max_table_elements,
+    }) = limits
+    else {
+        return ResourceLimits::default();
+    };
+    ResourceLimits {
+        max_memory_size: max_memory_bytes.map(|max| usize::try_from(max).unwrap_or(usize::MAX)),
+        max_table_elements,
+    }
+}
+
+/// Converts [`ResourceLimits`] enforced by the runtime into [`ComponentLimits`] reported over the
+/// control interface, returning [`None`] if no limits are set
+fn component_limits(limits: ResourceLimits) -> Option<ComponentLimits> {
+    if limits == ResourceLimits::default() {
+        return None;
+    }
+    let ResourceLimits {
+        max_memory_size,
+        max_table_elements,
+    } = limits;
+    Some(ComponentLimits {
+        max_memory_bytes: max_memory_size.map(|max| max.try_into().unwrap_or(u64::MAX)),
+        max_table_elements,
+    })
+}
+
 fn human_friendly_uptime(uptime: Duration) -> String {
     // strip sub-seconds, then convert to human-friendly format
     humantime::format_duration(
@@ -4292,6 +5861,94 @@ fn injector_to_headers(injector: &TraceContextInjector) -> async_nats::header::H
 
 #[cfg(test)]
 mod test {
+    use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+    use std::time::Duration;
+
+    use std::collections::VecDeque;
+
+    use tokio::time::Instant;
+
+    use super::{
+        provider_restart, watch_update, ProviderRestart, UpdateWatch, UPDATE_WATCH_INTERVAL,
+    };
+
+    #[test]
+    fn provider_restart_backoff() {
+        let window = Duration::from_secs(300);
+        let start = Instant::now();
+        let mut restarts = VecDeque::new();
+
+        // The backoff doubles with every restart within the window and is capped
+        let mut now = start;
+        for expected in [1, 2, 4, 8, 16, 32, 60, 60] {
+            assert_eq!(
+                provider_restart(&mut restarts, now, window, 10),
+                ProviderRestart::After(Duration::from_secs(expected))
+            );
+            restarts.push_back(now);
+            now += Duration::from_secs(1);
+        }
+
+        // Reaching the maximum number of restarts within the window is a crash loop
+        assert_eq!(
+            provider_restart(&mut restarts, now, window, 8),
+            ProviderRestart::CrashLoop
+        );
+        assert_eq!(restarts.len(), 8);
+
+        // Restarts outside of the window are forgotten
+        let later = start + window + Duration::from_secs(4);
+        assert_eq!(
+            provider_restart(&mut restarts, later, window, 8),
+            ProviderRestart::After(Duration::from_secs(16))
+        );
+        assert_eq!(
+            restarts,
+            VecDeque::from_iter((4..8).map(|i| start + Duration::from_secs(i)))
+        );
+        assert_eq!(
+            provider_restart(&mut restarts, later + window, window, 8),
+            ProviderRestart::After(Duration::from_secs(1))
+        );
+        assert!(restarts.is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn watch_update_completes_within_budget() {
+        let start = tokio::time::Instant::now();
+        let watch = watch_update(|| async { true }, || 2, 2, Duration::from_secs(5)).await;
+        assert_eq!(watch, UpdateWatch::Completed);
+        assert_eq!(start.elapsed(), Duration::from_secs(5));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn watch_update_fails_over_budget() {
+        let start = tokio::time::Instant::now();
+        let errors = AtomicU64::default();
+        let watch = watch_update(
+            || async { true },
+            || errors.fetch_add(1, Ordering::Relaxed),
+            2,
+            Duration::from_secs(60),
+        )
+        .await;
+        assert_eq!(watch, UpdateWatch::Failed(3));
+        assert_eq!(start.elapsed(), UPDATE_WATCH_INTERVAL * 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn watch_update_superseded() {
+        let current = AtomicBool::new(true);
+        let watch = watch_update(
+            || async { current.swap(false, Ordering::Relaxed) },
+            || 100,
+            u64::MAX,
+            Duration::from_secs(60),
+        )
+        .await;
+        assert_eq!(watch, UpdateWatch::Superseded);
+    }
+
     // Ensure that the helper function to translate a list of links into a map of imports works as expected
     #[test]
     fn can_compute_component_links() {
@@ -4308,6 +5965,7 @@ mod test {
                 name: "default".to_string(),
                 source_config: vec![],
                 target_config: vec![],
+                limits: None,
             },
             InterfaceLinkDefinition {
                 source_id: "source_component".to_string(),
@@ -4318,6 +5976,7 @@ mod test {
                 name: "secret".to_string(),
                 source_config: vec![],
                 target_config: vec!["my-secret".to_string()],
+                limits: None,
             },
             InterfaceLinkDefinition {
                 source_id: "source_component".to_string(),
@@ -4328,6 +5987,7 @@ mod test {
                 name: "secret".to_string(),
                 source_config: vec![],
                 target_config: vec!["my-secret".to_string()],
+                limits: None,
             },
             InterfaceLinkDefinition {
                 source_id: "http".to_string(),
@@ -4338,6 +5998,7 @@ mod test {
                 name: "default".to_string(),
                 source_config: vec!["some-port".to_string()],
                 target_config: vec![],
+                limits: None,
             },
             InterfaceLinkDefinition {
                 source_id: "source_component".to_string(),
@@ -4348,6 +6009,7 @@ mod test {
                 name: "default".to_string(),
                 source_config: vec![],
                 target_config: vec!["some-port".to_string()],
+                limits: None,
             },
             InterfaceLinkDefinition {
                 source_id: "source_component".to_string(),
@@ -4358,6 +6020,7 @@ mod test {
                 name: "default".to_string(),
                 source_config: vec![],
                 target_config: vec![],
+                limits: None,
             },
             InterfaceLinkDefinition {
                 source_id: "other_component".to_string(),
@@ -4368,6 +6031,7 @@ mod test {
                 name: "link2".to_string(),
                 source_config: vec![],
                 target_config: vec![],
+                limits: None,
             },
         ];
 
@@ -0,0 +1,340 @@
+use core::fmt;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use rand::Rng as _;
+use wrpc_transport::Value;
+
+use super::host_config::{CircuitBreakerConfig, RetryPolicy};
+
+/// Error returned when an invocation is rejected, because the circuit breaker of its target is
+/// open. It can be retrieved from invocation errors using [`anyhow::Error::downcast_ref`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CircuitBreakerOpen {
+    /// The target the invocation was rejected for
+    pub target: String,
+}
+
+impl fmt::Display for CircuitBreakerOpen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "circuit breaker of target `{}` is open", self.target)
+    }
+}
+
+impl std::error::Error for CircuitBreakerOpen {}
+
+/// State of the circuit breaker of a single target
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    /// Invocations are admitted, counting consecutive failures
+    Closed { failures: u32 },
+    /// Invocations are rejected until the deadline passes
+    Open { until: Instant },
+    /// A single trial invocation is in flight, which determines whether the breaker closes or
+    /// opens again. Another trial is admitted once the deadline passes, in case the outcome of
+    /// the trial is never recorded
+    HalfOpen { until: Instant },
+}
+
+/// Change of the state of a circuit breaker
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Transition {
+    Opened,
+    HalfOpened,
+    Closed,
+}
+
+impl Transition {
+    /// Returns the name of the lattice event published for the transition
+    pub(crate) fn event_name(self) -> &'static str {
+        match self {
+            Self::Opened => "circuit_breaker_opened",
+            Self::HalfOpened => "circuit_breaker_half_opened",
+            Self::Closed => "circuit_breaker_closed",
+        }
+    }
+}
+
+/// Circuit breakers of all lattice targets invoked by components on the host
+#[derive(Debug, Default)]
+pub(crate) struct CircuitBreakers {
+    config: CircuitBreakerConfig,
+    states: Mutex<HashMap<String, State>>,
+}
+
+impl CircuitBreakers {
+    pub(crate) fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            states: Mutex::default(),
+        }
+    }
+
+    /// Admits an invocation of `target` at time `now`, returning the transition of its breaker,
+    /// if any
+    pub(crate) fn admit(
+        &self,
+        target: &str,
+        now: Instant,
+    ) -> Result<Option<Transition>, CircuitBreakerOpen> {
+        if self.config.failure_threshold.is_none() {
+            return Ok(None);
+        }
+        let Ok(mut states) = self.states.lock() else {
+            return Ok(None);
+        };
+        let Some(state) = states.get_mut(target) else {
+            return Ok(None);
+        };
+        match *state {
+            State::Closed { .. } => Ok(None),
+            State::Open { until } if now >= until => {
+                *state = State::HalfOpen {
+                    until: now + self.config.open_timeout,
+                };
+                Ok(Some(Transition::HalfOpened))
+            }
+            State::HalfOpen { until } if now >= until => {
+                *state = State::HalfOpen {
+                    until: now + self.config.open_timeout,
+                };
+                Ok(None)
+            }
+            State::Open { .. } | State::HalfOpen { .. } => Err(CircuitBreakerOpen {
+                target: target.to_string(),
+            }),
+        }
+    }
+
+    /// Records the outcome of an admitted invocation of `target` at time `now`, returning the
+    /// transition of its breaker, if any
+    pub(crate) fn record(&self, target: &str, success: bool, now: Instant) -> Option<Transition> {
+        let threshold = self.config.failure_threshold?;
+        let mut states = self.states.lock().ok()?;
+        let state = states
+            .entry(target.to_string())
+            .or_insert(State::Closed { failures: 0 });
+        let open = State::Open {
+            until: now + self.config.open_timeout,
+        };
+        match (*state, success) {
+            (State::Closed { .. }, true) => {
+                *state = State::Closed { failures: 0 };
+                None
+            }
+            (State::Closed { failures }, false) => {
+                let failures = failures.saturating_add(1);
+                if failures >= threshold {
+                    *state = open;
+                    Some(Transition::Opened)
+                } else {
+                    *state = State::Closed { failures };
+                    None
+                }
+            }
+            (State::HalfOpen { .. }, true) => {
+                *state = State::Closed { failures: 0 };
+                Some(Transition::Closed)
+            }
+            (State::HalfOpen { .. }, false) => {
+                *state = open;
+                Some(Transition::Opened)
+            }
+            // Outcomes of invocations admitted before the breaker opened do not affect it
+            (State::Open { .. }, _) => None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Returns the maximum number of attempts made to invoke `function` of `instance`, which is 1
+    /// for operations that are not retried
+    pub(crate) fn attempts(&self, instance: &str, function: &str) -> u32 {
+        let retried = self.operations.iter().any(|operation| {
+            operation == instance
+                || operation
+                    .strip_prefix(instance)
+                    .and_then(|operation| operation.strip_prefix('.'))
+                    == Some(function)
+        });
+        if retried {
+            self.max_attempts.max(1)
+        } else {
+            1
+        }
+    }
+
+    /// Returns the backoff before the `retry`-th retry, a random duration between half of and the
+    /// full exponential backoff
+    pub(crate) fn backoff(&self, retry: u32) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2_u32.saturating_pow(retry.saturating_sub(1)))
+            .min(self.max_backoff);
+        backoff.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+    }
+}
+
+/// Returns a copy of `values` to retry an invocation with, unless they contain futures or streams,
+/// which can only be consumed once
+pub(crate) fn retry_params(values: &[Value]) -> Option<Vec<Value>> {
+    values.iter().map(retry_param).collect()
+}
+
+fn retry_param(value: &Value) -> Option<Value> {
+    let nested = |value: &Option<Box<Value>>| match value {
+        Some(value) => retry_param(value).map(|value| Some(Box::new(value))),
+        None => Some(None),
+    };
+    Some(match value {
+        Value::Bool(v) => Value::Bool(*v),
+        Value::U8(v) => Value::U8(*v),
+        Value::U16(v) => Value::U16(*v),
+        Value::U32(v) => Value::U32(*v),
+        Value::U64(v) => Value::U64(*v),
+        Value::S8(v) => Value::S8(*v),
+        Value::S16(v) => Value::S16(*v),
+        Value::S32(v) => Value::S32(*v),
+        Value::S64(v) => Value::S64(*v),
+        Value::Float32(v) => Value::Float32(*v),
+        Value::Float64(v) => Value::Float64(*v),
+        Value::Char(v) => Value::Char(*v),
+        Value::String(v) => Value::String(v.clone()),
+        Value::List(v) => Value::List(retry_params(v)?),
+        Value::Record(v) => Value::Record(retry_params(v)?),
+        Value::Tuple(v) => Value::Tuple(retry_params(v)?),
+        Value::Variant {
+            discriminant,
+            nested: v,
+        } => Value::Variant {
+            discriminant: *discriminant,
+            nested: nested(v)?,
+        },
+        Value::Enum(v) => Value::Enum(*v),
+        Value::Option(v) => Value::Option(nested(v)?),
+        Value::Result(Ok(v)) => Value::Result(Ok(nested(v)?)),
+        Value::Result(Err(v)) => Value::Result(Err(nested(v)?)),
+        Value::Flags(v) => Value::Flags(*v),
+        Value::Future(_) | Value::Stream(_) => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{CircuitBreakerConfig, CircuitBreakers, RetryPolicy, Transition};
+
+    #[test]
+    fn circuit_breaker() {
+        let breakers = CircuitBreakers::new(CircuitBreakerConfig {
+            failure_threshold: Some(2),
+            open_timeout: Duration::from_secs(10),
+        });
+        let now = Instant::now();
+        assert_eq!(breakers.admit("kv", now), Ok(None));
+        assert_eq!(breakers.record("kv", false, now), None);
+        assert_eq!(breakers.record("kv", false, now), Some(Transition::Opened));
+        assert!(breakers.admit("kv", now).is_err());
+        // Breakers are kept per target
+        assert_eq!(breakers.admit("blobstore", now), Ok(None));
+
+        // A single trial is let through once the breaker is half-open
+        let later = now + Duration::from_secs(10);
+        assert_eq!(
+            breakers.admit("kv", later),
+            Ok(Some(Transition::HalfOpened))
+        );
+        assert!(breakers.admit("kv", later).is_err());
+        assert_eq!(
+            breakers.record("kv", false, later),
+            Some(Transition::Opened)
+        );
+        assert!(breakers.admit("kv", later).is_err());
+
+        let later = later + Duration::from_secs(10);
+        assert_eq!(
+            breakers.admit("kv", later),
+            Ok(Some(Transition::HalfOpened))
+        );
+        assert_eq!(breakers.record("kv", true, later), Some(Transition::Closed));
+        assert_eq!(breakers.admit("kv", later), Ok(None));
+    }
+
+    #[test]
+    fn circuit_breaker_abandoned_trial() {
+        let breakers = CircuitBreakers::new(CircuitBreakerConfig {
+            failure_threshold: Some(1),
+            open_timeout: Duration::from_secs(10),
+        });
+        let now = Instant::now();
+        assert_eq!(breakers.record("kv", false, now), Some(Transition::Opened));
+        let later = now + Duration::from_secs(10);
+        assert_eq!(
+            breakers.admit("kv", later),
+            Ok(Some(Transition::HalfOpened))
+        );
+
+        // The outcome of the trial is never recorded, e.g. because it was cancelled, so another
+        // trial is admitted once the trial deadline passes
+        assert!(breakers
+            .admit("kv", later + Duration::from_secs(5))
+            .is_err());
+        let later = later + Duration::from_secs(10);
+        assert_eq!(breakers.admit("kv", later), Ok(None));
+        assert!(breakers.admit("kv", later).is_err());
+        assert_eq!(breakers.record("kv", true, later), Some(Transition::Closed));
+        assert_eq!(breakers.admit("kv", later), Ok(None));
+    }
+
+    #[test]
+    fn circuit_breaker_disabled() {
+        let breakers = CircuitBreakers::default();
+        let now = Instant::now();
+        for _ in 0..10 {
+            assert_eq!(breakers.record("kv", false, now), None);
+        }
+        assert_eq!(breakers.admit("kv", now), Ok(None));
+    }
+
+    #[test]
+    fn retry_policy() {
+        let policy = RetryPolicy {
+            operations: vec![
+                "wasi:keyvalue/eventual".to_string(),
+                "wasi:blobstore/blobstore.get-container-info".to_string(),
+                "wasi:blobstore/blobstore.delete-objects".to_string(),
+            ],
+            max_attempts: 4,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(300),
+        };
+        assert_eq!(policy.attempts("wasi:keyvalue/eventual", "get"), 4);
+        assert_eq!(
+            policy.attempts("wasi:blobstore/blobstore", "get-container-info"),
+            4
+        );
+        assert_eq!(
+            policy.attempts("wasi:blobstore/blobstore", "delete-container"),
+            1
+        );
+        assert_eq!(policy.attempts("wasi:keyvalue/atomic", "increment"), 1);
+        // Policies are looked up per function, so that retrying deletes does not retry writes
+        assert_eq!(
+            policy.attempts("wasi:blobstore/blobstore", "delete-objects"),
+            4
+        );
+        assert_eq!(
+            policy.attempts("wasi:blobstore/blobstore", "write-container-data"),
+            1
+        );
+        assert_eq!(policy.attempts("wasi:blobstore/blobstore", "delete"), 1);
+
+        let backoff = policy.backoff(1);
+        assert!(backoff >= Duration::from_millis(50) && backoff <= Duration::from_millis(100));
+        let backoff = policy.backoff(5);
+        assert!(backoff >= Duration::from_millis(150) && backoff <= Duration::from_millis(300));
+    }
+}
@@ -55,6 +55,9 @@ pub enum InvocationError {
     /// Returned when an invocation returns an error
     #[error("Unexpected error: {0}")]
     Unexpected(String),
+    /// Returned when the source of an invocation exceeded its rate limit
+    #[error("Rate limit exceeded for source {0}")]
+    RateLimited(String),
 }
 
 /// All errors that can occur when validating an invocation
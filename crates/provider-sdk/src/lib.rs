@@ -8,6 +8,7 @@ use core::{ComponentId, LatticeTarget, LinkName};
 use tracing::{error, info, warn};
 
 pub mod error;
+pub mod middleware;
 pub mod provider;
 
 /// Re-export of types from [`wasmcloud_core`]
@@ -15,8 +16,10 @@ pub use core::{
     HealthCheckRequest, HealthCheckResponse, InterfaceLinkDefinition, WasmCloudEntity, WitFunction,
     WitInterface, WitNamespace, WitPackage,
 };
+pub use middleware::Middleware;
 pub use provider::{
-    get_connection, load_host_data, run_provider, run_provider_handler, start_provider,
+    get_connection, load_host_data, run_provider, run_provider_handler,
+    run_provider_with_middleware, start_provider, start_provider_with_middleware,
     ProviderConnection,
 };
 pub use wasmcloud_core as core;
@@ -234,7 +237,8 @@ pub trait ProviderHandler: Sync {
         Ok(())
     }
 
-    /// Notify the provider that the link is dropped
+    /// Notify the provider that the link with the actor is dropped, `actor_id` is the target of
+    /// the link if this provider is its source and the source of the link otherwise
     async fn delete_link(&self, _actor_id: &str) -> ProviderOperationResult<()> {
         Ok(())
     }
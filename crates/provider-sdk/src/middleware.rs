@@ -0,0 +1,534 @@
+//! Middleware applied to wRPC invocations between receiving them and dispatching them to the
+//! provider
+//!
+//! A [`Middleware`] is an ordered chain of [`Layer`]s. Every layer sees the [`Invocation`] and
+//! decides whether, and how, to pass it on to the [`Next`] layer in the chain, with the provider's
+//! [`WrpcDispatch`] implementation being the innermost handler.
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use wasmcloud_provider_sdk::middleware::{Middleware, RateLimitLayer, TracingLayer};
+//!
+//! let middleware = Middleware::default()
+//!     .layer(TracingLayer)
+//!     .layer(RateLimitLayer::new(100, Duration::from_secs(1)));
+//! ```
+
+use core::fmt;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use tracing::{debug, warn, Instrument as _};
+use wasmcloud_core::rate_limit::TokenBucket;
+use wasmcloud_tracing::{global, Counter, Histogram, KeyValue, Meter, Unit};
+
+use crate::error::{InvocationError, InvocationResult};
+use crate::{Context, WrpcDispatch};
+
+/// An invocation received over wRPC, on its way to the provider
+pub struct Invocation {
+    /// Context of the invocation, including the ID of the source component
+    pub context: Context,
+    /// Interface the invocation targets, e.g. `wasmcloud:keyvalue/key-value`
+    pub interface: String,
+    /// Function of the interface the invocation targets, e.g. `get`
+    pub function: String,
+    /// Size of the parameters of the invocation in bytes, excluding any streamed values
+    pub payload_size: usize,
+    /// Parameters of the invocation
+    pub params: Vec<wrpc_transport::Value>,
+}
+
+impl Invocation {
+    /// Returns the operation of the invocation, of the form `<ns>:<pkg>/<interface>.<function>`
+    #[must_use]
+    pub fn operation(&self) -> String {
+        format!("{}.{}", self.interface, self.function)
+    }
+
+    /// Returns the ID of the source component of the invocation, if known
+    #[must_use]
+    pub fn source_id(&self) -> Option<&str> {
+        self.context.actor.as_deref()
+    }
+}
+
+impl fmt::Debug for Invocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Invocation")
+            .field("context", &self.context)
+            .field("interface", &self.interface)
+            .field("function", &self.function)
+            .field("payload_size", &self.payload_size)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns the size in bytes of `values`, excluding futures and streams, whose size is not known
+/// until they are received
+pub(crate) fn payload_size(values: &[wrpc_transport::Value]) -> usize {
+    use wrpc_transport::Value;
+
+    values
+        .iter()
+        .map(|value| match value {
+            Value::Bool(_) | Value::U8(_) | Value::S8(_) => 1,
+            Value::U16(_) | Value::S16(_) => 2,
+            Value::U32(_) | Value::S32(_) | Value::Float32(_) | Value::Char(_) | Value::Enum(_) => {
+                4
+            }
+            Value::U64(_) | Value::S64(_) | Value::Float64(_) | Value::Flags(_) => 8,
+            Value::String(s) => s.len(),
+            Value::List(values) | Value::Record(values) | Value::Tuple(values) => {
+                payload_size(values)
+            }
+            Value::Variant { nested, .. } | Value::Option(nested) => nested
+                .as_deref()
+                .map_or(0, |v| payload_size(core::slice::from_ref(v))),
+            Value::Result(Ok(nested) | Err(nested)) => nested
+                .as_deref()
+                .map_or(0, |v| payload_size(core::slice::from_ref(v))),
+            Value::Future(_) | Value::Stream(_) => 0,
+        })
+        .sum()
+}
+
+/// A single step of a [`Middleware`] chain
+#[async_trait]
+pub trait Layer: Send + Sync + 'static {
+    /// Handle `invocation`, usually by passing it on to `next` and returning its result
+    async fn handle(&self, invocation: Invocation, next: Next<'_>) -> InvocationResult<Vec<u8>>;
+}
+
+/// The remainder of a [`Middleware`] chain, ending with the provider
+pub struct Next<'a> {
+    layers: &'a [Arc<dyn Layer>],
+    provider: &'a (dyn WrpcDispatch + Send + Sync),
+}
+
+impl Next<'_> {
+    /// Pass `invocation` on to the next layer, or dispatch it to the provider if this is the end
+    /// of the chain
+    pub async fn run(self, invocation: Invocation) -> InvocationResult<Vec<u8>> {
+        if let Some((layer, layers)) = self.layers.split_first() {
+            layer
+                .handle(
+                    invocation,
+                    Next {
+                        layers,
+                        provider: self.provider,
+                    },
+                )
+                .await
+        } else {
+            let operation = invocation.operation();
+            self.provider
+                .dispatch_wrpc_dynamic(invocation.context, operation, invocation.params)
+                .await
+        }
+    }
+}
+
+/// An ordered chain of [`Layer`]s applied to every invocation of a provider, outermost first
+#[derive(Clone, Default)]
+pub struct Middleware(Vec<Arc<dyn Layer>>);
+
+impl fmt::Debug for Middleware {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Middleware")
+            .field("layers", &self.0.len())
+            .finish()
+    }
+}
+
+impl Middleware {
+    /// Appends `layer` to the chain, so that it handles invocations after all previously added
+    /// layers
+    #[must_use]
+    pub fn layer(mut self, layer: impl Layer) -> Self {
+        self.0.push(Arc::new(layer));
+        self
+    }
+
+    /// Run `invocation` through the chain and dispatch it to `provider`
+    pub async fn run(
+        &self,
+        provider: &(dyn WrpcDispatch + Send + Sync),
+        invocation: Invocation,
+    ) -> InvocationResult<Vec<u8>> {
+        Next {
+            layers: &self.0,
+            provider,
+        }
+        .run(invocation)
+        .await
+    }
+}
+
+/// Wraps every invocation in a span carrying the source, interface, function and payload size of
+/// the invocation, and logs the outcome and duration of the invocation
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TracingLayer;
+
+#[async_trait]
+impl Layer for TracingLayer {
+    async fn handle(&self, invocation: Invocation, next: Next<'_>) -> InvocationResult<Vec<u8>> {
+        let span = tracing::info_span!(
+            "invocation",
+            source_id = invocation.source_id().unwrap_or("<unknown>"),
+            interface = invocation.interface,
+            function = invocation.function,
+            payload_size = invocation.payload_size,
+        );
+        async move {
+            let start = Instant::now();
+            let res = next.run(invocation).await;
+            let elapsed_ms = start.elapsed().as_millis();
+            match &res {
+                Ok(response) => {
+                    debug!(
+                        elapsed_ms,
+                        response_size = response.len(),
+                        "invocation succeeded"
+                    );
+                }
+                Err(err) => warn!(elapsed_ms, %err, "invocation failed"),
+            }
+            res
+        }
+        .instrument(span)
+        .await
+    }
+}
+
+/// Token buckets of the sources of a [`RateLimitLayer`]
+#[derive(Debug)]
+struct Buckets {
+    buckets: HashMap<String, TokenBucket>,
+    /// Time buckets were last evicted at
+    evicted: Instant,
+}
+
+/// Limits the rate of invocations of every source component with a token bucket, failing
+/// invocations exceeding the limit with [`InvocationError::RateLimited`]. Once per period, the
+/// buckets of sources that were idle long enough for them to refill are evicted, so that only
+/// recently active sources are tracked
+#[derive(Debug)]
+pub struct RateLimitLayer {
+    capacity: f64,
+    refill_per_sec: f64,
+    period: Duration,
+    buckets: Mutex<Buckets>,
+}
+
+impl RateLimitLayer {
+    /// Allow every source component `limit` invocations per `period`, up to `limit` of which may
+    /// happen in a burst
+    #[must_use]
+    pub fn new(limit: u32, period: Duration) -> Self {
+        let capacity = f64::from(limit);
+        Self {
+            capacity,
+            refill_per_sec: capacity / period.as_secs_f64(),
+            period,
+            buckets: Mutex::new(Buckets {
+                buckets: HashMap::default(),
+                evicted: Instant::now(),
+            }),
+        }
+    }
+
+    /// Takes a token from the bucket of `source_id` at time `now`, returning whether one was
+    /// available
+    fn acquire(&self, source_id: &str, now: Instant) -> bool {
+        let Ok(mut buckets) = self.buckets.lock() else {
+            return true;
+        };
+        if now.saturating_duration_since(buckets.evicted) >= self.period {
+            // Full buckets are indistinguishable from new ones
+            buckets.buckets.retain(|_, bucket| !bucket.is_full(now));
+            buckets.evicted = now;
+        }
+        buckets
+            .buckets
+            .entry(source_id.to_string())
+            .or_insert_with(|| TokenBucket::new(self.capacity, self.refill_per_sec, now))
+            .try_acquire(now)
+    }
+}
+
+#[async_trait]
+impl Layer for RateLimitLayer {
+    async fn handle(&self, invocation: Invocation, next: Next<'_>) -> InvocationResult<Vec<u8>> {
+        let source_id = invocation.source_id().unwrap_or("<unknown>");
+        if !self.acquire(source_id, Instant::now()) {
+            return Err(InvocationError::RateLimited(source_id.to_string()));
+        }
+        next.run(invocation).await
+    }
+}
+
+/// Records OpenTelemetry metrics for the number, errors and duration of invocations, attributed
+/// to their source component, interface and function
+#[derive(Clone, Debug)]
+pub struct MetricsLayer {
+    invocations: Counter<u64>,
+    errors: Counter<u64>,
+    duration: Histogram<f64>,
+}
+
+impl MetricsLayer {
+    /// Construct a [`MetricsLayer`] recording metrics with the given meter
+    #[must_use]
+    pub fn new(meter: &Meter) -> Self {
+        Self {
+            invocations: meter
+                .u64_counter("wasmcloud_provider.invocations")
+                .with_description("Number of provider invocations")
+                .init(),
+            errors: meter
+                .u64_counter("wasmcloud_provider.invocation.errors")
+                .with_description("Number of provider invocations resulting in an error")
+                .init(),
+            duration: meter
+                .f64_histogram("wasmcloud_provider.invocation.duration")
+                .with_description("Duration in milliseconds each provider invocation took")
+                .with_unit(Unit::new("milliseconds"))
+                .init(),
+        }
+    }
+}
+
+impl Default for MetricsLayer {
+    /// Construct a [`MetricsLayer`] recording metrics with the global meter provider
+    fn default() -> Self {
+        Self::new(&global::meter("wasmcloud-provider-sdk"))
+    }
+}
+
+#[async_trait]
+impl Layer for MetricsLayer {
+    async fn handle(&self, invocation: Invocation, next: Next<'_>) -> InvocationResult<Vec<u8>> {
+        let attributes = [
+            KeyValue::new(
+                "source_id",
+                invocation.source_id().unwrap_or("<unknown>").to_string(),
+            ),
+            KeyValue::new("interface", invocation.interface.clone()),
+            KeyValue::new("function", invocation.function.clone()),
+        ];
+        let start = Instant::now();
+        let res = next.run(invocation).await;
+        self.duration
+            .record(start.elapsed().as_secs_f64() * 1000.0, &attributes);
+        self.invocations.add(1, &attributes);
+        if res.is_err() {
+            self.errors.add(1, &attributes);
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    use async_trait::async_trait;
+
+    use super::{Invocation, Layer, Middleware, Next, RateLimitLayer};
+    use crate::error::{InvocationError, InvocationResult};
+    use crate::{Context, WrpcDispatch};
+
+    type Events = Arc<Mutex<Vec<String>>>;
+
+    /// Records the operations it is invoked with and responds with the operation
+    struct Provider(Events);
+
+    #[async_trait]
+    impl WrpcDispatch for Provider {
+        async fn dispatch_wrpc_dynamic<'a>(
+            &'a self,
+            _ctx: Context,
+            operation: String,
+            _params: Vec<wrpc_transport::Value>,
+        ) -> InvocationResult<Vec<u8>> {
+            self.0
+                .lock()
+                .expect("failed to lock events")
+                .push(format!("provider {operation}"));
+            Ok(operation.into_bytes())
+        }
+    }
+
+    /// Records when it is entered and left
+    struct Record(&'static str, Events);
+
+    #[async_trait]
+    impl Layer for Record {
+        async fn handle(
+            &self,
+            invocation: Invocation,
+            next: Next<'_>,
+        ) -> InvocationResult<Vec<u8>> {
+            self.1
+                .lock()
+                .expect("failed to lock events")
+                .push(format!("enter {}", self.0));
+            let res = next.run(invocation).await;
+            self.1
+                .lock()
+                .expect("failed to lock events")
+                .push(format!("leave {}", self.0));
+            res
+        }
+    }
+
+    /// Fails every invocation without passing it on
+    struct Reject;
+
+    #[async_trait]
+    impl Layer for Reject {
+        async fn handle(
+            &self,
+            _invocation: Invocation,
+            _next: Next<'_>,
+        ) -> InvocationResult<Vec<u8>> {
+            Err(InvocationError::Unexpected("rejected".to_string()))
+        }
+    }
+
+    fn invocation() -> Invocation {
+        Invocation {
+            context: Context {
+                actor: Some("source".to_string()),
+                ..Context::default()
+            },
+            interface: "wasi:keyvalue/readwrite".to_string(),
+            function: "get".to_string(),
+            payload_size: 0,
+            params: Vec::default(),
+        }
+    }
+
+    fn take(events: &Events) -> Vec<String> {
+        std::mem::take(&mut *events.lock().expect("failed to lock events"))
+    }
+
+    #[tokio::test]
+    async fn test_middleware() {
+        let events = Events::default();
+        let provider = Provider(Arc::clone(&events));
+
+        // Without layers, invocations reach the provider directly
+        let res = Middleware::default()
+            .run(&provider, invocation())
+            .await
+            .expect("invocation failed");
+        assert_eq!(res, b"wasi:keyvalue/readwrite.get");
+        assert_eq!(take(&events), ["provider wasi:keyvalue/readwrite.get"]);
+
+        // Layers handle invocations in the order they were added, outermost first
+        let res = Middleware::default()
+            .layer(Record("outer", Arc::clone(&events)))
+            .layer(Record("inner", Arc::clone(&events)))
+            .run(&provider, invocation())
+            .await
+            .expect("invocation failed");
+        assert_eq!(res, b"wasi:keyvalue/readwrite.get");
+        assert_eq!(
+            take(&events),
+            [
+                "enter outer",
+                "enter inner",
+                "provider wasi:keyvalue/readwrite.get",
+                "leave inner",
+                "leave outer",
+            ]
+        );
+
+        // Layers that do not call `next` short-circuit the chain
+        let err = Middleware::default()
+            .layer(Record("outer", Arc::clone(&events)))
+            .layer(Reject)
+            .layer(Record("inner", Arc::clone(&events)))
+            .run(&provider, invocation())
+            .await
+            .expect_err("invocation was not rejected");
+        assert!(matches!(err, InvocationError::Unexpected(_)), "{err}");
+        assert_eq!(take(&events), ["enter outer", "leave outer"]);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_layer() {
+        let events = Events::default();
+        let provider = Provider(Arc::clone(&events));
+        let middleware =
+            Middleware::default().layer(RateLimitLayer::new(1, Duration::from_secs(60)));
+        middleware
+            .run(&provider, invocation())
+            .await
+            .expect("invocation failed");
+        let err = middleware
+            .run(&provider, invocation())
+            .await
+            .expect_err("invocation was not rate limited");
+        assert!(
+            matches!(&err, InvocationError::RateLimited(source) if source == "source"),
+            "{err}"
+        );
+        assert_eq!(take(&events), ["provider wasi:keyvalue/readwrite.get"]);
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let layer = RateLimitLayer::new(2, Duration::from_secs(1));
+        let now = Instant::now();
+        assert!(layer.acquire("a", now));
+        assert!(layer.acquire("a", now));
+        assert!(!layer.acquire("a", now));
+        // Buckets are kept per source
+        assert!(layer.acquire("b", now));
+        // Half a period refills one token
+        assert!(layer.acquire("a", now + Duration::from_millis(500)));
+        assert!(!layer.acquire("a", now + Duration::from_millis(500)));
+        // The bucket never holds more than the limit
+        let later = now + Duration::from_secs(60);
+        assert!(layer.acquire("a", later));
+        assert!(layer.acquire("a", later));
+        assert!(!layer.acquire("a", later));
+    }
+
+    #[test]
+    fn test_rate_limit_eviction() {
+        let buckets = |layer: &RateLimitLayer| {
+            layer
+                .buckets
+                .lock()
+                .expect("failed to lock buckets")
+                .buckets
+                .len()
+        };
+        let layer = RateLimitLayer::new(2, Duration::from_secs(1));
+        let now = Instant::now();
+        assert!(layer.acquire("idle", now));
+        let soon = now + Duration::from_millis(900);
+        assert!(layer.acquire("active", soon));
+        assert!(layer.acquire("active", soon));
+        assert_eq!(buckets(&layer), 2);
+
+        // Once per period, buckets of sources that were idle long enough to refill are evicted
+        let later = now + Duration::from_secs(1);
+        assert!(!layer.acquire("active", later));
+        assert_eq!(buckets(&layer), 1);
+        // Evicted sources start over with a full bucket
+        assert!(layer.acquire("idle", later));
+        assert!(layer.acquire("idle", later));
+        assert!(!layer.acquire("idle", later));
+        assert_eq!(buckets(&layer), 2);
+    }
+}
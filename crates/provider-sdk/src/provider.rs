@@ -30,6 +30,7 @@ use wasmcloud_core::TraceContext;
 use wasmcloud_tracing::context::attach_span_context;
 
 use crate::error::{InvocationResult, ProviderInitError, ProviderInitResult};
+use crate::middleware::{payload_size, Invocation, Middleware};
 use crate::{
     with_connection_event_logging, Context, Provider, ProviderHandler, WrpcDispatch,
     WrpcInvocationLookup, DEFAULT_NATS_ADDR,
@@ -376,6 +377,7 @@ async fn init_provider(name: &str) -> ProviderInitResult<ProviderInitState> {
         cluster_issuers: _,
         config,
         default_rpc_timeout_ms: _,
+        rpc_inbox_prefix,
         structured_logging,
         log_level,
         otel_config,
@@ -403,21 +405,25 @@ async fn init_provider(name: &str) -> ProviderInitResult<ProviderInitState> {
     } else {
         DEFAULT_NATS_ADDR
     };
-    let nats = with_connection_event_logging(
-        match (lattice_rpc_user_jwt.trim(), lattice_rpc_user_seed.trim()) {
-            ("", "") => async_nats::ConnectOptions::default(),
-            (rpc_jwt, rpc_seed) => {
-                let key_pair = Arc::new(nkeys::KeyPair::from_seed(rpc_seed).unwrap());
-                let jwt = rpc_jwt.to_owned();
-                async_nats::ConnectOptions::with_jwt(jwt, move |nonce| {
-                    let key_pair = key_pair.clone();
-                    async move { key_pair.sign(&nonce).map_err(async_nats::AuthError::new) }
-                })
-            }
-        },
-    )
-    .connect(nats_addr)
-    .await?;
+    let opts = match (lattice_rpc_user_jwt.trim(), lattice_rpc_user_seed.trim()) {
+        ("", "") => async_nats::ConnectOptions::default(),
+        (rpc_jwt, rpc_seed) => {
+            let key_pair = Arc::new(nkeys::KeyPair::from_seed(rpc_seed).unwrap());
+            let jwt = rpc_jwt.to_owned();
+            async_nats::ConnectOptions::with_jwt(jwt, move |nonce| {
+                let key_pair = key_pair.clone();
+                async move { key_pair.sign(&nonce).map_err(async_nats::AuthError::new) }
+            })
+        }
+    };
+    let opts = if let Some(inbox_prefix) = rpc_inbox_prefix {
+        opts.custom_inbox_prefix(inbox_prefix)
+    } else {
+        opts
+    };
+    let nats = with_connection_event_logging(opts)
+        .connect(nats_addr)
+        .await?;
     let nats = Arc::new(nats);
     let (health, shutdown, link_put, link_del) = try_join!(
         subscribe_health(
@@ -470,13 +476,27 @@ async fn init_provider(name: &str) -> ProviderInitResult<ProviderInitState> {
 pub fn start_provider(
     provider: impl Provider + Clone,
     friendly_name: &str,
+) -> ProviderInitResult<()> {
+    start_provider_with_middleware(provider, friendly_name, Middleware::default())
+}
+
+/// Starts a provider like [`start_provider`], running every invocation through `middleware` before
+/// dispatching it to the provider
+pub fn start_provider_with_middleware(
+    provider: impl Provider + Clone,
+    friendly_name: &str,
+    middleware: Middleware,
 ) -> ProviderInitResult<()> {
     let runtime = tokio::runtime::Builder::new_multi_thread()
         .enable_all()
         .build()
         .map_err(|e| ProviderInitError::Initialization(e.to_string()))?;
 
-    runtime.block_on(run_provider(provider, friendly_name))?;
+    runtime.block_on(run_provider_with_middleware(
+        provider,
+        friendly_name,
+        middleware,
+    ))?;
     // in the unlikely case there are any stuck threads,
     // close them so the process has a clean exit
     runtime.shutdown_timeout(std::time::Duration::from_secs(10));
@@ -585,8 +605,8 @@ async fn handle_provider_commands(
             }
             req = link_put.recv() => {
                 if let Some((ld, tx)) = req {
-                    // If the link has already been put, return early
-                    if connection.is_linked(&ld.source_id).await {
+                    // If the same link has already been put, return early
+                    if connection.has_link(&ld).await {
                         warn!("Ignoring duplicate link put");
                     } else {
                         info!("Linking actor with provider");
@@ -610,9 +630,10 @@ async fn handle_provider_commands(
             }
             req = link_del.recv() => {
                 if let Some((ld, tx)) = req {
-                    connection.delete_link(&ld.source_id).await;
+                    let linked_id = connection.linked_id(&ld);
+                    connection.delete_link(linked_id).await;
                     // notify provider that link is deleted
-                    if let Err(e) = provider.delete_link(&ld.source_id).await {
+                    if let Err(e) = provider.delete_link(linked_id).await {
                         error!(error = %e, "failed to delete link");
                     }
                     if tx.send(()).is_err() {
@@ -666,6 +687,7 @@ pub async fn run_provider_handler(
         link_name,
         WrpcInvocationLookup::default(),
         config,
+        Middleware::default(),
     )?;
     CONNECTION.set(connection).map_err(|_| {
         ProviderInitError::Initialization("Provider connection was already initialized".to_string())
@@ -694,6 +716,16 @@ pub async fn run_provider_handler(
 pub async fn run_provider(
     provider: impl Provider + Clone,
     friendly_name: &str,
+) -> ProviderInitResult<()> {
+    run_provider_with_middleware(provider, friendly_name, Middleware::default()).await
+}
+
+/// Runs the provider like [`run_provider`], running every invocation through `middleware` before
+/// dispatching it to the provider
+pub async fn run_provider_with_middleware(
+    provider: impl Provider + Clone,
+    friendly_name: &str,
+    middleware: Middleware,
 ) -> ProviderInitResult<()> {
     let ProviderInitState {
         nats,
@@ -725,6 +757,7 @@ pub async fn run_provider(
         link_name,
         invocation_map,
         config,
+        middleware,
     )?;
     CONNECTION.set(connection).map_err(|_| {
         ProviderInitError::Initialization("Provider connection was already initialized".to_string())
@@ -754,13 +787,13 @@ pub async fn run_provider(
     Ok(())
 }
 
-/// Source ID for a link
-type SourceId = String;
+/// ID of the component on the other end of a link, see [`ProviderConnection::linked_id`]
+type LinkedId = String;
 
 #[derive(Clone)]
 pub struct ProviderConnection {
-    /// Links currently active on the provider, by Source ID
-    links: Arc<RwLock<HashMap<SourceId, InterfaceLinkDefinition>>>,
+    /// Links currently active on the provider, by the ID of the linked component
+    links: Arc<RwLock<HashMap<LinkedId, InterfaceLinkDefinition>>>,
 
     /// NATS client used for performing RPCs
     nats: Arc<async_nats::Client>,
@@ -776,6 +809,9 @@ pub struct ProviderConnection {
     /// Mapping of NATS subjects to dynamic function information for incoming invocations
     #[allow(unused)]
     incoming_invocation_fn_map: Arc<WrpcInvocationLookup>,
+
+    /// Middleware every incoming invocation is run through before it is dispatched
+    middleware: Arc<Middleware>,
 }
 
 impl fmt::Debug for ProviderConnection {
@@ -803,6 +839,7 @@ fn wrpc_client(
 }
 
 impl ProviderConnection {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         nats: Arc<async_nats::Client>,
         provider_key: String,
@@ -811,6 +848,7 @@ impl ProviderConnection {
         link_name: String,
         incoming_invocation_fn_map: WrpcInvocationLookup,
         config: HashMap<String, String>,
+        middleware: Middleware,
     ) -> ProviderInitResult<ProviderConnection> {
         Ok(ProviderConnection {
             links: Arc::default(),
@@ -821,6 +859,7 @@ impl ProviderConnection {
             provider_key,
             incoming_invocation_fn_map: Arc::new(incoming_invocation_fn_map),
             config,
+            middleware: Arc::new(middleware),
         })
     }
 
@@ -839,10 +878,30 @@ impl ProviderConnection {
         &self.provider_key
     }
 
+    /// Returns the ID of the component on the other end of the link, which is the target
+    /// if this provider is the source of the link and the source otherwise
+    pub fn linked_id<'a>(&self, ld: &'a InterfaceLinkDefinition) -> &'a str {
+        if ld.source_id == self.provider_key {
+            &ld.target
+        } else {
+            &ld.source_id
+        }
+    }
+
     /// Stores actor with link definition
     pub async fn put_link(&self, ld: InterfaceLinkDefinition) {
         let mut update = self.links.write().await;
-        update.insert(ld.source_id.to_string(), ld);
+        update.insert(self.linked_id(&ld).to_string(), ld);
+    }
+
+    /// Returns the link with the actor, if any
+    pub async fn get_link(&self, actor_id: &str) -> Option<InterfaceLinkDefinition> {
+        self.links.read().await.get(actor_id).cloned()
+    }
+
+    /// Returns true if exactly this link is stored, i.e. putting it again would not change anything
+    pub(crate) async fn has_link(&self, ld: &InterfaceLinkDefinition) -> bool {
+        self.links.read().await.get(self.linked_id(ld)) == Some(ld)
     }
 
     /// Deletes link
@@ -869,7 +928,7 @@ impl ProviderConnection {
     /// It will exit if the nats client disconnects, or if a signal is received on the quit channel.
     pub async fn subscribe_rpc(
         &self,
-        provider: impl WrpcDispatch + Clone + Send + 'static,
+        provider: impl WrpcDispatch + Clone + Send + Sync + 'static,
         quit: QuitSignal,
         lattice: String,
         provider_id: impl AsRef<str>,
@@ -972,7 +1031,7 @@ impl ProviderConnection {
                             current.record("link_name", &tracing::field::display(&link_name));
 
                             // Perform RPC
-                            match this.handle_wrpc(provider.clone(), operation.clone(), source_id, params, context).in_current_span().await {
+                            match this.handle_wrpc(provider.clone(), world_key_name.clone(), wit_fn.clone(), source_id, params, context).in_current_span().await {
                                 Ok(bytes) => {
                                     // Assuming that the provider has processed the request and produced objects
                                     // that conform to wrpc, transmit the response that were returned by the invocation
@@ -1006,29 +1065,124 @@ impl ProviderConnection {
     /// # Arguments
     ///
     /// * `provider` - The Provider
-    /// * `operation` - The operation being performed (of the form `<ns>:<pkg>/<interface>.<function>`)
+    /// * `interface` - The interface being invoked (of the form `<ns>:<pkg>/<interface>`)
+    /// * `function` - The function of the interface being invoked
     /// * `source_id` - The ID of the origin which might represent one or more components/providers (ex. an actor public key)
     /// * `wrpc_invocation` - Details of the wRPC invocation
     async fn handle_wrpc(
         &self,
-        provider: impl WrpcDispatch + 'static,
-        operation: String,
+        provider: impl WrpcDispatch + Send + Sync + 'static,
+        interface: String,
+        function: String,
         source_id: String,
         invocation_params: Vec<wrpc_transport::Value>,
         context: HeaderMap,
     ) -> InvocationResult<Vec<u8>> {
-        // Dispatch the invocation to the provider
-        let span = tracing::debug_span!("dispatch", %source_id, %operation);
-        provider
-            .dispatch_wrpc_dynamic(
-                Context {
-                    actor: Some(source_id),
-                    tracing: convert_header_map_to_hashmap(&context),
-                },
-                operation,
-                invocation_params,
-            )
+        // Run the invocation through the middleware and dispatch it to the provider
+        let span = tracing::debug_span!("dispatch", %source_id, %interface, %function);
+        let invocation = Invocation {
+            context: Context {
+                actor: Some(source_id),
+                tracing: convert_header_map_to_hashmap(&context),
+            },
+            interface,
+            function,
+            payload_size: payload_size(&invocation_params),
+            params: invocation_params,
+        };
+        self.middleware
+            .run(&provider, invocation)
             .instrument(span)
             .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use wasmcloud_core::InterfaceLinkDefinition;
+
+    use super::ProviderConnection;
+    use crate::middleware::Middleware;
+
+    const PROVIDER: &str = "provider";
+
+    async fn connection() -> ProviderConnection {
+        // The client is never used, it only retries connecting in the background
+        let nats = async_nats::ConnectOptions::new()
+            .retry_on_initial_connect()
+            .connect("127.0.0.1:1")
+            .await
+            .expect("failed to construct NATS client");
+        ProviderConnection::new(
+            Arc::new(nats),
+            PROVIDER.to_string(),
+            "lattice".to_string(),
+            "host".to_string(),
+            "default".to_string(),
+            HashMap::default(),
+            HashMap::default(),
+            Middleware::default(),
+        )
+        .expect("failed to construct connection")
+    }
+
+    fn link(source_id: &str, target: &str, config: &str) -> InterfaceLinkDefinition {
+        InterfaceLinkDefinition {
+            source_id: source_id.to_string(),
+            target: target.to_string(),
+            name: "default".to_string(),
+            wit_namespace: "wasi".to_string(),
+            wit_package: "http".to_string(),
+            interfaces: vec!["incoming-handler".to_string()],
+            source_config: vec![config.to_string()],
+            target_config: Vec::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_links() {
+        let connection = connection().await;
+        // Links are keyed by the component on the other end, whichever end the provider is on
+        for (ld, linked_id) in [
+            (link(PROVIDER, "target", "a"), "target"),
+            (link("source", PROVIDER, "a"), "source"),
+        ] {
+            assert_eq!(connection.linked_id(&ld), linked_id);
+            assert!(!connection.has_link(&ld).await);
+            connection.put_link(ld.clone()).await;
+            assert!(connection.is_linked(linked_id).await);
+            assert!(connection.has_link(&ld).await);
+            assert_eq!(connection.get_link(linked_id).await, Some(ld.clone()));
+
+            // Putting a changed link with the same component replaces the stored link
+            let changed = InterfaceLinkDefinition {
+                source_config: vec!["b".to_string()],
+                ..ld.clone()
+            };
+            assert!(!connection.has_link(&changed).await);
+            connection.put_link(changed.clone()).await;
+            assert!(connection.has_link(&changed).await);
+            assert!(!connection.has_link(&ld).await);
+            assert_eq!(connection.get_link(linked_id).await, Some(changed.clone()));
+
+            connection.delete_link(connection.linked_id(&changed)).await;
+            assert!(!connection.is_linked(linked_id).await);
+            assert_eq!(connection.get_link(linked_id).await, None);
+        }
+
+        // Links to several components are kept side by side
+        connection.put_link(link(PROVIDER, "first", "a")).await;
+        connection.put_link(link(PROVIDER, "second", "a")).await;
+        connection.put_link(link("third", PROVIDER, "a")).await;
+        for linked_id in ["first", "second", "third"] {
+            assert!(connection.is_linked(linked_id).await, "{linked_id}");
+        }
+        connection.delete_link("second").await;
+        assert!(connection.is_linked("first").await);
+        assert!(!connection.is_linked("second").await);
+        assert!(connection.is_linked("third").await);
+    }
+}
@@ -3,10 +3,13 @@ use wasmcloud_provider_wit_bindgen::deps::wasmcloud_provider_sdk;
 use wasmcloud_provider_httpclient::HttpClientProvider;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let hd = wasmcloud_provider_sdk::load_host_data()?;
+    let provider = HttpClientProvider::new(&hd.config)?;
+
     // start_provider initializes the threaded tokio executor,
     // listens to lattice rpcs, handles actor links,
     // and returns only when it receives a shutdown message
-    wasmcloud_provider_sdk::start_provider(HttpClientProvider {}, "http-client-provider")?;
+    wasmcloud_provider_sdk::start_provider(provider, "http-client-provider")?;
 
     eprintln!("HttpClient provider exiting");
     Ok(())
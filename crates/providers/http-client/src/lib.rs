@@ -1,16 +1,30 @@
+//! HTTP client capability provider.
+//!
+//! Requests of actors are sent with a separate HTTP client per link, configured by the link
+//! values described in [`ClientSettings`]. Besides the buffered `wasmcloud:httpclient` contract,
+//! the provider serves `wrpc:http/outgoing-handler`, streaming request and response bodies of
+//! `wasi:http` requests.
+
 use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::{Arc, Once};
 
 use anyhow::Context as _;
 use anyhow::Result;
 use http::{HeaderMap, HeaderName, HeaderValue};
-use tracing::{error, instrument, trace, warn};
+use tokio::sync::RwLock;
+use tracing::{error, info, instrument, trace, warn};
 
 use wasmcloud_provider_wit_bindgen::deps::{
-    async_trait::async_trait, wasmcloud_provider_sdk::core::LinkDefinition,
-    wasmcloud_provider_sdk::Context,
+    async_trait::async_trait,
+    wasmcloud_provider_sdk::{Context, LinkConfig, ProviderOperationResult},
 };
 
+mod outgoing;
+mod settings;
+
+pub use settings::ClientSettings;
+
 wasmcloud_provider_wit_bindgen::generate!({
     impl_struct: HttpClientProvider,
     contract: "wasmcloud:httpclient",
@@ -18,9 +32,58 @@ wasmcloud_provider_wit_bindgen::generate!({
     wit_bindgen_cfg: "provider-http-client"
 });
 
+/// HTTP client of a link, along with the settings it was built with
+#[derive(Clone)]
+pub(crate) struct LinkClient {
+    pub(crate) client: reqwest::Client,
+    pub(crate) settings: Arc<ClientSettings>,
+}
+
+impl LinkClient {
+    fn new(settings: ClientSettings) -> Result<Self> {
+        Ok(Self {
+            client: settings.build_client()?,
+            settings: Arc::new(settings),
+        })
+    }
+}
+
 /// HTTP client capability provider implementation struct
-#[derive(Default, Clone)]
-pub struct HttpClientProvider;
+#[derive(Clone)]
+pub struct HttpClientProvider {
+    /// Provider configuration, which link values are merged into
+    config: Arc<HashMap<String, String>>,
+    /// Client used for requests of actors without a link
+    default_client: LinkClient,
+    /// Clients of linked actors, by source id
+    clients: Arc<RwLock<HashMap<String, LinkClient>>>,
+    /// Guards starting to serve `wrpc:http/outgoing-handler` once
+    serving: Arc<Once>,
+}
+
+impl HttpClientProvider {
+    /// Create a provider using the client settings in the provider configuration as defaults
+    pub fn new(config: &HashMap<String, String>) -> Result<Self> {
+        let settings =
+            ClientSettings::from_config(config).context("invalid provider configuration")?;
+        Ok(Self {
+            config: Arc::new(config.clone()),
+            default_client: LinkClient::new(settings)?,
+            clients: Arc::default(),
+            serving: Arc::new(Once::new()),
+        })
+    }
+
+    /// Returns the client of the link of `source_id`
+    pub(crate) async fn client(&self, source_id: Option<&str>) -> LinkClient {
+        if let Some(source_id) = source_id {
+            if let Some(client) = self.clients.read().await.get(source_id) {
+                return client.clone();
+            }
+        }
+        self.default_client.clone()
+    }
+}
 
 /// Implement the [httpclient contract](https://github.com/wasmCloud/interfaces/blob/main/httpclient)
 /// represented by the WIT interface @ `wit/provider-httpclient.wit`
@@ -32,8 +95,8 @@ impl WasmcloudHttpclientHttpClient for HttpClientProvider {
     /// error sending the request. If the remote server returned an http
     /// error (status other than 2xx), returns Ok with the status code and
     /// body returned from the remote server.
-    #[instrument(level = "debug", skip(self, _ctx, req), fields(actor_id = ?_ctx.actor, method = %req.method, url = %req.url))]
-    async fn request(&self, _ctx: Context, req: HttpRequest) -> HttpResponse {
+    #[instrument(level = "debug", skip(self, ctx, req), fields(actor_id = ?ctx.actor, method = %req.method, url = %req.url))]
+    async fn request(&self, ctx: Context, req: HttpRequest) -> HttpResponse {
         let headers: HeaderMap = match build_http_header_map(&req.headers) {
             Ok(headers) => headers,
             Err(e) => {
@@ -60,7 +123,10 @@ impl WasmcloudHttpclientHttpClient for HttpClientProvider {
 
         trace!("forwarding {} request to {}", &req.method, &req.url);
         // Perform request to upstream server that was requested by the actor
-        let response = match reqwest::Client::new()
+        let response = match self
+            .client(ctx.actor.as_deref())
+            .await
+            .client
             .request(method, &req.url)
             .headers(headers)
             .body(req.body)
@@ -125,25 +191,47 @@ impl WasmcloudHttpclientHttpClient for HttpClientProvider {
 /// Handle provider control commands
 #[async_trait]
 impl WasmcloudCapabilityProvider for HttpClientProvider {
-    /// Provider should perform any operations needed for a new link,
-    /// including setting up per-actor resources, and checking authorization.
-    /// If the link is allowed, return true, otherwise return false to deny the link.
-    #[instrument(level = "debug", skip(self, ld), fields(actor_id = %ld.actor_id))]
-    async fn put_link(&self, ld: &LinkDefinition) -> bool {
-        // Accept all links that are put without saving any information
-        true
+    /// Build the HTTP client of the linked actor from the link values, merged into the
+    /// provider configuration. Links with invalid client settings are rejected.
+    #[instrument(level = "debug", skip(self, link_config), fields(source_id = %link_config.get_source_id()))]
+    async fn receive_link_config_as_target(
+        &self,
+        link_config: impl LinkConfig,
+    ) -> ProviderOperationResult<()> {
+        // the lattice connection is only available once the provider has been started,
+        // so streaming requests are served starting with the first link
+        self.serving.call_once(|| {
+            tokio::spawn(outgoing::serve(self.clone()));
+        });
+
+        let mut config = self.config.as_ref().clone();
+        config.extend(link_config.get_config().clone());
+        let client = ClientSettings::from_config(&config)
+            .and_then(LinkClient::new)
+            .map_err(|err| {
+                warn!(?err, "rejecting link with invalid http client settings");
+                err
+            })?;
+        info!("established link");
+        self.clients
+            .write()
+            .await
+            .insert(link_config.get_source_id().to_string(), client);
+        Ok(())
     }
 
-    /// Handle notification that a link is dropped - close the connection
+    /// Handle notification that a link is dropped - drop the client of the actor
     #[instrument(level = "info", skip(self))]
-    async fn delete_link(&self, actor_id: &str) {
-        // Deleting links is a no-op since no link information was saved
+    async fn delete_link(&self, source_id: &str) -> ProviderOperationResult<()> {
+        self.clients.write().await.remove(source_id);
+        Ok(())
     }
 
-    /// Handle shutdown request by closing all connections
+    /// Handle shutdown request by dropping all clients, closing their connections
     #[instrument(level = "debug", skip(self))]
-    async fn shutdown(&self) {
-        // Shutting down is a no-op since no link information was saved
+    async fn shutdown(&self) -> ProviderOperationResult<()> {
+        self.clients.write().await.clear();
+        Ok(())
     }
 }
 
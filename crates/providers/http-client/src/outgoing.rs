@@ -0,0 +1,300 @@
+//! Streaming implementation of `wrpc:http/outgoing-handler`, which the host forwards the
+//! `wasi:http/outgoing-handler` requests of linked actors to.
+//!
+//! Request and response bodies are streamed between the actor and the remote server as they
+//! arrive, so neither has to fit in memory.
+
+use core::future::Future;
+use core::time::Duration;
+
+use bytes::Bytes;
+use futures::{Stream, StreamExt as _};
+use http::{HeaderMap, HeaderName, HeaderValue};
+use tracing::{debug, error, instrument, warn, Instrument as _};
+use wasmcloud_provider_wit_bindgen::deps::wasmcloud_provider_sdk::get_connection;
+use wrpc_interface_http::{
+    ErrorCode, Fields, IncomingRequest, Method, OutgoingHandler as _, Request, RequestOptions,
+    Response, Scheme,
+};
+use wrpc_transport::{AcceptedInvocation, Transmitter as _};
+
+use crate::{HttpClientProvider, LinkClient};
+
+/// Serve `wrpc:http/outgoing-handler.handle` invocations until the lattice connection is closed
+pub(crate) async fn serve(provider: HttpClientProvider) {
+    let connection = get_connection();
+    let wrpc = connection.get_wrpc_client(connection.provider_key());
+    let mut invocations = match wrpc.serve_handle().await {
+        Ok(invocations) => invocations,
+        Err(err) => {
+            error!(?err, "failed to serve `wrpc:http/outgoing-handler.handle`");
+            return;
+        }
+    };
+    while let Some(invocation) = invocations.next().await {
+        let AcceptedInvocation {
+            context,
+            params: (request, options),
+            result_subject,
+            error_subject,
+            transmitter,
+        } = match invocation {
+            Ok(invocation) => invocation,
+            Err(err) => {
+                warn!(
+                    ?err,
+                    "failed to accept `wrpc:http/outgoing-handler.handle` invocation"
+                );
+                continue;
+            }
+        };
+        let source_id = context
+            .as_ref()
+            .and_then(|headers| headers.get("source-id"))
+            .map(ToString::to_string);
+        let provider = provider.clone();
+        tokio::spawn(
+            async move {
+                let link = provider.client(source_id.as_deref()).await;
+                let response = handle(&link, request, options).await;
+                if let Err(err) = transmitter.transmit_static(result_subject, response).await {
+                    error!(?err, "failed to transmit response");
+                    if let Err(err) = transmitter
+                        .transmit_static(error_subject, format!("{err:#}"))
+                        .await
+                    {
+                        error!(?err, "failed to transmit error to invoker");
+                    }
+                }
+            }
+            .in_current_span(),
+        );
+    }
+}
+
+/// Send `request` to the remote server, returning the response once its headers are received.
+/// The response body is streamed to the actor while it is being transmitted, and ends early if it
+/// cannot be read from the remote server.
+#[instrument(level = "debug", skip_all, fields(method = ?request.method, authority = ?request.authority))]
+async fn handle(
+    link: &LinkClient,
+    request: IncomingRequest,
+    options: Option<RequestOptions>,
+) -> Result<
+    Response<
+        impl Stream<Item = Bytes> + Send + 'static,
+        impl Future<Output = Option<Fields>> + Send + 'static,
+    >,
+    ErrorCode,
+> {
+    let Request {
+        body,
+        // reqwest does not support sending request trailers
+        trailers: _,
+        method,
+        path_with_query,
+        scheme,
+        authority,
+        headers,
+    } = request;
+    let url = request_url(scheme, authority, path_with_query)?;
+    let method = request_method(method)?;
+    let headers = fields_to_header_map(headers)?;
+
+    // timeouts requested by the actor take precedence over the ones of the link
+    let options = options.unwrap_or_default();
+    let first_byte_timeout = options
+        .first_byte_timeout
+        .or(link.settings.first_byte_timeout);
+    let between_bytes_timeout = options
+        .between_bytes_timeout
+        .or(link.settings.between_bytes_timeout);
+
+    let send = link
+        .client
+        .request(method, url)
+        .headers(headers)
+        .body(reqwest::Body::wrap_stream(body))
+        .send();
+    let response = match first_byte_timeout {
+        Some(timeout) => tokio::time::timeout(timeout, send)
+            .await
+            .map_err(|_| ErrorCode::ConnectionReadTimeout)?,
+        None => send.await,
+    }
+    .map_err(error_code)?;
+    debug!(status = %response.status(), "received response headers");
+
+    let status = response.status().as_u16();
+    let headers = header_map_to_fields(response.headers());
+    let body = futures::stream::unfold(
+        Some(Box::pin(response.bytes_stream())),
+        move |stream| async move {
+            let mut stream = stream?;
+            match body_chunk(between_bytes_timeout, stream.next()).await? {
+                Ok(chunk) => Some((chunk, Some(stream))),
+                Err(err) => {
+                    // close the body stream, so that the actor does not wait for the rest of it
+                    warn!(?err, "failed to read the response body");
+                    None
+                }
+            }
+        },
+    );
+    Ok(Response {
+        body,
+        // reqwest does not expose response trailers
+        trailers: async { None },
+        status,
+        headers,
+    })
+}
+
+/// Await the next `chunk` of a response body for at most `timeout`, if set
+async fn body_chunk(
+    timeout: Option<Duration>,
+    chunk: impl Future<Output = Option<reqwest::Result<Bytes>>>,
+) -> Option<Result<Bytes, ErrorCode>> {
+    let chunk = match timeout {
+        Some(timeout) => match tokio::time::timeout(timeout, chunk).await {
+            Ok(chunk) => chunk,
+            Err(_) => return Some(Err(ErrorCode::ConnectionReadTimeout)),
+        },
+        None => chunk.await,
+    };
+    Some(chunk?.map_err(error_code))
+}
+
+fn request_url(
+    scheme: Option<Scheme>,
+    authority: Option<String>,
+    path_with_query: Option<String>,
+) -> Result<reqwest::Url, ErrorCode> {
+    let scheme = match scheme {
+        Some(Scheme::HTTP) => "http",
+        Some(Scheme::HTTPS) | None => "https",
+        Some(Scheme::Other(scheme)) => {
+            debug!(scheme, "unsupported request scheme");
+            return Err(ErrorCode::HttpProtocolError);
+        }
+    };
+    let authority = authority.ok_or(ErrorCode::HttpRequestUriInvalid)?;
+    let path_with_query = path_with_query.unwrap_or_else(|| "/".to_string());
+    reqwest::Url::parse(&format!("{scheme}://{authority}{path_with_query}"))
+        .map_err(|_| ErrorCode::HttpRequestUriInvalid)
+}
+
+fn request_method(method: Method) -> Result<reqwest::Method, ErrorCode> {
+    Ok(match method {
+        Method::Get => reqwest::Method::GET,
+        Method::Head => reqwest::Method::HEAD,
+        Method::Post => reqwest::Method::POST,
+        Method::Put => reqwest::Method::PUT,
+        Method::Delete => reqwest::Method::DELETE,
+        Method::Connect => reqwest::Method::CONNECT,
+        Method::Options => reqwest::Method::OPTIONS,
+        Method::Trace => reqwest::Method::TRACE,
+        Method::Patch => reqwest::Method::PATCH,
+        Method::Other(method) => reqwest::Method::from_bytes(method.as_bytes())
+            .map_err(|_| ErrorCode::HttpRequestMethodInvalid)?,
+    })
+}
+
+fn fields_to_header_map(fields: Fields) -> Result<HeaderMap, ErrorCode> {
+    let mut headers = HeaderMap::with_capacity(fields.len());
+    for (name, values) in fields {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| ErrorCode::InternalError(Some(format!("invalid header name `{name}`"))))?;
+        for value in values {
+            let value = HeaderValue::from_bytes(&value).map_err(|_| {
+                ErrorCode::InternalError(Some(format!("invalid value of header `{name}`")))
+            })?;
+            headers.append(&name, value);
+        }
+    }
+    Ok(headers)
+}
+
+fn header_map_to_fields(headers: &HeaderMap) -> Fields {
+    headers
+        .keys()
+        .map(|name| {
+            let values = headers
+                .get_all(name)
+                .iter()
+                .map(|value| Bytes::copy_from_slice(value.as_bytes()))
+                .collect();
+            (name.to_string(), values)
+        })
+        .collect()
+}
+
+fn error_code(err: reqwest::Error) -> ErrorCode {
+    if err.is_timeout() {
+        if err.is_connect() {
+            ErrorCode::ConnectionTimeout
+        } else {
+            ErrorCode::HttpResponseTimeout
+        }
+    } else if err.is_connect() {
+        ErrorCode::ConnectionRefused
+    } else {
+        ErrorCode::InternalError(Some(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::time::Duration;
+
+    use bytes::Bytes;
+    use wrpc_interface_http::{ErrorCode, Scheme};
+
+    use crate::outgoing::{body_chunk, request_url};
+
+    #[tokio::test]
+    async fn outgoing_body_chunk_timeout() {
+        let timeout = Some(Duration::from_millis(10));
+        assert_eq!(
+            body_chunk(timeout, futures::future::pending()).await,
+            Some(Err(ErrorCode::ConnectionReadTimeout))
+        );
+        assert_eq!(
+            body_chunk(timeout, async { Some(Ok(Bytes::from("test"))) }).await,
+            Some(Ok(Bytes::from("test")))
+        );
+        assert_eq!(body_chunk(None, async { None }).await, None);
+    }
+
+    #[test]
+    fn outgoing_request_url() {
+        let url = |scheme, authority: Option<&str>, path: Option<&str>| {
+            request_url(
+                scheme,
+                authority.map(ToString::to_string),
+                path.map(ToString::to_string),
+            )
+            .map(|url| url.to_string())
+        };
+        assert_eq!(
+            url(Some(Scheme::HTTP), Some("localhost:8080"), Some("/a?b=c")).as_deref(),
+            Ok("http://localhost:8080/a?b=c")
+        );
+        assert_eq!(
+            url(None, Some("example.com"), None).as_deref(),
+            Ok("https://example.com/")
+        );
+        assert_eq!(
+            url(Some(Scheme::HTTPS), None, Some("/")),
+            Err(ErrorCode::HttpRequestUriInvalid)
+        );
+        assert_eq!(
+            url(
+                Some(Scheme::Other("ftp".to_string())),
+                Some("example.com"),
+                None
+            ),
+            Err(ErrorCode::HttpProtocolError)
+        );
+    }
+}
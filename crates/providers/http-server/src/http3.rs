@@ -0,0 +1,141 @@
+//! HTTP/3 over QUIC, served on the UDP port of the bind address alongside the TCP listener
+//! when `tls.http3` is enabled. It shares the certificates, and their reloading, with the
+//! TCP listener.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use bytes::{Buf, Bytes};
+use tokio_rustls::rustls::{self, ServerConfig};
+use tracing::{debug, Instrument};
+
+use crate::tls::CertResolver;
+use crate::{dispatch_request, Inner, RouteTarget};
+
+/// Value of the `Alt-Svc` header advertising HTTP/3 on `port` to clients of the TCP listener
+pub(crate) fn alt_svc(port: u16) -> String {
+    format!("h3=\":{}\"; ma=86400", port)
+}
+
+/// Bind a QUIC endpoint to `addr`, using the certificates of `resolver`
+pub(crate) fn endpoint(
+    addr: SocketAddr,
+    resolver: Arc<CertResolver>,
+) -> Result<quinn::Endpoint, crate::HttpServerError> {
+    let mut config = ServerConfig::builder()
+        .with_safe_default_cipher_suites()
+        .with_safe_default_kx_groups()
+        .with_protocol_versions(&[&rustls::version::TLS13])
+        .map_err(|e| crate::HttpServerError::Init(format!("http3 tls config: {}", e)))?
+        .with_no_client_auth()
+        .with_cert_resolver(resolver);
+    config.alpn_protocols = vec![b"h3".to_vec()];
+    quinn::Endpoint::server(quinn::ServerConfig::with_crypto(Arc::new(config)), addr).map_err(|e| {
+        crate::HttpServerError::Settings(format!(
+            "failed binding http3 to address '{}' reason: {}",
+            addr, e
+        ))
+    })
+}
+
+/// Accept HTTP/3 connections on `endpoint`, calling the actor returned by `resolve`
+/// for the host and path of each request
+pub(crate) async fn serve<F>(endpoint: quinn::Endpoint, inner: Arc<Inner>, resolve: F)
+where
+    F: Fn(Option<&str>, &str) -> Option<Arc<RouteTarget>> + Clone + Send + Sync + 'static,
+{
+    while let Some(connecting) = endpoint.accept().await {
+        let inner = inner.clone();
+        let resolve = resolve.clone();
+        tokio::spawn(async move {
+            let conn = match connecting.await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    debug!(%err, "http3 handshake failed");
+                    return;
+                }
+            };
+            let mut conn = match h3::server::Connection::<_, Bytes>::new(h3_quinn::Connection::new(
+                conn,
+            ))
+            .await
+            {
+                Ok(conn) => conn,
+                Err(err) => {
+                    debug!(%err, "http3 connection failed");
+                    return;
+                }
+            };
+            loop {
+                match conn.accept().await {
+                    Ok(Some((req, stream))) => {
+                        let inner = inner.clone();
+                        let resolve = resolve.clone();
+                        tokio::spawn(async move {
+                            if let Err(err) = handle_request(req, stream, inner, resolve).await {
+                                debug!(%err, "http3 request failed");
+                            }
+                        });
+                    }
+                    Ok(None) => break,
+                    Err(err) => {
+                        debug!(%err, "http3 connection closed");
+                        break;
+                    }
+                }
+            }
+        });
+    }
+}
+
+async fn handle_request<F>(
+    req: http::Request<()>,
+    mut stream: h3::server::RequestStream<h3_quinn::BidiStream<Bytes>, Bytes>,
+    inner: Arc<Inner>,
+    resolve: F,
+) -> Result<(), h3::Error>
+where
+    F: Fn(Option<&str>, &str) -> Option<Arc<RouteTarget>>,
+{
+    let mut body = Vec::new();
+    while let Some(mut chunk) = stream.recv_data().await? {
+        while chunk.has_remaining() {
+            let bytes = chunk.chunk();
+            body.extend_from_slice(bytes);
+            let len = bytes.len();
+            chunk.advance(len);
+        }
+    }
+    let (parts, ()) = req.into_parts();
+    let path = parts.uri.path().to_string();
+    let query = parts.uri.query().unwrap_or_default().to_string();
+    let host = parts
+        .uri
+        .authority()
+        .map(|authority| authority.as_str())
+        .or_else(|| {
+            parts
+                .headers
+                .get(http::header::HOST)
+                .and_then(|host| host.to_str().ok())
+        });
+    let target = resolve(host, &path);
+    let span = tracing::debug_span!("http3 request", method = %parts.method, %path, %query, actor_id = tracing::field::Empty);
+    let response = dispatch_request(
+        inner,
+        target,
+        parts.method,
+        parts.headers,
+        path,
+        query,
+        Bytes::from(body),
+    )
+    .instrument(span)
+    .await;
+    let (parts, body) = response.into_parts();
+    stream
+        .send_response(http::Response::from_parts(parts, ()))
+        .await?;
+    stream.send_data(Bytes::from(body)).await?;
+    stream.finish().await
+}
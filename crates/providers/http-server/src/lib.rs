@@ -7,17 +7,22 @@
 //! ## Features:
 //!
 //! - HTTP/1 and HTTP/2
-//! - TLS
+//! - TLS, with certificates selected by server name (SNI) and reloaded
+//!   when their files change
+//! - HTTP/3 over QUIC (with the `http3` feature)
 //! - CORS support (select allowed_origins, allowed_methods,
 //!   allowed_headers.) Cors has sensible defaults so it should
 //!   work as-is for development purposes, and may need refinement
 //!   for production if a more secure configuration is required.
+//! - Requests are forwarded to linked components exporting `wasi:http/incoming-handler`
 //! - All settings can be specified at runtime, using per-actor link settings:
 //!   - bind interface/port
 //!   - logging level
 //!   - TLS
 //!   - Cors
 //! - Flexible confiuration loading: from host, or from local toml or json file.
+//! - Routing mode, in which actors share a listener and receive requests
+//!   by virtual host and path prefix
 //! - Fully asynchronous, using tokio lightweight "green" threads
 //! - Thread pool (for managing a pool of OS threads). The default
 //!   thread pool has one thread per cpu core.
@@ -31,45 +36,59 @@
 //! Tokio can manage a thread pool (of OS threads) to be shared
 //! by the all of the server green threads.
 //!
+//! Actors linked with a route (`virtual_host` and/or `path_prefix`) instead
+//! share the listener on their bind address, which dispatches each request
+//! to the actor with the best matching route, see [`Router`].
+//!
 
+use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
 
+use anyhow::{anyhow, Context as _};
 use bytes::Bytes;
 use flume::{bounded, Receiver, Sender};
-use futures::Future;
+use futures::{Future, StreamExt, TryStreamExt as _};
 use http::HeaderMap;
 use tokio::task::JoinHandle;
 use tracing::{debug, error, info, instrument, trace, warn, Instrument};
 use warp::path::FullPath;
 use warp::Filter;
+use wrpc_interface_http::IncomingHandler as _;
 
 use wasmcloud_provider_wit_bindgen::deps::{
     async_trait::async_trait,
-    serde::{Deserialize, Serialize},
     wasmcloud_provider_sdk,
-    wasmcloud_provider_sdk::core::{LinkDefinition, WasmCloudEntity},
-    wasmcloud_provider_sdk::error::InvocationError,
+    wasmcloud_provider_sdk::{LinkConfig, ProviderOperationResult},
 };
 
 mod hashmap_ci;
 pub(crate) use hashmap_ci::make_case_insensitive;
 
+#[cfg(feature = "http3")]
+mod http3;
+
+mod router;
+pub use router::{RouteTarget, Router};
+
 mod settings;
-pub use settings::{load_settings, ServiceSettings, CONTENT_LEN_LIMIT, DEFAULT_MAX_CONTENT_LEN};
+pub use settings::{
+    load_settings, Route, ServiceSettings, CONTENT_LEN_LIMIT, DEFAULT_MAX_CONTENT_LEN,
+};
+
+mod tls;
+pub use tls::CertResolver;
 
 mod warp_util;
-use warp_util::{convert_request_headers, convert_response_headers, cors_filter, opt_raw_query};
+use warp_util::{
+    convert_method, convert_request_headers, convert_response_headers, cors_filter, opt_raw_query,
+};
 
 wasmcloud_provider_wit_bindgen::generate!({
     impl_struct: HttpServerProvider,
     contract: "wasmcloud:httpserver",
-    replace_witified_maps: true,
-    exposed_interface_deny_list: [
-        "wasmcloud:bus/lattice",
-        "wasmcloud:bus/guest",
-    ],
     wit_bindgen_cfg: "provider-http-server"
 });
 
@@ -77,9 +96,95 @@ wasmcloud_provider_wit_bindgen::generate!({
 #[derive(Clone, Default)]
 pub struct HttpServerProvider {
     // map to store http server (and its link parameters) for each linked actor
-    actors: Arc<dashmap::DashMap<String, HttpServerCore>>,
+    actors: Arc<dashmap::DashMap<String, DedicatedServer>>,
+    // map to store the listeners shared by actors in routing mode, keyed by bind address
+    routed: Arc<tokio::sync::Mutex<HashMap<SocketAddr, RoutedServer>>>,
+}
+
+/// A listener serving a single actor
+struct DedicatedServer {
+    server: HttpServerCore,
+    join: JoinHandle<()>,
+}
+
+/// A listener shared by the actors linked in routing mode
+struct RoutedServer {
+    router: Arc<Router>,
+    server: HttpServerCore,
+}
+
+impl HttpServerProvider {
+    /// Add the route of an actor to the shared listener on its bind address,
+    /// starting the listener if this is the first actor routed on the address
+    async fn put_routed_link(
+        &self,
+        component_id: &str,
+        settings: ServiceSettings,
+        route: &Route,
+    ) -> Result<(), HttpServerError> {
+        let addr = settings
+            .address
+            .ok_or_else(|| HttpServerError::Settings("missing bind address".to_string()))?;
+        let target = RouteTarget::new(component_id, &settings);
+        let mut routed = self.routed.lock().await;
+        if let Some(shared) = routed.get(&addr) {
+            // listener-level settings are taken from the link that started the listener
+            if shared.server.settings.tls != settings.tls
+                || shared.server.settings.cors != settings.cors
+            {
+                return Err(HttpServerError::RouteConflict(format!(
+                    "tls and cors settings must match those of the other actors routed on '{}'",
+                    addr
+                )));
+            }
+            return shared.router.insert(route, target);
+        }
+
+        let router = Arc::new(Router::default());
+        router.insert(route, target)?;
+        let server = HttpServerCore::new(settings, call_actor);
+        server.start_routed(router.clone()).await?;
+        routed.insert(addr, RoutedServer { router, server });
+        Ok(())
+    }
+
+    /// Remove the routes and listeners of an actor, except for its route on the shared
+    /// listener on `keep`, which is replaced when the actor is routed on it again.
+    /// Returns once the dedicated listener of the actor, if any, has released its address
+    async fn remove_actor(&self, component_id: &str, keep: Option<SocketAddr>) {
+        if let Some((_, DedicatedServer { server, join })) = self.actors.remove(component_id) {
+            info!(%component_id, "httpserver stopping listener for actor");
+            server.begin_shutdown();
+            // in-flight requests may keep the listener alive, do not block the link on them
+            if tokio::time::timeout(LISTENER_SHUTDOWN_TIMEOUT, join)
+                .await
+                .is_err()
+            {
+                warn!(%component_id, "httpserver listener for actor did not shut down in time");
+            }
+        }
+        // stop shared listeners once no actor is routed on them anymore
+        self.routed.lock().await.retain(|addr, routed| {
+            if Some(*addr) == keep {
+                return true;
+            }
+            if routed.router.remove(component_id) {
+                info!(%component_id, %addr, "httpserver removed route of actor");
+            }
+            if routed.router.is_empty() {
+                info!(%addr, "httpserver stopping shared listener");
+                routed.server.begin_shutdown();
+                return false;
+            }
+            true
+        });
+    }
 }
 
+/// Maximum time to wait for the dedicated listener of an actor to release its address when the
+/// actor is unlinked or linked again
+const LISTENER_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Your provider can handle any of these methods
 /// to receive notification of new actor links, deleted links,
 /// and for handling health check.
@@ -88,41 +193,62 @@ pub struct HttpServerProvider {
 impl WasmcloudCapabilityProvider for HttpServerProvider {
     /// Provider should perform any operations needed for a new link,
     /// including setting up per-actor resources, and checking authorization.
-    /// If the link is allowed, return true, otherwise return false to deny the link.
-    async fn put_link(&self, ld: &LinkDefinition) -> bool {
-        let settings = match load_settings(&ld.values) {
-            Ok(s) => s,
-            Err(e) => {
-                error!(%e, ?ld, "httpserver failed to load settings for actor");
-                return false;
-            }
-        };
+    /// This provider is the source of links to the actors it forwards requests to.
+    #[instrument(level = "debug", skip_all, fields(component_id = %link_config.get_target_id()))]
+    async fn receive_link_config_as_source(
+        &self,
+        link_config: impl LinkConfig,
+    ) -> ProviderOperationResult<()> {
+        let component_id = link_config.get_target_id();
+        let values: Vec<(String, String)> = link_config
+            .get_config()
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        let settings = load_settings(&values).map_err(|e| {
+            error!(%e, "httpserver failed to load settings for actor");
+            e
+        })?;
+
+        // a link that is put again replaces the previous routes and listener of the actor
+        let keep = settings.route.as_ref().and(settings.address);
+        self.remove_actor(component_id, keep).await;
+
+        if let Some(route) = settings.route.clone() {
+            return self
+                .put_routed_link(component_id, settings, &route)
+                .await
+                .map_err(|e| {
+                    error!(%e, "httpserver failed to route requests to actor");
+                    e.into()
+                });
+        }
 
         // Start a server instance that calls the given actor
-        let http_server = HttpServerCore::new(settings.clone(), call_actor);
-        if let Err(e) = http_server.start(ld).await {
-            error!(%e, ?ld, "httpserver failed to start listener for actor");
-            return false;
-        }
+        let server = HttpServerCore::new(settings, call_actor);
+        let join = server.start(component_id).await.map_err(|e| {
+            error!(%e, "httpserver failed to start listener for actor");
+            e
+        })?;
 
         // Save the actor and server instance locally
-        self.actors.insert(ld.actor_id.to_string(), http_server);
-
-        true
+        self.actors
+            .insert(component_id.to_string(), DedicatedServer { server, join });
+        Ok(())
     }
 
     /// Handle notification that a link is dropped - stop the http listener
-    async fn delete_link(&self, actor_id: &str) {
-        if let Some(entry) = self.actors.remove(actor_id) {
-            info!(%actor_id, "httpserver stopping listener for actor");
-            entry.1.begin_shutdown();
-        }
+    async fn delete_link(&self, component_id: &str) -> ProviderOperationResult<()> {
+        self.remove_actor(component_id, None).await;
+        Ok(())
     }
 
     /// Handle shutdown request by shutting down all the http server threads
-    async fn shutdown(&self) {
+    async fn shutdown(&self) -> ProviderOperationResult<()> {
         // empty the actor link data and stop all servers
         self.actors.clear();
+        self.routed.lock().await.clear();
+        Ok(())
     }
 }
 
@@ -130,88 +256,125 @@ impl WasmcloudCapabilityProvider for HttpServerProvider {
 // Server //
 ////////////
 
-const HANDLE_REQUEST_METHOD: &str = "HttpServer.HandleRequest";
+/// Maximum number of TLS connections that completed their handshake, but were not yet picked up
+/// by the server
+const TLS_ACCEPT_BACKLOG: usize = 128;
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(crate = "wasmcloud_provider_wit_bindgen::deps::serde")]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug)]
 pub struct HttpRequest {
-    pub method: String,
+    pub method: http::Method,
+    pub scheme: wrpc_interface_http::Scheme,
+    pub authority: Option<String>,
     pub path: String,
     pub query_string: String,
     pub header: ::std::collections::HashMap<String, Vec<String>>,
-    #[serde(with = "::wasmcloud_provider_wit_bindgen::deps::serde_bytes")]
-    pub body: Vec<u8>,
+    pub body: Bytes,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(crate = "wasmcloud_provider_wit_bindgen::deps::serde")]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug)]
 pub struct HttpResponse {
     pub status_code: u16,
     pub header: ::std::collections::HashMap<String, Vec<String>>,
-    #[serde(with = "::wasmcloud_provider_wit_bindgen::deps::serde_bytes")]
     pub body: Vec<u8>,
 }
 
 pub struct Server<'a> {
-    ld: &'a LinkDefinition,
-    timeout: Option<std::time::Duration>,
+    component_id: &'a str,
 }
 
 impl<'a> Server<'a> {
-    pub fn new(ld: &'a LinkDefinition, timeout: Option<Duration>) -> Self {
-        Self { ld, timeout }
+    pub fn new(component_id: &'a str) -> Self {
+        Self { component_id }
     }
 
-    pub async fn handle_request(&self, req: HttpRequest) -> Result<HttpResponse, InvocationError> {
+    /// Forward `req` to `wrpc:http/incoming-handler.handle` of the actor, which is served by the
+    /// host for actors exporting `wasi:http/incoming-handler`
+    pub async fn handle_request(&self, req: HttpRequest) -> anyhow::Result<HttpResponse> {
         let connection = wasmcloud_provider_sdk::get_connection();
-
-        let client = connection.get_rpc_client();
-        let origin = WasmCloudEntity {
-            public_key: self.ld.provider_id.clone(),
-            link_name: self.ld.link_name.clone(),
-            contract_id: "wasmcloud:httpserver".to_string(),
-        };
-        let target = WasmCloudEntity {
-            public_key: self.ld.actor_id.clone(),
-            ..Default::default()
-        };
-
-        let data = wasmcloud_provider_sdk::serialize(&req)?;
-
-        let response = if let Some(timeout) = self.timeout {
-            client
-                .send_timeout(origin, target, HANDLE_REQUEST_METHOD, data, timeout)
-                .await?
+        let wrpc = connection.get_wrpc_client(self.component_id);
+
+        let HttpRequest {
+            method,
+            scheme,
+            authority,
+            path,
+            query_string,
+            header,
+            body,
+        } = req;
+        let path_with_query = if query_string.is_empty() {
+            path
         } else {
-            client
-                .send(origin, target, HANDLE_REQUEST_METHOD, data)
-                .await?
+            format!("{path}?{query_string}")
         };
-
-        if let Some(e) = response.error {
-            return Err(InvocationError::Unexpected(e));
-        }
-
-        let response: HttpResponse = wasmcloud_provider_sdk::deserialize(&response.msg)?;
-
-        Ok(response)
+        let request = wrpc_interface_http::Request {
+            body: futures::stream::iter((!body.is_empty()).then_some(body)),
+            trailers: async { None },
+            method: convert_method(&method),
+            path_with_query: Some(path_with_query),
+            scheme: Some(scheme),
+            authority,
+            headers: header
+                .into_iter()
+                .map(|(name, values)| (name, values.into_iter().map(Bytes::from).collect()))
+                .collect(),
+        };
+        let (response, tx) = wrpc
+            .invoke_handle(request)
+            .await
+            .context("failed to invoke `wrpc:http/incoming-handler.handle`")?;
+        let response = response.map_err(|err| anyhow!("actor returned an error: {err:?}"))?;
+        let wrpc_interface_http::Response {
+            body: mut response_body,
+            status,
+            headers,
+            ..
+        } = response;
+        let (body, ()) = tokio::try_join!(
+            async move {
+                let mut body = Vec::new();
+                while let Some(chunk) = response_body.try_next().await? {
+                    body.extend_from_slice(&chunk);
+                }
+                anyhow::Ok(body)
+            },
+            tx,
+        )
+        .context("failed to receive response body")?;
+        let header = headers
+            .into_iter()
+            .map(|(name, values)| {
+                // only headers with string values are forwarded to the client
+                let values = values
+                    .into_iter()
+                    .filter_map(|value| String::from_utf8(value.to_vec()).ok())
+                    .collect();
+                (name, values)
+            })
+            .collect();
+        Ok(HttpResponse {
+            status_code: status,
+            header,
+            body,
+        })
     }
 }
 
 /// Forward a [`Request`] to an Actor.
-#[instrument(level = "debug", skip_all, fields(actor_id = %ld.actor_id))]
+#[instrument(level = "debug", skip_all, fields(%component_id))]
 async fn call_actor(
-    ld: Arc<LinkDefinition>,
+    component_id: String,
     req: HttpRequest,
     timeout: Option<std::time::Duration>,
-) -> Result<HttpResponse, InvocationError> {
-    let sender = Server::new(&ld, timeout);
+) -> anyhow::Result<HttpResponse> {
+    let sender = Server::new(&component_id);
 
-    let rc = sender.handle_request(req).await;
+    let rc = match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, sender.handle_request(req)).await,
+        None => Ok(sender.handle_request(req).await),
+    };
     match rc {
-        Err(InvocationError::Timeout) => {
+        Err(_) => {
             error!("actor request timed out: returning 503",);
             Ok(HttpResponse {
                 status_code: 503,
@@ -220,16 +383,16 @@ async fn call_actor(
             })
         }
 
-        Ok(resp) => {
+        Ok(Ok(resp)) => {
             trace!(
                 status_code = %resp.status_code,
                 "http response received from actor"
             );
             Ok(resp)
         }
-        Err(e) => {
+        Ok(Err(e)) => {
             warn!(
-                error = %e,
+                error = ?e,
                 "actor responded with error"
             );
             Err(e)
@@ -258,16 +421,18 @@ pub enum HttpServerError {
 
     #[error("deserializing settings: {0}")]
     SettingsToml(toml::de::Error),
+
+    #[error("route conflict: {0}")]
+    RouteConflict(String),
 }
 
 /// Alias for functions that trigger an actor
 pub type AsyncCallActorFn = Box<
     dyn Fn(
-            Arc<LinkDefinition>,
+            String,
             HttpRequest,
             Option<Duration>,
-        )
-            -> Pin<Box<dyn Future<Output = Result<HttpResponse, InvocationError>> + Send + 'static>>
+        ) -> Pin<Box<dyn Future<Output = anyhow::Result<HttpResponse>> + Send + 'static>>
         + Send
         + Sync,
 >;
@@ -278,11 +443,11 @@ struct CallActorFn(AsyncCallActorFn);
 impl CallActorFn {
     fn call(
         &self,
-        ld: Arc<LinkDefinition>,
+        component_id: String,
         req: HttpRequest,
         timeout: Option<Duration>,
-    ) -> Pin<Box<dyn Future<Output = Result<HttpResponse, InvocationError>> + Send + 'static>> {
-        Box::pin((self.0.as_ref())(ld, req, timeout))
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<HttpResponse>> + Send + 'static>> {
+        Box::pin((self.0.as_ref())(component_id, req, timeout))
     }
 }
 
@@ -298,7 +463,7 @@ pub struct Inner {
 ///
 /// ```no_test
 ///   use wasmcloud_provider_httpserver::{HttpServer, load_settings};
-///   let settings = load_settings(ld.values)?;
+///   let settings = load_settings(&values)?;
 ///   let server = HttpServer::new(settings);
 ///   let task = server.serve()?;
 ///   tokio::task::spawn(task);
@@ -319,8 +484,8 @@ impl HttpServerCore {
     /// Initializes server with settings
     pub fn new<F, Fut>(settings: ServiceSettings, call_actor_fn: F) -> Self
     where
-        F: Fn(Arc<LinkDefinition>, HttpRequest, Option<Duration>) -> Fut + Send + Sync + 'static,
-        Fut: Future<Output = Result<HttpResponse, InvocationError>> + 'static + Send,
+        F: Fn(String, HttpRequest, Option<Duration>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<HttpResponse>> + 'static + Send,
     {
         let (shutdown_tx, shutdown_rx) = bounded(1);
         let call_actor_fn = Arc::new(call_actor_fn);
@@ -330,9 +495,9 @@ impl HttpServerCore {
                 shutdown_tx,
                 shutdown_rx,
                 call_actor: CallActorFn(Box::new(
-                    move |ld: Arc<LinkDefinition>, req: HttpRequest, timeout: Option<Duration>| {
+                    move |component_id: String, req: HttpRequest, timeout: Option<Duration>| {
                         let call_actor_fn = call_actor_fn.clone();
-                        Box::pin(call_actor_fn(ld, req, timeout))
+                        Box::pin(call_actor_fn(component_id, req, timeout))
                     },
                 )),
             }),
@@ -347,21 +512,41 @@ impl HttpServerCore {
     /// Start the server in a new thread
     /// ```no_test
     ///    use wasmcloud_provider_httpserver::{HttpServer, load_settings};
-    ///    let settings = load_settings(&ld.values)?;
+    ///    let settings = load_settings(&values)?;
     ///    let server = HttpServer::new(settings);
-    ///    let _ = server.start().await?;
+    ///    let _ = server.start(component_id).await?;
     /// ```
-    pub async fn start(&self, ld: &LinkDefinition) -> Result<JoinHandle<()>, HttpServerError> {
-        let timeout = self
-            .inner
-            .settings
-            .timeout_ms
-            .map(std::time::Duration::from_millis);
-
-        let ld = Arc::new(ld.clone());
-        let linkdefs = ld.clone();
-        let trace_ld = ld.clone();
+    pub async fn start(&self, component_id: &str) -> Result<JoinHandle<()>, HttpServerError> {
+        info!(
+            addr = ?self.settings.address,
+            %component_id,
+            "httpserver starting listener for actor",
+        );
+        let target = Arc::new(RouteTarget::new(component_id, &self.settings));
+        self.serve(move |_, _| Some(target.clone()))
+    }
+
+    /// Start the server in a new thread, dispatching requests to the actors in `router`
+    pub async fn start_routed(
+        &self,
+        router: Arc<Router>,
+    ) -> Result<JoinHandle<()>, HttpServerError> {
+        info!(
+            addr = ?self.settings.address,
+            "httpserver starting shared listener",
+        );
+        self.serve(move |host, path| router.resolve(host, path))
+    }
+
+    /// Start the server in a new thread, calling the actor returned by `resolve`
+    /// for the host and path of each request
+    fn serve<F>(&self, resolve: F) -> Result<JoinHandle<()>, HttpServerError>
+    where
+        F: Fn(Option<&str>, &str) -> Option<Arc<RouteTarget>> + Clone + Send + Sync + 'static,
+    {
         let arc_inner = self.inner.clone();
+        #[cfg(feature = "http3")]
+        let http3_resolve = resolve.clone();
         let route = warp::any()
             .and(warp::header::headers_cloned())
             .and(warp::method())
@@ -375,73 +560,17 @@ impl HttpServerCore {
                       body: Bytes,
                       path: FullPath,
                       query: String| {
-                    let span = tracing::debug_span!("http request", %method, path = %path.as_str(), %query);
-                    let ld = linkdefs.clone();
+                    let span = tracing::debug_span!("http request", %method, path = %path.as_str(), %query, actor_id = tracing::field::Empty);
+                    let host = headers.get(http::header::HOST).and_then(|host| host.to_str().ok());
+                    let target = resolve(host, path.as_str());
                     let arc_inner = arc_inner.clone();
-                    async move{
-                        if let Some(readonly_mode) = arc_inner.settings.readonly_mode{
-                            if readonly_mode && method!= http::method::Method::GET && method!= http::method::Method::HEAD {
-                                debug!("Cannot use other methods in Read Only Mode");
-                                // If this fails it is developer error, so unwrap is okay
-                                let resp = http::Response::builder().status(http::StatusCode::METHOD_NOT_ALLOWED).body(Vec::with_capacity(0)).unwrap();
-                                return Ok::<_, warp::Rejection>(resp)
-                            }
-                        }
-                        let hmap = convert_request_headers(&headers);
-                        let req = HttpRequest {
-                            body: Vec::from(body),
-                            header: hmap,
-                            method: method.as_str().to_ascii_uppercase(),
-                            path: path.as_str().to_string(),
-                            query_string: query,
-                        };
-                        trace!(
-                            ?req,
-                            "httpserver calling actor"
-                        );
-                        let response = match arc_inner.call_actor.call(ld.clone(), req, timeout).in_current_span().await {
-                            Ok(resp) => resp,
-                            Err(e) => {
-                                error!(
-                                    error = %e,
-                                    "Error sending Request to actor"
-                                );
-                                HttpResponse {
-                                    status_code: http::StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
-                                    body: Default::default(),
-                                    header: Default::default(),
-                                }
-                            }
-                        };
-                        let status = match http::StatusCode::from_u16(response.status_code) {
-                            Ok(status_code) => status_code,
-                            Err(e) => {
-                                error!(
-                                    status_code = %response.status_code,
-                                    error = %e,
-                                    "invalid response status code, changing to 500"
-                                );
-                                http::StatusCode::INTERNAL_SERVER_ERROR
-                            }
-                        };
-                        let http_builder = http::Response::builder()
-                        .status(status);
-                        let http_builder = if let Some(cache_control_header) = arc_inner.settings.cache_control.as_ref(){
-                            let mut builder = http_builder;
-                            builder = builder.header("Cache-Control",cache_control_header);
-                            builder
-                        }else{
-                            http_builder
-                        };
-                        // Unwrapping here because validation takes place for the linkdef
-                        let mut http_response = http_builder.body(response.body).unwrap();
-                        convert_response_headers(response.header, http_response.headers_mut());
-                        Ok::<_, warp::Rejection>(http_response)
+                    async move {
+                        let response = dispatch_request(arc_inner, target, method, headers, path.as_str().to_string(), query, body).await;
+                        Ok::<_, warp::Rejection>(response)
                     }.instrument(span)
                 },
             ).with(warp::trace(move |req_info| {
-                let actor_id = &trace_ld.actor_id;
-                let span = tracing::debug_span!("request", method = %req_info.method(), path = %req_info.path(), query = tracing::field::Empty, %actor_id);
+                let span = tracing::debug_span!("request", method = %req_info.method(), path = %req_info.path(), query = tracing::field::Empty);
                 if let Some(remote_addr) = req_info.remote_addr() {
                     span.record("remote_addr", &tracing::field::display(remote_addr));
                 }
@@ -450,11 +579,6 @@ impl HttpServerCore {
             }));
 
         let addr = self.settings.address.unwrap();
-        info!(
-            %addr,
-            actor_id = %ld.actor_id,
-            "httpserver starting listener for actor",
-        );
 
         // add Cors configuration, if enabled, and spawn either TlsServer or Server
         let cors = cors_filter(&self.settings)?;
@@ -462,20 +586,47 @@ impl HttpServerCore {
         let handle = tokio::runtime::Handle::current();
         let shutdown_rx = self.shutdown_rx.clone();
         let join = if self.settings.tls.is_set() {
-            let (_, fut) = server
-                .tls()
-                // unwrap ok here because tls.is_set confirmed both fields are some()
-                .key_path(self.settings.tls.priv_key_file.as_ref().unwrap())
-                .cert_path(self.settings.tls.cert_file.as_ref().unwrap())
-                // we'd prefer to use try_bind_with_graceful_shutdown but it's not supported
-                // for tls server yet. Waiting on https://github.com/seanmonstar/warp/pull/717
-                // attempt to bind to the address
-                .bind_with_graceful_shutdown(addr, async move {
+            // TLS is terminated here rather than by warp, so that certificates can be selected
+            // by server name and reloaded without restarting the listener
+            let resolver = Arc::new(tls::CertResolver::load(&self.settings.tls)?);
+            let listener = tls::bind(addr)?;
+            #[cfg(feature = "http3")]
+            let http3 = if self.settings.tls.http3.unwrap_or(false) {
+                let endpoint = http3::endpoint(addr, resolver.clone())?;
+                let inner = self.inner.clone();
+                Some(http3::serve(endpoint, inner, http3_resolve))
+            } else {
+                None
+            };
+            let acceptor = tls::acceptor(resolver.clone());
+            let (conn_tx, conn_rx) = bounded(TLS_ACCEPT_BACKLOG);
+            let fut = server.serve_incoming_with_graceful_shutdown(
+                conn_rx.into_stream().map(Ok::<_, std::io::Error>),
+                async move {
                     if let Err(err) = shutdown_rx.recv_async().await {
                         error!(%err, "shutting down httpserver listener");
                     }
-                });
-            handle.spawn(fut)
+                },
+            );
+            handle.spawn(async move {
+                #[cfg(feature = "http3")]
+                let http3 = async move {
+                    if let Some(http3) = http3 {
+                        http3.await;
+                    }
+                    std::future::pending::<()>().await
+                };
+                #[cfg(not(feature = "http3"))]
+                let http3 = std::future::pending::<()>();
+                // the accept loop, certificate watcher and HTTP/3 endpoint are dropped as soon
+                // as the server has shut down, which releases the listening sockets
+                tokio::select! {
+                    _ = fut => {}
+                    _ = tls::accept(listener, acceptor, conn_tx) => {}
+                    _ = resolver.watch() => {}
+                    _ = http3 => {}
+                }
+            })
         } else {
             let (_, fut) = server
                 .try_bind_with_graceful_shutdown(addr, async move {
@@ -497,9 +648,167 @@ impl HttpServerCore {
     }
 }
 
+/// Dispatch a request to the actor it is routed to, returning the response for the client
+pub(crate) async fn dispatch_request(
+    inner: Arc<Inner>,
+    target: Option<Arc<RouteTarget>>,
+    method: http::Method,
+    headers: HeaderMap,
+    path: String,
+    query: String,
+    body: Bytes,
+) -> http::Response<Vec<u8>> {
+    let Some(target) = target else {
+        debug!("no actor routed for request");
+        // If this fails it is developer error, so unwrap is okay
+        return http::Response::builder()
+            .status(http::StatusCode::NOT_FOUND)
+            .body(Vec::with_capacity(0))
+            .unwrap();
+    };
+    tracing::Span::current().record("actor_id", &tracing::field::display(&target.component_id));
+    if target.readonly_mode
+        && method != http::method::Method::GET
+        && method != http::method::Method::HEAD
+    {
+        debug!("Cannot use other methods in Read Only Mode");
+        // If this fails it is developer error, so unwrap is okay
+        return http::Response::builder()
+            .status(http::StatusCode::METHOD_NOT_ALLOWED)
+            .body(Vec::with_capacity(0))
+            .unwrap();
+    }
+    let hmap = convert_request_headers(&headers);
+    let scheme = if inner.settings.tls.is_set() {
+        wrpc_interface_http::Scheme::HTTPS
+    } else {
+        wrpc_interface_http::Scheme::HTTP
+    };
+    let authority = headers
+        .get(http::header::HOST)
+        .and_then(|host| host.to_str().ok())
+        .map(ToString::to_string);
+    let req = HttpRequest {
+        body,
+        header: hmap,
+        method,
+        scheme,
+        authority,
+        path,
+        query_string: query,
+    };
+    trace!(?req, "httpserver calling actor");
+    let response = match inner
+        .call_actor
+        .call(target.component_id.clone(), req, target.timeout)
+        .in_current_span()
+        .await
+    {
+        Ok(resp) => resp,
+        Err(e) => {
+            error!(error = ?e, "Error sending Request to actor");
+            HttpResponse {
+                status_code: http::StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                body: Default::default(),
+                header: Default::default(),
+            }
+        }
+    };
+    let status = match http::StatusCode::from_u16(response.status_code) {
+        Ok(status_code) => status_code,
+        Err(e) => {
+            error!(
+                status_code = %response.status_code,
+                error = %e,
+                "invalid response status code, changing to 500"
+            );
+            http::StatusCode::INTERNAL_SERVER_ERROR
+        }
+    };
+    let mut http_builder = http::Response::builder().status(status);
+    if let Some(cache_control_header) = target.cache_control.as_ref() {
+        http_builder = http_builder.header("Cache-Control", cache_control_header);
+    }
+    // advertise HTTP/3 to clients connected over TCP
+    #[cfg(feature = "http3")]
+    if inner.settings.tls.http3.unwrap_or(false) {
+        if let Some(addr) = inner.settings.address {
+            http_builder = http_builder.header(http::header::ALT_SVC, http3::alt_svc(addr.port()));
+        }
+    }
+    // Unwrapping here because validation takes place for the linkdef
+    let mut http_response = http_builder.body(response.body).unwrap();
+    convert_response_headers(response.header, http_response.headers_mut());
+    http_response
+}
+
 impl Drop for HttpServerCore {
     /// Drop the client connection. Does not block or fail if the client has already been closed.
     fn drop(&mut self) {
         let _ = self.shutdown_tx.try_send(true);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::net::SocketAddr;
+    use std::sync::Arc;
+
+    use crate::settings::{Route, ServiceSettings};
+    use crate::{
+        call_actor, HttpServerCore, HttpServerProvider, RouteTarget, RoutedServer, Router,
+    };
+
+    fn routed(component_ids: &[&str]) -> RoutedServer {
+        let router = Router::default();
+        for (i, component_id) in component_ids.iter().enumerate() {
+            let route = Route {
+                virtual_host: None,
+                path_prefix: Some(format!("/{i}")),
+            };
+            router
+                .insert(
+                    &route,
+                    RouteTarget::new(component_id, &ServiceSettings::default()),
+                )
+                .unwrap();
+        }
+        RoutedServer {
+            router: Arc::new(router),
+            server: HttpServerCore::new(ServiceSettings::default(), call_actor),
+        }
+    }
+
+    #[tokio::test]
+    async fn relink_removes_previous_routes() {
+        let a: SocketAddr = "127.0.0.1:8080".parse().unwrap();
+        let b: SocketAddr = "127.0.0.1:8081".parse().unwrap();
+        let provider = HttpServerProvider::default();
+        {
+            let mut routes = provider.routed.lock().await;
+            routes.insert(a, routed(&["x"]));
+            routes.insert(b, routed(&["x", "y"]));
+        }
+
+        // re-linking `x` on `a` removes its route on `b` and keeps the listener on `a`,
+        // where the route is replaced
+        provider.remove_actor("x", Some(a)).await;
+        {
+            let routes = provider.routed.lock().await;
+            assert_eq!(
+                routes[&a].router.resolve(None, "/0").unwrap().component_id,
+                "x"
+            );
+            assert!(routes[&b].router.resolve(None, "/0").is_none());
+            assert_eq!(
+                routes[&b].router.resolve(None, "/1").unwrap().component_id,
+                "y"
+            );
+        }
+
+        // listeners are stopped once their last route is removed
+        provider.remove_actor("y", None).await;
+        provider.remove_actor("x", None).await;
+        assert!(provider.routed.lock().await.is_empty());
+    }
+}
@@ -0,0 +1,236 @@
+//! Routing of requests to linked actors by virtual host and path prefix.
+//!
+//! In routing mode, all actors linked with a [`Route`] on the same address share a single
+//! listener, and every request is dispatched to the actor whose route matches it best:
+//! - routes for the request's virtual host take precedence over routes for any host
+//! - among those, the route with the longest matching path prefix wins
+//!
+//! Path prefixes match on whole path segments, so `/api` matches `/api` and `/api/users`,
+//! but not `/apis`.
+
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use crate::settings::{Route, ServiceSettings};
+use crate::HttpServerError;
+
+/// The actor a request is dispatched to, along with its per-actor settings
+#[derive(Debug)]
+pub struct RouteTarget {
+    pub(crate) component_id: String,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) readonly_mode: bool,
+    pub(crate) cache_control: Option<String>,
+}
+
+impl RouteTarget {
+    pub fn new(component_id: &str, settings: &ServiceSettings) -> Self {
+        Self {
+            component_id: component_id.to_string(),
+            timeout: settings.timeout_ms.map(Duration::from_millis),
+            readonly_mode: settings.readonly_mode.unwrap_or(false),
+            cache_control: settings.cache_control.clone(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct RouteEntry {
+    /// Normalized virtual host, `None` matching any host
+    host: Option<String>,
+    /// Normalized path prefix
+    prefix: String,
+    target: Arc<RouteTarget>,
+}
+
+impl RouteEntry {
+    fn matches(&self, host: Option<&str>, path: &str) -> bool {
+        if self.host.is_some() && self.host.as_deref() != host {
+            return false;
+        }
+        self.prefix == "/"
+            || path
+                .strip_prefix(self.prefix.as_str())
+                .map(|rest| rest.is_empty() || rest.starts_with('/'))
+                .unwrap_or(false)
+    }
+}
+
+/// Route table of the actors sharing a listener
+#[derive(Debug, Default)]
+pub struct Router {
+    routes: RwLock<Vec<RouteEntry>>,
+}
+
+impl Router {
+    /// Add a route to `target`, replacing any previous route of the same actor.
+    /// Returns an error if another actor is already routed the same virtual host and path prefix.
+    pub fn insert(&self, route: &Route, target: RouteTarget) -> Result<(), HttpServerError> {
+        let host = route.virtual_host.as_deref().map(normalize_host);
+        let prefix = normalize_prefix(route.path_prefix.as_deref().unwrap_or("/"));
+        let mut routes = self
+            .routes
+            .write()
+            .map_err(|_| HttpServerError::Init("route table lock poisoned".to_string()))?;
+        if let Some(conflict) = routes.iter().find(|entry| {
+            entry.host == host
+                && entry.prefix == prefix
+                && entry.target.component_id != target.component_id
+        }) {
+            return Err(HttpServerError::RouteConflict(format!(
+                "host '{}' and path prefix '{}' are already routed to actor {}",
+                host.as_deref().unwrap_or("*"),
+                prefix,
+                conflict.target.component_id
+            )));
+        }
+        routes.retain(|entry| entry.target.component_id != target.component_id);
+        routes.push(RouteEntry {
+            host,
+            prefix,
+            target: Arc::new(target),
+        });
+        Ok(())
+    }
+
+    /// Remove the route of `component_id`, returning whether there was one
+    pub fn remove(&self, component_id: &str) -> bool {
+        let Ok(mut routes) = self.routes.write() else {
+            return false;
+        };
+        let len = routes.len();
+        routes.retain(|entry| entry.target.component_id != component_id);
+        routes.len() != len
+    }
+
+    /// Returns true if no actor is routed
+    pub fn is_empty(&self) -> bool {
+        self.routes
+            .read()
+            .map(|routes| routes.is_empty())
+            .unwrap_or(true)
+    }
+
+    /// Find the actor to dispatch a request for `host` and `path` to
+    pub fn resolve(&self, host: Option<&str>, path: &str) -> Option<Arc<RouteTarget>> {
+        let host = host.map(normalize_host);
+        let routes = self.routes.read().ok()?;
+        routes
+            .iter()
+            .filter(|entry| entry.matches(host.as_deref(), path))
+            .max_by_key(|entry| (entry.host.is_some(), entry.prefix.len()))
+            .map(|entry| Arc::clone(&entry.target))
+    }
+}
+
+/// Lowercase the host and strip any port and trailing dot
+fn normalize_host(host: &str) -> String {
+    let host = match host.rsplit_once(':') {
+        // keep IPv6 literals, such as `[::1]`, intact
+        Some((name, port)) if !port.contains(']') => name,
+        _ => host,
+    };
+    host.trim_end_matches('.').to_ascii_lowercase()
+}
+
+/// Strip trailing slashes from the prefix, keeping the root prefix `/`
+fn normalize_prefix(prefix: &str) -> String {
+    match prefix.trim_end_matches('/') {
+        "" => "/".to_string(),
+        prefix => prefix.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::router::{RouteTarget, Router};
+    use crate::settings::{Route, ServiceSettings};
+
+    fn route(virtual_host: Option<&str>, path_prefix: Option<&str>) -> Route {
+        Route {
+            virtual_host: virtual_host.map(ToString::to_string),
+            path_prefix: path_prefix.map(ToString::to_string),
+        }
+    }
+
+    fn target(component_id: &str) -> RouteTarget {
+        RouteTarget::new(component_id, &ServiceSettings::default())
+    }
+
+    fn resolve(router: &Router, host: Option<&str>, path: &str) -> Option<String> {
+        router
+            .resolve(host, path)
+            .map(|target| target.component_id.clone())
+    }
+
+    #[test]
+    fn router_longest_prefix() {
+        let router = Router::default();
+        router.insert(&route(None, None), target("root")).unwrap();
+        router
+            .insert(&route(None, Some("/api")), target("api"))
+            .unwrap();
+        router
+            .insert(&route(None, Some("/api/v2/")), target("api-v2"))
+            .unwrap();
+
+        assert_eq!(resolve(&router, None, "/").as_deref(), Some("root"));
+        assert_eq!(resolve(&router, None, "/apis").as_deref(), Some("root"));
+        assert_eq!(resolve(&router, None, "/api").as_deref(), Some("api"));
+        assert_eq!(resolve(&router, None, "/api/v1/x").as_deref(), Some("api"));
+        assert_eq!(resolve(&router, None, "/api/v2").as_deref(), Some("api-v2"));
+        assert_eq!(
+            resolve(&router, None, "/api/v2/x").as_deref(),
+            Some("api-v2")
+        );
+    }
+
+    #[test]
+    fn router_virtual_host() {
+        let router = Router::default();
+        router
+            .insert(&route(None, Some("/static")), target("static"))
+            .unwrap();
+        router
+            .insert(&route(Some("Example.com"), None), target("example"))
+            .unwrap();
+
+        assert_eq!(
+            resolve(&router, Some("example.com:8080"), "/static/x").as_deref(),
+            Some("example")
+        );
+        assert_eq!(
+            resolve(&router, Some("other.com"), "/static/x").as_deref(),
+            Some("static")
+        );
+        assert_eq!(resolve(&router, Some("other.com"), "/"), None);
+        assert_eq!(resolve(&router, None, "/"), None);
+    }
+
+    #[test]
+    fn router_conflict() {
+        let router = Router::default();
+        router
+            .insert(&route(Some("example.com"), Some("/api")), target("a"))
+            .unwrap();
+        assert!(router
+            .insert(&route(Some("EXAMPLE.com"), Some("/api/")), target("b"))
+            .is_err());
+        // re-linking the same actor replaces its route
+        router
+            .insert(&route(Some("example.com"), Some("/v2")), target("a"))
+            .unwrap();
+        router
+            .insert(&route(Some("example.com"), Some("/api")), target("b"))
+            .unwrap();
+        assert_eq!(
+            resolve(&router, Some("example.com"), "/api").as_deref(),
+            Some("b")
+        );
+
+        assert!(router.remove("a"));
+        assert!(!router.remove("a"));
+        assert!(router.remove("b"));
+        assert!(router.is_empty());
+    }
+}
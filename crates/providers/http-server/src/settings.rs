@@ -29,6 +29,7 @@ use crate::HttpServerError;
 
 const DEFAULT_ADDR: &str = "127.0.0.1:8000";
 const DEFAULT_LOG_LEVEL: LogLevel = LogLevel::Debug;
+const DEFAULT_TLS_RELOAD_INTERVAL_SECS: u64 = 10;
 
 const CORS_ALLOWED_ORIGINS: &[&str] = &[];
 const CORS_ALLOWED_METHODS: &[&str] = &["GET", "POST", "PUT", "DELETE", "HEAD", "OPTIONS"];
@@ -90,6 +91,12 @@ pub struct ServiceSettings {
     /// The value may not be higher than i32::MAX
     pub max_content_len: Option<String>,
 
+    /// Route of the actor in routing mode. If set, the actor shares the listener on `address`
+    /// with all other actors linked with a route on the same address, and receives the requests
+    /// matching its virtual host and path prefix
+    #[serde(default)]
+    pub route: Option<Route>,
+
     /// capture any other configuration values
     #[serde(flatten)]
     extra: HashMap<String, serde_json::Value>,
@@ -106,6 +113,7 @@ impl Default for ServiceSettings {
             cache_control: None,
             readonly_mode: Some(false),
             max_content_len: Some(DEFAULT_MAX_CONTENT_LEN.to_string()),
+            route: None,
             extra: Default::default(),
         }
     }
@@ -159,6 +167,9 @@ impl ServiceSettings {
     /// Merge settings from other into self
     fn merge(&mut self, other: ServiceSettings) {
         merge!(self, other, address, cache_control, readonly_mode);
+        if let Some(route) = other.route {
+            self.route.get_or_insert_with(Route::default).merge(route);
+        }
         self.tls.merge(other.tls);
         self.cors.merge(other.cors);
         self.log.merge(other.log);
@@ -173,27 +184,53 @@ impl ServiceSettings {
         if self.address.is_none() {
             errors.push("missing bind address".to_string());
         }
+        let check_files = |errors: &mut Vec<String>, files: [(&str, &String); 2]| {
+            for f in files.iter() {
+                let path: &Path = f.1.as_ref();
+                if !path.is_file() {
+                    errors.push(format!(
+                        "missing tls.{} '{}'{}",
+                        f.0,
+                        &path.display(),
+                        if !path.is_absolute() {
+                            " : perhaps you should make the path absolute"
+                        } else {
+                            ""
+                        }
+                    ));
+                }
+            }
+        };
         match (&self.tls.cert_file, &self.tls.priv_key_file) {
             (None, None) => {}
             (Some(_), None) | (None, Some(_)) => {
                 errors.push("for tls, both 'cert_file' and 'priv_key_file' must be set".to_string())
             }
-            (Some(cert_file), Some(key_file)) => {
-                for f in [("cert_file", &cert_file), ("priv_key_file", &key_file)].iter() {
-                    let path: &Path = f.1.as_ref();
-                    if !path.is_file() {
-                        errors.push(format!(
-                            "missing tls.{} '{}'{}",
-                            f.0,
-                            &path.display(),
-                            if !path.is_absolute() {
-                                " : perhaps you should make the path absolute"
-                            } else {
-                                ""
-                            }
-                        ));
-                    }
-                }
+            (Some(cert_file), Some(key_file)) => check_files(
+                &mut errors,
+                [("cert_file", cert_file), ("priv_key_file", key_file)],
+            ),
+        }
+        for cert in self.tls.certificates.iter().flatten() {
+            if cert.server_names.is_empty() {
+                errors.push(format!(
+                    "tls certificate '{}' must have at least one server name",
+                    cert.cert_file
+                ));
+            }
+            check_files(
+                &mut errors,
+                [
+                    ("certificates.cert_file", &cert.cert_file),
+                    ("certificates.priv_key_file", &cert.priv_key_file),
+                ],
+            );
+        }
+        if self.tls.http3.unwrap_or(false) {
+            if !cfg!(feature = "http3") {
+                errors.push("http3 support is not enabled in this build".to_string());
+            } else if !self.tls.is_set() {
+                errors.push("http3 requires tls to be configured".to_string());
             }
         }
         if let Some(ref methods) = self.cors.allowed_methods {
@@ -211,6 +248,21 @@ impl ServiceSettings {
                 ));
             }
         }
+        if let Some(route) = self.route.as_ref() {
+            if let Some(host) = route.virtual_host.as_ref() {
+                if host.is_empty() || host.contains(['/', '?', '#']) {
+                    errors.push(format!("invalid route virtual_host: '{}'", host));
+                }
+            }
+            if let Some(prefix) = route.path_prefix.as_ref() {
+                if !prefix.starts_with('/') || prefix.contains(['?', '#']) {
+                    errors.push(format!(
+                        "invalid route path_prefix: '{}' (must begin with '/')",
+                        prefix
+                    ));
+                }
+            }
+        }
         if !errors.is_empty() {
             Err(HttpServerError::Settings(format!(
                 "\nInvalid httpserver settings: \n{}\n",
@@ -286,10 +338,42 @@ pub fn load_settings(values: &[(String, String)]) -> Result<ServiceSettings, Htt
         settings.readonly_mode = Some(readonly_mode.to_string().parse().unwrap_or(false));
     }
 
+    // accept route of the actor in routing mode
+    if let Some(virtual_host) = values.get("virtual_host") {
+        settings
+            .route
+            .get_or_insert_with(Route::default)
+            .virtual_host = Some(virtual_host.to_string());
+    }
+    if let Some(path_prefix) = values.get("path_prefix") {
+        settings
+            .route
+            .get_or_insert_with(Route::default)
+            .path_prefix = Some(path_prefix.to_string());
+    }
+
     settings.validate()?;
     Ok(settings)
 }
 
+/// Route of requests to an actor in routing mode
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(crate = "wasmcloud_provider_wit_bindgen::deps::serde")]
+pub struct Route {
+    /// Virtual host the requests are addressed to, matched against the `Host` header.
+    /// If not set, requests for any host are routed to the actor
+    pub virtual_host: Option<String>,
+
+    /// Path prefix of the requests, matched on whole path segments. Default "/"
+    pub path_prefix: Option<String>,
+}
+
+impl Route {
+    fn merge(&mut self, other: Route) {
+        merge!(self, other, virtual_host, path_prefix);
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(crate = "wasmcloud_provider_wit_bindgen::deps::serde")]
 pub struct Tls {
@@ -297,20 +381,68 @@ pub struct Tls {
     pub cert_file: Option<String>,
 
     pub priv_key_file: Option<String>,
+
+    /// additional certificates, selected by the server name (SNI) requested by the client.
+    /// `cert_file` is used if no server name matches, or the client does not send one
+    pub certificates: Option<Vec<SniCertificate>>,
+
+    /// how often (seconds) certificate files are checked for changes, which are loaded
+    /// without restarting the listener. 0 disables reloading. Default 10
+    pub reload_interval_secs: Option<u64>,
+
+    /// also serve HTTP/3 over QUIC on the UDP port of the bind address
+    pub http3: Option<bool>,
 }
 
 impl Tls {
     fn merge(&mut self, other: Tls) {
-        merge!(self, other, cert_file, priv_key_file);
+        merge!(
+            self,
+            other,
+            cert_file,
+            priv_key_file,
+            certificates,
+            reload_interval_secs,
+            http3
+        );
     }
 }
 
 impl Tls {
     pub fn is_set(&self) -> bool {
-        self.cert_file.is_some() && self.priv_key_file.is_some()
+        (self.cert_file.is_some() && self.priv_key_file.is_some())
+            || self
+                .certificates
+                .as_ref()
+                .map(|certs| !certs.is_empty())
+                .unwrap_or(false)
+    }
+
+    /// interval between checks of the certificate files for changes, if reloading is enabled
+    pub fn reload_interval(&self) -> Option<std::time::Duration> {
+        match self
+            .reload_interval_secs
+            .unwrap_or(DEFAULT_TLS_RELOAD_INTERVAL_SECS)
+        {
+            0 => None,
+            secs => Some(std::time::Duration::from_secs(secs)),
+        }
     }
 }
 
+/// A certificate served to clients requesting one of its server names
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(crate = "wasmcloud_provider_wit_bindgen::deps::serde")]
+pub struct SniCertificate {
+    /// server names the certificate is served for, e.g. `example.com` or `*.example.com`
+    pub server_names: Vec<String>,
+
+    /// path to the X.509 cert chain file. Must be PEM-encoded
+    pub cert_file: String,
+
+    pub priv_key_file: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(crate = "wasmcloud_provider_wit_bindgen::deps::serde")]
 pub struct Cors {
@@ -632,7 +764,7 @@ where
 mod test {
     use std::str::FromStr;
 
-    use crate::settings::{serde_json, CorsOrigin, ServiceSettings};
+    use crate::settings::{load_settings, serde_json, CorsOrigin, ServiceSettings};
 
     const GOOD_ORIGINS: &[&str] = &[
         // origins that should be parsed correctly
@@ -699,6 +831,24 @@ mod test {
         );
     }
 
+    #[test]
+    fn settings_route() {
+        let values = vec![
+            (
+                "config_json".to_string(),
+                r#"{"route": {"virtual_host": "example.com"}}"#.to_string(),
+            ),
+            ("path_prefix".to_string(), "/api".to_string()),
+        ];
+        let s = load_settings(&values).expect("load_settings");
+        let route = s.route.expect("route");
+        assert_eq!(route.virtual_host.as_deref(), Some("example.com"));
+        assert_eq!(route.path_prefix.as_deref(), Some("/api"));
+
+        let values = vec![("path_prefix".to_string(), "api".to_string())];
+        assert!(load_settings(&values).is_err());
+    }
+
     #[test]
     fn origins_deserialize() {
         // test CorsOrigin
@@ -0,0 +1,407 @@
+//! TLS for the http server.
+//!
+//! Certificates are selected by the server name (SNI) requested by the client, and are reloaded
+//! when their files change. Reloading only affects new handshakes, so the listener keeps running
+//! and established connections are not dropped.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+
+use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::rustls::server::{ClientHello, ResolvesServerCert};
+use tokio_rustls::rustls::sign::{any_supported_type, CertifiedKey};
+use tokio_rustls::rustls::{Certificate, PrivateKey, ServerConfig, SignatureScheme};
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+use tracing::{debug, error, info, warn};
+
+use crate::settings::Tls;
+use crate::HttpServerError;
+
+/// Maximum time a client may take to complete the TLS handshake
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Signature schemes used to check that a private key belongs to its certificate
+const KEY_CHECK_SCHEMES: [SignatureScheme; 5] = [
+    SignatureScheme::ED25519,
+    SignatureScheme::ECDSA_NISTP256_SHA256,
+    SignatureScheme::ECDSA_NISTP384_SHA384,
+    SignatureScheme::RSA_PSS_SHA256,
+    SignatureScheme::RSA_PKCS1_SHA256,
+];
+
+/// Certificates loaded from the files in the tls settings
+#[derive(Default)]
+struct Certificates {
+    /// certificate used if no server name matches
+    default: Option<Arc<CertifiedKey>>,
+    /// certificates by lowercase server name, wildcards in the form `*.example.com`
+    by_name: HashMap<String, Arc<CertifiedKey>>,
+}
+
+/// Resolves the certificate of each TLS handshake, using the most recently loaded certificates
+pub struct CertResolver {
+    tls: Tls,
+    certs: RwLock<Arc<Certificates>>,
+}
+
+impl CertResolver {
+    /// Load the certificates in the tls settings
+    pub fn load(tls: &Tls) -> Result<Self, HttpServerError> {
+        Ok(Self {
+            certs: RwLock::new(Arc::new(load_certificates(tls)?)),
+            tls: tls.clone(),
+        })
+    }
+
+    /// Load the certificates from their files again. If any of them fails to load,
+    /// the previously loaded certificates are kept
+    pub fn reload(&self) -> Result<(), HttpServerError> {
+        let certs = load_certificates(&self.tls)?;
+        let mut current = self
+            .certs
+            .write()
+            .map_err(|_| HttpServerError::Init("tls certificate lock poisoned".to_string()))?;
+        *current = Arc::new(certs);
+        Ok(())
+    }
+
+    /// Find the certificate for the server name requested by the client
+    fn lookup(&self, server_name: Option<&str>) -> Option<Arc<CertifiedKey>> {
+        let certs = self.certs.read().ok()?.clone();
+        let by_name = server_name.map(str::to_ascii_lowercase).and_then(|name| {
+            certs.by_name.get(&name).cloned().or_else(|| {
+                let (_, parent) = name.split_once('.')?;
+                certs.by_name.get(&format!("*.{}", parent)).cloned()
+            })
+        });
+        by_name.or_else(|| certs.default.clone())
+    }
+
+    /// Modification times of all certificate and key files
+    fn modified(&self) -> Vec<Option<SystemTime>> {
+        let default = [&self.tls.cert_file, &self.tls.priv_key_file]
+            .into_iter()
+            .flatten();
+        let sni = self
+            .tls
+            .certificates
+            .iter()
+            .flatten()
+            .flat_map(|cert| [&cert.cert_file, &cert.priv_key_file]);
+        default
+            .chain(sni)
+            .map(|path| {
+                std::fs::metadata(path)
+                    .and_then(|meta| meta.modified())
+                    .ok()
+            })
+            .collect()
+    }
+
+    /// Check the certificate files for changes at the interval in the tls settings,
+    /// reloading the certificates when any of them changed. Never returns
+    pub(crate) async fn watch(self: Arc<Self>) {
+        let Some(interval) = self.tls.reload_interval() else {
+            return std::future::pending().await;
+        };
+        let mut modified = self.modified();
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            let current = self.modified();
+            if current == modified {
+                continue;
+            }
+            // certificate and key files are usually replaced one after the other, so a failed
+            // reload is retried on the next tick until both are consistent
+            match self.reload() {
+                Ok(()) => {
+                    info!("httpserver reloaded tls certificates");
+                    modified = current;
+                }
+                Err(e) => {
+                    warn!(%e, "httpserver failed to reload tls certificates, keeping the previous ones")
+                }
+            }
+        }
+    }
+}
+
+impl ResolvesServerCert for CertResolver {
+    fn resolve(&self, client_hello: ClientHello) -> Option<Arc<CertifiedKey>> {
+        self.lookup(client_hello.server_name())
+    }
+}
+
+fn load_certificates(tls: &Tls) -> Result<Certificates, HttpServerError> {
+    let mut certs = Certificates::default();
+    if let (Some(cert_file), Some(key_file)) = (&tls.cert_file, &tls.priv_key_file) {
+        certs.default = Some(load_certified_key(cert_file, key_file)?);
+    }
+    for cert in tls.certificates.iter().flatten() {
+        let key = load_certified_key(&cert.cert_file, &cert.priv_key_file)?;
+        for name in &cert.server_names {
+            certs
+                .by_name
+                .insert(name.to_ascii_lowercase(), Arc::clone(&key));
+        }
+    }
+    Ok(certs)
+}
+
+fn load_certified_key(
+    cert_file: &str,
+    key_file: &str,
+) -> Result<Arc<CertifiedKey>, HttpServerError> {
+    let certs = read_pem(cert_file)?
+        .into_iter()
+        .filter_map(|item| match item {
+            rustls_pemfile::Item::X509Certificate(der) => Some(Certificate(der)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if certs.is_empty() {
+        return Err(HttpServerError::Settings(format!(
+            "no certificates found in tls cert file '{}'",
+            cert_file
+        )));
+    }
+    let key = read_pem(key_file)?
+        .into_iter()
+        .find_map(|item| match item {
+            rustls_pemfile::Item::RSAKey(der)
+            | rustls_pemfile::Item::PKCS8Key(der)
+            | rustls_pemfile::Item::ECKey(der) => Some(PrivateKey(der)),
+            _ => None,
+        })
+        .ok_or_else(|| {
+            HttpServerError::Settings(format!(
+                "no private key found in tls key file '{}'",
+                key_file
+            ))
+        })?;
+    let key = any_supported_type(&key).map_err(|e| {
+        HttpServerError::Settings(format!(
+            "unsupported private key in tls key file '{}': {}",
+            key_file, e
+        ))
+    })?;
+    check_key_matches(&certs[0], key.as_ref()).map_err(|e| {
+        HttpServerError::Settings(format!(
+            "private key in tls key file '{}' does not match the certificate in '{}': {}",
+            key_file, cert_file, e
+        ))
+    })?;
+    Ok(Arc::new(CertifiedKey::new(certs, key)))
+}
+
+/// Check that `key` is the private key of the end-entity certificate `cert`, by signing a
+/// message with the key and verifying the signature with the public key of the certificate
+fn check_key_matches(
+    cert: &Certificate,
+    key: &dyn tokio_rustls::rustls::sign::SigningKey,
+) -> Result<(), String> {
+    const MESSAGE: &[u8] = b"wasmcloud httpserver tls key check";
+
+    let signer = key
+        .choose_scheme(&KEY_CHECK_SCHEMES)
+        .ok_or_else(|| "no supported signature scheme".to_string())?;
+    let alg = match signer.scheme() {
+        SignatureScheme::ED25519 => &webpki::ED25519,
+        SignatureScheme::ECDSA_NISTP256_SHA256 => &webpki::ECDSA_P256_SHA256,
+        SignatureScheme::ECDSA_NISTP384_SHA384 => &webpki::ECDSA_P384_SHA384,
+        SignatureScheme::RSA_PSS_SHA256 => &webpki::RSA_PSS_2048_8192_SHA256_LEGACY_KEY,
+        SignatureScheme::RSA_PKCS1_SHA256 => &webpki::RSA_PKCS1_2048_8192_SHA256,
+        scheme => return Err(format!("unsupported signature scheme {:?}", scheme)),
+    };
+    let signature = signer.sign(MESSAGE).map_err(|e| e.to_string())?;
+    webpki::EndEntityCert::try_from(cert.0.as_slice())
+        .map_err(|e| format!("invalid certificate: {}", e))?
+        .verify_signature(alg, MESSAGE, &signature)
+        .map_err(|e| e.to_string())
+}
+
+fn read_pem(path: &str) -> Result<Vec<rustls_pemfile::Item>, HttpServerError> {
+    let file = File::open(path)
+        .map_err(|e| HttpServerError::Settings(format!("reading file {}: {}", path, e)))?;
+    rustls_pemfile::read_all(&mut BufReader::new(file))
+        .map_err(|e| HttpServerError::Settings(format!("invalid PEM file {}: {}", path, e)))
+}
+
+/// Create a TLS acceptor for HTTP/2 and HTTP/1.1 connections, using the certificates of `resolver`
+pub(crate) fn acceptor(resolver: Arc<CertResolver>) -> TlsAcceptor {
+    let mut config = ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_cert_resolver(resolver);
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    TlsAcceptor::from(Arc::new(config))
+}
+
+/// Bind a TCP listener to `addr`
+pub(crate) fn bind(addr: SocketAddr) -> Result<TcpListener, HttpServerError> {
+    std::net::TcpListener::bind(addr)
+        .and_then(|listener| {
+            listener.set_nonblocking(true)?;
+            TcpListener::from_std(listener)
+        })
+        .map_err(|e| {
+            HttpServerError::Settings(format!(
+                "failed binding to address '{}' reason: {}",
+                addr, e
+            ))
+        })
+}
+
+/// Accept connections on `listener` and send them to `tx` once their TLS handshake completed.
+/// Handshakes are performed concurrently, so that slow clients do not hold up others
+pub(crate) async fn accept(
+    listener: TcpListener,
+    acceptor: TlsAcceptor,
+    tx: flume::Sender<TlsStream<TcpStream>>,
+) {
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(err) => {
+                error!(%err, "httpserver failed to accept connection");
+                // avoid spinning on persistent errors, such as running out of file descriptors
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+        let acceptor = acceptor.clone();
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                Ok(Ok(stream)) => {
+                    let _ = tx.send_async(stream).await;
+                }
+                Ok(Err(err)) => debug!(%err, %peer, "tls handshake failed"),
+                Err(_) => debug!(%peer, "tls handshake timed out"),
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use crate::settings::{SniCertificate, Tls};
+    use crate::tls::CertResolver;
+
+    /// Write a self-signed certificate for `names` and its key to `dir`, returning their paths
+    fn write_cert(dir: &Path, file: &str, names: &[&str]) -> (String, String) {
+        let cert = rcgen::generate_simple_self_signed(
+            names.iter().map(ToString::to_string).collect::<Vec<_>>(),
+        )
+        .expect("generate certificate");
+        let cert_file = dir.join(format!("{file}.crt"));
+        let key_file = dir.join(format!("{file}.key"));
+        std::fs::write(
+            &cert_file,
+            cert.serialize_pem().expect("serialize certificate"),
+        )
+        .expect("write certificate");
+        std::fs::write(&key_file, cert.serialize_private_key_pem()).expect("write key");
+        (
+            cert_file.to_string_lossy().to_string(),
+            key_file.to_string_lossy().to_string(),
+        )
+    }
+
+    #[test]
+    fn tls_sni() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let (cert_file, priv_key_file) = write_cert(dir.path(), "default", &["localhost"]);
+        let (sni_cert, sni_key) = write_cert(dir.path(), "sni", &["example.com", "*.example.org"]);
+        let resolver = CertResolver::load(&Tls {
+            cert_file: Some(cert_file),
+            priv_key_file: Some(priv_key_file),
+            certificates: Some(vec![SniCertificate {
+                server_names: vec!["example.com".to_string(), "*.example.org".to_string()],
+                cert_file: sni_cert,
+                priv_key_file: sni_key,
+            }]),
+            ..Default::default()
+        })
+        .expect("load certificates");
+
+        let default = resolver.lookup(None).expect("default certificate");
+        let sni = resolver
+            .lookup(Some("example.com"))
+            .expect("sni certificate");
+        assert!(!Arc::ptr_eq(&default, &sni));
+        for name in ["EXAMPLE.com", "www.example.org"] {
+            let cert = resolver.lookup(Some(name)).expect("sni certificate");
+            assert!(Arc::ptr_eq(&cert, &sni), "{name}");
+        }
+        for name in ["localhost", "www.example.com", "a.b.example.org"] {
+            let cert = resolver.lookup(Some(name)).expect("default certificate");
+            assert!(Arc::ptr_eq(&cert, &default), "{name}");
+        }
+    }
+
+    #[test]
+    fn tls_key_mismatch() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let (cert_file, _) = write_cert(dir.path(), "server", &["localhost"]);
+        let (_, other_key_file) = write_cert(dir.path(), "other", &["localhost"]);
+        let tls = Tls {
+            cert_file: Some(cert_file.clone()),
+            priv_key_file: Some(other_key_file),
+            ..Default::default()
+        };
+        let err = CertResolver::load(&tls)
+            .err()
+            .expect("mismatched certificate and key were loaded");
+        assert!(err.to_string().contains("does not match"), "{err}");
+
+        // a reload swapping in a key of another certificate keeps the loaded certificates
+        let (cert_file, priv_key_file) = write_cert(dir.path(), "server", &["localhost"]);
+        let resolver = CertResolver::load(&Tls {
+            cert_file: Some(cert_file),
+            priv_key_file: Some(priv_key_file.clone()),
+            ..Default::default()
+        })
+        .expect("load certificates");
+        let before = resolver.lookup(Some("localhost")).expect("certificate");
+        write_cert(dir.path(), "other", &["localhost"]);
+        std::fs::copy(dir.path().join("other.key"), &priv_key_file).expect("replace key");
+        assert!(resolver.reload().is_err());
+        let current = resolver.lookup(Some("localhost")).expect("certificate");
+        assert!(Arc::ptr_eq(&current, &before));
+    }
+
+    #[test]
+    fn tls_reload() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let (cert_file, priv_key_file) = write_cert(dir.path(), "server", &["localhost"]);
+        let resolver = CertResolver::load(&Tls {
+            cert_file: Some(cert_file.clone()),
+            priv_key_file: Some(priv_key_file.clone()),
+            ..Default::default()
+        })
+        .expect("load certificates");
+        let before = resolver.lookup(Some("localhost")).expect("certificate");
+
+        write_cert(dir.path(), "server", &["localhost"]);
+        resolver.reload().expect("reload certificates");
+        let after = resolver.lookup(Some("localhost")).expect("certificate");
+        assert_ne!(before.cert, after.cert);
+
+        // a broken key file does not replace the loaded certificates
+        std::fs::write(&priv_key_file, "").expect("truncate key");
+        assert!(resolver.reload().is_err());
+        let current = resolver.lookup(Some("localhost")).expect("certificate");
+        assert!(Arc::ptr_eq(&current, &after));
+    }
+}
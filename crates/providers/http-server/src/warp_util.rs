@@ -65,6 +65,22 @@ pub(crate) fn convert_response_headers(
     }
 }
 
+/// Convert the method of a request from incoming warp server to the method of a `wrpc:http` request
+pub(crate) fn convert_method(method: &http::Method) -> wrpc_interface_http::Method {
+    match *method {
+        http::Method::GET => wrpc_interface_http::Method::Get,
+        http::Method::HEAD => wrpc_interface_http::Method::Head,
+        http::Method::POST => wrpc_interface_http::Method::Post,
+        http::Method::PUT => wrpc_interface_http::Method::Put,
+        http::Method::DELETE => wrpc_interface_http::Method::Delete,
+        http::Method::CONNECT => wrpc_interface_http::Method::Connect,
+        http::Method::OPTIONS => wrpc_interface_http::Method::Options,
+        http::Method::TRACE => wrpc_interface_http::Method::Trace,
+        http::Method::PATCH => wrpc_interface_http::Method::Patch,
+        _ => wrpc_interface_http::Method::Other(method.to_string()),
+    }
+}
+
 /// Get raw query as string or optional query
 pub(crate) fn opt_raw_query() -> impl Filter<Extract = (String,), Error = Infallible> + Copy {
     warp::any().and(
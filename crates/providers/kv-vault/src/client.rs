@@ -1,17 +1,30 @@
 //! Hashicorp vault client
 //!
-use std::{string::ToString, sync::Arc};
+use std::{future::Future, string::ToString, sync::Arc, time::Duration};
 
+use tokio::time::sleep;
+use vaultrs::api::kv2::requests::{ReadSecretRequest, SetSecretRequestOptions};
 use vaultrs::api::kv2::responses::SecretVersionMetadata;
 use vaultrs::client::{VaultClient, VaultClientSettings};
+use vaultrs::error::ClientError;
 
 use wasmcloud_provider_wit_bindgen::deps::serde::{de::DeserializeOwned, Serialize};
+use wasmcloud_provider_wit_bindgen::deps::serde_json::Value;
 
 use crate::{config::Config, error::VaultError};
 
 /// Vault HTTP api version. As of Vault 1.9.x (Feb 2022), all http api calls use version 1
 const API_VERSION: u8 = 1;
 
+/// Number of times a read-modify-write of a secret is attempted before giving up on
+/// concurrent modifications
+const MAX_UPDATE_ATTEMPTS: usize = 10;
+/// Delay before the first retry of a conflicting read-modify-write, doubled for every subsequent
+/// retry
+const UPDATE_BACKOFF_MIN: Duration = Duration::from_millis(10);
+/// Maximum delay between retries of a conflicting read-modify-write
+const UPDATE_BACKOFF_MAX: Duration = Duration::from_millis(500);
+
 /// Vault client connection information.
 #[derive(Clone)]
 pub struct Client {
@@ -75,6 +88,14 @@ impl Client {
             .map_err(VaultError::from)
     }
 
+    /// Deletes `version` of the secret, leaving any later version in place. Together with
+    /// [`Client::read_secret_version`] this never deletes a value that was written concurrently
+    pub async fn delete_version(&self, path: &str, version: u64) -> Result<(), VaultError> {
+        vaultrs::kv2::delete_versions(self.inner.as_ref(), &self.namespace, path, vec![version])
+            .await
+            .map_err(VaultError::from)
+    }
+
     /// Lists keys at the path
     pub async fn list_secrets(&self, path: &str) -> Result<Vec<String>, VaultError> {
         match vaultrs::kv2::list(self.inner.as_ref(), &self.namespace, path).await {
@@ -89,4 +110,218 @@ impl Client {
             Ok(secret_list) => Ok(secret_list),
         }
     }
+
+    /// Reads value of secret using namespace and key path, along with the current version of the
+    /// secret. Returns `None` if the secret does not exist or its latest version was deleted,
+    /// the version is 0 if the secret never existed
+    pub async fn read_secret_version(
+        &self,
+        path: &str,
+    ) -> Result<(Option<Value>, u64), VaultError> {
+        let endpoint = ReadSecretRequest::builder()
+            .mount(&self.namespace)
+            .path(path)
+            .build()
+            .expect("failed to build read request");
+        match vaultrs::api::exec_with_result(self.inner.as_ref(), endpoint).await {
+            Ok(res) => Ok((Some(res.data), res.metadata.version)),
+            // The metadata of deleted secrets is kept, so versioning continues where it left off
+            Err(ClientError::APIError { code: 404, .. }) => {
+                match vaultrs::kv2::read_metadata(self.inner.as_ref(), &self.namespace, path).await
+                {
+                    Ok(metadata) => Ok((None, metadata.current_version)),
+                    Err(ClientError::APIError { code: 404, .. }) => Ok((None, 0)),
+                    Err(e) => Err(e.into()),
+                }
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Writes value of secret using namespace and key path, if the current version of the secret
+    /// is `version`. A `version` of 0 only writes the secret if it does not exist yet
+    pub async fn write_secret_version<T: Serialize>(
+        &self,
+        path: &str,
+        data: &T,
+        version: u64,
+    ) -> Result<SecretVersionMetadata, VaultError> {
+        let cas = u32::try_from(version).map_err(|_| VaultError::InvalidValue {
+            path: path.to_string(),
+            reason: format!("version {version} is out of range for check-and-set"),
+        })?;
+        match vaultrs::kv2::set_with_options(
+            self.inner.as_ref(),
+            &self.namespace,
+            path,
+            data,
+            SetSecretRequestOptions { cas },
+        )
+        .await
+        {
+            Err(ClientError::APIError { code: 400, errors })
+                if errors.iter().any(|e| e.contains("check-and-set")) =>
+            {
+                Err(VaultError::Conflict {
+                    namespace: self.namespace.clone(),
+                    path: path.to_string(),
+                })
+            }
+            res => res.map_err(VaultError::from),
+        }
+    }
+
+    /// Atomically updates the secret at `path` using check-and-set. `f` is called with the current
+    /// value of the secret (`None` if it does not exist) and returns the value to write along with
+    /// the result of the update. `f` is called again with the new current value if the secret is
+    /// modified concurrently
+    pub async fn update_secret<R>(
+        &self,
+        path: &str,
+        f: impl FnMut(Option<Value>) -> Result<(Value, R), VaultError>,
+    ) -> Result<R, VaultError> {
+        update_versioned(
+            &self.namespace,
+            path,
+            || self.read_secret_version(path),
+            |value, version| async move {
+                self.write_secret_version(path, &value, version)
+                    .await
+                    .map(|_| ())
+            },
+            f,
+        )
+        .await
+    }
+}
+
+/// Atomically updates a versioned value using check-and-set, see [`Client::update_secret`].
+/// `read` returns the current value and version, `write` writes a value if the current version
+/// is the given one and fails with [`VaultError::Conflict`] otherwise
+async fn update_versioned<R, RF, WF>(
+    namespace: &str,
+    path: &str,
+    mut read: impl FnMut() -> RF,
+    mut write: impl FnMut(Value, u64) -> WF,
+    mut f: impl FnMut(Option<Value>) -> Result<(Value, R), VaultError>,
+) -> Result<R, VaultError>
+where
+    RF: Future<Output = Result<(Option<Value>, u64), VaultError>>,
+    WF: Future<Output = Result<(), VaultError>>,
+{
+    let mut backoff = UPDATE_BACKOFF_MIN;
+    for attempt in 1..=MAX_UPDATE_ATTEMPTS {
+        let (current, version) = read().await?;
+        let (value, res) = f(current)?;
+        match write(value, version).await {
+            Ok(()) => return Ok(res),
+            Err(VaultError::Conflict { .. }) if attempt < MAX_UPDATE_ATTEMPTS => {
+                sleep(backoff).await;
+                backoff = (backoff * 2).min(UPDATE_BACKOFF_MAX);
+            }
+            Err(VaultError::Conflict { .. }) => break,
+            Err(e) => return Err(e),
+        }
+    }
+    Err(VaultError::Conflict {
+        namespace: namespace.to_string(),
+        path: path.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use tokio::task::{yield_now, JoinSet};
+    use wasmcloud_provider_wit_bindgen::deps::serde_json::Value;
+
+    use super::{update_versioned, MAX_UPDATE_ATTEMPTS};
+    use crate::error::VaultError;
+
+    /// In-memory stand-in for a Vault secret, holding its value and version
+    type Secret = Arc<Mutex<(Option<Value>, u64)>>;
+
+    /// Increments the counter in `secret` like the provider does, yielding between the read and
+    /// the write so that concurrent increments conflict
+    async fn increment(secret: Secret) -> Result<u64, VaultError> {
+        update_versioned(
+            "secret",
+            "counter",
+            || {
+                let secret = Arc::clone(&secret);
+                async move {
+                    let current = secret.lock().unwrap().clone();
+                    yield_now().await;
+                    Ok(current)
+                }
+            },
+            |value, version| {
+                let secret = Arc::clone(&secret);
+                async move {
+                    let mut secret = secret.lock().unwrap();
+                    if secret.1 != version {
+                        return Err(VaultError::Conflict {
+                            namespace: "secret".to_string(),
+                            path: "counter".to_string(),
+                        });
+                    }
+                    *secret = (Some(value), version + 1);
+                    Ok(())
+                }
+            },
+            |value| {
+                let n = value.and_then(|v| v.as_u64()).unwrap_or_default() + 1;
+                Ok((Value::from(n), n))
+            },
+        )
+        .await
+    }
+
+    /// Runs `n` concurrent increments, returning the successful results and the number of
+    /// increments that failed due to conflicts
+    async fn increment_concurrently(secret: &Secret, n: usize) -> (Vec<u64>, usize) {
+        let mut tasks = JoinSet::new();
+        for _ in 0..n {
+            tasks.spawn(increment(Arc::clone(secret)));
+        }
+        let (mut succeeded, mut conflicts) = (Vec::new(), 0);
+        while let Some(res) = tasks.join_next().await {
+            match res.expect("increment task panicked") {
+                Ok(n) => succeeded.push(n),
+                Err(VaultError::Conflict { .. }) => conflicts += 1,
+                Err(e) => panic!("unexpected error: {e}"),
+            }
+        }
+        succeeded.sort_unstable();
+        (succeeded, conflicts)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_concurrent_updates_retry() {
+        let secret = Secret::default();
+        // Every round of attempts lets one increment through, so all of them succeed in time
+        let (succeeded, conflicts) = increment_concurrently(&secret, MAX_UPDATE_ATTEMPTS).await;
+        assert_eq!(
+            succeeded,
+            (1..=MAX_UPDATE_ATTEMPTS as u64).collect::<Vec<_>>()
+        );
+        assert_eq!(conflicts, 0);
+        let (value, version) = secret.lock().unwrap().clone();
+        assert_eq!(value, Some(Value::from(MAX_UPDATE_ATTEMPTS as u64)));
+        assert_eq!(version, MAX_UPDATE_ATTEMPTS as u64);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_concurrent_updates_conflict() {
+        let secret = Secret::default();
+        // Increments that keep conflicting give up, but no update is ever lost
+        let (succeeded, conflicts) = increment_concurrently(&secret, MAX_UPDATE_ATTEMPTS * 2).await;
+        assert!(conflicts > 0);
+        assert_eq!(succeeded.len() + conflicts, MAX_UPDATE_ATTEMPTS * 2);
+        assert_eq!(succeeded, (1..=succeeded.len() as u64).collect::<Vec<_>>());
+        let (value, version) = secret.lock().unwrap().clone();
+        assert_eq!(value, Some(Value::from(succeeded.len() as u64)));
+        assert_eq!(version, succeeded.len() as u64);
+    }
 }
@@ -9,6 +9,14 @@ pub enum VaultError {
     #[error("Key not found: namespace/key {namespace}/{path}")]
     NotFound { namespace: String, path: String },
 
+    /// The secret was modified concurrently and check-and-set failed, even after retrying
+    #[error("Key was modified concurrently: namespace/key {namespace}/{path}")]
+    Conflict { namespace: String, path: String },
+
+    /// The stored value can not be used for the requested operation
+    #[error("Invalid value at key {path}: {reason}")]
+    InvalidValue { path: String, reason: String },
+
     /// All other errors
     #[error("An error occurred with the request")]
     Client {
@@ -16,3 +24,15 @@ pub enum VaultError {
         source: vaultrs::error::ClientError,
     },
 }
+
+impl VaultError {
+    /// Name of the kind of error, used to attribute failures in metrics
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Self::NotFound { .. } => "not_found",
+            Self::Conflict { .. } => "conflict",
+            Self::InvalidValue { .. } => "invalid_value",
+            Self::Client { .. } => "client",
+        }
+    }
+}
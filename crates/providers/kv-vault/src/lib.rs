@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 use anyhow::{anyhow, Context as _, Result};
 use tokio::sync::RwLock;
@@ -8,7 +8,10 @@ use wasmcloud_provider_wit_bindgen::deps::{
     async_trait::async_trait,
     serde_json,
     serde_json::Value,
-    wasmcloud_provider_sdk::{Context, LinkConfig, ProviderOperationResult},
+    wasmcloud_provider_sdk::{
+        wasmcloud_tracing::{global, KeyValue},
+        Context, LinkConfig, ProviderOperationResult,
+    },
 };
 
 pub(crate) mod client;
@@ -22,6 +25,12 @@ use crate::error::VaultError;
 /// Token to indicate string data was passed during set
 pub const STRING_VALUE_MARKER: &str = "string_data___";
 
+/// Token to indicate the value is a list, managed by the `list_*` operations
+pub const LIST_VALUE_MARKER: &str = "list_data___";
+
+/// Token to indicate the value is a set, managed by the `set_*` operations
+pub const SET_VALUE_MARKER: &str = "set_data___";
+
 wasmcloud_provider_wit_bindgen::generate!({
     impl_struct: KvVaultProvider,
     contract: "wasmcloud:keyvalue",
@@ -56,6 +65,158 @@ impl KvVaultProvider {
             .clone();
         Ok(client)
     }
+
+    /// Records the failure of `operation`. The keyvalue contract cannot return errors, so failed
+    /// operations return empty or zero values to the caller. The failure is logged and counted in
+    /// the `wasmcloud_provider.kv_vault.errors` metric, attributed to the operation, the kind of
+    /// error and the calling component
+    fn record_error(&self, ctx: &Context, operation: &'static str, err: impl Into<anyhow::Error>) {
+        let err = err.into();
+        let kind = err
+            .downcast_ref::<VaultError>()
+            .map_or("link", VaultError::kind);
+        error!(operation, kind, "vault {operation} failed: {err:#}");
+        global::meter("wasmcloud-provider-kv-vault")
+            .u64_counter("wasmcloud_provider.kv_vault.errors")
+            .with_description("Number of keyvalue operations that failed")
+            .init()
+            .add(
+                1,
+                &[
+                    KeyValue::new("operation", operation),
+                    KeyValue::new("kind", kind),
+                    KeyValue::new("source_id", ctx.actor.clone().unwrap_or_default()),
+                ],
+            );
+    }
+
+    /// Reads the list or set (depending on `marker`) stored at `key`. Missing keys are empty
+    async fn read_collection(&self, ctx: &Context, key: &str, marker: &str) -> Result<Vec<String>> {
+        let client = self.get_client(ctx).await?;
+        let (value, _) = client.read_secret_version(key).await?;
+        Ok(decode_collection(key, value, marker)?)
+    }
+
+    /// Deletes the value stored at `key`, returning true if it existed. Only the version that was
+    /// read is deleted, so like the check-and-set updates a concurrent write is never lost
+    async fn clear(&self, ctx: &Context, key: &str) -> bool {
+        let client = match self.get_client(ctx).await {
+            Ok(client) => client,
+            Err(e) => {
+                self.record_error(ctx, "clear", e);
+                return false;
+            }
+        };
+
+        let version = match client.read_secret_version(key).await {
+            Ok((Some(_), version)) => version,
+            Ok((None, _)) => return false,
+            Err(e) => {
+                self.record_error(ctx, "clear", e);
+                return false;
+            }
+        };
+        match client.delete_version(key, version).await {
+            Ok(_) => true,
+            Err(e) => {
+                self.record_error(ctx, "clear", e);
+                false
+            }
+        }
+    }
+}
+
+/// Wraps a string value in a map, as Vault secrets must be maps
+fn encode_string(value: String) -> Value {
+    Value::Object(serde_json::Map::from_iter([(
+        STRING_VALUE_MARKER.to_string(),
+        Value::String(value),
+    )]))
+}
+
+/// Parses the counter stored at `key`, which may be written by `increment` or by `set` with a
+/// numeric value. Missing keys count as 0
+fn decode_counter(key: &str, value: Option<Value>) -> Result<i32, VaultError> {
+    let invalid = |reason: &str| VaultError::InvalidValue {
+        path: key.to_string(),
+        reason: reason.to_string(),
+    };
+    let value = match value {
+        None => return Ok(0),
+        Some(Value::Object(mut map)) => map
+            .remove(STRING_VALUE_MARKER)
+            .ok_or_else(|| invalid("value is not a number"))?,
+        Some(value) => value,
+    };
+    match value {
+        Value::String(s) => s
+            .trim()
+            .parse()
+            .map_err(|_| invalid("value is not a number")),
+        Value::Number(n) => n
+            .as_i64()
+            .and_then(|n| i32::try_from(n).ok())
+            .ok_or_else(|| invalid("value is not a 32-bit integer")),
+        _ => Err(invalid("value is not a number")),
+    }
+}
+
+/// Wraps the items of a list or set (depending on `marker`) in a map
+fn encode_collection(marker: &str, items: Vec<String>) -> Value {
+    Value::Object(serde_json::Map::from_iter([(
+        marker.to_string(),
+        Value::from(items),
+    )]))
+}
+
+/// Parses the items of the list or set (depending on `marker`) stored at `key`. Missing keys are
+/// empty
+fn decode_collection(
+    key: &str,
+    value: Option<Value>,
+    marker: &str,
+) -> Result<Vec<String>, VaultError> {
+    let kind = if marker == SET_VALUE_MARKER {
+        "set"
+    } else {
+        "list"
+    };
+    let Some(value) = value else {
+        return Ok(Vec::new());
+    };
+    let items = match value {
+        Value::Object(mut map) => map.remove(marker),
+        _ => None,
+    };
+    items
+        .and_then(|items| serde_json::from_value(items).ok())
+        .ok_or_else(|| VaultError::InvalidValue {
+            path: key.to_string(),
+            reason: format!("value is not a {kind}"),
+        })
+}
+
+/// Returns the items of `list` from `start` to `stop` (inclusive). Negative indices count from the
+/// end of the list and out of range indices are clamped to it
+fn list_range(list: Vec<String>, start: i32, stop: i32) -> Vec<String> {
+    let len = i64::try_from(list.len()).unwrap_or(i64::MAX);
+    let index = |i: i32| {
+        let i = i64::from(i);
+        if i < 0 {
+            (len + i).max(0)
+        } else {
+            i
+        }
+    };
+    let (start, stop) = (index(start), index(stop).min(len - 1));
+    if start > stop {
+        return Vec::new();
+    }
+    let (start, stop) = (start as usize, stop as usize);
+    list.into_iter()
+        .skip(start)
+        .take(stop - start + 1)
+        .collect()
 }
 
 /// Handle provider control commands, the minimum required of any provider on
@@ -145,7 +306,7 @@ impl WasmcloudKeyvalueKeyValue for KvVaultProvider {
         let client = match self.get_client(&ctx).await {
             Ok(client) => client,
             Err(e) => {
-                error!("failed to retrieve client: {e}");
+                self.record_error(&ctx, "get", e);
                 return GetResponse {
                     exists: false,
                     value: String::default(),
@@ -186,7 +347,7 @@ impl WasmcloudKeyvalueKeyValue for KvVaultProvider {
                 }
             }
             Err(e) => {
-                error!(error = %e, "vault read: other error");
+                self.record_error(&ctx, "get", e);
                 GetResponse {
                     exists: false,
                     value: String::default(),
@@ -210,7 +371,7 @@ impl WasmcloudKeyvalueKeyValue for KvVaultProvider {
         let client = match self.get_client(&ctx).await {
             Ok(client) => client,
             Err(e) => {
-                error!("failed to retrieve client: {e}");
+                self.record_error(&ctx, "del", e);
                 return false;
             }
         };
@@ -222,45 +383,129 @@ impl WasmcloudKeyvalueKeyValue for KvVaultProvider {
                 false
             }
             Err(e) => {
-                debug!(error = %e, "Error while deleting from vault");
+                self.record_error(&ctx, "del", e);
                 false
             }
         }
     }
 
     /// Increments a numeric value, returning the new value
-    async fn increment(&self, _ctx: Context, _arg: IncrementRequest) -> i32 {
-        error!("`increment` not implemented");
-        0
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(source_id = ?ctx.actor, key = %arg.key))]
+    async fn increment(&self, ctx: Context, arg: IncrementRequest) -> i32 {
+        let client = match self.get_client(&ctx).await {
+            Ok(client) => client,
+            Err(e) => {
+                self.record_error(&ctx, "increment", e);
+                return 0;
+            }
+        };
+
+        let res = client
+            .update_secret(&arg.key, |value| {
+                let value = decode_counter(&arg.key, value)?
+                    .checked_add(arg.value)
+                    .ok_or_else(|| VaultError::InvalidValue {
+                        path: arg.key.clone(),
+                        reason: "increment overflows".to_string(),
+                    })?;
+                Ok((encode_string(value.to_string()), value))
+            })
+            .await;
+        match res {
+            Ok(value) => value,
+            Err(e) => {
+                self.record_error(&ctx, "increment", e);
+                0
+            }
+        }
     }
 
     /// Append a value onto the end of a list. Returns the new list size
-    async fn list_add(&self, _ctx: Context, _arg: ListAddRequest) -> u32 {
-        error!("`list_add` not implemented");
-        0
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(source_id = ?ctx.actor, key = %arg.list_name))]
+    async fn list_add(&self, ctx: Context, arg: ListAddRequest) -> u32 {
+        let client = match self.get_client(&ctx).await {
+            Ok(client) => client,
+            Err(e) => {
+                self.record_error(&ctx, "list_add", e);
+                return 0;
+            }
+        };
+
+        let res = client
+            .update_secret(&arg.list_name, |value| {
+                let mut list = decode_collection(&arg.list_name, value, LIST_VALUE_MARKER)?;
+                list.push(arg.value.clone());
+                let len = list.len().try_into().unwrap_or(u32::MAX);
+                Ok((encode_collection(LIST_VALUE_MARKER, list), len))
+            })
+            .await;
+        match res {
+            Ok(len) => len,
+            Err(e) => {
+                self.record_error(&ctx, "list_add", e);
+                0
+            }
+        }
     }
 
     /// Deletes a list and its contents
     /// input: list name
     /// returns: true if the list existed and was deleted
-    async fn list_clear(&self, _ctx: Context, _arg: String) -> bool {
-        error!("`list_clear` not implemented");
-        false
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(source_id = ?ctx.actor, arg = %arg.to_string()))]
+    async fn list_clear(&self, ctx: Context, arg: String) -> bool {
+        self.clear(&ctx, &arg).await
     }
 
     /// Deletes an item from a list. Returns true if the item was removed.
-    async fn list_del(&self, _ctx: Context, _arg: ListDelRequest) -> bool {
-        error!("`list_del` not implemented");
-        false
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(source_id = ?ctx.actor, key = %arg.list_name))]
+    async fn list_del(&self, ctx: Context, arg: ListDelRequest) -> bool {
+        let client = match self.get_client(&ctx).await {
+            Ok(client) => client,
+            Err(e) => {
+                self.record_error(&ctx, "list_del", e);
+                return false;
+            }
+        };
+
+        let res = client
+            .update_secret(&arg.list_name, |value| {
+                let mut list = decode_collection(&arg.list_name, value, LIST_VALUE_MARKER)?;
+                let removed = match list.iter().position(|v| *v == arg.value) {
+                    Some(i) => {
+                        list.remove(i);
+                        true
+                    }
+                    None => false,
+                };
+                Ok((encode_collection(LIST_VALUE_MARKER, list), removed))
+            })
+            .await;
+        match res {
+            Ok(removed) => removed,
+            Err(e) => {
+                self.record_error(&ctx, "list_del", e);
+                false
+            }
+        }
     }
 
     /// Retrieves a range of values from a list using 0-based indices.
     /// Start and end values are inclusive, for example, (0,10) returns
     /// 11 items if the list contains at least 11 items. If the stop value
     /// is beyond the end of the list, it is treated as the end of the list.
-    async fn list_range(&self, _ctx: Context, _arg: ListRangeRequest) -> Vec<String> {
-        error!("`list_range` not implemented");
-        Vec::new()
+    /// Negative indices count from the end of the list.
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(source_id = ?ctx.actor, key = %arg.list_name))]
+    async fn list_range(&self, ctx: Context, arg: ListRangeRequest) -> Vec<String> {
+        match self
+            .read_collection(&ctx, &arg.list_name, LIST_VALUE_MARKER)
+            .await
+        {
+            Ok(list) => list_range(list, arg.start, arg.stop),
+            Err(e) => {
+                self.record_error(&ctx, "list_range", e);
+                Vec::new()
+            }
+        }
     }
 
     /// Sets the value of a key.
@@ -270,50 +515,108 @@ impl WasmcloudKeyvalueKeyValue for KvVaultProvider {
         let client = match self.get_client(&ctx).await {
             Ok(client) => client,
             Err(e) => {
-                error!("failed to retrieve client: {e}");
+                self.record_error(&ctx, "set", e);
                 return;
             }
         };
 
-        let value: Value = serde_json::from_str(&arg.value).unwrap_or_else(|_| {
-            let mut map = serde_json::Map::new();
-            map.insert(
-                STRING_VALUE_MARKER.to_string(),
-                Value::String(arg.value.clone()),
-            );
-            Value::Object(map)
-        });
+        let value: Value =
+            serde_json::from_str(&arg.value).unwrap_or_else(|_| encode_string(arg.value.clone()));
         match client.write_secret(&arg.key, &value).await {
             Ok(metadata) => {
                 debug!(?metadata, "set returned metadata");
             }
-            Err(VaultError::NotFound { namespace, path }) => {
-                error!(
-                    %namespace, %path,
-                    "write secret returned not found, returning empty results",
-                );
+            Err(e) => self.record_error(&ctx, "set", e),
+        }
+    }
+
+    /// Add an item into a set. Returns number of items added
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(source_id = ?ctx.actor, key = %arg.set_name))]
+    async fn set_add(&self, ctx: Context, arg: SetAddRequest) -> u32 {
+        let client = match self.get_client(&ctx).await {
+            Ok(client) => client,
+            Err(e) => {
+                self.record_error(&ctx, "set_add", e);
+                return 0;
             }
+        };
+
+        let res = client
+            .update_secret(&arg.set_name, |value| {
+                let mut set: BTreeSet<_> =
+                    decode_collection(&arg.set_name, value, SET_VALUE_MARKER)?
+                        .into_iter()
+                        .collect();
+                let added = set.insert(arg.value.clone());
+                Ok((
+                    encode_collection(SET_VALUE_MARKER, set.into_iter().collect()),
+                    added.into(),
+                ))
+            })
+            .await;
+        match res {
+            Ok(added) => added,
             Err(e) => {
-                error!(error = %e, "vault set: other error");
+                self.record_error(&ctx, "set_add", e);
+                0
             }
         }
     }
 
-    /// Add an item into a set. Returns number of items added
-    async fn set_add(&self, _ctx: Context, _arg: SetAddRequest) -> u32 {
-        error!("`set_add` not implemented");
-        0
-    }
+    /// Remove a item from the set. Returns number of items removed
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(source_id = ?ctx.actor, key = %arg.set_name))]
+    async fn set_del(&self, ctx: Context, arg: SetDelRequest) -> u32 {
+        let client = match self.get_client(&ctx).await {
+            Ok(client) => client,
+            Err(e) => {
+                self.record_error(&ctx, "set_del", e);
+                return 0;
+            }
+        };
 
-    /// Remove a item from the set. Returns
-    async fn set_del(&self, _ctx: Context, _arg: SetDelRequest) -> u32 {
-        error!("`set_del` not implemented");
-        0
+        let res = client
+            .update_secret(&arg.set_name, |value| {
+                let mut set: BTreeSet<_> =
+                    decode_collection(&arg.set_name, value, SET_VALUE_MARKER)?
+                        .into_iter()
+                        .collect();
+                let removed = set.remove(&arg.value);
+                Ok((
+                    encode_collection(SET_VALUE_MARKER, set.into_iter().collect()),
+                    removed.into(),
+                ))
+            })
+            .await;
+        match res {
+            Ok(removed) => removed,
+            Err(e) => {
+                self.record_error(&ctx, "set_del", e);
+                0
+            }
+        }
     }
 
-    async fn set_intersection(&self, _ctx: Context, _arg: Vec<String>) -> Vec<String> {
-        error!("`set_intersection` not implemented");
-        Vec::new()
+    /// Returns the items contained in all of the given sets
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(source_id = ?ctx.actor, keys = ?arg))]
+    async fn set_intersection(&self, ctx: Context, arg: Vec<String>) -> Vec<String> {
+        let mut intersection: Option<BTreeSet<String>> = None;
+        for key in arg {
+            let set = match self.read_collection(&ctx, &key, SET_VALUE_MARKER).await {
+                Ok(set) => set,
+                Err(e) => {
+                    self.record_error(&ctx, "set_intersection", e);
+                    return Vec::new();
+                }
+            };
+            intersection = Some(match intersection {
+                Some(intersection) => set
+                    .into_iter()
+                    .filter(|v| intersection.contains(v))
+                    .collect(),
+                None => set.into_iter().collect(),
+            });
+        }
+        intersection.unwrap_or_default().into_iter().collect()
     }
 
     /// returns a list of all secrets at the path
@@ -322,7 +625,7 @@ impl WasmcloudKeyvalueKeyValue for KvVaultProvider {
         let client = match self.get_client(&ctx).await {
             Ok(client) => client,
             Err(e) => {
-                error!("failed to retrieve client: {e}");
+                self.record_error(&ctx, "set_query", e);
                 return Vec::new();
             }
         };
@@ -337,22 +640,92 @@ impl WasmcloudKeyvalueKeyValue for KvVaultProvider {
                 Vec::new()
             }
             Err(e) => {
-                error!(error = %e, "vault list: other error");
+                self.record_error(&ctx, "set_query", e);
                 Vec::new()
             }
         }
     }
 
-    async fn set_union(&self, _ctx: Context, _arg: Vec<String>) -> Vec<String> {
-        error!("`set_union` not implemented");
-        Vec::new()
+    /// Returns the items contained in any of the given sets
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(source_id = ?ctx.actor, keys = ?arg))]
+    async fn set_union(&self, ctx: Context, arg: Vec<String>) -> Vec<String> {
+        let mut union = BTreeSet::new();
+        for key in arg {
+            match self.read_collection(&ctx, &key, SET_VALUE_MARKER).await {
+                Ok(set) => union.extend(set),
+                Err(e) => {
+                    self.record_error(&ctx, "set_union", e);
+                    return Vec::new();
+                }
+            }
+        }
+        union.into_iter().collect()
     }
 
     /// Deletes a set and its contents
     /// input: set name
     /// returns: true if the set existed and was deleted
-    async fn set_clear(&self, _ctx: Context, _arg: String) -> bool {
-        error!("`set_clear` not implemented");
-        false
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(source_id = ?ctx.actor, arg = %arg.to_string()))]
+    async fn set_clear(&self, ctx: Context, arg: String) -> bool {
+        self.clear(&ctx, &arg).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn list() -> Vec<String> {
+        ["a", "b", "c", "d"].map(String::from).to_vec()
+    }
+
+    #[test]
+    fn test_list_range() {
+        assert_eq!(list_range(list(), 0, 1), ["a", "b"]);
+        assert_eq!(list_range(list(), 1, 10), ["b", "c", "d"]);
+        assert_eq!(list_range(list(), 0, -1), ["a", "b", "c", "d"]);
+        assert_eq!(list_range(list(), -2, -1), ["c", "d"]);
+        assert_eq!(list_range(list(), -10, 0), ["a"]);
+        assert!(list_range(list(), 3, 1).is_empty());
+        assert!(list_range(list(), 4, 10).is_empty());
+        assert!(list_range(Vec::new(), 0, -1).is_empty());
+    }
+
+    #[test]
+    fn test_decode_counter() {
+        assert_eq!(decode_counter("k", None).unwrap(), 0);
+        assert_eq!(
+            decode_counter("k", Some(encode_string("41".to_string()))).unwrap(),
+            41
+        );
+        assert_eq!(decode_counter("k", Some(Value::from(-3))).unwrap(), -3);
+        assert!(matches!(
+            decode_counter("k", Some(encode_string("forty".to_string()))),
+            Err(VaultError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            decode_counter("k", Some(Value::from(i64::MAX))),
+            Err(VaultError::InvalidValue { .. })
+        ));
+    }
+
+    #[test]
+    fn test_decode_collection() {
+        assert!(decode_collection("k", None, LIST_VALUE_MARKER)
+            .unwrap()
+            .is_empty());
+        let value = encode_collection(LIST_VALUE_MARKER, list());
+        assert_eq!(
+            decode_collection("k", Some(value.clone()), LIST_VALUE_MARKER).unwrap(),
+            list()
+        );
+        assert!(matches!(
+            decode_collection("k", Some(value), SET_VALUE_MARKER),
+            Err(VaultError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            decode_collection("k", Some(encode_string("a".to_string())), LIST_VALUE_MARKER),
+            Err(VaultError::InvalidValue { .. })
+        ));
     }
 }
@@ -1,13 +1,14 @@
-// TODO(brooksmtownsend): bring the lattice control capability provider up-to-date with the control interface
-// use wasmcloud_provider_wit_bindgen::deps::wasmcloud_provider_sdk;
+//! wasmCloud Lattice Control capability provider
 
-// use wasmcloud_provider_lattice_controller::LatticeControllerProvider;
+use wasmcloud_provider_wit_bindgen::deps::wasmcloud_provider_sdk;
+
+use wasmcloud_provider_lattice_controller::LatticeControllerProvider;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // wasmcloud_provider_sdk::start_provider(
-    //     LatticeControllerProvider::with_cache_timeout_minutes(600),
-    //     Some("lattice-control-provider".to_string()),
-    // )?;
+    wasmcloud_provider_sdk::start_provider(
+        LatticeControllerProvider::from_host_data(wasmcloud_provider_sdk::load_host_data()?, 600),
+        "lattice-control-provider",
+    )?;
 
     eprintln!("Lattice Controller capability provider exiting");
     Ok(())
@@ -1,3 +1,6 @@
+use core::future::Future;
+use core::pin::Pin;
+
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -10,10 +13,19 @@ use wasmcloud_control_interface::Client;
 
 use crate::ConnectionConfig;
 
+/// Future of a client being created
+type ConnectFuture<C> = Pin<Box<dyn Future<Output = Result<C>> + Send>>;
+
+/// Creates a client from a connection configuration
+type Connector<C> = Arc<dyn Fn(ConnectionConfig) -> ConnectFuture<C> + Send + Sync>;
+
+/// Cache of clients keyed by the source component of the link they were configured by. Clients
+/// are only ever created from the configuration of their own link
 #[derive(Clone)]
-pub(crate) struct ClientCache {
+pub(crate) struct ClientCache<C = Client> {
     meta: Arc<RwLock<HashMap<String, ClientMetadata>>>,
-    clients: Arc<RwLock<HashMap<String, Client>>>,
+    clients: Arc<RwLock<HashMap<String, C>>>,
+    connect: Connector<C>,
 }
 
 #[derive(Debug, Clone)]
@@ -31,6 +43,20 @@ impl ClientMetadata {
 impl ClientCache {
     /// Creates a new client cache. Configures and starts the cache item expiration timer
     pub(crate) async fn new(expire_in_seconds: u64) -> Self {
+        Self::with_connector(
+            expire_in_seconds,
+            Arc::new(|config: ConnectionConfig| -> ConnectFuture<Client> {
+                Box::pin(async move { create_client(&config).await })
+            }),
+        )
+        .await
+    }
+}
+
+impl<C: Clone + Send + Sync + 'static> ClientCache<C> {
+    /// Creates a new client cache, which creates clients using `connect`. Configures and starts
+    /// the cache item expiration timer
+    async fn with_connector(expire_in_seconds: u64, connect: Connector<C>) -> Self {
         let meta = RwLock::new(HashMap::new());
         let clients = RwLock::new(HashMap::new());
 
@@ -44,6 +70,7 @@ impl ClientCache {
         let cc = ClientCache {
             meta: m.clone(),
             clients: c.clone(),
+            connect,
         };
 
         tokio::spawn(async move {
@@ -56,65 +83,72 @@ impl ClientCache {
         cc
     }
 
-    // At the moment the only thing that uses this function is the test
-    #[allow(dead_code)]
-    pub(crate) async fn remove_config(&self, lattice_id: &str) {
+    /// Removes the connection configuration corresponding to a given source component, along with
+    /// its active client, if any
+    pub(crate) async fn remove_config(&self, source_id: &str) {
         let mut m = self.meta.write().await;
+        m.remove(source_id);
+        drop(m);
 
-        m.remove(lattice_id);
+        let mut c = self.clients.write().await;
+        c.remove(source_id);
     }
 
-    /// Stores a connection configuration corresponding to a given lattice. No side effects, does _not_
-    /// create or establish a NATS connection
-    pub(crate) async fn put_config(&self, lattice_id: &str, config: ConnectionConfig) {
+    /// Stores a connection configuration corresponding to a given source component, replacing its
+    /// active client, if any. Does _not_ create or establish a NATS connection
+    pub(crate) async fn put_config(&self, source_id: &str, config: ConnectionConfig) {
         let mut m = self.meta.write().await;
 
         m.insert(
-            lattice_id.to_string(),
+            source_id.to_string(),
             ClientMetadata {
                 config,
                 last_accessed: Instant::now(),
             },
         );
+        drop(m);
+
+        let mut c = self.clients.write().await;
+        c.remove(source_id);
     }
 
     /// Retrieves a client from the cache. If one is already active, this will be returned. If not,
     /// one will be created from the stored connection configuration. If there is no active client
     /// and no suitable configuration, this function returns an error and will _not_ resort to
     /// fallback credentials
-    pub(crate) async fn get_client(&self, lattice_id: &str) -> Result<Client> {
+    pub(crate) async fn get_client(&self, source_id: &str) -> Result<C> {
         let c = {
             // Don't hold the read lock for the whole func
             let lock = self.clients.read().await;
-            lock.get(lattice_id).cloned()
+            lock.get(source_id).cloned()
         };
         if let Some(c) = c {
-            self.record_access(lattice_id).await;
+            self.record_access(source_id).await;
             Ok(c)
         } else {
             let meta = {
                 // Dispose of lock as soon as we get what we need
                 let lock = self.meta.read().await;
-                lock.get(lattice_id).cloned()
+                lock.get(source_id).cloned()
             };
             if let Some(cfg) = meta {
-                let client = create_client(&cfg.config).await?;
-                self.store_client(lattice_id, client.clone()).await;
+                let client = (self.connect)(cfg.config).await?;
+                self.store_client(source_id, client.clone()).await;
                 Ok(client)
             } else {
-                bail!("no client configuration for lattice [{lattice_id}] stored");
+                bail!("no client configuration for source [{source_id}] stored");
             }
         }
     }
 
-    async fn store_client(&self, lattice_id: &str, client: Client) {
+    async fn store_client(&self, source_id: &str, client: C) {
         let mut conns = self.clients.write().await;
-        conns.insert(lattice_id.to_string(), client);
+        conns.insert(source_id.to_string(), client);
     }
 
-    async fn record_access(&self, lattice_id: &str) {
+    async fn record_access(&self, source_id: &str) {
         let mut meta = self.meta.write().await;
-        meta.entry(lattice_id.to_string()).and_modify(|e| e.touch());
+        meta.entry(source_id.to_string()).and_modify(|e| e.touch());
     }
 }
 
@@ -125,11 +159,14 @@ async fn create_client(config: &ConnectionConfig) -> Result<wasmcloud_control_in
     let lattice = config.lattice.clone();
     let conn = connect(config).await?;
 
-    Ok(wasmcloud_control_interface::ClientBuilder::new(conn)
+    let mut builder = wasmcloud_control_interface::ClientBuilder::new(conn)
         .lattice(lattice)
         .timeout(timeout)
-        .auction_timeout(auction_timeout)
-        .build())
+        .auction_timeout(auction_timeout);
+    if let Some(prefix) = &config.topic_prefix {
+        builder = builder.topic_prefix(prefix);
+    }
+    Ok(builder.build())
 }
 
 /// Create a new nats connection
@@ -137,7 +174,9 @@ async fn connect(cfg: &ConnectionConfig) -> Result<async_nats::Client> {
     let cfg = cfg.clone();
     let opts = match (cfg.auth_jwt, cfg.auth_seed) {
         (Some(jwt), Some(seed)) => {
-            let key_pair = std::sync::Arc::new(KeyPair::from_seed(&seed).context("key init: {e}")?);
+            let key_pair = std::sync::Arc::new(
+                KeyPair::from_seed(&seed).context("failed to initialize key pair from seed")?,
+            );
             async_nats::ConnectOptions::with_jwt(jwt, move |nonce| {
                 let key_pair = key_pair.clone();
                 async move { key_pair.sign(&nonce).map_err(async_nats::AuthError::new) }
@@ -175,9 +214,9 @@ async fn connect(cfg: &ConnectionConfig) -> Result<async_nats::Client> {
 
 /// Discovers a list of expired (access time within grace period) connections
 /// and then removes them from the cache.
-async fn evacuate_cache(
+async fn evacuate_cache<C>(
     m: Arc<RwLock<HashMap<String, ClientMetadata>>>,
-    c: Arc<RwLock<HashMap<String, Client>>>,
+    c: Arc<RwLock<HashMap<String, C>>>,
     period: Duration,
 ) {
     let expired_keys: Vec<String> = {
@@ -200,7 +239,7 @@ async fn evacuate_cache(
     conns.retain(|k, _v| !expired_keys.contains(k));
 }
 
-/// The test suite below requires an anonymous localhost NATS
+/// Tests marked as ignored below require an anonymous localhost NATS
 ///
 /// You can run one locally using `docker`:
 ///
@@ -209,13 +248,92 @@ async fn evacuate_cache(
 /// ```
 #[cfg(test)]
 mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
     use std::time::Duration;
 
     use crate::ConnectionConfig;
 
-    use super::ClientCache;
+    use super::{evacuate_cache, ClientCache, ConnectFuture};
+
+    /// Cache of "clients" which are the configurations they were created from, along with the
+    /// number of clients created
+    async fn config_cache() -> (ClientCache<ConnectionConfig>, Arc<AtomicUsize>) {
+        let connects = Arc::new(AtomicUsize::default());
+        let cache = ClientCache::with_connector(60, {
+            let connects = Arc::clone(&connects);
+            Arc::new(
+                move |config: ConnectionConfig| -> ConnectFuture<ConnectionConfig> {
+                    connects.fetch_add(1, Ordering::Relaxed);
+                    Box::pin(async move { Ok(config) })
+                },
+            )
+        })
+        .await;
+        (cache, connects)
+    }
+
+    fn config(jwt: &str) -> ConnectionConfig {
+        ConnectionConfig {
+            auth_jwt: Some(jwt.to_string()),
+            auth_seed: Some(format!("{jwt}-seed")),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_link_isolation() {
+        let (cache, connects) = config_cache().await;
+        cache.put_config("a", config("a-jwt")).await;
+        cache.put_config("b", config("b-jwt")).await;
+
+        // Every link gets a client of its own configuration, which is reused
+        let client = cache.get_client("a").await.expect("failed to get client");
+        assert_eq!(client, config("a-jwt"));
+        let client = cache.get_client("b").await.expect("failed to get client");
+        assert_eq!(client, config("b-jwt"));
+        let client = cache.get_client("a").await.expect("failed to get client");
+        assert_eq!(client, config("a-jwt"));
+        assert_eq!(connects.load(Ordering::Relaxed), 2);
+
+        // Putting a link again replaces its client
+        cache.put_config("a", config("new-jwt")).await;
+        let client = cache.get_client("a").await.expect("failed to get client");
+        assert_eq!(client, config("new-jwt"));
+        assert_eq!(connects.load(Ordering::Relaxed), 3);
+
+        // Deleting a link removes its client, and never falls back to another one
+        cache.remove_config("b").await;
+        assert!(cache.get_client("b").await.is_err());
+        assert!(cache.get_client("unknown").await.is_err());
+        let client = cache.get_client("a").await.expect("failed to get client");
+        assert_eq!(client, config("new-jwt"));
+    }
+
+    #[tokio::test]
+    async fn test_evacuate_cache() {
+        let (cache, connects) = config_cache().await;
+        cache.put_config("a", config("a-jwt")).await;
+        cache.get_client("a").await.expect("failed to get client");
+        assert_eq!(connects.load(Ordering::Relaxed), 1);
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        evacuate_cache(
+            Arc::clone(&cache.meta),
+            Arc::clone(&cache.clients),
+            Duration::from_millis(1),
+        )
+        .await;
+        assert!(cache.clients.read().await.is_empty());
+
+        // The configuration is kept, so the client is created again
+        let client = cache.get_client("a").await.expect("failed to get client");
+        assert_eq!(client, config("a-jwt"));
+        assert_eq!(connects.load(Ordering::Relaxed), 2);
+    }
 
     #[tokio::test]
+    #[ignore = "requires a NATS server"]
     async fn test_cache_evacuation() {
         let cache = ClientCache::new(2).await;
         cache.put_config("test", ConnectionConfig::default()).await;
@@ -1,820 +1,860 @@
-// TODO(brooksmtownsend): bring the lattice control capability provider up-to-date with the control interface
-// //! wasmCloud Lattice Control capability provider
-// //!
-// //!
-// use std::collections::HashMap;
-// use std::sync::Arc;
-
-// use tokio::sync::{RwLock, RwLockReadGuard};
-// use tracing::{error, instrument, warn};
-// use wasmcloud_control_interface as interface_client;
-
-// mod client_cache;
-// use client_cache::ClientCache;
-
-// const DEFAULT_NATS_URI: &str = "0.0.0.0:4222";
-// const DEFAULT_TIMEOUT_MS: u64 = 2000;
-
-// // NOTE: Exercise caution when adjusting this value, as it can cause tests and
-// // other examples to fail, due to going against various timeouts set on the host and
-// // cooperating providers/actors, since the *entire* auction duration will be awaited
-// // for operations like `get-hosts`
-// const DEFAULT_AUCTION_TIMEOUT_MS: u64 = 3000;
-// const DEFAULT_LATTICE: &str = "default";
-
-// use wasmcloud_provider_wit_bindgen::deps::{
-//     async_trait::async_trait,
-//     serde::{Deserialize, Serialize},
-//     wasmcloud_provider_sdk::{load_host_data, Context},
-// };
-
-// wasmcloud_provider_wit_bindgen::generate!({
-//     impl_struct: LatticeControllerProvider,
-//     contract: "wasmcloud:latticecontrol",
-//     replace_witified_maps: true,
-//     wit_bindgen_cfg: "provider"
-// });
-
-// /// lattice-controller capability provider implementation
-// #[derive(Clone)]
-// pub struct LatticeControllerProvider {
-//     connection_timeout_mins: u64,
-//     connections: Arc<RwLock<Option<ClientCache>>>,
-// }
-
-// impl LatticeControllerProvider {
-//     /// Create a controller provider with a specified cache timeout in minutes
-//     pub fn with_cache_timeout_minutes(mins: u64) -> Self {
-//         Self {
-//             connection_timeout_mins: mins,
-//             connections: Arc::new(RwLock::new(None)),
-//         }
-//     }
-
-//     /// Retrieve the the connection cache, initializing if necessary
-//     ///
-//     /// This is necessary to avoid having to use an async `main()`,
-//     /// as the tokio runtime spawned will clash with an existing running one
-//     async fn get_connections(&self) -> RwLockReadGuard<ClientCache> {
-//         if self.connections.read().await.is_none() {
-//             let cache = ClientCache::new(self.connection_timeout_mins).await;
-//             let mut connections = self.connections.write().await;
-//             *connections = Some(cache);
-//             drop(connections);
-//         }
-//         let guard = self.connections.read().await;
-//         match RwLockReadGuard::try_map(guard, |v| v.as_ref()) {
-//             Ok(v) => v,
-//             Err(_) => unreachable!("the connections cache must have been initialized"),
-//         }
-//     }
-// }
-
-// /// Configuration for connecting a nats client.
-// /// More options are available if you use the json than variables in the values string map.
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// #[serde(crate = "wasmcloud_provider_wit_bindgen::deps::serde")]
-// struct ConnectionConfig {
-//     /// URIs used to connect to the cluster
-//     #[serde(default)]
-//     cluster_uris: Vec<String>,
-
-//     /// Authentication JWT
-//     #[serde(default)]
-//     auth_jwt: Option<String>,
-
-//     /// Authentication Seed
-//     #[serde(default)]
-//     auth_seed: Option<String>,
-
-//     /// Name of the lattice
-//     #[serde(default)]
-//     lattice: String,
-
-//     /// NATS JetStream domain
-//     #[serde(default)]
-//     js_domain: Option<String>,
-
-//     /// Operation timeout used for the lattice client interface
-//     timeout_ms: u64,
-
-//     /// Auction timeout used for the lattice client interface
-//     auction_timeout_ms: u64,
-// }
-
-// impl Default for ConnectionConfig {
-//     fn default() -> Self {
-//         Self {
-//             cluster_uris: vec![DEFAULT_NATS_URI.to_owned()],
-//             auth_jwt: None,
-//             js_domain: None,
-//             auth_seed: None,
-//             lattice: String::from(DEFAULT_LATTICE),
-//             timeout_ms: DEFAULT_TIMEOUT_MS,
-//             auction_timeout_ms: DEFAULT_AUCTION_TIMEOUT_MS,
-//         }
-//     }
-// }
-
-// /// Implement the basic requirements of a wasmcloud capability provider
-// #[async_trait]
-// impl WasmcloudCapabilityProvider for LatticeControllerProvider {
-//     #[instrument(level = "debug", skip(self, _ld), fields(actor_id = %_ld.actor_id))]
-//     async fn put_link(
-//         &self,
-//         _ld: &wasmcloud_provider_wit_bindgen::deps::wasmcloud_provider_sdk::core::LinkDefinition,
-//     ) -> bool {
-//         // This provider is *externally* multiplexed -- link definitions
-//         // contain no useful data
-//         true
-//     }
-
-//     /// Handle notification that a link is dropped
-//     #[instrument(level = "debug", skip(self), fields(actor_id = ?_actor_id))]
-//     async fn delete_link(&self, _actor_id: &str) {
-//         // Link definitions do not determine the NATS connections
-//         // so there is nothing to clean up per link removal
-//     }
-
-//     /// Handle shutdown request
-//     #[instrument(level = "debug", skip(self))]
-//     async fn shutdown(&self) {
-//         // No cleanup necessary for shutdown, since links do not
-//         // determine/regulate the NATS connections
-//     }
-// }
-
-// /// Implement the lattice-controller-provider provider contract specified in WIT (see provider.wit)
-// #[async_trait]
-// impl WasmcloudLatticeControlLatticeController for LatticeControllerProvider {
-//     /// Sets lattice credentials and stores them in the cache to be used to create a
-//     /// connection in the next operation that requires one
-//     #[instrument(level = "debug", skip_all, fields(actor_id = ?_ctx.actor, lattice_id = ?arg.lattice_id))]
-//     async fn set_lattice_credentials(
-//         &self,
-//         _ctx: Context,
-//         arg: SetLatticeCredentialsRequest,
-//     ) -> CtlOperationAck {
-//         let config = ConnectionConfig {
-//             cluster_uris: vec![arg
-//                 .nats_url
-//                 .clone()
-//                 .unwrap_or_else(|| "0.0.0.0:4222".to_string())],
-//             auth_jwt: arg.user_jwt.clone(),
-//             auth_seed: arg.user_seed.clone(),
-//             lattice: arg.lattice_id.to_string(),
-//             js_domain: arg.js_domain.clone(),
-//             ..Default::default()
-//         };
-
-//         // Setting the auction_timeout to a large value may trigger timeouts in distant code.
-//         //
-//         // - host configuration rpc_timeout
-//         // - `LatticeControllerSender`s which have embedded RPC clients w/ internal timeouts
-//         // - httpserver/messaging provider(s) which expect a certain response speed from calling actors
-//         //
-//         // Since auctions will *wait* until the auction_timeout to do operations like gathering hosts,
-//         // we must manually ensure this value is unlikely to cause timeouts.
-//         let host_data = match load_host_data() {
-//             Ok(host_data) => host_data,
-//             Err(e) => {
-//                 error!("failed to load host data: {e}");
-//                 return CtlOperationAck {
-//                     accepted: false,
-//                     error: "failed to load host data".into(),
-//                 };
-//             }
-//         };
-
-//         if host_data
-//             .default_rpc_timeout_ms
-//             .is_some_and(|v| v < config.timeout_ms)
-//         {
-//             warn!(
-//                 host_rpc_timeout_ms = host_data.default_rpc_timeout_ms,
-//                 auction_timeout = config.auction_timeout_ms,
-//                 "host default RPC timeout < auction timeout, operations that rely on auctions are likely to time out"
-//             );
-//         }
-
-//         self.get_connections()
-//             .await
-//             .put_config(&arg.lattice_id, config)
-//             .await;
-
-//         CtlOperationAck {
-//             accepted: true,
-//             error: "".to_string(),
-//         }
-//     }
-
-//     /// Sets registry credentials, storing them in the cache
-//     #[instrument(level = "debug", skip_all, fields(actor_id = ?_ctx.actor, lattice_id = ?arg.lattice_id))]
-//     async fn set_registry_credentials(
-//         &self,
-//         _ctx: Context,
-//         arg: SetRegistryCredentialsRequest,
-//     ) -> () {
-//         let client = match self
-//             .get_connections()
-//             .await
-//             .get_client(&arg.lattice_id)
-//             .await
-//         {
-//             Ok(client) => client,
-//             Err(e) => {
-//                 error!("failed to get client for connection: {e}");
-//                 return;
-//             }
-//         };
-
-//         let mut hm = HashMap::new();
-//         if let Some(ref c) = arg.credentials {
-//             for (k, v) in c {
-//                 hm.insert(
-//                     k.to_string(),
-//                     interface_client::RegistryCredential {
-//                         password: v.password.clone(),
-//                         token: v.token.clone(),
-//                         username: v.username.clone(),
-//                         registry_type: "".to_string(),
-//                     },
-//                 );
-//             }
-//         }
-
-//         if let Err(e) = client.put_registries(hm).await {
-//             error!("failed to set registry credentials: {e}");
-//         };
-//     }
-
-//     /// Auction a provider on the lattice
-//     #[instrument(level = "debug", skip_all, fields(actor_id = ?_ctx.actor, lattice_id = ?arg.lattice_id))]
-//     async fn auction_provider(
-//         &self,
-//         _ctx: Context,
-//         arg: ProviderAuctionRequest,
-//     ) -> Vec<ProviderAuctionAck> {
-//         let client = match self
-//             .get_connections()
-//             .await
-//             .get_client(&arg.lattice_id)
-//             .await
-//         {
-//             Ok(client) => client,
-//             Err(e) => {
-//                 error!("failed to get client for connection: {e}");
-//                 return Vec::new();
-//             }
-//         };
-
-//         client
-//             .perform_provider_auction(&arg.provider_ref, &arg.link_name, arg.constraints.clone())
-//             .await
-//             .map(|v| {
-//                 v.into_iter()
-//                     .map(|a| ProviderAuctionAck {
-//                         host_id: a.host_id,
-//                         link_name: a.link_name,
-//                         provider_ref: a.provider_ref,
-//                     })
-//                     .collect::<Vec<_>>()
-//             })
-//             .unwrap_or_else(|e| {
-//                 error!("failed to auction provider: {e}");
-//                 Vec::new()
-//             })
-//     }
-
-//     /// Auction an actor on the lattice
-//     #[instrument(level = "debug", skip_all, fields(actor_id = ?_ctx.actor, lattice_id = ?arg.lattice_id))]
-//     async fn auction_actor(&self, _ctx: Context, arg: ActorAuctionRequest) -> Vec<ActorAuctionAck> {
-//         let client = match self
-//             .get_connections()
-//             .await
-//             .get_client(&arg.lattice_id)
-//             .await
-//         {
-//             Ok(client) => client,
-//             Err(e) => {
-//                 error!("failed to get client for connection: {e}");
-//                 return Vec::new();
-//             }
-//         };
-
-//         client
-//             .perform_actor_auction(&arg.actor_ref, arg.constraints.clone())
-//             .await
-//             .map(|v| {
-//                 v.into_iter()
-//                     .map(|a| ActorAuctionAck {
-//                         actor_ref: a.actor_ref,
-//                         host_id: a.host_id,
-//                     })
-//                     .collect::<Vec<_>>()
-//             })
-//             .unwrap_or_else(|e| {
-//                 error!("failed to auction actor: {e}");
-//                 Vec::new()
-//             })
-//     }
-
-//     /// Retrieve all hosts on the lattice
-//     #[instrument(level = "debug", skip_all, fields(actor_id = ?_ctx.actor, lattice_id = %lattice_id))]
-//     async fn get_hosts(&self, _ctx: Context, lattice_id: String) -> Vec<Host> {
-//         let client = match self.get_connections().await.get_client(&lattice_id).await {
-//             Ok(client) => client,
-//             Err(e) => {
-//                 error!("failed to get client for connection: {e}");
-//                 return Vec::new();
-//             }
-//         };
-
-//         client
-//             .get_hosts()
-//             .await
-//             .map(|v| {
-//                 v.into_iter()
-//                     .map(|h| {
-//                         let lattice_prefix = h.lattice().cloned();
-//                         Host {
-//                             cluster_issuers: h.cluster_issuers,
-//                             ctl_host: h.ctl_host,
-//                             id: h.id,
-//                             js_domain: h.js_domain,
-//                             labels: h.labels,
-//                             lattice_prefix, // NOTE: the control interface type has been updated to just `lattice`, but the wit type is still `lattice_prefix`
-//                             prov_rpc_host: h.rpc_host.clone(),
-//                             rpc_host: h.rpc_host,
-//                             uptime_human: h.uptime_human,
-//                             uptime_seconds: h.uptime_seconds,
-//                             version: h.version,
-//                         }
-//                     })
-//                     .collect::<Vec<_>>()
-//             })
-//             .unwrap_or_else(|e| {
-//                 error!("failed to get hosts: {e}");
-//                 Vec::new()
-//             })
-//     }
-
-//     /// Retrieve inventory for a given host on the lattice
-//     #[instrument(level = "debug", skip_all, fields(actor_id = ?_ctx.actor, host = ?arg.host_id, lattice_id = ?arg.lattice_id))]
-//     async fn get_host_inventory(
-//         &self,
-//         _ctx: Context,
-//         arg: GetHostInventoryRequest,
-//     ) -> HostInventory {
-//         let client = match self
-//             .get_connections()
-//             .await
-//             .get_client(&arg.lattice_id)
-//             .await
-//         {
-//             Ok(client) => client,
-//             Err(e) => {
-//                 error!("failed to get client for connection: {e}");
-//                 return HostInventory {
-//                     host_id: String::default(),
-//                     labels: HashMap::new(),
-//                     actors: Vec::new(),
-//                     providers: Vec::new(),
-//                 };
-//             }
-//         };
-
-//         client
-//             .get_host_inventory(&arg.host_id)
-//             .await
-//             .map(|hi| HostInventory {
-//                 actors: hi
-//                     .actors
-//                     .into_iter()
-//                     .map(|ad| ActorDescription {
-//                         id: ad.id,
-//                         image_ref: ad.image_ref,
-//                         instances: ad
-//                             .instances
-//                             .into_iter()
-//                             .map(|ai| ActorInstance {
-//                                 annotations: ai.annotations,
-//                                 instance_id: ai.instance_id,
-//                                 revision: ai.revision,
-//                             })
-//                             .collect::<Vec<_>>(),
-//                         name: ad.name,
-//                     })
-//                     .collect::<Vec<_>>(),
-//                 host_id: hi.host_id,
-//                 labels: hi.labels,
-//                 providers: hi
-//                     .providers
-//                     .into_iter()
-//                     .map(|pd| ProviderDescription {
-//                         annotations: pd.annotations,
-//                         id: pd.id,
-//                         image_ref: pd.image_ref,
-//                         link_name: pd.link_name,
-//                         name: pd.name,
-//                         revision: pd.revision,
-//                     })
-//                     .collect::<Vec<_>>(),
-//             })
-//             .unwrap_or_else(|e| {
-//                 error!("failed to get host inventory : {e}");
-//                 HostInventory {
-//                     host_id: String::default(),
-//                     labels: HashMap::new(),
-//                     actors: Vec::new(),
-//                     providers: Vec::new(),
-//                 }
-//             })
-//     }
-
-//     /// Retrieve claims for a given client
-//     #[instrument(level = "debug", skip_all, fields(actor_id = ?_ctx.actor, lattice_id))]
-//     async fn get_claims(&self, _ctx: Context, lattice_id: String) -> GetClaimsResponse {
-//         let client = match self.get_connections().await.get_client(&lattice_id).await {
-//             Ok(client) => client,
-//             Err(e) => {
-//                 error!("failed to get client for connection: {e}");
-//                 return GetClaimsResponse { claims: Vec::new() };
-//             }
-//         };
-
-//         client
-//             .get_claims()
-//             .await
-//             .map(|claims| GetClaimsResponse { claims })
-//             .unwrap_or_else(|e| {
-//                 error!("failed to get claims: {e}");
-//                 GetClaimsResponse { claims: Vec::new() }
-//             })
-//     }
-
-//     /// Start an actor on the lattice
-//     #[instrument(level = "debug", skip_all, fields(actor_id = ?_ctx.actor, lattice_id = ?cmd.lattice_id))]
-//     async fn start_actor(&self, _ctx: Context, cmd: StartActorCommand) -> CtlOperationAck {
-//         let count = if cmd.count == 0 {
-//             1_u32
-//         } else {
-//             cmd.count.into()
-//         };
-
-//         let client = match self
-//             .get_connections()
-//             .await
-//             .get_client(&cmd.lattice_id)
-//             .await
-//         {
-//             Ok(client) => client,
-//             Err(e) => {
-//                 error!("failed to get client for connection: {e}");
-//                 return CtlOperationAck {
-//                     accepted: false,
-//                     error: "failed to get client for connection".into(),
-//                 };
-//             }
-//         };
-
-//         client
-//             .scale_actor(
-//                 &cmd.host_id,
-//                 &cmd.actor_ref,
-//                 count,
-//                 Some(cmd.annotations.clone()),
-//             )
-//             .await
-//             .map(|ack| CtlOperationAck {
-//                 accepted: ack.accepted,
-//                 error: ack.error,
-//             })
-//             .unwrap_or_else(|e| {
-//                 error!("failed to start actor: {e}");
-//                 CtlOperationAck {
-//                     accepted: false,
-//                     error: "failed to start actor".into(),
-//                 }
-//             })
-//     }
-
-//     /// Scale an actor on the lattice
-//     #[instrument(level = "debug", skip_all, fields(actor_id = ?_ctx.actor, lattice_id = ?cmd.lattice_id))]
-//     async fn scale_actor(&self, _ctx: Context, cmd: ScaleActorCommand) -> CtlOperationAck {
-//         let client = match self
-//             .get_connections()
-//             .await
-//             .get_client(&cmd.lattice_id)
-//             .await
-//         {
-//             Ok(client) => client,
-//             Err(e) => {
-//                 error!("failed to get client for connection: {e}");
-//                 return CtlOperationAck {
-//                     accepted: false,
-//                     error: "failed to get client for connection".into(),
-//                 };
-//             }
-//         };
-
-//         client
-//             .scale_actor(
-//                 &cmd.host_id,
-//                 &cmd.actor_ref,
-//                 cmd.count,
-//                 Some(cmd.annotations.clone()),
-//             )
-//             .await
-//             .map(|a| CtlOperationAck {
-//                 accepted: a.accepted,
-//                 error: a.error,
-//             })
-//             .unwrap_or_else(|e| {
-//                 error!("failed to scale actor: {e}");
-//                 CtlOperationAck {
-//                     accepted: false,
-//                     error: "failed to scale actor".into(),
-//                 }
-//             })
-//     }
-
-//     /// Advertise a link on the lattice
-//     #[instrument(level = "debug", skip_all, fields(actor_id = ?_ctx.actor, lattice_id = ?req.lattice_id))]
-//     async fn advertise_link(&self, _ctx: Context, req: AdvertiseLinkRequest) -> CtlOperationAck {
-//         let client = match self
-//             .get_connections()
-//             .await
-//             .get_client(&req.lattice_id)
-//             .await
-//         {
-//             Ok(client) => client,
-//             Err(e) => {
-//                 error!("failed to get client for connection: {e}");
-//                 return CtlOperationAck {
-//                     accepted: false,
-//                     error: "failed to get client for connection".into(),
-//                 };
-//             }
-//         };
-
-//         client
-//             .put_link(
-//                 &req.link.actor_id,
-//                 &req.link.provider_id,
-//                 &req.link.contract_id,
-//                 &req.link.link_name,
-//                 req.link.values.clone().unwrap_or_default(),
-//             )
-//             .await
-//             .map(|ack| CtlOperationAck {
-//                 accepted: ack.accepted,
-//                 error: ack.error,
-//             })
-//             .unwrap_or_else(|e| {
-//                 error!("failed to advertise link: {e}");
-//                 CtlOperationAck {
-//                     accepted: false,
-//                     error: "failed to advertise link".into(),
-//                 }
-//             })
-//     }
-
-//     /// Remove a link on the lattice
-//     #[instrument(level = "debug", skip_all, fields(actor_id = ?_ctx.actor, lattice_id = ?req.lattice_id))]
-//     async fn remove_link(
-//         &self,
-//         _ctx: Context,
-//         req: RemoveLinkDefinitionRequest,
-//     ) -> CtlOperationAck {
-//         let client = match self
-//             .get_connections()
-//             .await
-//             .get_client(&req.lattice_id)
-//             .await
-//         {
-//             Ok(client) => client,
-//             Err(e) => {
-//                 error!("failed to get client for connection: {e}");
-//                 return CtlOperationAck {
-//                     accepted: false,
-//                     error: "failed to start actor".into(),
-//                 };
-//             }
-//         };
-
-//         client
-//             .remove_link(&req.actor_id, &req.actor_id, &req.link_name)
-//             .await
-//             .map(|a| CtlOperationAck {
-//                 accepted: a.accepted,
-//                 error: a.error,
-//             })
-//             .unwrap_or_else(|e| {
-//                 error!("failed to remove link: {e}");
-//                 CtlOperationAck {
-//                     accepted: false,
-//                     error: "failed to remove link".into(),
-//                 }
-//             })
-//     }
-
-//     /// Retrieve links on the lattice
-//     #[instrument(level = "debug", skip_all, fields(actor_id = ?_ctx.actor, lattice_id))]
-//     async fn get_links(&self, _ctx: Context, lattice_id: String) -> Vec<LinkDefinition> {
-//         let client = match self.get_connections().await.get_client(&lattice_id).await {
-//             Ok(client) => client,
-//             Err(e) => {
-//                 error!("failed to get client for connection: {e}");
-//                 return Vec::new();
-//             }
-//         };
-
-//         client
-//             .query_links()
-//             .await
-//             .map(|links| {
-//                 links
-//                     .into_iter()
-//                     .map(|client_ld| LinkDefinition {
-//                         actor_id: client_ld.actor_id,
-//                         provider_id: client_ld.provider_id,
-//                         contract_id: client_ld.contract_id,
-//                         link_name: client_ld.link_name,
-//                         values: Some(client_ld.values),
-//                     })
-//                     .collect()
-//             })
-//             .unwrap_or_else(|e| {
-//                 error!("failed to get links: {e}");
-//                 Vec::new()
-//             })
-//     }
-
-//     /// Update an actor running on the lattice
-//     #[instrument(level = "debug", skip_all, fields(actor_id = ?_ctx.actor, lattice_id = ?cmd.lattice_id))]
-//     async fn update_actor(&self, _ctx: Context, cmd: UpdateActorCommand) -> CtlOperationAck {
-//         let client = match self
-//             .get_connections()
-//             .await
-//             .get_client(&cmd.lattice_id)
-//             .await
-//         {
-//             Ok(client) => client,
-//             Err(e) => {
-//                 error!("failed to get client for connection: {e}");
-//                 return CtlOperationAck {
-//                     accepted: false,
-//                     error: "failed to get client for connection".into(),
-//                 };
-//             }
-//         };
-
-//         client
-//             .update_actor(
-//                 &cmd.host_id,
-//                 &cmd.actor_id,
-//                 &cmd.new_actor_ref,
-//                 cmd.annotations.clone(),
-//             )
-//             .await
-//             .map(|a| CtlOperationAck {
-//                 accepted: a.accepted,
-//                 error: a.error,
-//             })
-//             .unwrap_or_else(|e| {
-//                 error!("failed to update actor: {e}");
-//                 CtlOperationAck {
-//                     accepted: false,
-//                     error: "failed to update actor".into(),
-//                 }
-//             })
-//     }
-
-//     /// Start a provider on the lattice
-//     #[instrument(
-//         level = "debug",
-//         skip_all,
-//         fields(
-//             actor_id = ?_ctx.actor,
-//             host_id = %cmd.host_id,
-//             provider_ref = %cmd.provider_ref,
-//             link_name = %cmd.link_name,
-//             lattice_id = ?cmd.lattice_id
-//         )
-//     )]
-//     async fn start_provider(&self, _ctx: Context, cmd: StartProviderCommand) -> CtlOperationAck {
-//         let client = match self
-//             .get_connections()
-//             .await
-//             .get_client(&cmd.lattice_id)
-//             .await
-//         {
-//             Ok(client) => client,
-//             Err(e) => {
-//                 error!("failed to get client for connection: {e}");
-//                 return CtlOperationAck {
-//                     accepted: false,
-//                     error: "failed to start actor".into(),
-//                 };
-//             }
-//         };
-
-//         client
-//             .start_provider(
-//                 &cmd.host_id,
-//                 &cmd.provider_ref,
-//                 Some(cmd.link_name.to_owned()),
-//                 Some(cmd.annotations.clone()),
-//                 Some(cmd.configuration.clone()),
-//             )
-//             .await
-//             .map(|a| CtlOperationAck {
-//                 accepted: a.accepted,
-//                 error: a.error,
-//             })
-//             .unwrap_or_else(|e| {
-//                 error!("failed to start provider: {e}");
-//                 CtlOperationAck {
-//                     accepted: false,
-//                     error: "failed to start provider".into(),
-//                 }
-//             })
-//     }
-
-//     /// Stop a provider on the lattice
-//     #[instrument(level = "debug", skip_all, fields(actor_id = ?_ctx.actor, lattice_id = ?cmd.lattice_id))]
-//     async fn stop_provider(&self, _ctx: Context, cmd: StopProviderCommand) -> CtlOperationAck {
-//         let client = match self
-//             .get_connections()
-//             .await
-//             .get_client(&cmd.lattice_id)
-//             .await
-//         {
-//             Ok(client) => client,
-//             Err(e) => {
-//                 error!("failed to get client for connection: {e}");
-//                 return CtlOperationAck {
-//                     accepted: false,
-//                     error: "failed to get client for connection".into(),
-//                 };
-//             }
-//         };
-
-//         client
-//             .stop_provider(
-//                 &cmd.host_id,
-//                 &cmd.provider_id,
-//                 &cmd.link_name,
-//                 &cmd.contract_id,
-//                 Some(cmd.annotations.clone()),
-//             )
-//             .await
-//             .map(|a| CtlOperationAck {
-//                 accepted: a.accepted,
-//                 error: a.error,
-//             })
-//             .unwrap_or_else(|e| {
-//                 error!("failed to stop provider: {e}");
-//                 CtlOperationAck {
-//                     accepted: false,
-//                     error: "failed to stop provider".into(),
-//                 }
-//             })
-//     }
-
-//     /// Stop a host on the lattice
-//     #[instrument(level = "debug", skip_all, fields(actor_id = ?_ctx.actor, lattice_id = ?cmd.lattice_id))]
-//     async fn stop_host(&self, _ctx: Context, cmd: StopHostCommand) -> CtlOperationAck {
-//         let client = match self
-//             .get_connections()
-//             .await
-//             .get_client(&cmd.lattice_id)
-//             .await
-//         {
-//             Ok(client) => client,
-//             Err(e) => {
-//                 error!("failed to get client for connection: {e}");
-//                 return CtlOperationAck {
-//                     accepted: false,
-//                     error: "failed to start actor".into(),
-//                 };
-//             }
-//         };
-
-//         client
-//             .stop_host(&cmd.host_id, cmd.timeout)
-//             .await
-//             .map(|a| CtlOperationAck {
-//                 accepted: a.accepted,
-//                 error: a.error,
-//             })
-//             .unwrap_or_else(|e| {
-//                 error!("failed to stop host: {e}");
-//                 CtlOperationAck {
-//                     accepted: false,
-//                     error: "failed to stop host".into(),
-//                 }
-//             })
-//     }
-// }
+//! wasmCloud Lattice Control capability provider
+//!
+//! Every component linked to this provider manages the lattice described by the configuration of
+//! its link, using its own NATS credentials.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{bail, Context as _};
+use tokio::sync::{RwLock, RwLockReadGuard};
+use tracing::{error, instrument, warn};
+use wasmcloud_control_interface as interface_client;
+
+mod client_cache;
+use client_cache::ClientCache;
+
+use wasmcloud_provider_wit_bindgen::deps::{
+    async_trait::async_trait,
+    serde::{Deserialize, Serialize},
+    wasmcloud_provider_sdk::core::HostData,
+    wasmcloud_provider_sdk::{Context, LinkConfig, ProviderOperationResult},
+};
+
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: LatticeControllerProvider,
+    contract: "wasmcloud:latticecontrol",
+    wit_bindgen_cfg: "provider"
+});
+
+const DEFAULT_NATS_URI: &str = "0.0.0.0:4222";
+const DEFAULT_TIMEOUT_MS: u64 = 2000;
+
+// NOTE: Exercise caution when adjusting this value, as it can cause tests and
+// other examples to fail, due to going against various timeouts set on the host and
+// cooperating providers/actors, since the *entire* auction duration will be awaited
+// for operations like `get-hosts`
+const DEFAULT_AUCTION_TIMEOUT_MS: u64 = 3000;
+const DEFAULT_LATTICE: &str = "default";
+
+const CONFIG_NATS_URI: &str = "cluster_uris";
+const CONFIG_NATS_CLIENT_JWT: &str = "client_jwt";
+const CONFIG_NATS_CLIENT_SEED: &str = "client_seed";
+const CONFIG_LATTICE: &str = "lattice";
+const CONFIG_TOPIC_PREFIX: &str = "topic_prefix";
+const CONFIG_TIMEOUT_MS: &str = "timeout_ms";
+const CONFIG_AUCTION_TIMEOUT_MS: &str = "auction_timeout_ms";
+const CONFIG_INHERIT_CREDENTIALS: &str = "inherit_credentials";
+
+/// lattice-controller capability provider implementation
+#[derive(Clone)]
+pub struct LatticeControllerProvider {
+    connection_timeout_mins: u64,
+    connections: Arc<RwLock<Option<ClientCache>>>,
+    default_config: ConnectionConfig,
+}
+
+impl LatticeControllerProvider {
+    /// Create a controller provider with a specified cache timeout in minutes, using the
+    /// provider configuration in [`HostData`] as the defaults for the connection of every link
+    pub fn from_host_data(host_data: &HostData, connection_timeout_mins: u64) -> Self {
+        let default_config = ConnectionConfig::from_map(&host_data.config).unwrap_or_else(|e| {
+            warn!("Failed to build connection configuration, falling back to default: {e:?}");
+            ConnectionConfig::default()
+        });
+        Self {
+            connection_timeout_mins,
+            connections: Arc::new(RwLock::new(None)),
+            default_config,
+        }
+    }
+
+    /// Retrieve the the connection cache, initializing if necessary
+    ///
+    /// This is necessary to avoid having to use an async `main()`,
+    /// as the tokio runtime spawned will clash with an existing running one
+    async fn get_connections(&self) -> RwLockReadGuard<ClientCache> {
+        if self.connections.read().await.is_none() {
+            let mut connections = self.connections.write().await;
+            if connections.is_none() {
+                *connections = Some(ClientCache::new(self.connection_timeout_mins * 60).await);
+            }
+        }
+        let guard = self.connections.read().await;
+        match RwLockReadGuard::try_map(guard, |v| v.as_ref()) {
+            Ok(v) => v,
+            Err(_) => unreachable!("the connections cache must have been initialized"),
+        }
+    }
+
+    /// Retrieve the control interface client for the lattice configured on the link of the
+    /// component that sent the invocation
+    async fn get_client(&self, ctx: &Context) -> anyhow::Result<interface_client::Client> {
+        let source_id = ctx
+            .actor
+            .as_deref()
+            .context("invocation is missing the source component")?;
+        self.get_connections().await.get_client(source_id).await
+    }
+}
+
+/// Configuration for connecting a control interface client to a lattice.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(crate = "wasmcloud_provider_wit_bindgen::deps::serde")]
+struct ConnectionConfig {
+    /// URIs used to connect to the cluster
+    #[serde(default)]
+    cluster_uris: Vec<String>,
+
+    /// Authentication JWT
+    #[serde(default)]
+    auth_jwt: Option<String>,
+
+    /// Authentication Seed
+    #[serde(default)]
+    auth_seed: Option<String>,
+
+    /// Name of the lattice
+    #[serde(default)]
+    lattice: String,
+
+    /// Prefix of the control interface topics, the control interface default if not set
+    #[serde(default)]
+    topic_prefix: Option<String>,
+
+    /// Operation timeout used for the lattice client interface
+    timeout_ms: u64,
+
+    /// Auction timeout used for the lattice client interface
+    auction_timeout_ms: u64,
+
+    /// Whether links that set no credentials use the ones of this configuration. Only read from
+    /// the provider configuration, so that links cannot opt into the credentials of the provider
+    #[serde(default)]
+    inherit_credentials: bool,
+}
+
+impl Default for ConnectionConfig {
+    fn default() -> Self {
+        Self {
+            cluster_uris: vec![DEFAULT_NATS_URI.to_owned()],
+            auth_jwt: None,
+            auth_seed: None,
+            lattice: String::from(DEFAULT_LATTICE),
+            topic_prefix: None,
+            timeout_ms: DEFAULT_TIMEOUT_MS,
+            auction_timeout_ms: DEFAULT_AUCTION_TIMEOUT_MS,
+            inherit_credentials: false,
+        }
+    }
+}
+
+impl ConnectionConfig {
+    /// Construct a configuration from the values in `values`, falling back to `self` for every
+    /// value that is not set
+    fn merge_map(&self, values: &HashMap<String, String>) -> anyhow::Result<ConnectionConfig> {
+        let mut config = self.clone();
+        // If the default configuration has a URL in it, and then the link also provides a URL,
+        // the assumption is to replace/override rather than combine the two
+        if let Some(uris) = values.get(CONFIG_NATS_URI) {
+            config.cluster_uris = uris.split(',').map(String::from).collect();
+        }
+        // Credentials are only ever taken as a pair, so that a link never combines its own JWT
+        // with the seed of the default configuration. Links setting none only use the ones of the
+        // default configuration if it explicitly allows that
+        match (
+            values.get(CONFIG_NATS_CLIENT_JWT),
+            values.get(CONFIG_NATS_CLIENT_SEED),
+        ) {
+            (Some(jwt), Some(seed)) => {
+                config.auth_jwt = Some(jwt.clone());
+                config.auth_seed = Some(seed.clone());
+            }
+            (None, None) if self.inherit_credentials => {}
+            (None, None) => {
+                config.auth_jwt = None;
+                config.auth_seed = None;
+            }
+            _ => bail!("if you specify jwt, you must also specify a seed"),
+        }
+        if let Some(lattice) = values.get(CONFIG_LATTICE) {
+            config.lattice = lattice.clone();
+        }
+        if let Some(prefix) = values.get(CONFIG_TOPIC_PREFIX) {
+            config.topic_prefix = Some(prefix.clone());
+        }
+        if let Some(timeout) = values.get(CONFIG_TIMEOUT_MS) {
+            config.timeout_ms = timeout
+                .parse()
+                .with_context(|| format!("invalid `{CONFIG_TIMEOUT_MS}` value `{timeout}`"))?;
+        }
+        if let Some(timeout) = values.get(CONFIG_AUCTION_TIMEOUT_MS) {
+            config.auction_timeout_ms = timeout.parse().with_context(|| {
+                format!("invalid `{CONFIG_AUCTION_TIMEOUT_MS}` value `{timeout}`")
+            })?;
+        }
+        Ok(config)
+    }
+
+    /// Construct a configuration from the passed provider configuration
+    fn from_map(values: &HashMap<String, String>) -> anyhow::Result<ConnectionConfig> {
+        let mut config = Self::default().merge_map(values)?;
+        if let Some(inherit) = values.get(CONFIG_INHERIT_CREDENTIALS) {
+            config.inherit_credentials = inherit.parse().with_context(|| {
+                format!("invalid `{CONFIG_INHERIT_CREDENTIALS}` value `{inherit}`")
+            })?;
+        }
+        Ok(config)
+    }
+}
+
+/// Converts a result of a control interface command to a [`CtlOperationAck`]
+fn ack(res: Result<interface_client::CtlResponse<()>, impl ToString>) -> CtlOperationAck {
+    match res {
+        Ok(interface_client::CtlResponse {
+            success, message, ..
+        }) => CtlOperationAck {
+            accepted: success,
+            error: message,
+        },
+        Err(e) => CtlOperationAck {
+            accepted: false,
+            error: e.to_string(),
+        },
+    }
+}
+
+/// Converts a result of a control interface query to the response, or the error that occurred
+fn response<T>(
+    res: Result<interface_client::CtlResponse<T>, impl ToString>,
+) -> Result<Option<T>, String> {
+    match res {
+        Ok(interface_client::CtlResponse {
+            success: true,
+            response,
+            ..
+        }) => Ok(response),
+        Ok(interface_client::CtlResponse { message, .. }) => Err(message),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Implements the conversion of the outcome of a query to its response record
+macro_rules! impl_from_query_result {
+    ($response:ident, $field:ident: $ty:ty) => {
+        impl From<Result<$ty, String>> for $response {
+            fn from(res: Result<$ty, String>) -> Self {
+                match res {
+                    Ok($field) => Self {
+                        accepted: true,
+                        error: String::new(),
+                        $field,
+                    },
+                    Err(error) => Self {
+                        accepted: false,
+                        error,
+                        $field: Default::default(),
+                    },
+                }
+            }
+        }
+    };
+}
+
+impl_from_query_result!(ProviderAuctionResponse, acks: Vec<ProviderAuctionAck>);
+impl_from_query_result!(ActorAuctionResponse, acks: Vec<ActorAuctionAck>);
+impl_from_query_result!(GetHostsResponse, hosts: Vec<Host>);
+impl_from_query_result!(GetHostInventoryResponse, inventory: Option<HostInventory>);
+impl_from_query_result!(GetClaimsResponse, claims: Vec<Claims>);
+impl_from_query_result!(GetLinksResponse, links: Vec<LinkDefinition>);
+impl_from_query_result!(GetConfigResponse, values: Vec<KeyValue>);
+
+fn into_map(entries: Vec<KeyValue>) -> HashMap<String, String> {
+    entries
+        .into_iter()
+        .map(|KeyValue { key, value }| (key, value))
+        .collect()
+}
+
+/// Converts annotations of a command, where no annotations are represented by an empty list
+fn into_annotations(entries: Vec<KeyValue>) -> Option<HashMap<String, String>> {
+    (!entries.is_empty()).then(|| into_map(entries))
+}
+
+fn into_entries(map: HashMap<String, String>) -> Vec<KeyValue> {
+    map.into_iter()
+        .map(|(key, value)| KeyValue { key, value })
+        .collect()
+}
+
+/// Implement the basic requirements of a wasmcloud capability provider
+#[async_trait]
+impl WasmcloudCapabilityProvider for LatticeControllerProvider {
+    /// Store the connection configuration of the link, merged with the provider defaults, and
+    /// verify that the lattice can be reached with it
+    #[instrument(level = "debug", skip_all, fields(source_id = %link_config.get_source_id()))]
+    async fn receive_link_config_as_target(
+        &self,
+        link_config: impl LinkConfig,
+    ) -> ProviderOperationResult<()> {
+        let source_id = link_config.get_source_id();
+        let config = match self.default_config.merge_map(link_config.get_config()) {
+            Ok(config) => config,
+            Err(e) => {
+                error!("Failed to build connection configuration: {e:?}");
+                return Err(e.context("failed to build connection config").into());
+            }
+        };
+        let connections = self.get_connections().await;
+        connections.put_config(source_id, config).await;
+        if let Err(e) = connections.get_client(source_id).await {
+            error!("Failed to connect to lattice: {e:?}");
+            connections.remove_config(source_id).await;
+            return Err(e.context("failed to connect to lattice").into());
+        }
+        Ok(())
+    }
+
+    /// Handle notification that a link is dropped: forget the credentials of the link
+    #[instrument(level = "debug", skip(self))]
+    async fn delete_link(&self, source_id: &str) -> ProviderOperationResult<()> {
+        self.get_connections().await.remove_config(source_id).await;
+        Ok(())
+    }
+
+    /// Handle shutdown request
+    #[instrument(level = "debug", skip(self))]
+    async fn shutdown(&self) -> ProviderOperationResult<()> {
+        // Dropping the cache closes all NATS connections
+        self.connections.write().await.take();
+        Ok(())
+    }
+}
+
+impl LatticeControllerProvider {
+    /// Auction a provider on the lattice of the link of the invoking component
+    async fn provider_auction(
+        &self,
+        ctx: &Context,
+        req: ProviderAuctionRequest,
+    ) -> Result<Vec<ProviderAuctionAck>, String> {
+        let client = self.get_client(ctx).await.map_err(|e| e.to_string())?;
+        let acks = client
+            .perform_provider_auction(
+                &req.provider_ref,
+                &req.provider_id,
+                into_map(req.constraints),
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(acks
+            .into_iter()
+            .filter_map(|ack| ack.response)
+            .map(|ack| ProviderAuctionAck {
+                provider_ref: ack.provider_ref,
+                provider_id: ack.provider_id,
+                host_id: ack.host_id,
+                constraints: into_entries(ack.constraints),
+            })
+            .collect())
+    }
+
+    /// Auction an actor on the lattice of the link of the invoking component
+    async fn actor_auction(
+        &self,
+        ctx: &Context,
+        req: ActorAuctionRequest,
+    ) -> Result<Vec<ActorAuctionAck>, String> {
+        let client = self.get_client(ctx).await.map_err(|e| e.to_string())?;
+        let acks = client
+            .perform_actor_auction(&req.actor_ref, &req.actor_id, into_map(req.constraints))
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(acks
+            .into_iter()
+            .filter_map(|ack| ack.response)
+            .map(|ack| ActorAuctionAck {
+                actor_ref: ack.actor_ref,
+                actor_id: ack.actor_id,
+                host_id: ack.host_id,
+                constraints: into_entries(ack.constraints),
+            })
+            .collect())
+    }
+
+    /// Retrieve all hosts on the lattice of the link of the invoking component
+    async fn hosts(&self, ctx: &Context) -> Result<Vec<Host>, String> {
+        let client = self.get_client(ctx).await.map_err(|e| e.to_string())?;
+        let hosts = client.get_hosts().await.map_err(|e| e.to_string())?;
+        Ok(hosts
+            .into_iter()
+            .filter_map(|host| host.response)
+            .map(|host| Host {
+                id: host.id,
+                friendly_name: host.friendly_name,
+                uptime_seconds: host.uptime_seconds,
+                uptime_human: host.uptime_human,
+                labels: into_entries(host.labels),
+                version: host.version,
+                cluster_issuers: host.cluster_issuers,
+                js_domain: host.js_domain,
+                ctl_host: host.ctl_host,
+                rpc_host: host.rpc_host,
+                lattice: host.lattice,
+            })
+            .collect())
+    }
+
+    /// Retrieve inventory for a given host, `None` if the host returned none
+    async fn host_inventory(
+        &self,
+        ctx: &Context,
+        host_id: &str,
+    ) -> Result<Option<HostInventory>, String> {
+        let client = self.get_client(ctx).await.map_err(|e| e.to_string())?;
+        let Some(inventory) = response(client.get_host_inventory(host_id).await)? else {
+            return Ok(None);
+        };
+        Ok(Some(HostInventory {
+            host_id: inventory.host_id,
+            friendly_name: inventory.friendly_name,
+            labels: into_entries(inventory.labels),
+            actors: inventory
+                .actors
+                .into_iter()
+                .map(|actor| ActorDescription {
+                    id: actor.id,
+                    image_ref: actor.image_ref,
+                    name: actor.name,
+                    annotations: actor.annotations.map(into_entries).unwrap_or_default(),
+                    revision: actor.revision,
+                    max_instances: actor.max_instances,
+                })
+                .collect(),
+            providers: inventory
+                .providers
+                .into_iter()
+                .map(|provider| ProviderDescription {
+                    id: provider.id,
+                    image_ref: provider.image_ref,
+                    name: provider.name,
+                    revision: provider.revision,
+                    annotations: provider.annotations.map(into_entries).unwrap_or_default(),
+                })
+                .collect(),
+            version: inventory.version,
+            uptime_seconds: inventory.uptime_seconds,
+        }))
+    }
+
+    /// Retrieve claims known to the lattice of the link of the invoking component
+    async fn claims(&self, ctx: &Context) -> Result<Vec<Claims>, String> {
+        let client = self.get_client(ctx).await.map_err(|e| e.to_string())?;
+        let claims = response(client.get_claims().await)?.unwrap_or_default();
+        Ok(claims
+            .into_iter()
+            .map(|claims| Claims {
+                values: into_entries(claims),
+            })
+            .collect())
+    }
+
+    /// Retrieve all links on the lattice of the link of the invoking component
+    async fn links(&self, ctx: &Context) -> Result<Vec<LinkDefinition>, String> {
+        let client = self.get_client(ctx).await.map_err(|e| e.to_string())?;
+        let links = response(client.get_links().await)?.unwrap_or_default();
+        Ok(links
+            .into_iter()
+            .map(|link| LinkDefinition {
+                source_id: link.source_id,
+                target: link.target,
+                name: link.name,
+                wit_namespace: link.wit_namespace,
+                wit_package: link.wit_package,
+                interfaces: link.interfaces,
+                source_config: link.source_config,
+                target_config: link.target_config,
+                limits: link.limits.map(
+                    |interface_client::LinkLimits {
+                         rate_per_second,
+                         burst,
+                         max_in_flight,
+                     }| LinkLimits {
+                        rate_per_second,
+                        burst,
+                        max_in_flight,
+                    },
+                ),
+            })
+            .collect())
+    }
+
+    /// Retrieve a named config, empty if it does not exist
+    async fn config(&self, ctx: &Context, name: &str) -> Result<Vec<KeyValue>, String> {
+        let client = self.get_client(ctx).await.map_err(|e| e.to_string())?;
+        match client.get_config(name).await {
+            // Hosts respond to queries of unknown configs with an unsuccessful, empty response
+            Ok(interface_client::CtlResponse {
+                success, response, ..
+            }) => Ok(response
+                .filter(|_| success)
+                .map(into_entries)
+                .unwrap_or_default()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+/// Implement the lattice-controller-provider provider contract specified in WIT (see provider.wit)
+#[async_trait]
+impl WasmcloudLatticeControlLatticeController for LatticeControllerProvider {
+    /// Auction a provider on the lattice
+    #[instrument(level = "debug", skip_all, fields(source_id = ?ctx.actor, provider_ref = %req.provider_ref))]
+    async fn auction_provider(
+        &self,
+        ctx: Context,
+        req: ProviderAuctionRequest,
+    ) -> ProviderAuctionResponse {
+        self.provider_auction(&ctx, req).await.into()
+    }
+
+    /// Auction an actor on the lattice
+    #[instrument(level = "debug", skip_all, fields(source_id = ?ctx.actor, actor_ref = %req.actor_ref))]
+    async fn auction_actor(&self, ctx: Context, req: ActorAuctionRequest) -> ActorAuctionResponse {
+        self.actor_auction(&ctx, req).await.into()
+    }
+
+    /// Retrieve all hosts on the lattice
+    #[instrument(level = "debug", skip_all, fields(source_id = ?ctx.actor))]
+    async fn get_hosts(&self, ctx: Context) -> GetHostsResponse {
+        self.hosts(&ctx).await.into()
+    }
+
+    /// Retrieve inventory for a given host on the lattice
+    #[instrument(level = "debug", skip_all, fields(source_id = ?ctx.actor, host_id = %host_id))]
+    async fn get_host_inventory(&self, ctx: Context, host_id: String) -> GetHostInventoryResponse {
+        self.host_inventory(&ctx, &host_id).await.into()
+    }
+
+    /// Retrieve claims for the lattice
+    #[instrument(level = "debug", skip_all, fields(source_id = ?ctx.actor))]
+    async fn get_claims(&self, ctx: Context) -> GetClaimsResponse {
+        self.claims(&ctx).await.into()
+    }
+
+    /// Scale an actor on a given host
+    #[instrument(level = "debug", skip_all, fields(source_id = ?ctx.actor, host_id = %cmd.host_id, actor_id = %cmd.actor_id))]
+    async fn scale_actor(&self, ctx: Context, cmd: ScaleActorCommand) -> CtlOperationAck {
+        let client = match self.get_client(&ctx).await {
+            Ok(client) => client,
+            Err(e) => return ack(Err(e)),
+        };
+        ack(client
+            .scale_actor(
+                &cmd.host_id,
+                &cmd.actor_ref,
+                &cmd.actor_id,
+                cmd.max_instances,
+                into_annotations(cmd.annotations),
+                cmd.config,
+                None,
+            )
+            .await)
+    }
+
+    /// Perform a live update of an actor on a given host
+    #[instrument(level = "debug", skip_all, fields(source_id = ?ctx.actor, host_id = %cmd.host_id, actor_id = %cmd.actor_id))]
+    async fn update_actor(&self, ctx: Context, cmd: UpdateActorCommand) -> CtlOperationAck {
+        let client = match self.get_client(&ctx).await {
+            Ok(client) => client,
+            Err(e) => return ack(Err(e)),
+        };
+        ack(client
+            .update_actor(
+                &cmd.host_id,
+                &cmd.actor_id,
+                &cmd.new_actor_ref,
+                into_annotations(cmd.annotations),
+                None,
+            )
+            .await)
+    }
+
+    /// Put a link into the lattice
+    #[instrument(level = "debug", skip_all, fields(source_id = ?ctx.actor, link_source_id = %link.source_id, target = %link.target))]
+    async fn put_link(&self, ctx: Context, link: LinkDefinition) -> CtlOperationAck {
+        let client = match self.get_client(&ctx).await {
+            Ok(client) => client,
+            Err(e) => return ack(Err(e)),
+        };
+        ack(client
+            .put_link(interface_client::InterfaceLinkDefinition {
+                source_id: link.source_id,
+                target: link.target,
+                name: link.name,
+                wit_namespace: link.wit_namespace,
+                wit_package: link.wit_package,
+                interfaces: link.interfaces,
+                source_config: link.source_config,
+                target_config: link.target_config,
+                limits: link.limits.map(
+                    |LinkLimits {
+                         rate_per_second,
+                         burst,
+                         max_in_flight,
+                     }| interface_client::LinkLimits {
+                        rate_per_second,
+                        burst,
+                        max_in_flight,
+                    },
+                ),
+            })
+            .await)
+    }
+
+    /// Remove a link from the lattice
+    #[instrument(level = "debug", skip_all, fields(source_id = ?ctx.actor, link_source_id = %req.source_id, name = %req.name))]
+    async fn delete_link(&self, ctx: Context, req: DeleteLinkRequest) -> CtlOperationAck {
+        let client = match self.get_client(&ctx).await {
+            Ok(client) => client,
+            Err(e) => return ack(Err(e)),
+        };
+        ack(client
+            .delete_link(
+                &req.source_id,
+                &req.name,
+                &req.wit_namespace,
+                &req.wit_package,
+            )
+            .await)
+    }
+
+    /// Retrieve all links on the lattice
+    #[instrument(level = "debug", skip_all, fields(source_id = ?ctx.actor))]
+    async fn get_links(&self, ctx: Context) -> GetLinksResponse {
+        self.links(&ctx).await.into()
+    }
+
+    /// Start a provider on a given host
+    #[instrument(level = "debug", skip_all, fields(source_id = ?ctx.actor, host_id = %cmd.host_id, provider_id = %cmd.provider_id))]
+    async fn start_provider(&self, ctx: Context, cmd: StartProviderCommand) -> CtlOperationAck {
+        let client = match self.get_client(&ctx).await {
+            Ok(client) => client,
+            Err(e) => return ack(Err(e)),
+        };
+        ack(client
+            .start_provider(
+                &cmd.host_id,
+                &cmd.provider_ref,
+                &cmd.provider_id,
+                into_annotations(cmd.annotations),
+                cmd.config,
+            )
+            .await)
+    }
+
+    /// Stop a provider on a given host
+    #[instrument(level = "debug", skip_all, fields(source_id = ?ctx.actor, host_id = %cmd.host_id, provider_id = %cmd.provider_id))]
+    async fn stop_provider(&self, ctx: Context, cmd: StopProviderCommand) -> CtlOperationAck {
+        let client = match self.get_client(&ctx).await {
+            Ok(client) => client,
+            Err(e) => return ack(Err(e)),
+        };
+        ack(client.stop_provider(&cmd.host_id, &cmd.provider_id).await)
+    }
+
+    /// Stop a host
+    #[instrument(level = "debug", skip_all, fields(source_id = ?ctx.actor, host_id = %cmd.host_id))]
+    async fn stop_host(&self, ctx: Context, cmd: StopHostCommand) -> CtlOperationAck {
+        let client = match self.get_client(&ctx).await {
+            Ok(client) => client,
+            Err(e) => return ack(Err(e)),
+        };
+        ack(client.stop_host(&cmd.host_id, cmd.timeout_ms).await)
+    }
+
+    /// Put a named config into the lattice
+    #[instrument(level = "debug", skip_all, fields(source_id = ?ctx.actor, name = %name))]
+    async fn put_config(
+        &self,
+        ctx: Context,
+        name: String,
+        values: Vec<KeyValue>,
+    ) -> CtlOperationAck {
+        let client = match self.get_client(&ctx).await {
+            Ok(client) => client,
+            Err(e) => return ack(Err(e)),
+        };
+        ack(client.put_config(&name, into_map(values)).await)
+    }
+
+    /// Retrieve a named config from the lattice
+    #[instrument(level = "debug", skip_all, fields(source_id = ?ctx.actor, name = %name))]
+    async fn get_config(&self, ctx: Context, name: String) -> GetConfigResponse {
+        self.config(&ctx, &name).await.into()
+    }
+
+    /// Delete a named config from the lattice
+    #[instrument(level = "debug", skip_all, fields(source_id = ?ctx.actor, name = %name))]
+    async fn delete_config(&self, ctx: Context, name: String) -> CtlOperationAck {
+        let client = match self.get_client(&ctx).await {
+            Ok(client) => client,
+            Err(e) => return ack(Err(e)),
+        };
+        ack(client.delete_config(&name).await)
+    }
+
+    /// Put registry credentials on all hosts of the lattice
+    #[instrument(level = "debug", skip_all, fields(source_id = ?ctx.actor))]
+    async fn put_registries(
+        &self,
+        ctx: Context,
+        credentials: Vec<RegistryCredentialEntry>,
+    ) -> CtlOperationAck {
+        let client = match self.get_client(&ctx).await {
+            Ok(client) => client,
+            Err(e) => return ack(Err(e)),
+        };
+        let registries = credentials
+            .into_iter()
+            .map(
+                |RegistryCredentialEntry {
+                     registry,
+                     credential,
+                 }| {
+                    (
+                        registry,
+                        interface_client::RegistryCredential {
+                            password: credential.password,
+                            token: credential.token,
+                            username: credential.username,
+                            registry_type: credential.registry_type,
+                        },
+                    )
+                },
+            )
+            .collect();
+        ack(client.put_registries(registries).await)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::{
+        ConnectionConfig, CONFIG_AUCTION_TIMEOUT_MS, CONFIG_INHERIT_CREDENTIALS, CONFIG_LATTICE,
+        CONFIG_NATS_CLIENT_JWT, CONFIG_NATS_CLIENT_SEED, CONFIG_NATS_URI, CONFIG_TIMEOUT_MS,
+        CONFIG_TOPIC_PREFIX,
+    };
+
+    fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_merge_map() {
+        let defaults = ConnectionConfig {
+            cluster_uris: vec!["nats://default:4222".to_string()],
+            auth_jwt: Some("default-jwt".to_string()),
+            auth_seed: Some("default-seed".to_string()),
+            lattice: "default-lattice".to_string(),
+            topic_prefix: Some("default-prefix".to_string()),
+            timeout_ms: 1000,
+            auction_timeout_ms: 2000,
+            inherit_credentials: true,
+        };
+        assert_eq!(
+            defaults
+                .merge_map(&HashMap::default())
+                .expect("failed to merge empty values"),
+            defaults
+        );
+
+        let merged = defaults
+            .merge_map(&values(&[
+                (CONFIG_NATS_URI, "nats://a:4222,nats://b:4222"),
+                (CONFIG_NATS_CLIENT_JWT, "link-jwt"),
+                (CONFIG_NATS_CLIENT_SEED, "link-seed"),
+                (CONFIG_LATTICE, "link-lattice"),
+                (CONFIG_TOPIC_PREFIX, "link-prefix"),
+                (CONFIG_TIMEOUT_MS, "10"),
+                (CONFIG_AUCTION_TIMEOUT_MS, "20"),
+            ]))
+            .expect("failed to merge values");
+        assert_eq!(
+            merged,
+            ConnectionConfig {
+                cluster_uris: vec!["nats://a:4222".to_string(), "nats://b:4222".to_string()],
+                auth_jwt: Some("link-jwt".to_string()),
+                auth_seed: Some("link-seed".to_string()),
+                lattice: "link-lattice".to_string(),
+                topic_prefix: Some("link-prefix".to_string()),
+                timeout_ms: 10,
+                auction_timeout_ms: 20,
+                inherit_credentials: true,
+            }
+        );
+
+        // Values that are not set fall back to the defaults
+        let merged = defaults
+            .merge_map(&values(&[(CONFIG_LATTICE, "link-lattice")]))
+            .expect("failed to merge values");
+        assert_eq!(
+            merged,
+            ConnectionConfig {
+                lattice: "link-lattice".to_string(),
+                ..defaults.clone()
+            }
+        );
+
+        // Credentials of the defaults are only used by links without credentials if allowed
+        let isolated = ConnectionConfig {
+            inherit_credentials: false,
+            ..defaults.clone()
+        };
+        let merged = isolated
+            .merge_map(&values(&[(CONFIG_LATTICE, "link-lattice")]))
+            .expect("failed to merge values");
+        assert_eq!((merged.auth_jwt, merged.auth_seed), (None, None));
+        // Links cannot allow it themselves
+        let merged = isolated
+            .merge_map(&values(&[(CONFIG_INHERIT_CREDENTIALS, "true")]))
+            .expect("failed to merge values");
+        assert_eq!((merged.auth_jwt, merged.auth_seed), (None, None));
+
+        // Credentials are never combined with the ones of the defaults
+        assert!(defaults
+            .merge_map(&values(&[(CONFIG_NATS_CLIENT_JWT, "link-jwt")]))
+            .is_err());
+        assert!(defaults
+            .merge_map(&values(&[(CONFIG_NATS_CLIENT_SEED, "link-seed")]))
+            .is_err());
+
+        assert!(defaults
+            .merge_map(&values(&[(CONFIG_TIMEOUT_MS, "soon")]))
+            .is_err());
+        assert!(defaults
+            .merge_map(&values(&[(CONFIG_AUCTION_TIMEOUT_MS, "-1")]))
+            .is_err());
+    }
+
+    #[test]
+    fn test_from_map() {
+        assert_eq!(
+            ConnectionConfig::from_map(&HashMap::default()).expect("failed to parse empty values"),
+            ConnectionConfig::default()
+        );
+        assert!(
+            ConnectionConfig::from_map(&values(&[(CONFIG_INHERIT_CREDENTIALS, "true")]))
+                .expect("failed to parse values")
+                .inherit_credentials
+        );
+        assert!(
+            ConnectionConfig::from_map(&values(&[(CONFIG_INHERIT_CREDENTIALS, "yes")])).is_err()
+        );
+    }
+}
@@ -0,0 +1,611 @@
+use core::future::Future;
+use core::time::Duration;
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, bail, ensure, Context as _};
+use rdkafka::admin::{AdminClient, AdminOptions, NewTopic, TopicReplication};
+use rdkafka::client::DefaultClientContext;
+use rdkafka::config::ClientConfig;
+use rdkafka::consumer::{Consumer, StreamConsumer};
+use rdkafka::error::KafkaError;
+use rdkafka::message::{Header, Headers, OwnedHeaders, OwnedMessage};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::types::RDKafkaErrorCode;
+use rdkafka::{Message as _, Offset, TopicPartitionList};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, warn};
+use uuid::Uuid;
+
+use crate::Message;
+
+/// Link config value for hosts, accepted as a comma separated string
+const KAFKA_HOSTS: &str = "HOSTS";
+const DEFAULT_HOST: &str = "127.0.0.1:9092";
+
+/// Link config value for topic, accepted as a single string
+const KAFKA_TOPIC: &str = "TOPIC";
+const DEFAULT_TOPIC: &str = "my-topic";
+
+/// Link config value for the consumer group shared by all provider instances consuming the topic
+const KAFKA_CONSUMER_GROUP: &str = "CONSUMER_GROUP";
+
+/// Link config value enabling request/reply emulation, accepted as `true` or `false`
+const KAFKA_REQUEST_REPLY: &str = "REQUEST_REPLY";
+
+/// Link config value for the topic replies to requests are received on
+const KAFKA_REPLY_TOPIC: &str = "REPLY_TOPIC";
+const DEFAULT_REPLY_TOPIC_SUFFIX: &str = ".reply";
+
+/// Link config value for the timeout of requests that do not specify one, in milliseconds
+const KAFKA_REQUEST_TIMEOUT_MS: &str = "REQUEST_TIMEOUT_MS";
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Header carrying the id used to correlate a reply with its request
+const CORRELATION_ID_HEADER: &str = "correlation-id";
+/// Header carrying the topic a reply should be sent to
+const REPLY_TO_HEADER: &str = "reply-to";
+
+/// Separates the reply topic from the correlation id in the `reply_to` of delivered requests.
+/// `#` is not a valid character in Kafka topic names, so the two can always be told apart
+const REPLY_TO_SEPARATOR: char = '#';
+
+/// Timeout for messages to be queued by the producer
+const PRODUCE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Timeout for the metadata and offset requests made when positioning a consumer
+const METADATA_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Configuration of a Kafka connection, parsed from link configuration values
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConnectionConfig {
+    /// Bootstrap server hosts
+    pub hosts: Vec<String>,
+    /// Topic to consume
+    pub topic: String,
+    /// Consumer group to consume the topic in. If not set, every provider instance consumes all
+    /// messages on the topic
+    pub consumer_group: Option<String>,
+    /// Topic to receive replies on, if request/reply emulation is enabled
+    pub reply_topic: Option<String>,
+    /// Timeout of requests that do not specify one
+    pub request_timeout: Duration,
+}
+
+impl ConnectionConfig {
+    /// Parse a [`ConnectionConfig`] from link configuration values
+    pub fn from_values<'a>(values: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let values: HashMap<_, _> = values
+            .into_iter()
+            .map(|(k, v)| (k, v.trim()))
+            .filter(|(_, v)| !v.is_empty())
+            .collect();
+        let hosts = values
+            .get(KAFKA_HOSTS)
+            .copied()
+            .unwrap_or(DEFAULT_HOST)
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .collect();
+        let topic = values
+            .get(KAFKA_TOPIC)
+            .copied()
+            .unwrap_or(DEFAULT_TOPIC)
+            .to_string();
+        let consumer_group = values.get(KAFKA_CONSUMER_GROUP).map(ToString::to_string);
+        let reply_topic = values
+            .get(KAFKA_REQUEST_REPLY)
+            .is_some_and(|v| v.eq_ignore_ascii_case("true"))
+            .then(|| {
+                values.get(KAFKA_REPLY_TOPIC).map_or_else(
+                    || format!("{topic}{DEFAULT_REPLY_TOPIC_SUFFIX}"),
+                    |v| v.to_string(),
+                )
+            });
+        let request_timeout = values
+            .get(KAFKA_REQUEST_TIMEOUT_MS)
+            .and_then(|v| match v.parse() {
+                Ok(ms) => Some(Duration::from_millis(ms)),
+                Err(e) => {
+                    warn!("invalid {KAFKA_REQUEST_TIMEOUT_MS} value `{v}`, using default: {e}");
+                    None
+                }
+            })
+            .unwrap_or(DEFAULT_REQUEST_TIMEOUT);
+        Self {
+            hosts,
+            topic,
+            consumer_group,
+            reply_topic,
+            request_timeout,
+        }
+    }
+
+    fn client_config(&self) -> ClientConfig {
+        let mut config = ClientConfig::new();
+        config.set("bootstrap.servers", self.hosts.join(","));
+        config
+    }
+
+    /// Builds a consumer subscribed to `topic` in consumer group `group`. Partitions the group has
+    /// not committed an offset for yet are consumed from the beginning, so that messages produced
+    /// while the group is still being joined are not lost
+    fn group_consumer(&self, group: &str, topic: &str) -> anyhow::Result<StreamConsumer> {
+        let consumer: StreamConsumer = self
+            .client_config()
+            .set("group.id", group)
+            .set("auto.offset.reset", "earliest")
+            .create()
+            .context("failed to create consumer")?;
+        consumer
+            .subscribe(&[topic])
+            .with_context(|| format!("failed to subscribe to topic `{topic}`"))?;
+        Ok(consumer)
+    }
+
+    /// Builds a consumer of all partitions of `topic` outside of any consumer group. The partitions
+    /// are assigned at their current end offsets before this returns, so every message produced
+    /// afterwards is consumed.
+    ///
+    /// This blocks on metadata requests to the brokers
+    fn consumer_at_end(&self, topic: &str) -> anyhow::Result<StreamConsumer> {
+        let consumer: StreamConsumer = self
+            .client_config()
+            .set("enable.auto.commit", "false")
+            .create()
+            .context("failed to create consumer")?;
+        let metadata = consumer
+            .fetch_metadata(Some(topic), METADATA_TIMEOUT)
+            .with_context(|| format!("failed to fetch metadata of topic `{topic}`"))?;
+        let partitions: Vec<_> = metadata
+            .topics()
+            .iter()
+            .filter(|t| t.name() == topic)
+            .flat_map(|t| t.partitions().iter().map(|p| p.id()))
+            .collect();
+        ensure!(!partitions.is_empty(), "topic `{topic}` has no partitions");
+        let mut assignment = TopicPartitionList::new();
+        for partition in partitions {
+            let (_, high) = consumer
+                .fetch_watermarks(topic, partition, METADATA_TIMEOUT)
+                .with_context(|| {
+                    format!("failed to fetch offsets of partition {partition} of topic `{topic}`")
+                })?;
+            assignment
+                .add_partition_offset(topic, partition, Offset::Offset(high))
+                .context("failed to build partition assignment")?;
+        }
+        consumer
+            .assign(&assignment)
+            .with_context(|| format!("failed to assign partitions of topic `{topic}`"))?;
+        Ok(consumer)
+    }
+}
+
+/// Runs [`ConnectionConfig::consumer_at_end`] on the blocking thread pool
+async fn consumer_at_end(config: &ConnectionConfig, topic: &str) -> anyhow::Result<StreamConsumer> {
+    let config = config.clone();
+    let topic = topic.to_string();
+    tokio::task::spawn_blocking(move || config.consumer_at_end(&topic))
+        .await
+        .context("consumer task panicked")?
+}
+
+/// Requests awaiting a reply, keyed by correlation id
+#[derive(Clone, Default)]
+struct PendingReplies(Arc<Mutex<HashMap<String, oneshot::Sender<Message>>>>);
+
+impl PendingReplies {
+    /// Registers a new request, returning its correlation id and a receiver for its reply
+    fn register(&self) -> (String, oneshot::Receiver<Message>) {
+        let id = Uuid::new_v4().to_string();
+        let (tx, rx) = oneshot::channel();
+        self.0
+            .lock()
+            .expect("failed to lock replies")
+            .insert(id.clone(), tx);
+        (id, rx)
+    }
+
+    /// Stops waiting for a reply to the request with correlation id `id`
+    fn cancel(&self, id: &str) {
+        self.0.lock().expect("failed to lock replies").remove(id);
+    }
+
+    /// Hands `msg` consumed from `reply_topic` to the request it correlates with. Returns false
+    /// if `msg` has no correlation id or it does not match a pending request
+    fn resolve(&self, msg: &OwnedMessage, reply_topic: &str) -> bool {
+        let Some(id) = header(msg, CORRELATION_ID_HEADER) else {
+            debug!("ignoring reply without correlation id");
+            return false;
+        };
+        let Some(tx) = self.0.lock().expect("failed to lock replies").remove(&id) else {
+            // Replies to requests of other connections end up here as well
+            return false;
+        };
+        tx.send(Message {
+            body: msg.payload().map(<[u8]>::to_vec).unwrap_or_default(),
+            reply_to: None,
+            subject: reply_topic.to_string(),
+        })
+        .is_ok()
+    }
+}
+
+/// Splits the `reply_to` of a delivered request, as published to by a reply, into the topic to
+/// reply to and the correlation id of the request
+fn split_reply_to(subject: &str) -> Option<(&str, &str)> {
+    subject.split_once(REPLY_TO_SEPARATOR)
+}
+
+/// Creates topics on demand. Topics known to exist are remembered, so that they are only created
+/// once per connection
+struct Topics {
+    admin: AdminClient<DefaultClientContext>,
+    ensured: Mutex<HashSet<String>>,
+}
+
+impl Topics {
+    /// Create `topic` if it is not known to exist yet
+    async fn ensure(&self, topic: &str) {
+        if self
+            .ensured
+            .lock()
+            .expect("failed to lock topics")
+            .contains(topic)
+        {
+            return;
+        }
+        if ensure_topic(&self.admin, topic).await {
+            self.ensured
+                .lock()
+                .expect("failed to lock topics")
+                .insert(topic.to_string());
+        }
+    }
+}
+
+/// Receives replies to requests and hands them to the pending requests they correlate with
+struct ReplyListener {
+    topic: String,
+    pending: PendingReplies,
+    handle: JoinHandle<()>,
+}
+
+/// A Kafka connection for a single link: a producer, a consumer task forwarding messages on the
+/// configured topic and, if request/reply emulation is enabled, a [`ReplyListener`]
+pub struct KafkaConnection {
+    producer: FutureProducer,
+    topics: Topics,
+    consumer_handle: JoinHandle<()>,
+    replies: Option<ReplyListener>,
+    request_timeout: Duration,
+}
+
+impl Drop for KafkaConnection {
+    fn drop(&mut self) {
+        self.consumer_handle.abort();
+        if let Some(ReplyListener { handle, .. }) = &self.replies {
+            handle.abort();
+        }
+    }
+}
+
+impl KafkaConnection {
+    /// Connect to Kafka using `config`, calling `handle_message` for each message consumed from
+    /// the configured topic
+    pub async fn connect<F, Fut>(
+        config: &ConnectionConfig,
+        handle_message: F,
+    ) -> anyhow::Result<Self>
+    where
+        F: Fn(Message) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send,
+    {
+        let producer: FutureProducer = config
+            .client_config()
+            .create()
+            .context("failed to create producer")?;
+        let topics = Topics {
+            admin: config
+                .client_config()
+                .create()
+                .context("failed to create admin client")?,
+            ensured: Mutex::default(),
+        };
+
+        topics.ensure(&config.topic).await;
+        // Without a consumer group, every connection consumes all messages on the topic
+        let consumer = match &config.consumer_group {
+            Some(group) => config.group_consumer(group, &config.topic)?,
+            None => consumer_at_end(config, &config.topic).await?,
+        };
+        let topic = config.topic.clone();
+        let consumer_handle = tokio::spawn(async move {
+            loop {
+                let msg = match consumer.recv().await {
+                    Ok(msg) => msg.detach(),
+                    Err(e) => {
+                        warn!("failed to consume message on topic `{topic}`: {e}");
+                        continue;
+                    }
+                };
+                let Some(body) = msg.payload() else {
+                    continue;
+                };
+                let reply_to = header(&msg, REPLY_TO_HEADER)
+                    .zip(header(&msg, CORRELATION_ID_HEADER))
+                    .map(|(topic, id)| format!("{topic}{REPLY_TO_SEPARATOR}{id}"));
+                handle_message(Message {
+                    body: body.to_vec(),
+                    reply_to,
+                    subject: topic.clone(),
+                })
+                .await;
+            }
+        });
+
+        let replies = if let Some(reply_topic) = &config.reply_topic {
+            topics.ensure(reply_topic).await;
+            // Each connection must see all replies to pick out the ones to its own requests. The
+            // consumer is positioned before any request can be sent, so no reply is missed
+            let consumer = consumer_at_end(config, reply_topic).await?;
+            let pending = PendingReplies::default();
+            let handle = tokio::spawn({
+                let pending = pending.clone();
+                let reply_topic = reply_topic.clone();
+                async move {
+                    loop {
+                        let msg = match consumer.recv().await {
+                            Ok(msg) => msg.detach(),
+                            Err(e) => {
+                                warn!("failed to consume reply on topic `{reply_topic}`: {e}");
+                                continue;
+                            }
+                        };
+                        pending.resolve(&msg, &reply_topic);
+                    }
+                }
+            });
+            Some(ReplyListener {
+                topic: reply_topic.clone(),
+                pending,
+                handle,
+            })
+        } else {
+            None
+        };
+
+        Ok(Self {
+            producer,
+            topics,
+            consumer_handle,
+            replies,
+            request_timeout: config.request_timeout,
+        })
+    }
+
+    /// Publish `msg`. If the subject is the `reply_to` of a delivered request, the message is
+    /// sent as a reply to that request
+    pub async fn publish(&self, msg: &Message) -> anyhow::Result<()> {
+        let (topic, headers) = match split_reply_to(&msg.subject) {
+            Some((topic, id)) => (
+                topic,
+                OwnedHeaders::new().insert(Header {
+                    key: CORRELATION_ID_HEADER,
+                    value: Some(id),
+                }),
+            ),
+            None => {
+                self.topics.ensure(&msg.subject).await;
+                (msg.subject.as_str(), OwnedHeaders::new())
+            }
+        };
+        let headers = headers.insert(Header {
+            key: "source",
+            value: Some("wasm"),
+        });
+        self.produce(topic, &msg.body, headers).await
+    }
+
+    /// Send `body` to `topic` and wait for a reply correlated with it. If `timeout` is not set,
+    /// the configured request timeout is used
+    pub async fn request(
+        &self,
+        topic: &str,
+        body: &[u8],
+        timeout: Option<Duration>,
+    ) -> anyhow::Result<Message> {
+        let Some(ReplyListener {
+            topic: reply_topic,
+            pending,
+            ..
+        }) = &self.replies
+        else {
+            bail!("request/reply is not enabled for this link, set `{KAFKA_REQUEST_REPLY}=true`");
+        };
+        self.topics.ensure(topic).await;
+        let (id, rx) = pending.register();
+
+        let headers = OwnedHeaders::new()
+            .insert(Header {
+                key: CORRELATION_ID_HEADER,
+                value: Some(&id),
+            })
+            .insert(Header {
+                key: REPLY_TO_HEADER,
+                value: Some(reply_topic),
+            })
+            .insert(Header {
+                key: "source",
+                value: Some("wasm"),
+            });
+        let timeout = timeout.unwrap_or(self.request_timeout);
+        let res = match self.produce(topic, body, headers).await {
+            Ok(()) => tokio::time::timeout(timeout, rx)
+                .await
+                .map_err(|_| anyhow!("timed out waiting for reply after {timeout:?}"))
+                .and_then(|reply| reply.context("reply listener stopped")),
+            Err(e) => Err(e),
+        };
+        if res.is_err() {
+            pending.cancel(&id);
+        }
+        res
+    }
+
+    async fn produce(&self, topic: &str, body: &[u8], headers: OwnedHeaders) -> anyhow::Result<()> {
+        self.producer
+            .send(
+                FutureRecord::<(), _>::to(topic)
+                    .payload(body)
+                    .headers(headers),
+                PRODUCE_TIMEOUT,
+            )
+            .await
+            .map_err(|(e, _)| anyhow!(e).context(format!("failed to produce to `{topic}`")))?;
+        Ok(())
+    }
+}
+
+/// Returns the UTF-8 value of header `key` on `msg`, if set
+fn header(msg: &OwnedMessage, key: &str) -> Option<String> {
+    msg.headers()?
+        .iter()
+        .find(|header| header.key == key)
+        .and_then(|header| header.value)
+        .and_then(|value| String::from_utf8(value.to_vec()).ok())
+}
+
+/// Create `topic` if it doesn't exist yet. Returns whether the topic exists
+async fn ensure_topic(admin: &AdminClient<DefaultClientContext>, topic: &str) -> bool {
+    // TODO: accept link config values for these
+    let new_topic = NewTopic::new(topic, 1, TopicReplication::Fixed(1));
+    match admin
+        .create_topics(
+            &[new_topic],
+            &AdminOptions::new().operation_timeout(Some(Duration::from_secs(1))),
+        )
+        .await
+    {
+        Ok(results) => results.into_iter().all(|res| match res {
+            Ok(_) | Err((_, RDKafkaErrorCode::TopicAlreadyExists)) => true,
+            Err((topic, code)) => {
+                warn!("could not create topic `{topic}`: {code}");
+                false
+            }
+        }),
+        Err(KafkaError::AdminOp(code)) => {
+            warn!("could not create topic `{topic}`: {code}");
+            false
+        }
+        Err(e) => {
+            error!("failed to create topic `{topic}`: {e}");
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::time::Duration;
+
+    use rdkafka::message::{Header, OwnedHeaders, OwnedMessage, Timestamp};
+    use tokio::sync::oneshot::error::TryRecvError;
+
+    use super::{split_reply_to, ConnectionConfig, PendingReplies, CORRELATION_ID_HEADER};
+
+    fn reply(correlation_id: Option<&str>, body: &str) -> OwnedMessage {
+        let headers = correlation_id.map(|id| {
+            OwnedHeaders::new().insert(Header {
+                key: CORRELATION_ID_HEADER,
+                value: Some(id),
+            })
+        });
+        OwnedMessage::new(
+            Some(body.as_bytes().to_vec()),
+            None,
+            "orders.reply".to_string(),
+            Timestamp::NotAvailable,
+            0,
+            0,
+            headers,
+        )
+    }
+
+    #[test]
+    fn test_default_connection_config() {
+        let config = ConnectionConfig::from_values([]);
+        assert_eq!(config.hosts, ["127.0.0.1:9092"]);
+        assert_eq!(config.topic, "my-topic");
+        assert_eq!(config.consumer_group, None);
+        assert_eq!(config.reply_topic, None);
+        assert_eq!(config.request_timeout, Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_connection_config() {
+        let config = ConnectionConfig::from_values([
+            ("HOSTS", "127.0.0.1:9092, 127.0.0.1:9093"),
+            ("TOPIC", " orders "),
+            ("CONSUMER_GROUP", "order-processors"),
+            ("REQUEST_REPLY", "true"),
+            ("REQUEST_TIMEOUT_MS", "250"),
+        ]);
+        assert_eq!(config.hosts, ["127.0.0.1:9092", "127.0.0.1:9093"]);
+        assert_eq!(config.topic, "orders");
+        assert_eq!(config.consumer_group.as_deref(), Some("order-processors"));
+        assert_eq!(config.reply_topic.as_deref(), Some("orders.reply"));
+        assert_eq!(config.request_timeout, Duration::from_millis(250));
+
+        let config =
+            ConnectionConfig::from_values([("REQUEST_REPLY", "false"), ("REPLY_TOPIC", "replies")]);
+        assert_eq!(config.reply_topic, None);
+
+        let config =
+            ConnectionConfig::from_values([("REQUEST_REPLY", "TRUE"), ("REPLY_TOPIC", "replies")]);
+        assert_eq!(config.reply_topic.as_deref(), Some("replies"));
+    }
+
+    #[test]
+    fn test_pending_replies_correlation() {
+        let pending = PendingReplies::default();
+        let (first_id, mut first) = pending.register();
+        let (second_id, mut second) = pending.register();
+        assert_ne!(first_id, second_id);
+
+        assert!(!pending.resolve(&reply(None, "no id"), "orders.reply"));
+        assert!(!pending.resolve(&reply(Some("unknown"), "other"), "orders.reply"));
+        assert!(pending.resolve(&reply(Some(&second_id), "second"), "orders.reply"));
+
+        let msg = second
+            .try_recv()
+            .expect("second request should have a reply");
+        assert_eq!(msg.body, b"second");
+        assert_eq!(msg.subject, "orders.reply");
+        assert!(matches!(first.try_recv(), Err(TryRecvError::Empty)));
+
+        // A reply is only handed out once
+        assert!(!pending.resolve(&reply(Some(&second_id), "again"), "orders.reply"));
+
+        pending.cancel(&first_id);
+        assert!(!pending.resolve(&reply(Some(&first_id), "late"), "orders.reply"));
+        assert!(matches!(first.try_recv(), Err(TryRecvError::Closed)));
+    }
+
+    #[test]
+    fn test_split_reply_to() {
+        assert_eq!(
+            split_reply_to("orders.reply#3f2c9a1e"),
+            Some(("orders.reply", "3f2c9a1e"))
+        );
+        // Correlation ids are taken as is, even if they contain the separator
+        assert_eq!(
+            split_reply_to("orders.reply#a#b"),
+            Some(("orders.reply", "a#b"))
+        );
+        assert_eq!(split_reply_to("orders"), None);
+    }
+}
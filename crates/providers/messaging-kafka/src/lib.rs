@@ -1,14 +1,11 @@
 //! Implementation for wasmcloud:messaging
 //!
-use std::collections::{BTreeMap, HashMap};
+use core::time::Duration;
+
+use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 
-use futures::StreamExt;
-use rskafka::client::consumer::{StartOffset, StreamConsumerBuilder};
-use rskafka::client::partition::{Compression, UnknownTopicHandling};
-use rskafka::client::ClientBuilder;
-use rskafka::record::{Record, RecordAndOffset};
-use tokio::task::JoinHandle;
+use anyhow::{anyhow, Context as _};
 use tracing::{debug, error, instrument, warn};
 
 use wasmcloud_provider_wit_bindgen::deps::{
@@ -16,32 +13,35 @@ use wasmcloud_provider_wit_bindgen::deps::{
     wasmcloud_provider_sdk::{Context, LinkConfig, ProviderOperationResult},
 };
 
+mod connection;
+pub use connection::{ConnectionConfig, KafkaConnection};
+
 wasmcloud_provider_wit_bindgen::generate!({
     impl_struct: KafkaMessagingProvider,
     contract: "wasmcloud:messaging",
     wit_bindgen_cfg: "provider-messaging-kafka"
 });
 
-/// Linkdef value for hosts, accepted as a comma separated string
-const KAFKA_HOSTS: &str = "HOSTS";
-const DEFAULT_HOST: &str = "127.0.0.1:9092";
-
-/// Linkdef value for topic, accepted as a single string
-const KAFKA_TOPIC: &str = "TOPIC";
-const DEFAULT_TOPIC: &str = "my-topic";
-
-#[derive(Clone)]
-/// A struct that contains a consumer task handler and the host connection strings
-struct KafkaConnection {
-    connection_hosts: Vec<String>,
-    consumer_handle: Arc<JoinHandle<()>>,
-}
-
 #[derive(Clone, Default)]
 pub struct KafkaMessagingProvider {
-    // Map of actor ID to the JoinHandle where messages are consumed. When a link is put
-    // we spawn a tokio::task to handle messages, and on delete the task is closed
-    connections: Arc<RwLock<HashMap<String, KafkaConnection>>>,
+    // Map of actor ID to the connection used for it. When a link is put we connect and spawn
+    // a tokio::task to handle messages, and on delete the connection is dropped
+    connections: Arc<RwLock<HashMap<String, Arc<KafkaConnection>>>>,
+}
+
+impl KafkaMessagingProvider {
+    /// Returns the connection for the actor invoking the provider
+    fn connection(&self, ctx: &Context) -> anyhow::Result<Arc<KafkaConnection>> {
+        let source_id = ctx.actor.as_ref().context("no actor in request")?;
+        let connections = self
+            .connections
+            .read()
+            .map_err(|e| anyhow!("failed to read connections: {e}"))?;
+        connections
+            .get(source_id)
+            .cloned()
+            .with_context(|| format!("actor not linked: {source_id}"))
+    }
 }
 
 #[async_trait]
@@ -53,107 +53,39 @@ impl WasmcloudCapabilityProvider for KafkaMessagingProvider {
     ) -> ProviderOperationResult<()> {
         let source_id = link_config.get_source_id();
         debug!("putting link for actor [{source_id}]");
-        let config_values = link_config.get_config();
-
-        // Collect comma separated hosts into a Vec<String>
-        let hosts = config_values
-            .iter()
-            .find_map(|(k, v)| {
-                if *k == KAFKA_HOSTS {
-                    Some(v.to_string())
-                } else {
-                    None
-                }
-            })
-            .unwrap_or_else(|| DEFAULT_HOST.to_string())
-            .trim()
-            .split(',')
-            .map(|s| s.to_string())
-            .collect::<Vec<String>>();
-
-        // Retrieve or use default topic, trimming off extra whitespace
-        let topic = config_values
-            .iter()
-            .find_map(|(k, v)| {
-                if *k == KAFKA_TOPIC {
-                    Some(v.to_string())
-                } else {
-                    None
-                }
-            })
-            .unwrap_or_else(|| DEFAULT_TOPIC.to_string())
-            .trim()
-            .to_string();
-
-        // Do some basic validation before spawning off in a thread
-        let Ok(client) = ClientBuilder::new(hosts.clone()).build().await else {
-            warn!(
-                source_id,
-                "failed to create Kafka client for actor, messages won't be received",
-            );
-            return Ok(());
-        };
-
-        // Create a partition client
-        let Ok(partition_client) = client
-            .partition_client(&topic, 0, UnknownTopicHandling::Error)
-            .await
-        else {
-            warn!(
-                source_id,
-                "failed to create partition client for actor, messages won't be received",
-            );
-            return Ok(());
-        };
+        let config = ConnectionConfig::from_values(
+            link_config
+                .get_config()
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str())),
+        );
 
-        // Clone for moving into thread
-        let component_id = source_id.clone();
-        let join = tokio::task::spawn(async move {
+        // Clone for moving into the consumer task
+        let component_id = source_id.to_string();
+        let handle_message = move |msg| {
             let component_id = component_id.clone();
-
-            // construct stream consumer
-            let mut stream =
-            // StartOffset::Latest only processes new messages, but Earliest will send every message.
-            // This could be a linkdef tunable value in the future
-                StreamConsumerBuilder::new(Arc::new(partition_client), StartOffset::Latest)
-                .with_max_wait_ms(100)
-                .build();
-
-            // Continue to pull records off the stream until it closes
-            while let Some(Ok((
-                RecordAndOffset {
-                    record:
-                        Record {
-                            value: Some(message),
-                            ..
-                        },
-                    ..
-                },
-                _water_mark,
-            ))) = stream.next().await
-            {
-                let component_id = component_id.clone();
+            async move {
                 if let Err(e) = InvocationHandler::new(component_id)
-                    .handle_message(Message {
-                        body: message,
-                        reply_to: None,
-                        subject: topic.to_owned(),
-                    })
+                    .handle_message(msg)
                     .await
                 {
-                    eprintln!("Unable to send subscription: {:?}", e);
+                    error!("unable to send subscription: {e:?}");
                 }
             }
-        });
+        };
+        let connection = match KafkaConnection::connect(&config, handle_message).await {
+            Ok(connection) => connection,
+            Err(e) => {
+                warn!(
+                    source_id,
+                    "failed to connect to Kafka for actor, messages won't be received: {e:#}",
+                );
+                return Ok(());
+            }
+        };
 
         let mut connections = self.connections.write().unwrap();
-        connections.insert(
-            source_id.into(),
-            KafkaConnection {
-                consumer_handle: Arc::new(join),
-                connection_hosts: hosts,
-            },
-        );
+        connections.insert(source_id.into(), Arc::new(connection));
 
         Ok(())
     }
@@ -164,13 +96,7 @@ impl WasmcloudCapabilityProvider for KafkaMessagingProvider {
         debug!("deleting link for actor {}", source_id);
 
         let mut connections = self.connections.write().unwrap();
-        if let Some(KafkaConnection {
-            consumer_handle: handle,
-            ..
-        }) = connections.remove(source_id)
-        {
-            handle.abort()
-        } else {
+        if connections.remove(source_id).is_none() {
             debug!("Linkdef deleted for non-existent consumer, ignoring")
         }
         Ok(())
@@ -178,13 +104,11 @@ impl WasmcloudCapabilityProvider for KafkaMessagingProvider {
 
     /// Handle shutdown request with any cleanup necessary
     async fn shutdown(&self) -> ProviderOperationResult<()> {
+        // dropping the connections stops their consumer tasks
         self.connections
             .write()
             .expect("failed to write connections")
-            .drain()
-            .for_each(|(_source_id, connection)| {
-                connection.consumer_handle.abort();
-            });
+            .clear();
         Ok(())
     }
 }
@@ -193,105 +117,44 @@ impl WasmcloudCapabilityProvider for KafkaMessagingProvider {
 #[async_trait]
 impl WasmcloudMessagingMessaging for KafkaMessagingProvider {
     #[instrument(
-        level = "debug", 
+        level = "debug",
         skip_all,
         fields(subject = %msg.subject, reply_to = ?msg.reply_to, body_len = %msg.body.len())
     )]
     async fn publish(&self, ctx: Context, msg: Message) -> () {
         debug!("publishing message: {msg:?}");
 
-        let hosts = {
-            let connections = match self.connections.read() {
-                Ok(connections) => connections,
-                Err(e) => {
-                    error!("failed to read connections: {e}");
-                    return;
-                }
-            };
-
-            let config = match connections.get(&ctx.actor.clone().unwrap()) {
-                Some(config) => config,
-                None => {
-                    error!("no actor config for connection");
-                    return;
-                }
-            };
-
-            config.connection_hosts.clone()
-        };
-
-        let client = match ClientBuilder::new(hosts).build().await {
-            Ok(client) => client,
-            Err(e) => {
-                error!("failed to build client: {e}");
-                return;
-            }
-        };
-
-        // Ensure topic exists
-        let controller_client = match client.controller_client() {
-            Ok(controller_client) => controller_client,
+        let connection = match self.connection(&ctx) {
+            Ok(connection) => connection,
             Err(e) => {
-                error!("failed to build controller client: {e}");
+                error!("{e:#}");
                 return;
             }
         };
-
-        // TODO: accept linkdef tunable values for these
-        if let Err(e) = controller_client
-            .create_topic(
-                msg.subject.to_owned(),
-                1,     // partition
-                1,     // replication factor
-                1_000, // timeout (ms)
-            )
-            .await
-        {
-            warn!("could not create topic: {e:?}")
+        if let Err(e) = connection.publish(&msg).await {
+            error!("failed to publish message: {e:#}");
         }
-
-        // Get a partition-bound client
-        let partition_client = match client
-            .partition_client(
-                msg.subject.to_owned(),
-                0, // partition
-                UnknownTopicHandling::Error,
-            )
-            .await
-        {
-            Ok(partition_client) => partition_client,
-            Err(e) => {
-                error!("failed to create partition client: {e}");
-                return;
-            }
-        };
-
-        // produce some data
-        let records = vec![Record {
-            key: None,
-            value: Some(msg.body.clone()),
-            headers: BTreeMap::from([("source".to_owned(), b"wasm".to_vec())]),
-            timestamp: chrono::offset::Utc::now(),
-        }];
-
-        if let Err(e) = partition_client
-            .produce(records, Compression::default())
-            .await
-        {
-            error!("failed to produce record: {e}");
-        };
     }
 
-    #[instrument(level = "debug", skip_all, fields(subject = %_msg.subject))]
-    async fn request(&self, _ctx: Context, _msg: RequestMessage) -> Message {
-        // Kafka does not support request-reply in the traditional sense. You can publish to a
-        // topic, and get an acknowledgement that it was received, but you can't get a
-        // reply from a consumer on the other side.
-        error!("not implemented (Kafka does not officially support the request-reply paradigm)");
-        Message {
-            subject: String::default(),
-            reply_to: None,
-            body: Vec::new(),
-        }
+    #[instrument(level = "debug", skip_all, fields(subject = %msg.subject, timeout_ms = %msg.timeout_ms))]
+    async fn request(&self, ctx: Context, msg: RequestMessage) -> Message {
+        // Kafka does not support request-reply in the traditional sense, so it is emulated by
+        // sending a correlation id and reply topic along with the request and waiting for a
+        // message with the same correlation id on the reply topic
+        let timeout = (msg.timeout_ms > 0).then(|| Duration::from_millis(msg.timeout_ms.into()));
+        let reply = match self.connection(&ctx) {
+            Ok(connection) => connection.request(&msg.subject, &msg.body, timeout).await,
+            Err(e) => Err(e),
+        };
+        // The contract has no way to return errors. Replies always have the reply topic as
+        // subject, so a message without one tells the caller that the request failed
+        reply.unwrap_or_else(|e| {
+            error!("request failed: {e:#}");
+            Message {
+                subject: String::default(),
+                reply_to: None,
+                body: Vec::new(),
+            }
+        })
     }
 }
@@ -0,0 +1,156 @@
+//! NOTE: to run the tests in this file, you must start a local instance
+//! of Kafka (or some other Kafka-compatible broker, like Redpanda) to use.
+//!
+//! For example, with docker, you can start Redpanda:
+//!
+//! ```console
+//! docker run --rm \
+//! -p 9092:9092 \
+//! docker.redpanda.com/redpandadata/redpanda:v23.3.5 \
+//! redpanda start --overprovisioned --smp 1 \
+//! --kafka-addr 0.0.0.0:9092 --advertise-kafka-addr 127.0.0.1:9092
+//! ```
+//!
+//! When running the tests, you can set the `KAFKA_HOSTS` ENV variable to point
+//! the tests at a different broker (defaults to `127.0.0.1:9092`), for example:
+//!
+//! ```console
+//! export KAFKA_HOSTS=127.0.0.1:9092
+//! cargo test --test request_reply -- --ignored --nocapture
+//! ```
+
+use core::time::Duration;
+
+use std::env;
+use std::sync::{Arc, OnceLock};
+
+use tokio::sync::mpsc;
+use wasmcloud_provider_kafka::{ConnectionConfig, KafkaConnection, Message};
+
+/// Helper function to create a [`ConnectionConfig`] for `topic` with local testing overrides
+fn test_config(topic: &str, extra: &[(&str, &str)]) -> ConnectionConfig {
+    let hosts = env::var("KAFKA_HOSTS").unwrap_or_else(|_| "127.0.0.1:9092".into());
+    ConnectionConfig::from_values(
+        [("HOSTS", hosts.as_str()), ("TOPIC", topic)]
+            .into_iter()
+            .chain(extra.iter().copied()),
+    )
+}
+
+/// Unique topic name, so that tests don't see each others messages
+fn test_topic(name: &str) -> String {
+    format!("messaging-kafka.test.{name}.{}", std::process::id())
+}
+
+/// Tests
+/// - request
+/// - publish to the `reply_to` of a received request
+#[tokio::test]
+#[ignore = "requires a Kafka broker"]
+async fn test_request_reply() {
+    let topic = test_topic("request-reply");
+
+    // The responder echoes every request back to its `reply_to`
+    let responder_connection: Arc<OnceLock<KafkaConnection>> = Arc::default();
+    let responder = {
+        let responder_connection = Arc::clone(&responder_connection);
+        KafkaConnection::connect(&test_config(&topic, &[]), move |msg| {
+            let responder_connection = Arc::clone(&responder_connection);
+            async move {
+                let reply_to = msg.reply_to.expect("request should have a reply_to");
+                responder_connection
+                    .get()
+                    .expect("responder not connected")
+                    .publish(&Message {
+                        subject: reply_to,
+                        reply_to: None,
+                        body: msg.body,
+                    })
+                    .await
+                    .expect("failed to publish reply");
+            }
+        })
+        .await
+        .expect("failed to connect responder")
+    };
+    assert!(responder_connection.set(responder).is_ok());
+
+    let requester = KafkaConnection::connect(
+        &test_config(
+            &test_topic("requester"),
+            &[
+                ("REQUEST_REPLY", "true"),
+                ("REPLY_TOPIC", &format!("{topic}.reply")),
+            ],
+        ),
+        |_| async {},
+    )
+    .await
+    .expect("failed to connect requester");
+
+    let reply = requester
+        .request(&topic, b"hello", Some(Duration::from_secs(10)))
+        .await
+        .expect("failed to receive reply");
+    assert_eq!(reply.body, b"hello");
+    assert_eq!(reply.subject, format!("{topic}.reply"));
+}
+
+/// Tests
+/// - messages are delivered once to a consumer group
+/// - connections without a consumer group receive every message
+#[tokio::test]
+#[ignore = "requires a Kafka broker"]
+async fn test_consumer_groups() {
+    let topic = test_topic("consumer-groups");
+    let group = [("CONSUMER_GROUP", "messaging-kafka-test")];
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut connections = Vec::new();
+    for name in ["grouped-1", "grouped-2", "ungrouped"] {
+        let extra: &[_] = if name == "ungrouped" { &[] } else { &group };
+        let tx = tx.clone();
+        let connection = KafkaConnection::connect(&test_config(&topic, extra), move |msg| {
+            let tx = tx.clone();
+            async move {
+                tx.send((name, msg.body)).expect("failed to send message");
+            }
+        })
+        .await
+        .expect("failed to connect");
+        connections.push(connection);
+    }
+    drop(tx);
+
+    // Grouped connections consume the topic from the beginning until the group has committed
+    // offsets, so the message is received even if it is published before the group is joined
+    connections[0]
+        .publish(&Message {
+            subject: topic.clone(),
+            reply_to: None,
+            body: b"hello".to_vec(),
+        })
+        .await
+        .expect("failed to publish");
+
+    let mut received = Vec::new();
+    while received.len() < 2 {
+        let (name, body) = tokio::time::timeout(Duration::from_secs(30), rx.recv())
+            .await
+            .expect("timed out waiting for message")
+            .expect("consumers stopped");
+        assert_eq!(body, b"hello");
+        received.push(name);
+    }
+    // The group must not deliver the message a second time
+    assert!(
+        tokio::time::timeout(Duration::from_secs(2), rx.recv())
+            .await
+            .is_err(),
+        "message was delivered more than twice"
+    );
+    received.sort_unstable();
+    assert_eq!(received.len(), 2, "received: {received:?}");
+    assert!(received[0].starts_with("grouped-"));
+    assert_eq!(received[1], "ungrouped");
+}
@@ -1,4 +1,4 @@
-use super::{Ctx, Instance, InterfaceInstance};
+use super::{apply_execution_limits, map_execution_limit_trap, Ctx, Instance, InterfaceInstance};
 
 use crate::capability::http::types;
 use crate::capability::{IncomingHttp, OutgoingHttp};
@@ -87,14 +87,17 @@ impl Instance {
     pub async fn into_incoming_http(
         mut self,
     ) -> anyhow::Result<InterfaceInstance<incoming_http_bindings::IncomingHttp>> {
+        apply_execution_limits(&mut self.store, self.limits)?;
         let (bindings, _) = incoming_http_bindings::IncomingHttp::instantiate_pre(
             &mut self.store,
             &self.instance_pre,
         )
-        .await?;
+        .await
+        .map_err(map_execution_limit_trap)?;
         Ok(InterfaceInstance {
             store: Mutex::new(self.store),
             bindings,
+            limits: self.limits,
         })
     }
 }
@@ -106,6 +109,7 @@ impl IncomingHttp for InterfaceInstance<incoming_http_bindings::IncomingHttp> {
         request: http::Request<HyperIncomingBody>,
     ) -> anyhow::Result<Result<http::Response<HyperOutgoingBody>, types::ErrorCode>> {
         let mut store = self.store.lock().await;
+        apply_execution_limits(&mut store, self.limits)?;
         let ctx = store.data_mut();
         let request = ctx
             .new_incoming_request(request)
@@ -117,7 +121,8 @@ impl IncomingHttp for InterfaceInstance<incoming_http_bindings::IncomingHttp> {
         self.bindings
             .wasi_http_incoming_handler()
             .call_handle(&mut *store, request, response)
-            .await?;
+            .await
+            .map_err(map_execution_limit_trap)?;
         response_rx.try_recv().context("a response was not set")
     }
 }
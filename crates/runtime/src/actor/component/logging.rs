@@ -1,4 +1,4 @@
-use super::{Ctx, Instance, InterfaceInstance};
+use super::{apply_execution_limits, map_execution_limit_trap, Ctx, Instance, InterfaceInstance};
 
 use crate::capability::logging::logging;
 use crate::capability::Logging;
@@ -34,11 +34,15 @@ impl Instance {
     pub async fn into_logging(
         mut self,
     ) -> anyhow::Result<InterfaceInstance<logging_bindings::Logging>> {
+        apply_execution_limits(&mut self.store, self.limits)?;
         let (bindings, _) =
-            logging_bindings::Logging::instantiate_pre(&mut self.store, &self.instance_pre).await?;
+            logging_bindings::Logging::instantiate_pre(&mut self.store, &self.instance_pre)
+                .await
+                .map_err(map_execution_limit_trap)?;
         Ok(InterfaceInstance {
             store: Mutex::new(self.store),
             bindings,
+            limits: self.limits,
         })
     }
 }
@@ -66,6 +70,7 @@ impl Logging for InterfaceInstance<logging_bindings::Logging> {
         message: String,
     ) -> anyhow::Result<()> {
         let mut store = self.store.lock().await;
+        apply_execution_limits(&mut store, self.limits)?;
         // NOTE: It appears that unifying the `Level` type is not possible currently
         use logging_bindings::exports::wasi::logging::logging::Level;
         let level = match level {
@@ -81,5 +86,6 @@ impl Logging for InterfaceInstance<logging_bindings::Logging> {
             .wasi_logging_logging()
             .call_log(&mut *store, level, &context, &message)
             .await
+            .map_err(map_execution_limit_trap)
     }
 }
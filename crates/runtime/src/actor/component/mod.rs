@@ -1,5 +1,6 @@
 use crate::actor::claims;
 use crate::capability::{builtin, Bus, Interfaces};
+use crate::runtime::ExecutionLimits;
 use crate::Runtime;
 
 use core::fmt::{self, Debug};
@@ -21,6 +22,7 @@ use wasmcloud_core::CallTargetInterface;
 use wasmtime::component::{
     self, types, InstancePre, Linker, ResourceTable, ResourceTableError, Type, Val,
 };
+use wasmtime::{StoreLimits, StoreLimitsBuilder};
 use wasmtime_wasi::preview2::command::{self};
 use wasmtime_wasi::preview2::pipe::{AsyncWriteStream, ClosedInputStream, ClosedOutputStream};
 use wasmtime_wasi::preview2::{
@@ -40,6 +42,81 @@ mod messaging;
 
 type TableResult<T> = Result<T, ResourceTableError>;
 
+/// Error returned when a component invocation is interrupted for exceeding an execution limit
+/// configured on the [`Runtime`]. It can be retrieved from invocation errors using [`anyhow::Error::downcast_ref`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExecutionLimitExceeded {
+    /// The invocation ran past its wall-clock execution deadline
+    Deadline,
+    /// The invocation exhausted its fuel budget
+    Fuel,
+}
+
+impl fmt::Display for ExecutionLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Deadline => write!(f, "component invocation exceeded its execution deadline"),
+            Self::Fuel => write!(f, "component invocation exhausted its fuel budget"),
+        }
+    }
+}
+
+impl std::error::Error for ExecutionLimitExceeded {}
+
+/// Resource limits enforced on each [Instance] of a [Component]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ResourceLimits {
+    /// Maximum size, in bytes, any single linear memory of an instance may grow to
+    pub max_memory_size: Option<usize>,
+    /// Maximum number of elements any single table of an instance may grow to
+    pub max_table_elements: Option<u32>,
+}
+
+impl From<ResourceLimits> for StoreLimits {
+    fn from(
+        ResourceLimits {
+            max_memory_size,
+            max_table_elements,
+        }: ResourceLimits,
+    ) -> Self {
+        let mut limits = StoreLimitsBuilder::new();
+        if let Some(max_memory_size) = max_memory_size {
+            limits = limits.memory_size(max_memory_size);
+        }
+        if let Some(max_table_elements) = max_table_elements {
+            limits = limits.table_elements(max_table_elements);
+        }
+        limits.build()
+    }
+}
+
+/// Attaches [`ExecutionLimitExceeded`] context to `err` if it was caused by an execution limit trap
+fn map_execution_limit_trap(err: anyhow::Error) -> anyhow::Error {
+    match err.downcast_ref::<wasmtime::Trap>() {
+        Some(wasmtime::Trap::Interrupt) => err.context(ExecutionLimitExceeded::Deadline),
+        Some(wasmtime::Trap::OutOfFuel) => err.context(ExecutionLimitExceeded::Fuel),
+        _ => err,
+    }
+}
+
+/// Resets the execution budget of `store` according to `limits`
+fn apply_execution_limits(
+    store: &mut wasmtime::Store<Ctx>,
+    ExecutionLimits {
+        epoch_deadline,
+        fuel,
+    }: ExecutionLimits,
+) -> anyhow::Result<()> {
+    if let Some(ticks) = epoch_deadline {
+        store.epoch_deadline_trap();
+        store.set_epoch_deadline(ticks);
+    }
+    if let Some(fuel) = fuel {
+        store.set_fuel(fuel).context("failed to set fuel")?;
+    }
+    Ok(())
+}
+
 /// `StdioStream` delegates all stream I/O to inner stream if such is set and
 /// mimics [`ClosedInputStream`] and [`ClosedOutputStream`] otherwise
 struct StdioStream<T>(Arc<Mutex<Option<T>>>);
@@ -191,6 +268,7 @@ struct Ctx {
     handler: builtin::Handler,
     stderr: StdioStream<Box<dyn HostOutputStream>>,
     custom_result_types: HashMap<String, HashMap<String, Vec<Type>>>,
+    limits: StoreLimits,
 }
 
 impl WasiView for Ctx {
@@ -219,6 +297,8 @@ pub struct Component {
     exports: Arc<HashMap<String, HashMap<String, DynamicFunction>>>,
     ty: types::Component,
     instance_pre: wasmtime::component::InstancePre<Ctx>,
+    limits: ExecutionLimits,
+    resource_limits: ResourceLimits,
 }
 
 impl Debug for Component {
@@ -230,6 +310,8 @@ impl Debug for Component {
             .field("polyfilled_imports", &self.polyfilled_imports)
             .field("exports", &self.exports)
             .field("ty", &self.ty)
+            .field("limits", &self.limits)
+            .field("resource_limits", &self.resource_limits)
             .finish_non_exhaustive()
     }
 }
@@ -412,6 +494,8 @@ fn instantiate(
     handler: impl Into<builtin::Handler>,
     ty: types::Component,
     instance_pre: InstancePre<Ctx>,
+    limits: ExecutionLimits,
+    resource_limits: ResourceLimits,
 ) -> anyhow::Result<Instance> {
     let stdin = StdioStream::default();
     let stdout = StdioStream::default();
@@ -482,11 +566,15 @@ fn instantiate(
         handler,
         stderr,
         custom_result_types,
+        limits: resource_limits.into(),
     };
-    let store = wasmtime::Store::new(engine, ctx);
+    let mut store = wasmtime::Store::new(engine, ctx);
+    store.limiter(|ctx| &mut ctx.limits);
+    apply_execution_limits(&mut store, limits)?;
     Ok(Instance {
         store,
         instance_pre,
+        limits,
     })
 }
 
@@ -558,6 +646,8 @@ impl Component {
             exports: Arc::new(function_exports(&resolve, exports)),
             ty,
             instance_pre,
+            limits: rt.execution_limits,
+            resource_limits: ResourceLimits::default(),
         })
     }
 
@@ -607,6 +697,18 @@ impl Component {
         self.claims.as_ref()
     }
 
+    /// [`ResourceLimits`] enforced on each [Instance] of this [Component].
+    pub fn resource_limits(&self) -> ResourceLimits {
+        self.resource_limits
+    }
+
+    /// Sets the [`ResourceLimits`] enforced on each [Instance] of this [Component] instantiated
+    /// after this call.
+    pub fn set_resource_limits(&mut self, resource_limits: ResourceLimits) -> &mut Self {
+        self.resource_limits = resource_limits;
+        self
+    }
+
     /// Like [Self::instantiate], but moves the [Component].
     #[instrument]
     pub fn into_instance(self) -> anyhow::Result<Instance> {
@@ -618,7 +720,14 @@ impl Component {
     pub fn into_instance_claims(
         self,
     ) -> anyhow::Result<(Instance, Option<jwt::Claims<jwt::Actor>>)> {
-        let instance = instantiate(&self.engine, self.handler, self.ty, self.instance_pre)?;
+        let instance = instantiate(
+            &self.engine,
+            self.handler,
+            self.ty,
+            self.instance_pre,
+            self.limits,
+            self.resource_limits,
+        )?;
         Ok((instance, self.claims))
     }
 
@@ -630,9 +739,21 @@ impl Component {
             self.handler.clone(),
             self.ty.clone(),
             self.instance_pre.clone(),
+            self.limits,
+            self.resource_limits,
         )
     }
 
+    /// Instantiates a [Component] and runs its initialization without invoking any exports using
+    /// [Instance::warm_up]
+    #[instrument(level = "debug", skip(self))]
+    pub async fn warm_up(&self) -> anyhow::Result<()> {
+        self.instantiate()
+            .context("failed to instantiate component")?
+            .warm_up()
+            .await
+    }
+
     /// Instantiates a [Component] producing an [Instance] and invokes an operation on it using [Instance::call]
     #[instrument(level = "trace", skip_all)]
     pub async fn call(
@@ -658,6 +779,7 @@ impl From<Component> for Option<jwt::Claims<jwt::Actor>> {
 pub struct Instance {
     store: wasmtime::Store<Ctx>,
     instance_pre: InstancePre<Ctx>,
+    limits: ExecutionLimits,
 }
 
 impl Debug for Instance {
@@ -701,7 +823,28 @@ impl Instance {
         Ok(self)
     }
 
+    /// Instantiates the component within the [Instance] store, which allocates its memories and
+    /// tables and runs its start function, without invoking any exports.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the component cannot be instantiated within the limits of the [`Runtime`]
+    #[instrument(level = "debug", skip(self))]
+    pub async fn warm_up(&mut self) -> anyhow::Result<()> {
+        apply_execution_limits(&mut self.store, self.limits)?;
+        self.instance_pre
+            .instantiate_async(&mut self.store)
+            .await
+            .map_err(map_execution_limit_trap)
+            .context("failed to instantiate component")?;
+        Ok(())
+    }
+
     /// Invoke an operation on an [Instance] producing a result.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`ExecutionLimitExceeded`] in the error chain if the invocation is interrupted by the [`Runtime`]
     #[instrument(skip(self, params, instance, name), fields(interface = instance, function = name))]
     pub async fn call(
         &mut self,
@@ -709,10 +852,12 @@ impl Instance {
         name: &str,
         params: Vec<wrpc_transport::Value>,
     ) -> anyhow::Result<Vec<wrpc_transport::Value>> {
+        apply_execution_limits(&mut self.store, self.limits)?;
         let component = self
             .instance_pre
             .instantiate_async(&mut self.store)
             .await
+            .map_err(map_execution_limit_trap)
             .context("failed to instantiate component")?;
         let func = {
             let mut exports = component.exports(&mut self.store);
@@ -734,6 +879,7 @@ impl Instance {
         let mut results = vec![Val::Bool(false); results_ty.len()];
         func.call_async(&mut self.store, &params, &mut results)
             .await
+            .map_err(map_execution_limit_trap)
             .context("failed to call function")?;
         func.post_return_async(&mut self.store)
             .await
@@ -750,4 +896,5 @@ impl Instance {
 pub struct InterfaceInstance<T> {
     store: Mutex<wasmtime::Store<Ctx>>,
     bindings: T,
+    limits: ExecutionLimits,
 }
@@ -1,7 +1,8 @@
 mod component;
 
 pub use component::{
-    Component, Instance as ComponentInstance, InterfaceInstance as ComponentInterfaceInstance,
+    Component, ExecutionLimitExceeded, Instance as ComponentInstance,
+    InterfaceInstance as ComponentInterfaceInstance, ResourceLimits,
 };
 
 use core::fmt::Debug;
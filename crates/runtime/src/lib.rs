@@ -16,7 +16,9 @@ pub mod runtime;
 /// wasmCloud I/O functionality
 pub mod io;
 
-pub use actor::{Component, ComponentInstance, Config as ActorConfig};
+pub use actor::{
+    Component, ComponentInstance, Config as ActorConfig, ExecutionLimitExceeded, ResourceLimits,
+};
 pub use runtime::*;
 
 pub use async_trait::async_trait;
@@ -8,6 +8,8 @@ use core::fmt;
 use core::fmt::Debug;
 
 use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 
 use anyhow::Context;
 use wasmtime::{InstanceAllocationStrategy, PoolingAllocationConfig};
@@ -16,12 +18,26 @@ const KB: u64 = 1024;
 const MB: u64 = KB * 1024;
 const GB: u64 = MB * 1024;
 
+/// Interval at which the engine epoch is incremented when execution deadlines are enabled
+const EPOCH_TICK_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Execution limits applied to each component invocation
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct ExecutionLimits {
+    /// Number of epoch ticks an invocation may execute for before being interrupted
+    pub(crate) epoch_deadline: Option<u64>,
+    /// Amount of fuel an invocation may consume before being interrupted
+    pub(crate) fuel: Option<u64>,
+}
+
 /// [`RuntimeBuilder`] used to configure and build a [Runtime]
 #[derive(Clone, Default)]
 pub struct RuntimeBuilder {
     engine_config: wasmtime::Config,
     max_components: u32,
     max_component_size: u64,
+    max_execution_time: Option<Duration>,
+    max_execution_fuel: Option<u64>,
     handler: builtin::HandlerBuilder,
     actor_config: ActorConfig,
 }
@@ -41,6 +57,8 @@ impl RuntimeBuilder {
             // Why so large you ask? Well, python components are chonky, like 35MB for a hello world
             // chonky. So this is pretty big for now.
             max_component_size: 50 * MB,
+            max_execution_time: None,
+            max_execution_fuel: None,
             handler: builtin::HandlerBuilder::default(),
             actor_config: ActorConfig::default(),
         }
@@ -157,6 +175,28 @@ impl RuntimeBuilder {
         }
     }
 
+    /// Sets the maximum wall-clock time a single component invocation may execute for.
+    /// Invocations exceeding it are interrupted with [`ExecutionLimitExceeded::Deadline`](crate::ExecutionLimitExceeded::Deadline).
+    /// Unlimited by default
+    #[must_use]
+    pub fn max_execution_time(self, max_execution_time: Duration) -> Self {
+        Self {
+            max_execution_time: Some(max_execution_time),
+            ..self
+        }
+    }
+
+    /// Sets the amount of fuel a single component invocation may consume.
+    /// Invocations exceeding it are interrupted with [`ExecutionLimitExceeded::Fuel`](crate::ExecutionLimitExceeded::Fuel).
+    /// Unlimited by default
+    #[must_use]
+    pub fn max_execution_fuel(self, max_execution_fuel: u64) -> Self {
+        Self {
+            max_execution_fuel: Some(max_execution_fuel),
+            ..self
+        }
+    }
+
     /// Turns this builder into a [`Runtime`]
     ///
     /// # Errors
@@ -196,16 +236,47 @@ impl RuntimeBuilder {
             .table_keep_resident((10 * MB) as usize);
         self.engine_config
             .allocation_strategy(InstanceAllocationStrategy::Pooling(pooling_config));
+        self.engine_config
+            .epoch_interruption(self.max_execution_time.is_some())
+            .consume_fuel(self.max_execution_fuel.is_some());
         let engine =
             wasmtime::Engine::new(&self.engine_config).context("failed to construct engine")?;
+        let epoch_deadline = self
+            .max_execution_time
+            .map(|max| {
+                spawn_epoch_ticker(&engine)?;
+                let ticks = max.as_nanos().div_ceil(EPOCH_TICK_INTERVAL.as_nanos());
+                anyhow::Ok(u64::try_from(ticks).unwrap_or(u64::MAX).max(1))
+            })
+            .transpose()?;
         Ok(Runtime {
             engine,
             handler: self.handler,
             actor_config: self.actor_config,
+            execution_limits: ExecutionLimits {
+                epoch_deadline,
+                fuel: self.max_execution_fuel,
+            },
         })
     }
 }
 
+/// Spawns a thread incrementing the epoch of `engine` every [`EPOCH_TICK_INTERVAL`] until the engine is dropped
+fn spawn_epoch_ticker(engine: &wasmtime::Engine) -> anyhow::Result<()> {
+    let engine = engine.weak();
+    thread::Builder::new()
+        .name("wasmcloud-runtime-epoch".into())
+        .spawn(move || {
+            while let Some(engine) = engine.upgrade() {
+                engine.increment_epoch();
+                drop(engine);
+                thread::sleep(EPOCH_TICK_INTERVAL);
+            }
+        })
+        .context("failed to spawn epoch ticker thread")?;
+    Ok(())
+}
+
 impl TryFrom<RuntimeBuilder> for Runtime {
     type Error = anyhow::Error;
 
@@ -220,6 +291,7 @@ pub struct Runtime {
     pub(crate) engine: wasmtime::Engine,
     pub(crate) handler: builtin::HandlerBuilder,
     pub(crate) actor_config: ActorConfig,
+    pub(crate) execution_limits: ExecutionLimits,
 }
 
 impl Debug for Runtime {
@@ -227,6 +299,7 @@ impl Debug for Runtime {
         f.debug_struct("Runtime")
             .field("handler", &self.handler)
             .field("actor_config", &self.actor_config)
+            .field("execution_limits", &self.execution_limits)
             .field("runtime", &"wasmtime")
             .finish_non_exhaustive()
     }
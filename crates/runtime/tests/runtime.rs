@@ -23,7 +23,7 @@ use wasmcloud_runtime::capability::provider::{
 use wasmcloud_runtime::capability::{
     self, guest_config, messaging, IncomingHttp, LatticeInterfaceTarget,
 };
-use wasmcloud_runtime::{Component, Runtime};
+use wasmcloud_runtime::{Component, ExecutionLimitExceeded, ResourceLimits, Runtime};
 use wasmtime_wasi_http::body::HyperIncomingBody;
 
 static LOGGER: Lazy<()> = Lazy::new(|| {
@@ -517,3 +517,168 @@ async fn builtins() -> anyhow::Result<()> {
     );
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn fuel_exhausted() -> anyhow::Result<()> {
+    init();
+
+    let wasm = fs::read(test_actors::RUST_BUILTINS_COMPONENT_REACTOR_PREVIEW2_SIGNED)
+        .await
+        .context("failed to read Wasm")?;
+    let rt = Runtime::builder()
+        .max_execution_fuel(1)
+        .build()
+        .context("failed to construct runtime")?;
+    let actor = Component::new(&rt, wasm).context("failed to construct actor")?;
+    let req = http::Request::builder()
+        .method("POST")
+        .uri("/")
+        .body(
+            http_body_util::Empty::new()
+                .map_err(|_| unreachable!())
+                .boxed(),
+        )
+        .expect("failed to construct request");
+    let err = match actor
+        .instantiate()
+        .context("failed to instantiate")?
+        .into_incoming_http()
+        .await
+    {
+        Ok(actor) => actor
+            .handle(req)
+            .await
+            .expect_err("invocation should have exhausted its fuel"),
+        Err(err) => err,
+    };
+    ensure!(
+        err.downcast_ref::<ExecutionLimitExceeded>() == Some(&ExecutionLimitExceeded::Fuel),
+        "invocation should have failed with `ExecutionLimitExceeded::Fuel`, got `{err:?}`"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn deadline_exceeded() -> anyhow::Result<()> {
+    init();
+
+    let mut wasm = wat::parse_str(
+        r#"(module
+            (memory (export "memory") 1)
+            (func (export "spin") (loop $l (br $l)))
+        )"#,
+    )
+    .context("failed to parse WAT")?;
+    let mut resolve = wit_parser::Resolve::default();
+    let pkg = resolve
+        .push(
+            wit_parser::UnresolvedPackage::parse(
+                Path::new("spin.wit"),
+                "package test:spin; world spin { export spin: func(); }",
+            )
+            .context("failed to parse WIT")?,
+        )
+        .context("failed to resolve WIT")?;
+    let world = resolve
+        .select_world(pkg, None)
+        .context("failed to select world")?;
+    wit_component::embed_component_metadata(
+        &mut wasm,
+        &resolve,
+        world,
+        wit_component::StringEncoding::UTF8,
+    )
+    .context("failed to embed component metadata")?;
+
+    let rt = Runtime::builder()
+        .max_execution_time(Duration::from_millis(100))
+        .build()
+        .context("failed to construct runtime")?;
+    let actor = Component::new(&rt, wasm).context("failed to construct actor")?;
+    let err = tokio::time::timeout(
+        Duration::from_secs(10),
+        actor
+            .instantiate()
+            .context("failed to instantiate")?
+            .call("", "spin", vec![]),
+    )
+    .await
+    .context("invocation was not interrupted")?
+    .err()
+    .context("invocation should have exceeded its deadline")?;
+    ensure!(
+        err.downcast_ref::<ExecutionLimitExceeded>() == Some(&ExecutionLimitExceeded::Deadline),
+        "invocation should have failed with `ExecutionLimitExceeded::Deadline`, got `{err:?}`"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn memory_limit_exceeded() -> anyhow::Result<()> {
+    init();
+
+    let wasm = fs::read(test_actors::RUST_BUILTINS_COMPONENT_REACTOR_PREVIEW2_SIGNED)
+        .await
+        .context("failed to read Wasm")?;
+    let rt = Runtime::new().context("failed to construct runtime")?;
+    let mut actor = Component::new(&rt, wasm).context("failed to construct actor")?;
+    actor.set_resource_limits(ResourceLimits {
+        max_memory_size: Some(64 * 1024),
+        ..Default::default()
+    });
+    let req = http::Request::builder()
+        .method("POST")
+        .uri("/")
+        .body(
+            http_body_util::Empty::new()
+                .map_err(|_| unreachable!())
+                .boxed(),
+        )
+        .expect("failed to construct request");
+    let res = match actor
+        .instantiate()
+        .context("failed to instantiate")?
+        .into_incoming_http()
+        .await
+    {
+        Ok(actor) => actor.handle(req).await.map(|_| ()),
+        Err(err) => Err(err),
+    };
+    let err = res.expect_err("instantiation should have exceeded the memory limit");
+    ensure!(
+        format!("{err:#}").contains("exceeds memory limits"),
+        "instantiation should have failed with a memory limit error, got `{err:?}`"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn warm_up() -> anyhow::Result<()> {
+    init();
+
+    let wasm = fs::read(test_actors::RUST_BUILTINS_COMPONENT_REACTOR_PREVIEW2_SIGNED)
+        .await
+        .context("failed to read Wasm")?;
+    let rt = Runtime::new().context("failed to construct runtime")?;
+    let mut actor = Component::new(&rt, wasm).context("failed to construct actor")?;
+    actor
+        .warm_up()
+        .await
+        .context("failed to warm up actor within default limits")?;
+
+    // Creating a store alone succeeds, warming up must actually allocate the memory
+    actor.set_resource_limits(ResourceLimits {
+        max_memory_size: Some(64 * 1024),
+        ..Default::default()
+    });
+    actor.instantiate().context("failed to instantiate")?;
+    let err = actor
+        .warm_up()
+        .await
+        .expect_err("warm up should have exceeded the memory limit");
+    ensure!(
+        format!("{err:#}").contains("exceeds memory limits"),
+        "warm up should have failed with a memory limit error, got `{err:?}`"
+    );
+    Ok(())
+}
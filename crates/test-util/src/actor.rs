@@ -60,6 +60,7 @@ pub async fn assert_start_actor(
             count,
             None,
             config,
+            None,
         )
         .await
         .map_err(|e| anyhow!(e).context("failed to start actor"))?;
@@ -108,6 +109,7 @@ pub async fn assert_scale_actor(
             count,
             annotations,
             config,
+            None,
         )
         .await
         .map_err(|e| anyhow!(e).context("failed to start actor"))?;
@@ -31,6 +31,7 @@ pub async fn assert_advertise_link(
             interfaces,
             source_config,
             target_config,
+            limits: None,
         })
         .await
         .map_err(|e| anyhow!(e).context("failed to advertise link"))?;
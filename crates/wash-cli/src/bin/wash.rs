@@ -6,6 +6,7 @@ use serde_json::json;
 use tracing_subscriber::EnvFilter;
 use wash_cli::app::{self, AppCliCommand};
 use wash_cli::build::{self, BuildCommand};
+use wash_cli::bundle::{self, BundleCliCommand};
 use wash_cli::call::{self, CallCli};
 use wash_cli::common;
 use wash_cli::completions::{self, CompletionOpts};
@@ -26,11 +27,13 @@ use wash_lib::cli::get::GetCommand;
 use wash_lib::cli::inspect::InspectCliCommand;
 use wash_lib::cli::label::LabelHostCommand;
 use wash_lib::cli::link::LinkCommand;
+use wash_lib::cli::logs::LogsCommand;
 use wash_lib::cli::registry::{RegistryCommand, RegistryPullCommand, RegistryPushCommand};
 use wash_lib::cli::scale::ScaleCommand;
 use wash_lib::cli::spy::SpyCommand;
 use wash_lib::cli::start::StartCommand;
 use wash_lib::cli::stop::StopCommand;
+use wash_lib::cli::testing::TestCommand;
 use wash_lib::cli::update::UpdateCommand;
 use wash_lib::cli::{CommandOutput, OutputKind};
 use wash_lib::drain::Drain as DrainSelection;
@@ -53,6 +56,7 @@ Build:
   new          Create a new project from template
   build        Build (and sign) a wasmCloud actor, capability provider, or interface
   dev          Run a actor development loop (experimental)
+  test         Run the tests of a component against mocked capabilities
   inspect      Inspect capability provider or actor module
   par          Create, inspect, and modify capability provider archive files
 
@@ -73,11 +77,13 @@ Iterate:
   call         Invoke a simple function on a component running in a wasmCloud host
   ctl          Interact with a wasmCloud control interface (deprecated, use above commands)
   label        Label (or un-label) a host with a key=value label pair
+  logs         Show the output of a capability provider running in a host
 
 Publish:
   pull         Pull an artifact from an OCI compliant registry
   push         Push an artifact to an OCI compliant registry
   reg          Perform operations on an OCI registry
+  bundle       Create and push signed offline bundles of artifacts
 
 Configure:
   completions  Generate shell completions for wash
@@ -134,6 +140,9 @@ enum CliCommand {
     /// Build (and sign) a wasmCloud actor, provider, or interface
     #[clap(name = "build")]
     Build(BuildCommand),
+    /// Create and push signed offline bundles of artifacts
+    #[clap(name = "bundle", subcommand)]
+    Bundle(BundleCliCommand),
     /// Invoke a simple function on a component running in a wasmCloud host
     #[clap(name = "call")]
     Call(CallCli),
@@ -179,6 +188,9 @@ enum CliCommand {
     /// Link an actor and a provider
     #[clap(name = "link", alias = "links", subcommand)]
     Link(LinkCommand),
+    /// Show the output of a capability provider running in a host
+    #[clap(name = "logs", subcommand)]
+    Logs(LogsCommand),
     /// Create a new project from template
     #[clap(name = "new", subcommand)]
     New(NewCliCommand),
@@ -209,6 +221,9 @@ enum CliCommand {
     /// Label (or un-label) a host
     #[clap(name = "label", alias = "tag")]
     Label(LabelHostCommand),
+    /// Run the tests of a component against mocked capabilities
+    #[clap(name = "test")]
+    Test(TestCommand),
     /// Update an actor running in a host to a newer version
     #[clap(name = "update", subcommand)]
     Update(UpdateCommand),
@@ -246,6 +261,7 @@ async fn main() {
     let res: anyhow::Result<CommandOutput> = match cli.command {
         CliCommand::App(app_cli) => app::handle_command(app_cli, output_kind).await,
         CliCommand::Build(build_cli) => build::handle_command(build_cli).await,
+        CliCommand::Bundle(bundle_cli) => bundle::handle_command(bundle_cli, output_kind).await,
         CliCommand::Call(call_cli) => call::handle_command(call_cli.command()).await,
         CliCommand::Capture(capture_cli) => {
             if !cli.experimental {
@@ -275,6 +291,7 @@ async fn main() {
         CliCommand::Keys(keys_cli) => keys::handle_command(keys_cli),
         CliCommand::Lint(lint_cli) => smithy::handle_lint_command(lint_cli).await,
         CliCommand::Link(link_cli) => common::link_cmd::handle_command(link_cli, output_kind).await,
+        CliCommand::Logs(logs_cli) => wash_lib::cli::logs::handle_command(logs_cli).await,
         CliCommand::New(new_cli) => generate::handle_command(new_cli).await,
         CliCommand::Par(par_cli) => par::handle_command(par_cli, output_kind).await,
         CliCommand::Reg(reg_cli) => {
@@ -303,6 +320,7 @@ async fn main() {
         CliCommand::Label(label_cli) => {
             common::label_cmd::handle_command(label_cli, output_kind).await
         }
+        CliCommand::Test(test_cli) => wash_lib::cli::testing::handle_command(test_cli).await,
         CliCommand::Update(update_cli) => {
             common::update_cmd::handle_command(update_cli, output_kind).await
         }
@@ -1,15 +1,17 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{collections::HashMap, ffi::OsString, path::PathBuf};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use clap::Parser;
 use serde_json::json;
 
 use wash_lib::{
     build::{build_project, sign_actor_wasm, SignConfig},
     cli::CommandOutput,
-    parser::{get_config, TypeConfig},
+    parser::{get_config, get_workspace_config, TypeConfig, WorkspaceConfig},
 };
 
+use crate::workspace::{build_member, run_workspace, workspace_output};
+
 /// Build (and sign) a wasmCloud actor, provider, or interface
 #[derive(Debug, Parser, Clone)]
 #[clap(name = "build")]
@@ -51,9 +53,24 @@ pub struct BuildCommand {
     /// Skip building the artifact and only use configuration to sign
     #[clap(long = "sign-only", conflicts_with = "build_only")]
     pub sign_only: bool,
+
+    /// Build only the workspace members with these names, if the configuration is a workspace
+    #[clap(long = "member", name = "member")]
+    pub members: Vec<String>,
+
+    /// Number of workspace members to build at once. Defaults to the number of CPUs
+    #[clap(short = 'j', long = "jobs")]
+    pub jobs: Option<usize>,
 }
 
 pub async fn handle_command(command: BuildCommand) -> Result<CommandOutput> {
+    if let Some(workspace) = get_workspace_config(command.config_path.clone(), Some(true))? {
+        return build_workspace(&workspace, &command).await;
+    }
+    if !command.members.is_empty() {
+        bail!("--member can only be used to build a workspace");
+    }
+
     let config = get_config(command.config_path, Some(true))?;
 
     match config.project_type {
@@ -129,6 +146,38 @@ pub async fn handle_command(command: BuildCommand) -> Result<CommandOutput> {
     }
 }
 
+/// Build the workspace members in dependency order, passing the signing options to each build
+async fn build_workspace(
+    workspace: &WorkspaceConfig,
+    command: &BuildCommand,
+) -> Result<CommandOutput> {
+    let mut args: Vec<OsString> = Vec::new();
+    if let Some(keys_directory) = &command.keys_directory {
+        args.extend(["--keys-directory".into(), keys_directory.into()]);
+    }
+    if let Some(issuer) = &command.issuer {
+        args.extend(["--issuer".into(), issuer.into()]);
+    }
+    if let Some(subject) = &command.subject {
+        args.extend(["--subject".into(), subject.into()]);
+    }
+    if command.disable_keygen {
+        args.push("--disable-keygen".into());
+    }
+    if command.build_only {
+        args.push("--build-only".into());
+    }
+    if command.sign_only {
+        args.push("--sign-only".into());
+    }
+
+    let results = run_workspace(workspace, &command.members, command.jobs, |member| {
+        build_member(member, &args)
+    })
+    .await?;
+    workspace_output("build", results)
+}
+
 #[cfg(test)]
 mod test {
 
@@ -0,0 +1,290 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use anyhow::{bail, ensure, Context, Result};
+use clap::{Parser, Subcommand};
+use nkeys::KeyPairType;
+use oci_distribution::Reference;
+use serde_json::json;
+use tokio::fs;
+use wash_lib::cli::registry::AuthOpts;
+use wash_lib::cli::{extract_keypair, CommandOutput, OutputKind};
+use wash_lib::registry::{
+    pull_oci_artifact, push_oci_artifact_bytes, OciPullOptions, OciPushOptions,
+};
+use wasmcloud_bundle::{normalize_reference, Bundle};
+use wasmcloud_control_interface::RegistryCredential;
+
+use crate::appearance::spinner::Spinner;
+use crate::common::registry_cmd::{resolve_registry_credentials, SHOWER_EMOJI};
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum BundleCliCommand {
+    /// Create a signed offline bundle of components and capability providers
+    #[clap(name = "create")]
+    Create(CreateCommand),
+    /// Push the artifacts in an offline bundle to an OCI compliant registry
+    #[clap(name = "push")]
+    Push(PushCommand),
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct CreateCommand {
+    /// Artifacts to bundle, either OCI references to pull (e.g. ghcr.io/wasmcloud/echo:0.1.0) or
+    /// `<reference>=<path>` to bundle a local file under the given OCI reference
+    #[clap(name = "artifacts", required = true)]
+    artifacts: Vec<String>,
+
+    /// File output destination path
+    #[clap(long = "destination", default_value = "bundle.tar")]
+    destination: PathBuf,
+
+    /// Location of key files for signing. Defaults to $WASH_KEYS ($HOME/.wash/keys)
+    #[clap(
+        short = 'd',
+        long = "directory",
+        env = "WASH_KEYS",
+        hide_env_values = true
+    )]
+    directory: Option<PathBuf>,
+
+    /// Path to issuer seed key (account). If this flag is not provided, the will be sourced from $WASH_KEYS ($HOME/.wash/keys) or generated for you if it cannot be found.
+    #[clap(
+        short = 'i',
+        long = "issuer",
+        env = "WASH_ISSUER_KEY",
+        hide_env_values = true
+    )]
+    issuer: Option<String>,
+
+    /// Disables autogeneration of signing keys
+    #[clap(long = "disable-keygen")]
+    disable_keygen: bool,
+
+    /// Allow latest artifact tags
+    #[clap(long = "allow-latest")]
+    allow_latest: bool,
+
+    #[clap(flatten)]
+    opts: AuthOpts,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct PushCommand {
+    /// Path to the bundle
+    #[clap(name = "bundle")]
+    bundle: PathBuf,
+
+    /// Registry to push artifacts to (e.g. registry.internal:5000), replacing the registry of their
+    /// references. If omitted, artifacts are pushed to the references they are bundled under
+    #[clap(short = 'r', long = "registry", env = "WASH_REG_URL")]
+    registry: Option<String>,
+
+    /// Account public keys the bundle must be signed by. Required unless `--allow-any-issuer` is set
+    #[clap(
+        long = "trusted-issuer",
+        name = "trusted_issuers",
+        required_unless_present = "allow_any_issuer"
+    )]
+    trusted_issuers: Vec<String>,
+
+    /// Accept bundles signed by any key if no trusted issuers are set
+    #[clap(long = "allow-any-issuer", name = "allow_any_issuer")]
+    allow_any_issuer: bool,
+
+    /// Allow latest artifact tags
+    #[clap(long = "allow-latest")]
+    allow_latest: bool,
+
+    #[clap(flatten)]
+    opts: AuthOpts,
+}
+
+pub async fn handle_command(
+    command: BundleCliCommand,
+    output_kind: OutputKind,
+) -> Result<CommandOutput> {
+    match command {
+        BundleCliCommand::Create(cmd) => handle_create(cmd, output_kind).await,
+        BundleCliCommand::Push(cmd) => handle_push(cmd, output_kind).await,
+    }
+}
+
+/// Returns the registry credentials to use for `registry`, preferring the ones given on the CLI
+async fn credentials(opts: &AuthOpts, registry: &str) -> Result<RegistryCredential> {
+    match (&opts.user, &opts.password) {
+        (Some(user), Some(password)) => Ok(RegistryCredential {
+            username: Some(user.clone()),
+            password: Some(password.clone()),
+            ..Default::default()
+        }),
+        _ => resolve_registry_credentials(registry).await,
+    }
+}
+
+async fn handle_create(cmd: CreateCommand, output_kind: OutputKind) -> Result<CommandOutput> {
+    let issuer = extract_keypair(
+        cmd.issuer,
+        Some(cmd.destination.display().to_string()),
+        cmd.directory,
+        KeyPairType::Account,
+        cmd.disable_keygen,
+        output_kind,
+    )?;
+
+    let spinner = Spinner::new(&output_kind)?;
+    let mut builder = Bundle::builder();
+    let mut references = Vec::with_capacity(cmd.artifacts.len());
+    for artifact in cmd.artifacts {
+        let (reference, data) = if let Some((reference, path)) = artifact.split_once('=') {
+            let data = fs::read(path)
+                .await
+                .with_context(|| format!("failed to read `{path}`"))?;
+            (reference.to_string(), data)
+        } else {
+            spinner.update_spinner_message(format!(" Downloading {artifact} ..."));
+            let image: Reference = normalize_reference(&artifact)?
+                .parse()
+                .with_context(|| format!("invalid OCI reference `{artifact}`"))?;
+            let credentials = credentials(&cmd.opts, image.registry()).await?;
+            let data = pull_oci_artifact(
+                image.whole(),
+                OciPullOptions {
+                    digest: None,
+                    allow_latest: cmd.allow_latest,
+                    user: credentials.username,
+                    password: credentials.password,
+                    insecure: cmd.opts.insecure,
+                },
+            )
+            .await
+            .with_context(|| format!("failed to pull `{artifact}`"))?;
+            (artifact, data)
+        };
+        if data.starts_with(b"\0asm") {
+            builder.component(&reference, data)?;
+        } else {
+            builder.provider(&reference, data).await?;
+        }
+        references.push(reference);
+    }
+
+    spinner.update_spinner_message(format!(
+        " Writing bundle to {} ...",
+        cmd.destination.display()
+    ));
+    let file = fs::File::create(&cmd.destination)
+        .await
+        .with_context(|| format!("failed to create `{}`", cmd.destination.display()))?;
+    builder.write(file, &issuer).await?;
+    spinner.finish_and_clear();
+
+    let mut map = HashMap::new();
+    map.insert("destination".to_string(), json!(cmd.destination));
+    map.insert("issuer".to_string(), json!(issuer.public_key()));
+    map.insert("artifacts".to_string(), json!(references));
+    Ok(CommandOutput::new(
+        format!(
+            "{SHOWER_EMOJI} Successfully created bundle {} with {} artifact(s), signed by {}",
+            cmd.destination.display(),
+            references.len(),
+            issuer.public_key()
+        ),
+        map,
+    ))
+}
+
+/// Replaces the registry of the OCI `reference` with `registry`
+fn retarget(reference: &str, registry: &str) -> Result<String> {
+    let reference: Reference = reference
+        .parse()
+        .with_context(|| format!("invalid OCI reference `{reference}`"))?;
+    let registry = registry.trim_end_matches('/').to_string();
+    let repository = reference.repository().to_string();
+    let reference = match (reference.digest(), reference.tag()) {
+        (Some(digest), _) => Reference::with_digest(registry, repository, digest.to_string()),
+        (None, Some(tag)) => Reference::with_tag(registry, repository, tag.to_string()),
+        (None, None) => bail!("OCI reference `{reference}` has neither a tag nor a digest"),
+    };
+    Ok(reference.whole())
+}
+
+async fn handle_push(cmd: PushCommand, output_kind: OutputKind) -> Result<CommandOutput> {
+    let bundle = Bundle::load(&cmd.bundle).await?;
+    ensure!(
+        !cmd.trusted_issuers.is_empty() || cmd.allow_any_issuer,
+        "no trusted issuers set, pass `--trusted-issuer` or `--allow-any-issuer`"
+    );
+    ensure!(
+        cmd.trusted_issuers.is_empty()
+            || cmd
+                .trusted_issuers
+                .iter()
+                .any(|issuer| issuer == bundle.issuer()),
+        "bundle is signed by untrusted issuer `{}`",
+        bundle.issuer()
+    );
+
+    let spinner = Spinner::new(&output_kind)?;
+    let mut pushed = Vec::with_capacity(bundle.manifest().artifacts.len());
+    for (artifact, data) in bundle.artifacts() {
+        let url = match &cmd.registry {
+            Some(registry) => retarget(&artifact.reference, registry)?,
+            None => artifact.reference.clone(),
+        };
+        spinner.update_spinner_message(format!(" Pushing {url} ..."));
+        let image: Reference = url.parse()?;
+        let credentials = credentials(&cmd.opts, image.registry()).await?;
+        push_oci_artifact_bytes(
+            url.clone(),
+            data.to_vec(),
+            OciPushOptions {
+                config: None,
+                allow_latest: cmd.allow_latest,
+                user: credentials.username,
+                password: credentials.password,
+                insecure: cmd.opts.insecure,
+                annotations: None,
+            },
+        )
+        .await
+        .with_context(|| format!("failed to push `{url}`"))?;
+        pushed.push(url);
+    }
+    spinner.finish_and_clear();
+
+    let mut map = HashMap::new();
+    map.insert("issuer".to_string(), json!(bundle.issuer()));
+    map.insert("pushed".to_string(), json!(pushed));
+    Ok(CommandOutput::new(
+        format!(
+            "{SHOWER_EMOJI} Successfully pushed {} artifact(s) from bundle signed by {}:\n{}",
+            pushed.len(),
+            bundle.issuer(),
+            pushed.join("\n")
+        ),
+        map,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::retarget;
+
+    #[test]
+    fn test_retarget() {
+        assert_eq!(
+            retarget("ghcr.io/wasmcloud/echo:0.1.0", "registry.internal:5000/")
+                .expect("failed to retarget"),
+            "registry.internal:5000/wasmcloud/echo:0.1.0"
+        );
+        assert_eq!(
+            retarget(
+                "ghcr.io/wasmcloud/echo@sha256:a17a163afa8447622055deb049587641a9e23243a6cc4411eb33bd4267214cf3",
+                "localhost:5000"
+            )
+            .expect("failed to retarget"),
+            "localhost:5000/wasmcloud/echo@sha256:a17a163afa8447622055deb049587641a9e23243a6cc4411eb33bd4267214cf3"
+        );
+    }
+}
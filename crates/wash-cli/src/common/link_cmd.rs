@@ -3,31 +3,30 @@ use std::collections::HashMap;
 use anyhow::{bail, Result};
 use serde_json::json;
 use wash_lib::cli::link::{
-    create_link, delete_link, query_links, LinkCommand, LinkDelCommand, LinkPutCommand,
-    LinkQueryCommand,
+    create_link, delete_link, query_links, validate_link, LinkCommand, LinkDelCommand,
+    LinkQueryCommand, LinkValidation,
 };
 use wash_lib::cli::{CommandOutput, OutputKind};
-use wash_lib::id::validate_contract_id;
-use wasmcloud_control_interface::LinkDefinition;
+use wasmcloud_control_interface::InterfaceLinkDefinition;
 
 use crate::appearance::spinner::Spinner;
 use crate::ctl::{link_del_output, links_table};
 
 /// Generate output for link put command
 pub fn link_put_output(
-    actor_id: impl AsRef<str>,
-    provider_id: impl AsRef<str>,
+    source_id: impl AsRef<str>,
+    target: impl AsRef<str>,
     failure: Option<String>,
 ) -> Result<CommandOutput> {
-    let actor_id = actor_id.as_ref();
-    let provider_id = provider_id.as_ref();
+    let source_id = source_id.as_ref();
+    let target = target.as_ref();
     match failure {
         None => {
             let mut map = HashMap::new();
-            map.insert("actor_id".to_string(), json!(actor_id));
-            map.insert("provider_id".to_string(), json!(provider_id));
+            map.insert("source_id".to_string(), json!(source_id));
+            map.insert("target".to_string(), json!(target));
             Ok(CommandOutput::new(
-                format!("Published link ({actor_id}) <-> ({provider_id}) successfully"),
+                format!("Published link ({source_id}) <-> ({target}) successfully"),
                 map,
             ))
         }
@@ -35,8 +34,40 @@ pub fn link_put_output(
     }
 }
 
+/// Generate output for link put --dry-run command
+pub fn link_validate_output(
+    source_id: impl AsRef<str>,
+    target: impl AsRef<str>,
+    validation: LinkValidation,
+) -> Result<CommandOutput> {
+    let source_id = source_id.as_ref();
+    let target = target.as_ref();
+    match validation {
+        LinkValidation {
+            violations: None,
+            invalid_schemas,
+        } => {
+            let mut map = HashMap::new();
+            map.insert("source_id".to_string(), json!(source_id));
+            map.insert("target".to_string(), json!(target));
+            map.insert("valid".to_string(), json!(true));
+            let mut text =
+                format!("Link ({source_id}) <-> ({target}) is valid and was not published");
+            if let Some(invalid_schemas) = invalid_schemas {
+                text.push_str(&format!("\nWarning: {invalid_schemas}"));
+                map.insert("invalid_schemas".to_string(), json!(invalid_schemas));
+            }
+            Ok(CommandOutput::new(text, map))
+        }
+        LinkValidation {
+            violations: Some(v),
+            ..
+        } => bail!("Link is not valid: {}", v),
+    }
+}
+
 /// Generate output for the link query command
-pub fn link_query_output(list: Vec<LinkDefinition>) -> CommandOutput {
+pub fn link_query_output(list: Vec<InterfaceLinkDefinition>) -> CommandOutput {
     let mut map = HashMap::new();
     map.insert("links".to_string(), json!(list));
     CommandOutput::new(links_table(list), map)
@@ -49,54 +80,48 @@ pub async fn handle_command(
     let sp: Spinner = Spinner::new(&output_kind)?;
     let out: CommandOutput = match command {
         LinkCommand::Del(LinkDelCommand {
-            actor_id,
+            source_id,
             contract_id,
             link_name,
             opts,
         }) => {
-            let link_name = link_name.clone().unwrap_or_else(|| "default".to_string());
-
-            validate_contract_id(&contract_id)?;
+            let link_name = link_name.unwrap_or_else(|| "default".to_string());
 
             sp.update_spinner_message(format!(
-                "Deleting link for {} on {} ({}) ... ",
-                actor_id, contract_id, link_name,
+                "Deleting link for {source_id} on {contract_id} ({link_name}) ... ",
             ));
 
-            let failure = delete_link(opts.try_into()?, &contract_id, &actor_id, &link_name)
+            let failure = delete_link(opts.try_into()?, &source_id, &contract_id, &link_name)
                 .await
                 .map_or_else(|e| Some(format!("{e}")), |_| None);
 
-            link_del_output(&actor_id, &contract_id, &link_name, failure)?
+            link_del_output(&source_id, &contract_id, &link_name, failure)?
         }
-        LinkCommand::Put(LinkPutCommand {
-            opts,
-            contract_id,
-            actor_id,
-            provider_id,
-            link_name,
-            values,
-        }) => {
-            validate_contract_id(&contract_id)?;
+        LinkCommand::Put(cmd) => {
+            let wco = cmd.opts.clone().try_into()?;
+            let dry_run = cmd.dry_run;
+            let link = InterfaceLinkDefinition::try_from(cmd)?;
+            let (source_id, target) = (link.source_id.clone(), link.target.clone());
 
-            sp.update_spinner_message(format!(
-                "Defining link between {actor_id} and {provider_id} ... ",
-            ));
+            if dry_run {
+                sp.update_spinner_message(format!(
+                    "Validating link between {source_id} and {target} ... ",
+                ));
 
-            let link_name = link_name.unwrap_or_else(|| "default".to_string());
+                let validation = validate_link(wco, link).await?;
+
+                link_validate_output(&source_id, &target, validation)?
+            } else {
+                sp.update_spinner_message(format!(
+                    "Defining link between {source_id} and {target} ... ",
+                ));
 
-            let failure = create_link(
-                opts.try_into()?,
-                &contract_id,
-                &actor_id,
-                &provider_id,
-                &link_name,
-                &values,
-            )
-            .await
-            .map_or_else(|e| Some(format!("{e}")), |_| None);
+                let failure = create_link(wco, link)
+                    .await
+                    .map_or_else(|e| Some(format!("{e}")), |_| None);
 
-            link_put_output(&actor_id, &provider_id, failure)?
+                link_put_output(&source_id, &target, failure)?
+            }
         }
         LinkCommand::Query(LinkQueryCommand { opts }) => {
             sp.update_spinner_message("Querying Links ... ".to_string());
@@ -27,6 +27,7 @@ use wash_lib::{
 use wasmcloud_control_interface::RegistryCredential;
 
 use crate::appearance::spinner::Spinner;
+use crate::workspace::push_workspace;
 
 pub const SHOWER_EMOJI: &str = "\u{1F6BF}";
 pub const PROVIDER_ARCHIVE_FILE_EXTENSION: &str = ".par.gz";
@@ -134,14 +135,21 @@ pub async fn registry_push(
     cmd: RegistryPushCommand,
     output_kind: OutputKind,
 ) -> Result<CommandOutput> {
-    let image: Reference = resolve_artifact_ref(&cmd.url, &cmd.registry.unwrap_or_default())?;
+    if cmd.workspace.is_some() {
+        return push_workspace(cmd).await;
+    }
+    // Without --workspace, both are required by the CLI parser, so these are just safety fallbacks
+    let url = cmd.url.context("missing artifact url")?;
+    let artifact = cmd.artifact.context("missing artifact path")?;
+
+    let image: Reference = resolve_artifact_ref(&url, &cmd.registry.unwrap_or_default())?;
     let artifact_url = image.whole();
     if artifact_url.starts_with("localhost:") && !cmd.opts.insecure {
         warn!(" Unless an SSL certificate has been installed, pushing to localhost without the --insecure option will fail")
     }
 
     let spinner = Spinner::new(&output_kind)?;
-    spinner.update_spinner_message(format!(" Pushing {} to {} ...", cmd.artifact, artifact_url));
+    spinner.update_spinner_message(format!(" Pushing {artifact} to {artifact_url} ..."));
 
     let credentials = match (cmd.opts.user, cmd.opts.password) {
         (Some(user), Some(password)) => Ok(RegistryCredential {
@@ -156,7 +164,7 @@ pub async fn registry_push(
 
     push_oci_artifact(
         artifact_url.clone(),
-        cmd.artifact,
+        artifact,
         OciPushOptions {
             config: cmd.config.map(PathBuf::from),
             allow_latest: cmd.allow_latest,
@@ -239,7 +247,7 @@ fn resolve_artifact_ref(url: &str, registry: &str) -> Result<Reference> {
     bail!("Unable to resolve artifact url from specified registry and repository")
 }
 
-async fn resolve_registry_credentials(registry: &str) -> Result<RegistryCredential> {
+pub(crate) async fn resolve_registry_credentials(registry: &str) -> Result<RegistryCredential> {
     let Ok(project_config) = get_config(None, Some(true)) else {
         return Ok(RegistryCredential::default());
     };
@@ -249,6 +257,8 @@ async fn resolve_registry_credentials(registry: &str) -> Result<RegistryCredenti
 
 #[cfg(test)]
 mod tests {
+    use std::path::PathBuf;
+
     use crate::common::registry_cmd::{RegistryCommand, RegistryPullCommand, RegistryPushCommand};
     use clap::Parser;
 
@@ -354,8 +364,8 @@ mod tests {
                 opts,
                 ..
             }) => {
-                assert_eq!(&url, echo_push_basic);
-                assert_eq!(artifact, format!("{TESTDIR}/echopush.wasm"));
+                assert_eq!(url.as_deref(), Some(echo_push_basic.as_str()));
+                assert_eq!(artifact, Some(format!("{TESTDIR}/echopush.wasm")));
                 assert!(opts.insecure);
             }
             _ => panic!("`reg push` constructed incorrect command"),
@@ -380,8 +390,8 @@ mod tests {
                 allow_latest,
                 ..
             }) => {
-                assert_eq!(&url, logging_push_all_flags);
-                assert_eq!(artifact, format!("{TESTDIR}/logging.par.gz"));
+                assert_eq!(url.as_deref(), Some(logging_push_all_flags.as_str()));
+                assert_eq!(artifact, Some(format!("{TESTDIR}/logging.par.gz")));
                 assert!(opts.insecure);
                 assert!(allow_latest);
             }
@@ -414,8 +424,8 @@ mod tests {
                 config,
                 ..
             }) => {
-                assert_eq!(&url, logging_push_all_options);
-                assert_eq!(artifact, format!("{TESTDIR}/logging.par.gz"));
+                assert_eq!(url.as_deref(), Some(logging_push_all_options.as_str()));
+                assert_eq!(artifact, Some(format!("{TESTDIR}/logging.par.gz")));
                 assert!(opts.insecure);
                 assert!(allow_latest);
                 assert_eq!(config.unwrap(), format!("{TESTDIR}/config.json"));
@@ -424,5 +434,45 @@ mod tests {
             }
             _ => panic!("`reg push` constructed incorrect command"),
         };
+
+        // Push the members of a workspace instead of a single artifact
+        let push_workspace: Cmd = Parser::try_parse_from([
+            "reg",
+            "push",
+            "--workspace",
+            "--member",
+            "echo",
+            "--member",
+            "logging",
+            "-j",
+            "2",
+        ])
+        .unwrap();
+        match push_workspace.reg {
+            RegistryCommand::Push(RegistryPushCommand {
+                url,
+                artifact,
+                workspace,
+                members,
+                jobs,
+                ..
+            }) => {
+                assert!(url.is_none());
+                assert!(artifact.is_none());
+                assert_eq!(workspace, Some(PathBuf::from(".")));
+                assert_eq!(members, vec!["echo".to_string(), "logging".to_string()]);
+                assert_eq!(jobs, Some(2));
+            }
+            _ => panic!("`reg push` constructed incorrect command"),
+        };
+        assert!(Parser::try_parse_from([
+            "reg",
+            "push",
+            "localhost:5000/echo:0.1.0",
+            "echo.wasm",
+            "--workspace",
+        ])
+        .map(|_: Cmd| ())
+        .is_err());
     }
 }
@@ -112,8 +112,10 @@ mod test {
             "arch=x86_64",
             "--host-id",
             HOST_ID,
-            "--link-name",
-            "default",
+            "--provider-id",
+            "provider",
+            "--config",
+            "provider-config",
             "--skip-wait",
             "wasmcloud.azurecr.io/provider:v1",
         ])?;
@@ -122,7 +124,8 @@ mod test {
                 opts,
                 host_id,
                 provider_ref,
-                link_name,
+                provider_id,
+                config,
                 constraints,
                 auction_timeout_ms,
                 config_json,
@@ -134,7 +137,8 @@ mod test {
                 assert_eq!(opts.timeout_ms, 2001);
                 assert_eq!(config_json, None);
                 assert_eq!(auction_timeout_ms, 2002);
-                assert_eq!(link_name, "default".to_string());
+                assert_eq!(provider_id.unwrap(), "provider".to_string());
+                assert_eq!(config, vec!["provider-config".to_string()]);
                 assert_eq!(constraints.unwrap(), vec!["arch=x86_64".to_string()]);
                 assert_eq!(host_id.unwrap(), HOST_ID.to_string());
                 assert_eq!(provider_ref, "wasmcloud.azurecr.io/provider:v1".to_string());
@@ -352,33 +352,72 @@ mod test {
             "2001",
             "--link-name",
             "default",
+            "--interface",
+            "readwrite",
+            "--source-config",
+            "source",
+            "--target-config",
+            "target",
+            "--rate-per-second",
+            "10",
+            "--burst",
+            "20",
+            "--max-in-flight",
+            "5",
             ACTOR_ID,
             PROVIDER_ID,
-            "wasmcloud:provider",
-            "THING=foo",
+            "wasi:keyvalue",
+            "--dry-run",
         ])?;
         use wash_lib::cli::link::LinkPutCommand;
         match link_all.command {
             CtlCliCommand::Link(LinkCommand::Put(LinkPutCommand {
                 opts,
-                actor_id,
-                provider_id,
+                source_id,
+                target,
                 contract_id,
+                interfaces,
+                source_config,
+                target_config,
                 link_name,
-                values,
+                rate_per_second,
+                burst,
+                max_in_flight,
+                dry_run,
             })) => {
                 assert_eq!(&opts.ctl_host.unwrap(), CTL_HOST);
                 assert_eq!(&opts.ctl_port.unwrap(), CTL_PORT);
                 assert_eq!(&opts.lattice.unwrap(), DEFAULT_LATTICE);
                 assert_eq!(opts.timeout_ms, 2001);
-                assert_eq!(actor_id, ACTOR_ID);
-                assert_eq!(provider_id, PROVIDER_ID);
-                assert_eq!(contract_id, "wasmcloud:provider".to_string());
+                assert_eq!(source_id, ACTOR_ID);
+                assert_eq!(target, PROVIDER_ID);
+                assert_eq!(contract_id, "wasi:keyvalue".to_string());
+                assert_eq!(interfaces, vec!["readwrite".to_string()]);
+                assert_eq!(source_config, vec!["source".to_string()]);
+                assert_eq!(target_config, vec!["target".to_string()]);
                 assert_eq!(link_name.unwrap(), "default".to_string());
-                assert_eq!(values, vec!["THING=foo".to_string()]);
+                assert_eq!(rate_per_second, Some(10));
+                assert_eq!(burst, Some(20));
+                assert_eq!(max_in_flight, Some(5));
+                assert!(dry_run);
             }
             cmd => panic!("ctl link put constructed incorrect command {cmd:?}"),
         }
+        for limit in ["--rate-per-second", "--burst", "--max-in-flight"] {
+            let res: Result<Cmd, _> = Parser::try_parse_from([
+                "ctl",
+                "link",
+                "put",
+                "--interface",
+                "readwrite",
+                limit,
+                "0",
+                ACTOR_ID,
+                PROVIDER_ID,
+                "wasi:keyvalue",
+            ]);
+            assert!(res.is_err(), "`{limit} 0` should be rejected");
+        }
         let update_all: Cmd = Parser::try_parse_from([
             "ctl",
             "update",
@@ -432,6 +471,12 @@ mod test {
             "1",
             "--annotations",
             "foo=bar",
+            "--actor-id",
+            "actor",
+            "--max-memory-bytes",
+            "1048576",
+            "--max-table-elements",
+            "100",
         ])?;
 
         match scale_actor_all.command {
@@ -439,8 +484,11 @@ mod test {
                 opts,
                 host_id,
                 actor_ref,
+                actor_id,
                 max_instances,
                 annotations,
+                max_memory_bytes,
+                max_table_elements,
             })) => {
                 assert_eq!(&opts.ctl_host.unwrap(), CTL_HOST);
                 assert_eq!(&opts.ctl_port.unwrap(), CTL_PORT);
@@ -450,6 +498,9 @@ mod test {
                 assert_eq!(actor_ref, "wasmcloud.azurecr.io/actor:v2".to_string());
                 assert_eq!(max_instances, 1);
                 assert_eq!(annotations, vec!["foo=bar".to_string()]);
+                assert_eq!(actor_id.unwrap(), "actor".to_string());
+                assert_eq!(max_memory_bytes, Some(1048576));
+                assert_eq!(max_table_elements, Some(100));
             }
             cmd => panic!("ctl scale actor constructed incorrect command {cmd:?}"),
         }
@@ -13,11 +13,12 @@ use wash_lib::generate::emoji;
 use wash_lib::{
     actor::{scale_actor, start_actor, StartActorArgs},
     build::{build_project, SignConfig},
-    cli::dev::run_dev_loop,
-    cli::CommandOutput,
+    cli::dev::{restart_dev_provider, run_dev_loop, start_dev_provider, DevArtifact, DevProvider},
+    cli::{start::read_config_json, CommandOutput},
+    common::component_id_from_ref,
     config::downloads_dir,
-    id::{ModuleId, ServerId},
-    parser::get_config,
+    id::ServerId,
+    parser::{get_config, TypeConfig},
 };
 use wasmcloud_control_interface::Host;
 
@@ -65,6 +66,11 @@ pub struct DevCommand {
         help = "Run the wasmCloud host in a subprocess (rather than detached mode)"
     )]
     pub use_host_subprocess: bool,
+
+    /// Path to a configuration JSON file a provider is started with, ignored for actors. When
+    /// attaching to a running provider without it, the configuration it was started with is reused
+    #[clap(long = "config-json", env = "WASH_DEV_CONFIG_JSON")]
+    pub config_json: Option<PathBuf>,
 }
 
 /// Utility struct for holding a wasmCloud host subprocess.
@@ -185,7 +191,11 @@ pub async fn handle_command(
             tokio::spawn(async move {
                 loop {
                     match wait_ctl_client.get_hosts().await {
-                        Ok(hs) => match &hs[..] {
+                        Ok(hs) => match &hs
+                            .into_iter()
+                            .filter_map(|h| h.response)
+                            .collect::<Vec<_>>()[..]
+                        {
                             [] => {}
                             [h] => {
                                 eprintln!(
@@ -220,7 +230,10 @@ pub async fn handle_command(
     let hosts = ctl_client
         .get_hosts()
         .await
-        .or_else(|e| bail!("failed to retrieve hosts from lattice: {e}"))?;
+        .or_else(|e| bail!("failed to retrieve hosts from lattice: {e}"))?
+        .into_iter()
+        .filter_map(|h| h.response)
+        .collect::<Vec<_>>();
     let host: Host = match &hosts[..] {
         [] => bail!("0 hosts detected, is wasmCloud running?"),
         [h] => h.clone(),
@@ -268,38 +281,122 @@ pub async fn handle_command(
         artifact_path.display()
     );
 
-    // Since we're using the actor from file on disk, the ref should be the file path (canonicalized) on disk as URI
-    let actor_ref = format!("file://{}", artifact_path.display());
-    let actor_id;
+    // Since we're using the artifact from file on disk, the ref should be the file path (canonicalized) on disk as URI
+    let artifact_ref = format!("file://{}", artifact_path.display());
 
-    // Attempt to find or create the actor, scaling any existing actors to zero if it exists
-    let inventory = ctl_client.get_host_inventory(&host.id).await.or_else(|e| {
-        bail!(
-            "failed to retrieve host inventory for host [{}]: {e}",
-            &host.id
-        )
-    })?;
-    if let Some(existing_actor) = inventory
-        .actors
-        .into_iter()
-        .find(|a| a.image_ref == Some(actor_ref.clone()))
-    {
-        actor_id = existing_actor.id;
-        scale_actor(&ctl_client, &host.id, &actor_ref, 1, None).await?;
-    } else {
-        // Start the actor for the first time
-        actor_id = start_actor(StartActorArgs {
-            ctl_client: &ctl_client,
-            host_id: &host.id,
-            actor_ref: &actor_ref,
-            count: 1,
-            skip_wait: false,
-            timeout_ms: None,
-        })
-        .await?
-        .actor_id
-        .ok_or_else(|| anyhow!("failed to do thing"))?;
-    }
+    let inventory = ctl_client
+        .get_host_inventory(&host.id)
+        .await
+        .or_else(|e| {
+            bail!(
+                "failed to retrieve host inventory for host [{}]: {e}",
+                &host.id
+            )
+        })?
+        .response
+        .with_context(|| format!("host [{}] did not return an inventory", &host.id))?;
+    let artifact = match project_cfg.project_type {
+        TypeConfig::Actor(_) => {
+            // Attempt to find or create the actor, scaling any existing actors to zero if it exists
+            let actor_id = if let Some(existing_actor) = inventory
+                .actors
+                .into_iter()
+                .find(|a| a.image_ref == artifact_ref)
+            {
+                scale_actor(
+                    &ctl_client,
+                    &host.id,
+                    &artifact_ref,
+                    &existing_actor.id,
+                    1,
+                    None,
+                    None,
+                )
+                .await?;
+                existing_actor.id
+            } else {
+                // Start the actor for the first time
+                start_actor(StartActorArgs {
+                    ctl_client: &ctl_client,
+                    host_id: &host.id,
+                    actor_ref: &artifact_ref,
+                    actor_id: &component_id_from_ref(&artifact_ref),
+                    count: 1,
+                    skip_wait: false,
+                    timeout_ms: None,
+                })
+                .await?
+                .actor_id
+                .ok_or_else(|| anyhow!("failed to do thing"))?
+            };
+            DevArtifact::Actor {
+                actor_id,
+                actor_ref: artifact_ref,
+            }
+        }
+        TypeConfig::Provider(_) => {
+            // Restart a provider that is already running from the same archive, so that it picks
+            // up the new build, otherwise start it for the first time
+            let existing_provider = inventory
+                .providers
+                .into_iter()
+                .find(|p| p.image_ref.as_deref() == Some(artifact_ref.as_str()));
+            let provider_id = existing_provider
+                .as_ref()
+                .map(|p| p.id.clone())
+                .unwrap_or_else(|| component_id_from_ref(&artifact_ref));
+            let mut config = Vec::new();
+            if let Some(path) = cmd.config_json {
+                let config_name = format!("{provider_id}-config-json");
+                let values = read_config_json(&path)?;
+                let ack = ctl_client
+                    .put_config(&config_name, values)
+                    .await
+                    .map_err(|e| anyhow!("failed to put provider configuration: {e}"))?;
+                if !ack.success {
+                    bail!("failed to put provider configuration: {}", ack.message);
+                }
+                config.push(config_name);
+            } else if existing_provider.is_some() {
+                // Restarting without `--config-json` must not drop the configuration the running
+                // provider was started with
+                let config_name = format!("{provider_id}-config-json");
+                let resp = ctl_client
+                    .get_config(&config_name)
+                    .await
+                    .map_err(|e| anyhow!("failed to get provider configuration: {e}"))?;
+                if !resp.success || resp.response.is_none() {
+                    bail!(
+                        "failed to recover the configuration of running provider [{provider_id}] from [{config_name}]: {}. Pass it again with --config-json",
+                        resp.message
+                    );
+                }
+                config.push(config_name);
+            }
+            let provider = if existing_provider.is_some() {
+                let provider = DevProvider {
+                    provider_id,
+                    provider_ref: artifact_ref,
+                    config,
+                };
+                restart_dev_provider(&ctl_client, &host.id, &provider).await?;
+                provider
+            } else {
+                start_dev_provider(&ctl_client, &host.id, &artifact_ref, &provider_id, config)
+                    .await?
+            };
+            eprintln!(
+                "{} {}",
+                emoji::GREEN_CHECK,
+                style(format!(
+                    "provider [{}] started and healthy",
+                    provider.provider_id
+                ))
+                .bold(),
+            );
+            DevArtifact::Provider(provider)
+        }
+    };
 
     // Set up a oneshot channel to remove
     let (stop_tx, mut stop_rx) = mpsc::channel::<()>(1);
@@ -359,8 +456,7 @@ pub async fn handle_command(
                 pause_watch.store(true, Ordering::SeqCst);
                 run_dev_loop(
                     &project_cfg,
-                    ModuleId::from_str(&actor_id)?,
-                    &actor_ref,
+                    &artifact,
                     ServerId::from_str(&host.id)?,
                     &ctl_client,
                     sign_cfg.clone(),
@@ -1,6 +1,7 @@
 pub mod app;
 pub mod appearance;
 pub mod build;
+pub mod bundle;
 pub mod call;
 pub mod common;
 pub mod completions;
@@ -16,3 +17,4 @@ pub mod smithy;
 pub mod ui;
 pub mod up;
 pub mod util;
+pub mod workspace;
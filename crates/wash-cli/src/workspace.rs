@@ -0,0 +1,451 @@
+//! Build and push the members of a workspace in dependency order.
+//!
+//! Builds run in the directory of the project, so every member is built by a separate `wash build`
+//! process, which allows building the members of a stage in parallel.
+
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use futures::{stream, StreamExt};
+use oci_distribution::Reference;
+use serde_json::json;
+use term_table::{
+    row::Row,
+    table_cell::{Alignment, TableCell},
+    Table,
+};
+use tokio::process::Command;
+use wash_lib::cli::{labels_vec_to_hashmap, registry::RegistryPushCommand, CommandOutput};
+use wash_lib::parser::{get_workspace_config, TypeConfig, WorkspaceConfig, WorkspaceMember};
+use wash_lib::registry::{push_oci_artifact, OciPushOptions};
+use wasmcloud_control_interface::RegistryCredential;
+
+/// Outcome of building or pushing a workspace member
+#[derive(Debug, Clone)]
+pub enum MemberOutcome {
+    /// The member succeeded, with the path or reference of its artifact
+    Succeeded(String),
+    Failed(String),
+    /// The member was not built, because the dependency with this name failed or was skipped
+    Skipped(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct MemberResult {
+    pub name: String,
+    pub outcome: MemberOutcome,
+    pub elapsed: Duration,
+}
+
+/// Run `op` for the selected members of the workspace, or all members if none are selected, in
+/// dependency order. Up to `jobs` members of a stage are run at once, and members which depend on
+/// a member that failed or was skipped are skipped.
+pub async fn run_workspace<'a, F, Fut>(
+    workspace: &'a WorkspaceConfig,
+    members: &[String],
+    jobs: Option<usize>,
+    op: F,
+) -> Result<Vec<MemberResult>>
+where
+    F: Fn(&'a WorkspaceMember) -> Fut,
+    Fut: Future<Output = Result<String>>,
+{
+    let jobs = jobs
+        .or_else(|| std::thread::available_parallelism().ok().map(usize::from))
+        .unwrap_or(1)
+        .max(1);
+    let mut results = Vec::with_capacity(workspace.members.len());
+    let mut unavailable = HashSet::new();
+    for stage in workspace.build_stages(members)? {
+        let mut ready = Vec::with_capacity(stage.len());
+        for member in stage {
+            match member
+                .depends_on
+                .iter()
+                .find(|dep| unavailable.contains(dep.as_str()))
+            {
+                Some(dep) => {
+                    unavailable.insert(member.name());
+                    results.push(MemberResult {
+                        name: member.name().to_string(),
+                        outcome: MemberOutcome::Skipped(dep.clone()),
+                        elapsed: Duration::ZERO,
+                    });
+                }
+                None => ready.push(member),
+            }
+        }
+
+        let stage_results = stream::iter(ready)
+            .map(|member| {
+                eprintln!("Building workspace member [{}]...", member.name());
+                let start = Instant::now();
+                let result = op(member);
+                async move { (member, result.await, start.elapsed()) }
+            })
+            .buffer_unordered(jobs)
+            .collect::<Vec<_>>()
+            .await;
+        for (member, result, elapsed) in stage_results {
+            let outcome = match result {
+                Ok(artifact) => MemberOutcome::Succeeded(artifact),
+                Err(err) => {
+                    unavailable.insert(member.name());
+                    MemberOutcome::Failed(format!("{err:#}"))
+                }
+            };
+            results.push(MemberResult {
+                name: member.name().to_string(),
+                outcome,
+                elapsed,
+            });
+        }
+    }
+    Ok(results)
+}
+
+/// Build a workspace member with `wash build` in a separate process, passing `args` to it, and
+/// return the path of the built artifact
+pub async fn build_member(member: &WorkspaceMember, args: &[OsString]) -> Result<String> {
+    let wash = std::env::current_exe().context("failed to find the wash executable")?;
+    let output = Command::new(wash)
+        .arg("build")
+        .arg("--config-path")
+        .arg(&member.config.common.path)
+        .args(["--output", "json"])
+        .args(args)
+        .output()
+        .await
+        .context("failed to run wash build")?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        let (toolchain_output, json_output) = split_json_output(&stderr);
+        // Show the output of the toolchain, which is not part of the error message
+        if !toolchain_output.trim().is_empty() {
+            eprintln!(
+                "Output of building workspace member [{}]:\n{toolchain_output}",
+                member.name()
+            );
+        }
+        match json_output
+            .as_ref()
+            .and_then(|output| output.get("error"))
+            .and_then(serde_json::Value::as_str)
+        {
+            Some(err) => bail!("{err}"),
+            None => bail!("wash build failed: {}", output.status),
+        }
+    }
+    split_json_output(&stdout)
+        .1
+        .as_ref()
+        .and_then(|output| output.get("actor_path").or_else(|| output.get("path")))
+        .and_then(serde_json::Value::as_str)
+        .map(ToString::to_string)
+        .context("failed to find the built artifact in the output of wash build")
+}
+
+/// Split the output of a wash command into the preceding output and the JSON object printed at
+/// the end of it
+fn split_json_output(output: &str) -> (&str, Option<serde_json::Value>) {
+    let start = output.rfind("\n{").map(|i| i + 1).unwrap_or_default();
+    match serde_json::from_str(output[start..].trim()) {
+        Ok(json) => (&output[..start], Some(json)),
+        Err(_) => (output, None),
+    }
+}
+
+/// Build and push the members of the workspace, tagged as `<registry>/<name>:<version>`
+pub async fn push_workspace(cmd: RegistryPushCommand) -> Result<CommandOutput> {
+    let workspace = get_workspace_config(cmd.workspace.clone(), Some(true))?
+        .context("wasmcloud.toml does not configure a workspace")?;
+    let annotations = labels_vec_to_hashmap(cmd.annotations.clone().unwrap_or_default())?;
+    let results = run_workspace(&workspace, &cmd.members, cmd.jobs, |member| {
+        push_member(member, &cmd, &annotations)
+    })
+    .await?;
+    workspace_output("push", results)
+}
+
+async fn push_member(
+    member: &WorkspaceMember,
+    cmd: &RegistryPushCommand,
+    annotations: &HashMap<String, String>,
+) -> Result<String> {
+    let artifact = build_member(member, &[]).await?;
+
+    let registry = cmd
+        .registry
+        .as_ref()
+        .or(member.config.common.registry.url.as_ref())
+        .context("no registry is configured, please specify one with --registry or registry.url")?;
+    let image: Reference = format!(
+        "{}/{}:{}",
+        registry.trim(),
+        member.name(),
+        member.config.common.version
+    )
+    .to_ascii_lowercase()
+    .parse()
+    .context("failed to parse artifact url from registry, name and version")?;
+
+    let credentials = match (&cmd.opts.user, &cmd.opts.password) {
+        (Some(user), Some(password)) => RegistryCredential {
+            username: Some(user.clone()),
+            password: Some(password.clone()),
+            ..Default::default()
+        },
+        _ => {
+            member
+                .config
+                .resolve_registry_credentials(image.registry())
+                .await?
+        }
+    };
+    let push_insecure = matches!(
+        &member.config.project_type,
+        TypeConfig::Actor(actor) if actor.push_insecure
+    );
+
+    push_oci_artifact(
+        image.whole(),
+        &artifact,
+        OciPushOptions {
+            config: None,
+            allow_latest: cmd.allow_latest,
+            user: credentials.username,
+            password: credentials.password,
+            insecure: cmd.opts.insecure || push_insecure,
+            annotations: Some(annotations.clone()),
+        },
+    )
+    .await
+    .with_context(|| format!("failed to push {artifact} to {}", image.whole()))?;
+    Ok(image.whole())
+}
+
+/// Summarize the results of running `command` for the workspace members, failing if any member
+/// failed
+pub fn workspace_output(command: &str, results: Vec<MemberResult>) -> Result<CommandOutput> {
+    let table = results_table(&results);
+    let failed = results
+        .iter()
+        .filter(|result| !matches!(result.outcome, MemberOutcome::Succeeded(_)))
+        .map(|result| result.name.as_str())
+        .collect::<Vec<_>>();
+    if !failed.is_empty() {
+        eprintln!("{table}");
+        bail!(
+            "failed to {command} workspace members [{}]",
+            failed.join(", ")
+        );
+    }
+
+    let members = results
+        .iter()
+        .map(|result| {
+            let artifact = match &result.outcome {
+                MemberOutcome::Succeeded(artifact) => artifact.as_str(),
+                MemberOutcome::Failed(_) | MemberOutcome::Skipped(_) => "",
+            };
+            json!({
+                "name": result.name,
+                "artifact": artifact,
+                "duration_ms": result.elapsed.as_millis(),
+            })
+        })
+        .collect::<Vec<_>>();
+    Ok(CommandOutput::new(
+        table,
+        HashMap::from([("members".to_string(), json!(members))]),
+    ))
+}
+
+fn results_table(results: &[MemberResult]) -> String {
+    let mut table = Table::new();
+    crate::util::configure_table_style(&mut table);
+
+    table.add_row(Row::new(vec![
+        TableCell::new_with_alignment("Member", 1, Alignment::Left),
+        TableCell::new_with_alignment("Status", 1, Alignment::Left),
+        TableCell::new_with_alignment("Duration", 1, Alignment::Left),
+        TableCell::new_with_alignment("Artifact / Error", 1, Alignment::Left),
+    ]));
+
+    for result in results {
+        let (status, detail) = match &result.outcome {
+            MemberOutcome::Succeeded(artifact) => ("ok", artifact.clone()),
+            MemberOutcome::Failed(err) => ("failed", err.clone()),
+            MemberOutcome::Skipped(dep) => ("skipped", format!("dependency [{dep}] was not built")),
+        };
+        table.add_row(Row::new(vec![
+            TableCell::new_with_alignment(result.name.clone(), 1, Alignment::Left),
+            TableCell::new_with_alignment(status, 1, Alignment::Left),
+            TableCell::new_with_alignment(
+                format!("{:.1}s", result.elapsed.as_secs_f64()),
+                1,
+                Alignment::Left,
+            ),
+            TableCell::new_with_alignment(detail, 1, Alignment::Left),
+        ]));
+    }
+
+    table.render()
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    use anyhow::bail;
+    use semver::Version;
+    use wash_lib::parser::{
+        ActorConfig, CommonConfig, LanguageConfig, ProjectConfig, RegistryConfig, RustConfig,
+        TypeConfig, WorkspaceConfig, WorkspaceMember,
+    };
+
+    use super::{run_workspace, split_json_output, MemberOutcome};
+
+    /// Workspace with members of the given names and dependencies
+    fn test_workspace(members: &[(&str, &[&str])]) -> WorkspaceConfig {
+        WorkspaceConfig {
+            path: PathBuf::from("/workspace"),
+            members: members
+                .iter()
+                .map(|(name, depends_on)| WorkspaceMember {
+                    config: ProjectConfig {
+                        language: LanguageConfig::Rust(RustConfig::default()),
+                        project_type: TypeConfig::Actor(ActorConfig::default()),
+                        common: CommonConfig {
+                            name: name.to_string(),
+                            version: Version::new(0, 1, 0),
+                            revision: 0,
+                            path: PathBuf::from("/workspace").join(name),
+                            wasm_bin_name: None,
+                            registry: RegistryConfig::default(),
+                        },
+                    },
+                    depends_on: depends_on.iter().map(ToString::to_string).collect(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Names of the members of each stage, sorted within a stage
+    fn stage_names(workspace: &WorkspaceConfig, names: &[String]) -> Vec<Vec<String>> {
+        workspace
+            .build_stages(names)
+            .expect("failed to order workspace members")
+            .into_iter()
+            .map(|stage| {
+                let mut names: Vec<_> = stage
+                    .into_iter()
+                    .map(|member| member.name().to_string())
+                    .collect();
+                names.sort();
+                names
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_build_stages_diamond() {
+        let workspace = test_workspace(&[
+            ("app", &["left", "right"]),
+            ("left", &["base"]),
+            ("right", &["base"]),
+            ("base", &[]),
+        ]);
+        assert_eq!(
+            stage_names(&workspace, &[]),
+            vec![vec!["base"], vec!["left", "right"], vec!["app"]]
+        );
+        // Dependencies that are not selected are expected to be built already
+        assert_eq!(
+            stage_names(&workspace, &["app".to_string(), "left".to_string()]),
+            vec![vec!["left"], vec!["app"]]
+        );
+    }
+
+    #[test]
+    fn test_build_stages_cycle() {
+        let workspace = test_workspace(&[("a", &["b"]), ("b", &["a"]), ("c", &[])]);
+        let err = workspace
+            .build_stages(&[])
+            .expect_err("cyclic dependencies were accepted");
+        assert!(
+            err.to_string().contains("[a, b] have cyclic dependencies"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn test_build_stages_unknown_dependency() {
+        let workspace = test_workspace(&[("a", &["missing"])]);
+        let err = workspace
+            .build_stages(&[])
+            .expect_err("unknown dependency was accepted");
+        assert!(
+            err.to_string().contains("unknown member [missing]"),
+            "unexpected error: {err}"
+        );
+
+        let workspace = test_workspace(&[("a", &[])]);
+        assert!(workspace.build_stages(&["missing".to_string()]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_workspace_skips_failed_dependency() {
+        let workspace = test_workspace(&[
+            ("base", &[]),
+            ("other", &[]),
+            ("left", &["base"]),
+            ("app", &["left", "other"]),
+        ]);
+        let results = run_workspace(&workspace, &[], Some(1), |member: &WorkspaceMember| {
+            let name = member.name().to_string();
+            async move {
+                if name == "base" {
+                    bail!("failed to build");
+                }
+                Ok(format!("{name}.wasm"))
+            }
+        })
+        .await
+        .expect("failed to run workspace");
+        let outcomes: HashMap<_, _> = results
+            .into_iter()
+            .map(|result| (result.name, result.outcome))
+            .collect();
+        assert_eq!(outcomes.len(), 4);
+        assert!(
+            matches!(&outcomes["base"], MemberOutcome::Failed(err) if err == "failed to build")
+        );
+        assert!(
+            matches!(&outcomes["other"], MemberOutcome::Succeeded(path) if path == "other.wasm")
+        );
+        assert!(matches!(&outcomes["left"], MemberOutcome::Skipped(dep) if dep == "base"));
+        assert!(matches!(&outcomes["app"], MemberOutcome::Skipped(dep) if dep == "left"));
+    }
+
+    #[test]
+    fn test_split_json_output() {
+        let (output, json) = split_json_output(
+            "   Compiling echo v0.1.0\n    Finished release\n\n{\n  \"path\": \"/tmp/echo_s.wasm\",\n  \"nested\": {\n    \"a\": 1\n  }\n}\n",
+        );
+        assert_eq!(output, "   Compiling echo v0.1.0\n    Finished release\n\n");
+        assert_eq!(
+            json.as_ref().and_then(|json| json.get("path")),
+            Some(&serde_json::json!("/tmp/echo_s.wasm"))
+        );
+
+        let (output, json) = split_json_output("error: could not compile");
+        assert_eq!(output, "error: could not compile");
+        assert!(json.is_none());
+    }
+}
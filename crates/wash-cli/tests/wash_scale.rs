@@ -6,6 +6,7 @@ use anyhow::{Context, Result};
 use serial_test::serial;
 use tokio::process::Command;
 use wash_lib::cli::output::{GetHostInventoriesCommandOutput, ScaleCommandOutput};
+use wasmcloud_control_interface::ComponentLimits;
 
 #[tokio::test]
 #[serial]
@@ -25,6 +26,10 @@ async fn integration_scale_actor_serial() -> Result<()> {
             ECHO_OCI_REF,
             "--max",
             "10",
+            "--max-memory-bytes",
+            "10485760",
+            "--max-table-elements",
+            "1000",
             "--output",
             "json",
             "--timeout-ms",
@@ -79,18 +84,14 @@ async fn integration_scale_actor_serial() -> Result<()> {
             continue;
         } else {
             assert_eq!(actors.len(), 1);
-            let max = actors[0]
-                .instances
-                .iter()
-                .map(|i| {
-                    if i.max_instances == 0 {
-                        1
-                    } else {
-                        i.max_instances
-                    }
+            assert_eq!(actors[0].max_instances, 10);
+            assert_eq!(
+                actors[0].limits,
+                Some(ComponentLimits {
+                    max_memory_bytes: Some(10485760),
+                    max_table_elements: Some(1000),
                 })
-                .sum::<u32>();
-            assert_eq!(max, 10);
+            );
             break;
         }
     }
@@ -159,20 +160,9 @@ async fn integration_scale_actor_serial() -> Result<()> {
             continue;
         } else {
             assert_eq!(actors.len(), 1);
-            // NOTE: This is a compensation for the fact that <0.79.0 hosts include individual instances,
-            // but 0.79.0+ hosts include instances with max counts on them.
-            let max = actors[0]
-                .instances
-                .iter()
-                .map(|i| {
-                    if i.max_instances == 0 {
-                        1
-                    } else {
-                        i.max_instances
-                    }
-                })
-                .sum::<u32>();
-            assert_eq!(max, 5);
+            assert_eq!(actors[0].max_instances, 5);
+            // Scaling without limits removes the limits set before
+            assert_eq!(actors[0].limits, None);
             break;
         }
     }
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use anyhow::{bail, Context, Result};
 use tokio::time::Duration;
-use wasmcloud_control_interface::{Client as CtlClient, CtlOperationAck};
+use wasmcloud_control_interface::{Client as CtlClient, ComponentLimits, CtlResponse};
 
 use crate::{
     common::boxed_err_to_anyhow,
@@ -17,6 +17,7 @@ pub struct StartActorArgs<'a> {
     pub ctl_client: &'a CtlClient,
     pub host_id: &'a str,
     pub actor_ref: &'a str,
+    pub actor_id: &'a str,
     pub count: u32,
     pub skip_wait: bool,
     pub timeout_ms: Option<u64>,
@@ -35,6 +36,7 @@ pub async fn start_actor(
         ctl_client,
         host_id,
         actor_ref,
+        actor_id,
         count,
         skip_wait,
         timeout_ms,
@@ -57,13 +59,13 @@ pub async fn start_actor(
 
     // Start the actor
     let ack = ctl_client
-        .scale_actor(host_id, actor_ref, count, None)
+        .scale_actor(host_id, actor_ref, actor_id, count, None, Vec::new(), None)
         .await
         .map_err(boxed_err_to_anyhow)
         .with_context(|| format!("Failed to start actor: {}", actor_ref))?;
 
-    if !ack.accepted {
-        bail!("Start actor ack not accepted: {}", ack.error);
+    if !ack.success {
+        bail!("Start actor ack not accepted: {}", ack.message);
     }
 
     // If skip_wait is specified, return incomplete information immediately
@@ -71,7 +73,7 @@ pub async fn start_actor(
         return Ok(ActorStartedInfo {
             host_id: host_id.into(),
             actor_ref: actor_ref.into(),
-            actor_id: None,
+            actor_id: Some(actor_id.into()),
         });
     }
 
@@ -106,22 +108,32 @@ pub async fn scale_actor(
     client: &CtlClient,
     host_id: &str,
     actor_ref: &str,
+    actor_id: &str,
     max_instances: u32,
     annotations: Option<HashMap<String, String>>,
+    limits: Option<ComponentLimits>,
 ) -> Result<()> {
     let ack = client
-        .scale_actor(host_id, actor_ref, max_instances, annotations)
+        .scale_actor(
+            host_id,
+            actor_ref,
+            actor_id,
+            max_instances,
+            annotations,
+            Vec::new(),
+            limits,
+        )
         .await
         .map_err(boxed_err_to_anyhow)?;
 
-    if !ack.accepted {
-        bail!("Operation failed: {}", ack.error);
+    if !ack.success {
+        bail!("Operation failed: {}", ack.message);
     }
 
     Ok(())
 }
 
-/// Stop an actor
+/// Stop an actor by scaling it to zero instances
 pub async fn stop_actor(
     client: &CtlClient,
     host_id: &str,
@@ -140,13 +152,36 @@ pub async fn stop_actor(
         .await
         .map_err(boxed_err_to_anyhow)?;
 
+    // The host needs the reference of the running actor to scale it
+    let inventory = client
+        .get_host_inventory(host_id)
+        .await
+        .map_err(boxed_err_to_anyhow)?;
+    let Some(actor_ref) = inventory.response.and_then(|inventory| {
+        inventory
+            .actors
+            .into_iter()
+            .find(|actor| actor.id == actor_id)
+            .map(|actor| actor.image_ref)
+    }) else {
+        bail!("Actor {actor_id} is not running on host {host_id}");
+    };
+
     let ack = client
-        .stop_actor(host_id, actor_id, annotations)
+        .scale_actor(
+            host_id,
+            &actor_ref,
+            actor_id,
+            0,
+            annotations,
+            Vec::new(),
+            None,
+        )
         .await
         .map_err(boxed_err_to_anyhow)?;
 
-    if !ack.accepted {
-        bail!("Operation failed: {}", ack.error);
+    if !ack.success {
+        bail!("Operation failed: {}", ack.message);
     }
 
     if skip_wait {
@@ -175,9 +210,9 @@ pub async fn update_actor(
     host_id: &str,
     actor_id: &str,
     actor_ref: &str,
-) -> Result<CtlOperationAck> {
+) -> Result<CtlResponse<()>> {
     client
-        .update_actor(host_id, actor_id, actor_ref, None)
+        .update_actor(host_id, actor_id, actor_ref, None, None)
         .await
         .map_err(boxed_err_to_anyhow)
 }
@@ -1,18 +1,31 @@
 use std::io::ErrorKind;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process;
 
 use anyhow::{anyhow, bail, Context, Result};
 use nkeys::KeyPairType;
+use provider_archive::ProviderArchive;
 use tracing::{trace, warn};
 
 use crate::build::SignConfig;
-use crate::cli::par::{create_provider_archive, detect_arch, ParCreateArgs};
 use crate::cli::{extract_keypair, OutputKind};
-use crate::parser::{CommonConfig, LanguageConfig, ProviderConfig};
+use crate::parser::{
+    par_target_for_rust_target, CommonConfig, LanguageConfig, ProviderBuilder, ProviderConfig,
+    RustConfig,
+};
 
-/// Build a capability provider for the current machine's architecture
-/// and operating system using provided configuration.
+/// A target the provider is built for
+struct BuildTarget<'a> {
+    /// Rust target triple, the default rust toolchain's if not set
+    rust_target: Option<&'a str>,
+    par_target: String,
+    builder: ProviderBuilder,
+    linker: Option<&'a PathBuf>,
+}
+
+/// Build a capability provider for each configured target, or for the current machine's
+/// architecture and operating system, and add them to a single provider archive signed
+/// using provided configuration.
 pub(crate) async fn build_provider(
     provider_config: &ProviderConfig,
     language_config: &LanguageConfig,
@@ -22,34 +35,36 @@ pub(crate) async fn build_provider(
     let LanguageConfig::Rust(rust_config) = language_config else {
         bail!("Unsupported language for provider: {:?}", language_config)
     };
-    let mut command = match rust_config.cargo_path.as_ref() {
-        Some(path) => process::Command::new(path),
-        None => process::Command::new("cargo"),
-    };
 
     // Change directory into the project directory
     std::env::set_current_dir(&common_config.path)?;
     trace!("Building provider in {:?}", common_config.path);
 
-    // Build for a specified target if provided, or the default rust target
-    let mut build_args = Vec::with_capacity(4);
-    build_args.extend_from_slice(&["build", "--release"]);
-    if let Some(override_target) = &provider_config.rust_target {
-        build_args.extend_from_slice(&["--target", override_target]);
+    let targets = if provider_config.targets.is_empty() {
+        // Build for a specified target if provided, or the default rust target
+        let par_target = match &provider_config.rust_target {
+            Some(rust_target) => par_target_for_rust_target(rust_target)?,
+            None => format!("{}-{}", provider_config.arch, provider_config.os),
+        };
+        vec![BuildTarget {
+            rust_target: provider_config.rust_target.as_deref(),
+            par_target,
+            builder: ProviderBuilder::Cargo,
+            linker: None,
+        }]
+    } else {
+        provider_config
+            .targets
+            .iter()
+            .map(|target| BuildTarget {
+                rust_target: Some(target.rust_target.as_str()),
+                par_target: target.par_target.clone(),
+                builder: target.builder,
+                linker: target.linker.as_ref(),
+            })
+            .collect()
     };
 
-    let result = command.args(build_args).status().map_err(|e| {
-        if e.kind() == ErrorKind::NotFound {
-            anyhow!("{:?} command is not found", command.get_program())
-        } else {
-            anyhow!(e)
-        }
-    })?;
-
-    if !result.success() {
-        bail!("Compiling provider failed: {result}")
-    }
-
     let metadata = cargo_metadata::MetadataCommand::new().no_deps().exec()?;
     let bin_name = if let Some(bin_name) = &provider_config.bin_name {
         bin_name.to_string()
@@ -68,33 +83,40 @@ pub(crate) async fn build_provider(
                 })
             }).context("Could not infer provider binary name in metadata, please specify under provider.bin_name")?
     };
-    let mut provider_path_buf = rust_config
+    let target_dir = rust_config
         .target_path
         .clone()
         .unwrap_or_else(|| PathBuf::from(metadata.target_directory.as_std_path()));
-    if let Some(override_target) = &provider_config.rust_target {
-        provider_path_buf.push(override_target);
+
+    let mut par = ProviderArchive::new(
+        &provider_config.wit_world.clone().unwrap_or_default(),
+        &common_config.name,
+        &provider_config.vendor,
+        Some(common_config.revision),
+        Some(common_config.version.to_string()),
+    );
+    let mut provider_paths = Vec::with_capacity(targets.len());
+    for target in &targets {
+        let provider_path_buf = build_target(rust_config, target, &target_dir, &bin_name)?;
+        trace!("Retrieving provider binary from {:?}", provider_path_buf);
+        let provider_bytes = tokio::fs::read(&provider_path_buf)
+            .await
+            .with_context(|| format!("failed to read provider binary {provider_path_buf:?}"))?;
+        par.add_library(&target.par_target, &provider_bytes)
+            .map_err(|e| anyhow!(e))
+            .with_context(|| {
+                format!(
+                    "failed to add provider binary for [{}] to provider archive",
+                    target.par_target
+                )
+            })?;
+        provider_paths.push(provider_path_buf);
     }
-    provider_path_buf.push("release");
-    provider_path_buf.push(&bin_name);
-
-    trace!("Retrieving provider binary from {:?}", provider_path_buf);
-
-    let provider_bytes = tokio::fs::read(&provider_path_buf).await?;
-
-    let mut par = create_provider_archive(
-        ParCreateArgs {
-            capid: provider_config.wit_world.clone().unwrap_or_default(),
-            vendor: provider_config.vendor.to_string(),
-            revision: Some(common_config.revision),
-            version: Some(common_config.version.to_string()),
-            schema: None,
-            name: common_config.name.to_string(),
-            arch: detect_arch(),
-        },
-        &provider_bytes,
-    )
-    .context("failed to create initial provider archive with built provider")?;
+    // Keys are generated next to the binary of the first target, if needed
+    let provider_path_buf = provider_paths
+        .into_iter()
+        .next()
+        .context("no provider targets to build")?;
 
     // If no signing config supplied, just return the path to the provider
     let Some(sign_config) = signing_config else {
@@ -135,3 +157,66 @@ pub(crate) async fn build_provider(
         common_config.path.join(destination)
     })
 }
+
+/// Compile the provider for `target`, returning the path of the built binary
+fn build_target(
+    rust_config: &RustConfig,
+    target: &BuildTarget<'_>,
+    target_dir: &Path,
+    bin_name: &str,
+) -> Result<PathBuf> {
+    let mut command = match (target.builder, rust_config.cargo_path.as_ref()) {
+        (ProviderBuilder::Cross, _) => process::Command::new("cross"),
+        (ProviderBuilder::Cargo, Some(path)) => process::Command::new(path),
+        (ProviderBuilder::Cargo, None) => process::Command::new("cargo"),
+    };
+
+    let mut build_args = Vec::with_capacity(4);
+    build_args.extend_from_slice(&["build", "--release"]);
+    if let Some(rust_target) = target.rust_target {
+        build_args.extend_from_slice(&["--target", rust_target]);
+    };
+    if let (Some(linker), Some(rust_target)) = (target.linker, target.rust_target) {
+        command.env(
+            format!(
+                "CARGO_TARGET_{}_LINKER",
+                rust_target.to_uppercase().replace(['-', '.'], "_")
+            ),
+            linker,
+        );
+    }
+
+    trace!(
+        "Building provider for [{}] with {:?}",
+        target.par_target,
+        command.get_program()
+    );
+    let result = command.args(build_args).status().map_err(|e| {
+        if e.kind() == ErrorKind::NotFound {
+            anyhow!("{:?} command is not found", command.get_program())
+        } else {
+            anyhow!(e)
+        }
+    })?;
+
+    if !result.success() {
+        bail!(
+            "Compiling provider for [{}] failed: {result}",
+            target.par_target
+        )
+    }
+
+    let mut provider_path_buf = target_dir.to_path_buf();
+    if let Some(rust_target) = target.rust_target {
+        provider_path_buf.push(rust_target);
+    }
+    provider_path_buf.push("release");
+    if target.rust_target.map_or(cfg!(windows), |rust_target| {
+        rust_target.contains("-windows")
+    }) {
+        provider_path_buf.push(format!("{bin_name}.exe"));
+    } else {
+        provider_path_buf.push(bin_name);
+    }
+    Ok(provider_path_buf)
+}
@@ -1,8 +1,9 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::convert::TryFrom;
 use std::io::Cursor;
 use std::path::{Path, PathBuf};
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use async_compression::tokio::{bufread::GzipDecoder, write::GzipEncoder};
 use futures::TryStreamExt;
 use serde::{Deserialize, Serialize};
@@ -12,10 +13,41 @@ use tokio::{
 };
 use tokio_tar::Archive;
 use wasmcloud_control_interface::HostInventory;
+use wasmcloud_core::{Invocation, InvocationResponse};
 
+pub const MANIFEST_FILE: &str = "manifest.json";
 pub const INVENTORY_FILE: &str = "inventory.json";
 pub const MESSAGES_DIR: &str = "messages";
 
+/// The version of the capture format written by [`WriteCapture`]. Captures written before the
+/// format was versioned do not contain a manifest and are read as version 1
+pub const CAPTURE_FORMAT_VERSION: u32 = 2;
+
+/// The prefix of legacy wasmbus RPC subjects
+const WASMBUS_RPC_PREFIX: &str = "wasmbus.rpc.";
+/// The protocol segment of wRPC subjects, which are of the form
+/// `{lattice}.{target}.wrpc.0.0.1.{instance}.{function}`
+const WRPC_PROTOCOL: &str = ".wrpc.0.0.1.";
+
+/// Metadata describing a capture
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureManifest {
+    /// The version of the capture format
+    pub version: u32,
+    /// The lattice the capture was taken from
+    #[serde(default)]
+    pub lattice: Option<String>,
+}
+
+impl Default for CaptureManifest {
+    fn default() -> Self {
+        Self {
+            version: 1,
+            lattice: None,
+        }
+    }
+}
+
 /// A subset of NATS message info that we need to serialize for now. Basically it is all the types that easily
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SerializableMessage {
@@ -25,6 +57,9 @@ pub struct SerializableMessage {
     pub description: Option<String>,
     pub length: usize,
     pub published: time::OffsetDateTime,
+    /// Headers of the message. Not present in captures written before format version 2
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub headers: Option<BTreeMap<String, Vec<String>>>,
 }
 
 impl TryFrom<async_nats::jetstream::Message> for SerializableMessage {
@@ -32,6 +67,17 @@ impl TryFrom<async_nats::jetstream::Message> for SerializableMessage {
 
     fn try_from(msg: async_nats::jetstream::Message) -> Result<Self, Self::Error> {
         let published = msg.info().map_err(|e| anyhow::anyhow!("{e:?}"))?.published;
+        let headers = msg.message.headers.map(|headers| {
+            headers
+                .iter()
+                .map(|(name, values)| {
+                    (
+                        name.to_string(),
+                        values.iter().map(ToString::to_string).collect(),
+                    )
+                })
+                .collect()
+        });
         Ok(Self {
             subject: msg.message.subject.to_string(),
             reply: msg.message.reply.map(|s| s.to_string()),
@@ -39,6 +85,7 @@ impl TryFrom<async_nats::jetstream::Message> for SerializableMessage {
             description: msg.message.description,
             length: msg.message.length,
             published,
+            headers,
         })
     }
 }
@@ -52,6 +99,7 @@ impl TryFrom<async_nats::jetstream::Message> for SerializableMessage {
 /// NOTE: The interior structure of the tarball is not a guaranteed API and may change in the
 /// future. All interactions should be done through this type
 pub struct ReadCapture {
+    pub manifest: CaptureManifest,
     pub inventory: Vec<HostInventory>,
     // NOTE: A further optimization would be to only load based off of a filter here rather than
     // possibly having thousands of messages
@@ -65,13 +113,24 @@ impl ReadCapture {
         let mut archive = Archive::new(GzipDecoder::new(tokio::io::BufReader::new(file)));
 
         let mut capture = ReadCapture {
+            manifest: CaptureManifest::default(),
             inventory: Vec::new(),
             messages: Vec::new(),
         };
         let mut entries = archive.entries()?;
         while let Some(mut entry) = entries.try_next().await? {
             let path = entry.path()?;
-            if path.file_name().unwrap_or_default() == INVENTORY_FILE {
+            if path.file_name().unwrap_or_default() == MANIFEST_FILE {
+                let mut buf = Vec::new();
+                entry.read_to_end(&mut buf).await?;
+                capture.manifest = serde_json::from_slice(&buf)?;
+                if capture.manifest.version > CAPTURE_FORMAT_VERSION {
+                    bail!(
+                        "capture format version {} is not supported, this version of wash supports up to version {CAPTURE_FORMAT_VERSION}",
+                        capture.manifest.version
+                    );
+                }
+            } else if path.file_name().unwrap_or_default() == INVENTORY_FILE {
                 let mut buf = Vec::new();
                 entry.read_to_end(&mut buf).await?;
                 // We can't use a reader because it is async
@@ -94,6 +153,327 @@ impl ReadCapture {
         }
         Ok(capture)
     }
+
+    /// Returns all invocations contained in the capture in the order they were published, each
+    /// paired with its response if the response was captured as well
+    pub fn invocations(&self) -> Vec<CapturedInvocation> {
+        let mut invocations: Vec<CapturedInvocation> = Vec::new();
+        // Maps reply subjects to the index of the invocation awaiting a response on them
+        let mut pending = HashMap::new();
+        for msg in &self.messages {
+            if let Some(invocation) = CapturedInvocation::parse(msg) {
+                if let Some(reply) = &msg.reply {
+                    pending.insert(reply.as_str(), invocations.len());
+                }
+                invocations.push(invocation);
+                continue;
+            }
+            // Legacy responses are published to the reply subject directly, wRPC results and
+            // errors are published to subjects nested under it
+            let response = if let Some(&i) = pending.get(msg.subject.as_str()) {
+                (invocations[i].protocol == InvocationProtocol::Wasmbus)
+                    .then(|| {
+                        rmp_serde::from_slice::<InvocationResponse>(&msg.payload)
+                            .ok()
+                            .map(|res| match res.error {
+                                Some(error) => (i, CapturedResponse::Error(error)),
+                                None => (i, CapturedResponse::Ok(res.msg.into())),
+                            })
+                    })
+                    .flatten()
+            } else if let Some(reply) = msg.subject.strip_suffix(".results") {
+                // Results exceeding the maximum NATS payload are published in several chunks
+                if let Some(&i) = pending.get(reply) {
+                    match &mut invocations[i].response {
+                        Some(CapturedResponse::Ok(payload)) => {
+                            let mut results = bytes::BytesMut::from(payload.as_ref());
+                            results.extend_from_slice(&msg.payload);
+                            *payload = results.freeze();
+                        }
+                        Some(CapturedResponse::Error(_)) => {}
+                        response @ None => {
+                            *response = Some(CapturedResponse::Ok(msg.payload.clone()))
+                        }
+                    }
+                }
+                None
+            } else if let Some(reply) = msg.subject.strip_suffix(".error") {
+                pending.get(reply).map(|&i| {
+                    (
+                        i,
+                        CapturedResponse::Error(String::from_utf8_lossy(&msg.payload).into()),
+                    )
+                })
+            } else {
+                None
+            };
+            if let Some((i, response)) = response {
+                invocations[i].response.get_or_insert(response);
+            }
+        }
+        invocations
+    }
+}
+
+/// The protocol a captured invocation was sent with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InvocationProtocol {
+    /// Legacy wasmbus RPC, using msgpack encoded [`Invocation`]s
+    Wasmbus,
+    /// wRPC over NATS
+    Wrpc,
+}
+
+/// A response to a captured invocation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CapturedResponse {
+    /// The invocation succeeded and returned the contained payload
+    Ok(bytes::Bytes),
+    /// The invocation failed with the contained error
+    Error(String),
+}
+
+/// An invocation found in a capture
+#[derive(Debug, Clone)]
+pub struct CapturedInvocation {
+    pub protocol: InvocationProtocol,
+    /// The subject the invocation was published on
+    pub subject: String,
+    /// The ID of the entity that sent the invocation, if known
+    pub origin: Option<String>,
+    /// The ID of the entity the invocation was sent to
+    pub target: String,
+    /// The interface invoked, e.g. `wasi:http/incoming-handler@0.2.0` or `HttpServer`
+    pub interface: String,
+    /// The operation invoked on the interface, e.g. `handle` or `HandleRequest`
+    pub operation: String,
+    /// The payload the invocation was published with
+    pub payload: bytes::Bytes,
+    /// The headers the invocation was published with
+    pub headers: BTreeMap<String, Vec<String>>,
+    pub published: time::OffsetDateTime,
+    /// The captured response, if any
+    pub response: Option<CapturedResponse>,
+}
+
+impl CapturedInvocation {
+    /// Parses an invocation from a captured message. Returns `None` if the message is not an
+    /// invocation
+    pub fn parse(msg: &SerializableMessage) -> Option<Self> {
+        let headers = msg.headers.clone().unwrap_or_default();
+        if msg.subject.starts_with(WASMBUS_RPC_PREFIX) {
+            let inv: Invocation = rmp_serde::from_slice(&msg.payload).ok()?;
+            let (interface, operation) = match inv.operation.rsplit_once('.') {
+                Some((interface, operation)) => (interface.to_string(), operation.to_string()),
+                None => (inv.target.contract_id.clone(), inv.operation.clone()),
+            };
+            return Some(Self {
+                protocol: InvocationProtocol::Wasmbus,
+                subject: msg.subject.clone(),
+                origin: Some(inv.origin.public_key),
+                target: inv.target.public_key,
+                interface,
+                operation,
+                payload: msg.payload.clone(),
+                headers,
+                published: msg.published,
+                response: None,
+            });
+        }
+        let (prefix, function) = msg.subject.split_once(WRPC_PROTOCOL)?;
+        let (_lattice, target) = prefix.split_once('.')?;
+        // Versioned interface names contain dots, function names never do
+        let (interface, operation) = function.rsplit_once('.').unwrap_or(("", function));
+        let origin = headers
+            .get("source-id")
+            .and_then(|values| values.first())
+            .cloned();
+        Some(Self {
+            protocol: InvocationProtocol::Wrpc,
+            subject: msg.subject.clone(),
+            origin,
+            target: target.to_string(),
+            interface: interface.to_string(),
+            operation: operation.to_string(),
+            payload: msg.payload.clone(),
+            headers,
+            published: msg.published,
+            response: None,
+        })
+    }
+
+    /// Returns the body of the invocation, which is the inner message of legacy invocations and
+    /// the encoded parameters of wRPC invocations
+    pub fn body(&self) -> bytes::Bytes {
+        match self.protocol {
+            InvocationProtocol::Wasmbus => rmp_serde::from_slice::<Invocation>(&self.payload)
+                .map(|inv| inv.msg.into())
+                .unwrap_or_default(),
+            InvocationProtocol::Wrpc => self.payload.clone(),
+        }
+    }
+}
+
+/// A filter selecting invocations from a capture. Unset conditions match all invocations
+#[derive(Debug, Clone, Default)]
+pub struct CaptureFilter {
+    /// IDs of entities that must all take part in an invocation, either as origin or target
+    pub ids: Vec<String>,
+    /// The interface invoked. Unversioned names match all versions of the interface
+    pub interface: Option<String>,
+    /// The operation invoked
+    pub operation: Option<String>,
+    /// Only match invocations published at or after this time
+    pub since: Option<time::OffsetDateTime>,
+    /// Only match invocations published at or before this time
+    pub until: Option<time::OffsetDateTime>,
+}
+
+impl CaptureFilter {
+    /// Returns whether `invocation` matches all conditions of the filter
+    pub fn matches(&self, invocation: &CapturedInvocation) -> bool {
+        self.ids
+            .iter()
+            .all(|id| invocation.origin.as_deref() == Some(id.as_str()) || invocation.target == *id)
+            && self.interface.as_ref().map_or(true, |interface| {
+                invocation
+                    .interface
+                    .strip_prefix(interface.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('@'))
+            })
+            && self
+                .operation
+                .as_ref()
+                .map_or(true, |operation| invocation.operation == *operation)
+            && self
+                .since
+                .map_or(true, |since| invocation.published >= since)
+            && self
+                .until
+                .map_or(true, |until| invocation.published <= until)
+    }
+}
+
+/// A single difference between a captured response and the response to its replay
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ResponseDifference {
+    /// The path of the differing value, e.g. `$.ok.headers[0]`
+    pub path: String,
+    /// The captured value, `None` if it is missing
+    pub captured: Option<serde_json::Value>,
+    /// The replayed value, `None` if it is missing
+    pub replayed: Option<serde_json::Value>,
+}
+
+impl CapturedResponse {
+    /// Returns a JSON representation of the response used for comparisons. Payloads of legacy
+    /// invocations are decoded from msgpack or CBOR where possible, all other payloads are
+    /// represented as strings if they are valid UTF-8 and as byte arrays otherwise
+    pub fn to_json(&self, protocol: InvocationProtocol) -> serde_json::Value {
+        match self {
+            Self::Ok(payload) => serde_json::json!({ "ok": decode_payload(protocol, payload) }),
+            Self::Error(error) => serde_json::json!({ "error": error }),
+        }
+    }
+}
+
+fn decode_payload(protocol: InvocationProtocol, payload: &[u8]) -> serde_json::Value {
+    if protocol == InvocationProtocol::Wasmbus {
+        if let Ok(value) = rmp_serde::from_slice(payload) {
+            return value;
+        }
+        if let Ok(value) = serde_cbor::from_slice(payload) {
+            return value;
+        }
+    }
+    match std::str::from_utf8(payload) {
+        Ok(s) => s.into(),
+        Err(_) => payload.into(),
+    }
+}
+
+/// Compares a captured response with the response to its replay, returning all differences
+pub fn diff_responses(
+    protocol: InvocationProtocol,
+    captured: &CapturedResponse,
+    replayed: &CapturedResponse,
+) -> Vec<ResponseDifference> {
+    if captured == replayed {
+        return Vec::new();
+    }
+    let captured_json = captured.to_json(protocol);
+    let replayed_json = replayed.to_json(protocol);
+    let differences = diff_json(&captured_json, &replayed_json);
+    if differences.is_empty() {
+        // Differing payloads may decode to the same value, so fall back to the raw payloads
+        let raw = |response: &CapturedResponse| match response {
+            CapturedResponse::Ok(payload) => serde_json::json!({ "ok": payload.as_ref() }),
+            CapturedResponse::Error(error) => serde_json::json!({ "error": error }),
+        };
+        return diff_json(&raw(captured), &raw(replayed));
+    }
+    differences
+}
+
+/// Returns all differences between two JSON values. Objects are compared by key and arrays by
+/// index, all other values are compared as a whole
+pub fn diff_json(
+    captured: &serde_json::Value,
+    replayed: &serde_json::Value,
+) -> Vec<ResponseDifference> {
+    let mut differences = Vec::new();
+    diff_json_at("$".to_string(), captured, replayed, &mut differences);
+    differences
+}
+
+fn diff_json_at(
+    path: String,
+    captured: &serde_json::Value,
+    replayed: &serde_json::Value,
+    differences: &mut Vec<ResponseDifference>,
+) {
+    use serde_json::Value;
+
+    match (captured, replayed) {
+        (Value::Object(captured), Value::Object(replayed)) => {
+            let keys: BTreeSet<_> = captured.keys().chain(replayed.keys()).collect();
+            for key in keys {
+                let path = format!("{path}.{key}");
+                match (captured.get(key), replayed.get(key)) {
+                    (Some(captured), Some(replayed)) => {
+                        diff_json_at(path, captured, replayed, differences)
+                    }
+                    (captured, replayed) => differences.push(ResponseDifference {
+                        path,
+                        captured: captured.cloned(),
+                        replayed: replayed.cloned(),
+                    }),
+                }
+            }
+        }
+        (Value::Array(captured), Value::Array(replayed)) => {
+            for i in 0..captured.len().max(replayed.len()) {
+                let path = format!("{path}[{i}]");
+                match (captured.get(i), replayed.get(i)) {
+                    (Some(captured), Some(replayed)) => {
+                        diff_json_at(path, captured, replayed, differences)
+                    }
+                    (captured, replayed) => differences.push(ResponseDifference {
+                        path,
+                        captured: captured.cloned(),
+                        replayed: replayed.cloned(),
+                    }),
+                }
+            }
+        }
+        (captured, replayed) if captured != replayed => differences.push(ResponseDifference {
+            path,
+            captured: Some(captured.clone()),
+            replayed: Some(replayed.clone()),
+        }),
+        _ => {}
+    }
 }
 
 pub struct WriteCapture {
@@ -102,13 +482,27 @@ pub struct WriteCapture {
 }
 
 impl WriteCapture {
-    /// Create a new WriteCapture that will write the capture tarball to the given path with the
-    /// expected inventory
-    pub async fn start(inventory: Vec<HostInventory>, path: impl AsRef<Path>) -> Result<Self> {
+    /// Create a new WriteCapture that will write the capture tarball of the given lattice to the
+    /// given path with the expected inventory
+    pub async fn start(
+        lattice_id: &str,
+        inventory: Vec<HostInventory>,
+        path: impl AsRef<Path>,
+    ) -> Result<Self> {
         let file = File::create(path).await?;
         let encoder = GzipEncoder::new(file);
         let mut builder = tokio_tar::Builder::new(encoder);
-        // We always start by encoding the inventory first
+        // We always start by encoding the manifest and the inventory first
+        let manifest_data = serde_json::to_vec(&CaptureManifest {
+            version: CAPTURE_FORMAT_VERSION,
+            lattice: Some(lattice_id.to_string()),
+        })?;
+        let mut header = tokio_tar::Header::new_gnu();
+        header.set_size(manifest_data.len() as u64);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, MANIFEST_FILE, Cursor::new(manifest_data))
+            .await?;
         let inventory_data = serde_json::to_vec(&inventory)?;
         let mut header = tokio_tar::Header::new_gnu();
         header.set_size(inventory_data.len() as u64);
@@ -163,6 +557,7 @@ mod test {
         let tempdir = tempfile::tempdir().unwrap();
         let tarball = tempdir.path().join("capture.tar.gz");
         let mut capture = WriteCapture::start(
+            "default",
             vec![HostInventory {
                 host_id: "test".to_string(),
                 ..Default::default()
@@ -179,6 +574,7 @@ mod test {
                 description: None,
                 length: 5,
                 published: time::OffsetDateTime::now_utc(),
+                headers: None,
             })
             .await
             .expect("Should be able to add a message");
@@ -190,6 +586,7 @@ mod test {
                 description: None,
                 length: 6,
                 published: time::OffsetDateTime::now_utc(),
+                headers: None,
             })
             .await
             .expect("Should be able to add a message");
@@ -203,6 +600,15 @@ mod test {
             .await
             .expect("Should be able to load a capture");
 
+        assert_eq!(
+            capture.manifest.version, CAPTURE_FORMAT_VERSION,
+            "Should have the current format version"
+        );
+        assert_eq!(
+            capture.manifest.lattice.as_deref(),
+            Some("default"),
+            "Should have the lattice of the capture"
+        );
         assert_eq!(
             capture.inventory.len(),
             1,
@@ -226,4 +632,221 @@ mod test {
             "Should have the right ordering"
         );
     }
+
+    fn message(
+        subject: &str,
+        reply: Option<&str>,
+        payload: impl Into<bytes::Bytes>,
+    ) -> SerializableMessage {
+        let payload = payload.into();
+        SerializableMessage {
+            subject: subject.to_string(),
+            reply: reply.map(ToString::to_string),
+            length: payload.len(),
+            payload,
+            description: None,
+            published: time::OffsetDateTime::UNIX_EPOCH,
+            headers: None,
+        }
+    }
+
+    #[test]
+    fn test_invocations() {
+        let legacy = rmp_serde::to_vec_named(&Invocation {
+            origin: wasmcloud_core::WasmCloudEntity {
+                public_key: "MACTOR".to_string(),
+                ..Default::default()
+            },
+            target: wasmcloud_core::WasmCloudEntity {
+                public_key: "VPROVIDER".to_string(),
+                link_name: "default".to_string(),
+                contract_id: "wasmcloud:keyvalue".to_string(),
+            },
+            operation: "KeyValue.Get".to_string(),
+            msg: b"key".to_vec(),
+            ..Default::default()
+        })
+        .expect("Should be able to encode an invocation");
+        let legacy_response = rmp_serde::to_vec_named(&InvocationResponse {
+            msg: b"value".to_vec(),
+            ..Default::default()
+        })
+        .expect("Should be able to encode a response");
+        let mut wrpc = message(
+            "default.http-component.wrpc.0.0.1.wasi:http/incoming-handler@0.2.0.handle",
+            Some("_INBOX.2"),
+            "params",
+        );
+        wrpc.headers = Some(BTreeMap::from([(
+            "source-id".to_string(),
+            vec!["http-server".to_string()],
+        )]));
+        let capture = ReadCapture {
+            manifest: CaptureManifest::default(),
+            inventory: Vec::new(),
+            messages: vec![
+                message(
+                    "wasmbus.rpc.default.VPROVIDER.default",
+                    Some("_INBOX.1"),
+                    legacy,
+                ),
+                wrpc,
+                message("_INBOX.2", None, ""),
+                message("_INBOX.2.error", None, "failed"),
+                message("_INBOX.1", None, legacy_response),
+                message("wasmbus.evt.default", None, "{}"),
+            ],
+        };
+
+        let invocations = capture.invocations();
+        assert_eq!(invocations.len(), 2, "Should only find invocations");
+
+        assert_eq!(invocations[0].protocol, InvocationProtocol::Wasmbus);
+        assert_eq!(invocations[0].origin.as_deref(), Some("MACTOR"));
+        assert_eq!(invocations[0].target, "VPROVIDER");
+        assert_eq!(invocations[0].interface, "KeyValue");
+        assert_eq!(invocations[0].operation, "Get");
+        assert_eq!(invocations[0].body(), "key");
+        assert_eq!(
+            invocations[0].response,
+            Some(CapturedResponse::Ok("value".into())),
+            "Should pair legacy responses by reply subject"
+        );
+
+        assert_eq!(invocations[1].protocol, InvocationProtocol::Wrpc);
+        assert_eq!(invocations[1].origin.as_deref(), Some("http-server"));
+        assert_eq!(invocations[1].target, "http-component");
+        assert_eq!(invocations[1].interface, "wasi:http/incoming-handler@0.2.0");
+        assert_eq!(invocations[1].operation, "handle");
+        assert_eq!(invocations[1].body(), "params");
+        assert_eq!(
+            invocations[1].response,
+            Some(CapturedResponse::Error("failed".to_string())),
+            "Should pair wRPC errors by reply subject"
+        );
+
+        let filter = |filter: CaptureFilter| {
+            invocations
+                .iter()
+                .filter(|inv| filter.matches(inv))
+                .map(|inv| inv.target.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            filter(CaptureFilter::default()),
+            ["VPROVIDER", "http-component"]
+        );
+        assert_eq!(
+            filter(CaptureFilter {
+                interface: Some("wasi:http/incoming-handler".to_string()),
+                ..Default::default()
+            }),
+            ["http-component"],
+            "Unversioned interfaces should match all versions"
+        );
+        assert!(filter(CaptureFilter {
+            interface: Some("wasi:http/incoming".to_string()),
+            ..Default::default()
+        })
+        .is_empty());
+        assert_eq!(
+            filter(CaptureFilter {
+                ids: vec!["MACTOR".to_string(), "VPROVIDER".to_string()],
+                operation: Some("Get".to_string()),
+                ..Default::default()
+            }),
+            ["VPROVIDER"]
+        );
+        assert!(filter(CaptureFilter {
+            since: Some(time::OffsetDateTime::UNIX_EPOCH + time::Duration::SECOND),
+            ..Default::default()
+        })
+        .is_empty());
+    }
+
+    #[test]
+    fn test_chunked_results() {
+        let capture = ReadCapture {
+            manifest: CaptureManifest::default(),
+            inventory: Vec::new(),
+            messages: vec![
+                message(
+                    "default.kv-component.wrpc.0.0.1.wasi:keyvalue/readwrite@0.1.0.get",
+                    Some("_INBOX.1"),
+                    "params",
+                ),
+                message("_INBOX.1", None, ""),
+                message("_INBOX.1.results", None, "first "),
+                message("_INBOX.1.results.0", None, "async"),
+                message("_INBOX.1.results", None, "second"),
+            ],
+        };
+        let invocations = capture.invocations();
+        assert_eq!(invocations.len(), 1);
+        assert_eq!(
+            invocations[0].response,
+            Some(CapturedResponse::Ok("first second".into())),
+            "Should join result chunks"
+        );
+    }
+
+    #[test]
+    fn test_diff_responses() {
+        let encode = |value: serde_json::Value| -> bytes::Bytes {
+            rmp_serde::to_vec(&value)
+                .expect("Should be able to encode a value")
+                .into()
+        };
+        let captured = CapturedResponse::Ok(encode(serde_json::json!({
+            "status": 200,
+            "headers": ["a", "b"],
+        })));
+        assert!(diff_responses(InvocationProtocol::Wasmbus, &captured, &captured).is_empty());
+
+        let replayed = CapturedResponse::Ok(encode(serde_json::json!({
+            "status": 500,
+            "headers": ["a"],
+            "body": "oops",
+        })));
+        assert_eq!(
+            diff_responses(InvocationProtocol::Wasmbus, &captured, &replayed),
+            [
+                ResponseDifference {
+                    path: "$.ok.body".to_string(),
+                    captured: None,
+                    replayed: Some("oops".into()),
+                },
+                ResponseDifference {
+                    path: "$.ok.headers[1]".to_string(),
+                    captured: Some("b".into()),
+                    replayed: None,
+                },
+                ResponseDifference {
+                    path: "$.ok.status".to_string(),
+                    captured: Some(200.into()),
+                    replayed: Some(500.into()),
+                },
+            ]
+        );
+
+        assert_eq!(
+            diff_responses(
+                InvocationProtocol::Wrpc,
+                &CapturedResponse::Ok("result".into()),
+                &CapturedResponse::Error("failed".to_string())
+            ),
+            [
+                ResponseDifference {
+                    path: "$.error".to_string(),
+                    captured: None,
+                    replayed: Some("failed".into()),
+                },
+                ResponseDifference {
+                    path: "$.ok".to_string(),
+                    captured: Some("result".into()),
+                    replayed: None,
+                },
+            ]
+        );
+    }
 }
@@ -1,22 +1,28 @@
 use std::{path::PathBuf, time::Duration};
 
-use anyhow::Result;
+use anyhow::{bail, ensure, Context, Result};
 use async_nats::jetstream::{
     consumer::{pull::Config as ConsumerConfig, AckPolicy, DeliverPolicy},
     stream::Config,
 };
+use bytes::Buf;
 use clap::{Parser, Subcommand};
-use futures::TryStreamExt;
+use futures::{StreamExt, TryStreamExt};
 use tokio::io::{stdin, stdout, AsyncReadExt, AsyncWriteExt};
 use tokio::time::Instant;
-use wasmcloud_core::Invocation;
+use wasmcloud_core::nats::rpc_inbox_prefix;
+use wasmcloud_core::InvocationResponse;
+use wrpc_transport::{ReceiveContext, Value};
+use wrpc_types::{DynamicFunction, Type};
 
 use super::{CliConnectionOpts, CommandOutput};
 use crate::config::WashConnectionOptions;
 use crate::{
-    capture::{ReadCapture, WriteCapture},
-    id::{ModuleId, ServiceId},
-    spier::{ObservedInvocation, ObservedMessage},
+    capture::{
+        diff_json, diff_responses, CaptureFilter, CapturedInvocation, CapturedResponse,
+        InvocationProtocol, ReadCapture, ResponseDifference, WriteCapture,
+    },
+    spier::ObservedMessage,
 };
 
 pub const CAPTURE_STREAM_NAME: &str = "wash-capture";
@@ -36,6 +42,16 @@ pub struct CaptureCommand {
     #[clap(name = "window_size", long = "window-size", default_value = "60")]
     pub window_size_minutes: u64,
 
+    /// When enabling capture, also capture responses to invocations so that replays can compare
+    /// against them. Responses are captured from the RPC inboxes of the lattice, which hosts and
+    /// their providers only use when the hosts are started with `--rpc-lattice-inbox`
+    #[clap(
+        name = "capture_responses",
+        long = "capture-responses",
+        requires = "enable"
+    )]
+    pub capture_responses: bool,
+
     #[clap(flatten)]
     pub opts: CliConnectionOpts,
 
@@ -55,85 +71,409 @@ pub struct CaptureReplayCommand {
     /// source or the target of the invocation. If provided with an provider ID, it will filter down
     /// to interactions only between the actor and provider
     #[clap(name = "actor_id", long = "actor-id", value_parser)]
-    pub actor_id: Option<ModuleId>,
+    pub actor_id: Option<String>,
 
     /// A provider ID to filter captured invocations by. This will filter anywhere the provider is
     /// the source or the target of the invocation. If provided with an actor ID, it will filter
     /// down to interactions only between the actor and provider
     #[clap(name = "provider_id", long = "provider-id", value_parser)]
-    pub provider_id: Option<ServiceId>,
+    pub provider_id: Option<String>,
+
+    /// An interface to filter captured invocations by, e.g. `wasi:http/incoming-handler`.
+    /// Interfaces without a version match all versions of the interface
+    #[clap(name = "interface", long = "interface")]
+    pub interface: Option<String>,
+
+    /// An operation to filter captured invocations by, e.g. `handle`
+    #[clap(name = "operation", long = "operation")]
+    pub operation: Option<String>,
+
+    /// Only replay invocations published at or after this RFC 3339 timestamp
+    #[clap(name = "since", long = "since", value_parser = parse_timestamp)]
+    pub since: Option<time::OffsetDateTime>,
+
+    /// Only replay invocations published at or before this RFC 3339 timestamp
+    #[clap(name = "until", long = "until", value_parser = parse_timestamp)]
+    pub until: Option<time::OffsetDateTime>,
+
+    /// Re-drive the captured invocations against the running lattice and compare the responses
+    /// with the captured ones. Only invocations with a captured response are re-driven
+    #[clap(name = "redrive", long = "redrive", conflicts_with = "component")]
+    pub redrive: bool,
+
+    /// Re-drive the captured wRPC invocations against the component at this path instead of a
+    /// running lattice and compare the results with the captured ones. Imports of the component
+    /// are not linked to any capabilities
+    #[clap(name = "component", long = "component")]
+    pub component: Option<PathBuf>,
 
     /// Whether or not to step through the replay one message at a time
     #[clap(name = "interactive", long = "interactive")]
     pub interactive: bool,
 
+    #[clap(flatten)]
+    pub opts: CliConnectionOpts,
+
     /// The file path to the capture file to read from
     #[clap(name = "capturefile")]
     pub capture_file_path: PathBuf,
 }
 
-pub async fn handle_replay_command(cmd: CaptureReplayCommand) -> Result<CommandOutput> {
-    let capture = ReadCapture::load(cmd.capture_file_path).await?;
+fn parse_timestamp(s: &str) -> Result<time::OffsetDateTime> {
+    time::OffsetDateTime::parse(s, &time::format_description::well_known::Rfc3339)
+        .with_context(|| format!("`{s}` is not a valid RFC 3339 timestamp"))
+}
 
-    let filtered = capture.messages.into_iter().filter_map(|msg| {
-        let mut inv: Invocation = rmp_serde::from_slice(&msg.payload).ok()?;
+/// Time to wait for a further chunk of results after a chunk of the maximum NATS payload size
+const RESULT_CHUNK_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// The target captured invocations are re-driven against
+enum Redrive {
+    Lattice {
+        nats: async_nats::Client,
+        timeout: Duration,
+    },
+    Component(wasmcloud_runtime::Component),
+}
 
-        if let Some(actor_id) = &cmd.actor_id {
-            if (inv.origin.is_actor() && inv.origin.public_key != actor_id.as_ref())
-                || (inv.target.is_actor() && inv.target.public_key != actor_id.as_ref())
-            {
-                return None;
+impl Redrive {
+    /// Re-drives `invocation`, returning all differences between the captured and the replayed
+    /// response
+    async fn redrive(
+        &self,
+        invocation: &CapturedInvocation,
+        captured: &CapturedResponse,
+    ) -> Result<Vec<ResponseDifference>> {
+        match self {
+            Self::Lattice { nats, timeout } => {
+                let replayed = tokio::time::timeout(*timeout, redrive_lattice(nats, invocation))
+                    .await
+                    .context("timed out waiting for a response")??;
+                Ok(diff_responses(invocation.protocol, captured, &replayed))
+            }
+            Self::Component(component) => {
+                let (captured, replayed) =
+                    redrive_component(component, invocation, captured).await?;
+                Ok(diff_json(&captured, &replayed))
             }
         }
+    }
+}
 
-        if let Some(provider_id) = &cmd.provider_id {
-            if (inv.origin.is_provider() && inv.origin.public_key != provider_id.as_ref())
-                || (inv.target.is_provider() && inv.target.public_key != provider_id.as_ref())
-            {
-                return None;
+async fn redrive_lattice(
+    nats: &async_nats::Client,
+    invocation: &CapturedInvocation,
+) -> Result<CapturedResponse> {
+    match invocation.protocol {
+        InvocationProtocol::Wasmbus => {
+            let msg = nats
+                .request(invocation.subject.clone(), invocation.payload.clone())
+                .await
+                .context("failed to send invocation")?;
+            let res: InvocationResponse =
+                rmp_serde::from_slice(&msg.payload).context("failed to decode response")?;
+            Ok(match res.error {
+                Some(error) => CapturedResponse::Error(error),
+                None => CapturedResponse::Ok(res.msg.into()),
+            })
+        }
+        InvocationProtocol::Wrpc => {
+            // Results and errors are published to subjects nested under the reply subject
+            let inbox = nats.new_inbox();
+            let mut responses = nats
+                .subscribe(format!("{inbox}.>"))
+                .await
+                .context("failed to subscribe to responses")?;
+            let mut headers = async_nats::HeaderMap::new();
+            for (name, values) in &invocation.headers {
+                for value in values {
+                    headers.append(name.as_str(), value.as_str());
+                }
+            }
+            nats.publish_with_reply_and_headers(
+                invocation.subject.clone(),
+                inbox,
+                headers,
+                invocation.payload.clone(),
+            )
+            .await
+            .context("failed to send invocation")?;
+            // Results exceeding the maximum NATS payload are split into chunks of the maximum
+            // size, so a shorter chunk is the last one
+            let max_payload = nats.server_info().max_payload;
+            let mut results = bytes::BytesMut::new();
+            loop {
+                let msg = if results.is_empty() {
+                    responses.next().await
+                } else {
+                    match tokio::time::timeout(RESULT_CHUNK_TIMEOUT, responses.next()).await {
+                        Ok(msg) => msg,
+                        // The last chunk happened to be exactly the maximum size
+                        Err(_) => break,
+                    }
+                }
+                .context("response subscription ended")?;
+                if msg.subject.ends_with(".error") {
+                    return Ok(CapturedResponse::Error(
+                        String::from_utf8_lossy(&msg.payload).into(),
+                    ));
+                }
+                // Async values are published to subjects nested under the results subject
+                if !msg.subject.ends_with(".results") {
+                    continue;
+                }
+                let len = msg.payload.len();
+                results.extend_from_slice(&msg.payload);
+                if len < max_payload {
+                    break;
+                }
             }
+            Ok(CapturedResponse::Ok(results.freeze()))
         }
+    }
+}
 
-        let body = inv.msg;
-        inv.msg = Vec::new();
-        let from = inv.origin.public_key.clone();
-        let to = inv.target.public_key.clone();
-
-        Some((
-            ObservedInvocation {
-                invocation: inv,
-                timestamp: chrono::Local::now(),
-                from,
-                to,
-                message: ObservedMessage::parse(body),
-            },
-            msg.published,
-        ))
-    });
+async fn redrive_component(
+    component: &wasmcloud_runtime::Component,
+    invocation: &CapturedInvocation,
+    captured: &CapturedResponse,
+) -> Result<(serde_json::Value, serde_json::Value)> {
+    ensure!(
+        invocation.protocol == InvocationProtocol::Wrpc,
+        "only wRPC invocations can be re-driven against a component"
+    );
+    let Some(DynamicFunction::Static { params, results }) = component
+        .exports()
+        .get(&invocation.interface)
+        .and_then(|functions| functions.get(&invocation.operation))
+    else {
+        bail!(
+            "component does not export `{}.{}`",
+            invocation.interface,
+            invocation.operation
+        )
+    };
+    let captured = match captured {
+        CapturedResponse::Ok(payload) => {
+            let values = decode_values(results, payload.clone())
+                .await
+                .context("failed to decode captured results")?;
+            serde_json::json!({ "ok": values.into_iter().map(value_to_json).collect::<Vec<_>>() })
+        }
+        CapturedResponse::Error(error) => serde_json::json!({ "error": error }),
+    };
+    let params = decode_values(params, invocation.payload.clone())
+        .await
+        .context("failed to decode captured parameters")?;
+    let replayed = match component
+        .call(&invocation.interface, &invocation.operation, params)
+        .await
+    {
+        Ok(values) => {
+            serde_json::json!({ "ok": values.into_iter().map(value_to_json).collect::<Vec<_>>() })
+        }
+        Err(err) => serde_json::json!({ "error": format!("{err:#}") }),
+    };
+    Ok((captured, replayed))
+}
+
+/// Decodes wRPC encoded values of the given types from `payload`
+async fn decode_values(types: &[Type], payload: bytes::Bytes) -> Result<Vec<Value>> {
+    let mut payload: Box<dyn Buf + Send> = Box::new(payload);
+    let mut values = Vec::with_capacity(types.len());
+    for ty in types {
+        let (value, rest) =
+            Value::receive_context_sync(ty, payload, &mut futures::stream::empty()).await?;
+        values.push(value);
+        payload = rest;
+    }
+    Ok(values)
+}
+
+/// Converts a wRPC value into JSON. Records and tuples become arrays, since wRPC values do not
+/// carry field names
+pub(crate) fn value_to_json(value: Value) -> serde_json::Value {
+    use serde_json::json;
+
+    let nested =
+        |value: Option<Box<Value>>| value.map_or(serde_json::Value::Null, |v| value_to_json(*v));
+    match value {
+        Value::Bool(v) => v.into(),
+        Value::U8(v) => v.into(),
+        Value::U16(v) => v.into(),
+        Value::U32(v) => v.into(),
+        Value::U64(v) => v.into(),
+        Value::S8(v) => v.into(),
+        Value::S16(v) => v.into(),
+        Value::S32(v) => v.into(),
+        Value::S64(v) => v.into(),
+        Value::Float32(v) => v.into(),
+        Value::Float64(v) => v.into(),
+        Value::Char(v) => v.to_string().into(),
+        Value::String(v) => v.into(),
+        Value::List(values) | Value::Record(values) | Value::Tuple(values) => {
+            values.into_iter().map(value_to_json).collect()
+        }
+        Value::Variant {
+            discriminant,
+            nested: value,
+        } => json!({ "case": discriminant, "value": nested(value) }),
+        Value::Enum(discriminant) => discriminant.into(),
+        Value::Option(value) => nested(value),
+        Value::Result(Ok(value)) => json!({ "ok": nested(value) }),
+        Value::Result(Err(value)) => json!({ "err": nested(value) }),
+        Value::Flags(flags) => flags.into(),
+        Value::Future(_) => "<future>".into(),
+        Value::Stream(_) => "<stream>".into(),
+    }
+}
+
+pub async fn handle_replay_command(cmd: CaptureReplayCommand) -> Result<CommandOutput> {
+    let capture = ReadCapture::load(&cmd.capture_file_path).await?;
+
+    let filter = CaptureFilter {
+        ids: cmd
+            .actor_id
+            .iter()
+            .map(ToString::to_string)
+            .chain(cmd.provider_id.iter().map(ToString::to_string))
+            .collect(),
+        interface: cmd.interface,
+        operation: cmd.operation,
+        since: cmd.since,
+        until: cmd.until,
+    };
+    let invocations: Vec<_> = capture
+        .invocations()
+        .into_iter()
+        .filter(|inv| filter.matches(inv))
+        .collect();
+
+    let redrive = if let Some(path) = &cmd.component {
+        let wasm = tokio::fs::read(path)
+            .await
+            .with_context(|| format!("failed to read component from `{}`", path.display()))?;
+        let rt = wasmcloud_runtime::Runtime::new().context("failed to construct runtime")?;
+        let component = wasmcloud_runtime::Component::new(&rt, wasm)
+            .with_context(|| format!("failed to compile component `{}`", path.display()))?;
+        Some(Redrive::Component(component))
+    } else if cmd.redrive {
+        let wco: WashConnectionOptions = cmd.opts.try_into()?;
+        let timeout = Duration::from_millis(wco.timeout_ms);
+        let nats = wco.into_nats_client().await?;
+        Some(Redrive::Lattice { nats, timeout })
+    } else {
+        None
+    };
 
+    let (mut matched, mut differed, mut failed, mut skipped) = (0, 0, 0, 0);
+    let mut results = Vec::new();
     let mut out = stdout();
-    for (msg, published) in filtered {
+    for inv in &invocations {
+        let message = match inv.protocol {
+            InvocationProtocol::Wasmbus => ObservedMessage::parse(inv.body().to_vec()),
+            InvocationProtocol::Wrpc => ObservedMessage::Raw(inv.body().to_vec()),
+        };
+        let response = match &inv.response {
+            Some(CapturedResponse::Ok(payload)) => match inv.protocol {
+                InvocationProtocol::Wasmbus => ObservedMessage::parse(payload.to_vec()).to_string(),
+                InvocationProtocol::Wrpc => ObservedMessage::Raw(payload.to_vec()).to_string(),
+            },
+            Some(CapturedResponse::Error(error)) => format!("error: {error}"),
+            None => "<not captured>".to_string(),
+        };
         println!(
             r#"
 [{}]
-From: {}  To: {}  Host: {}
-
-Operation: {}
-Message: {}"#,
-            published,
-            msg.from,
-            msg.to,
-            msg.invocation.host_id,
-            msg.invocation.operation,
-            msg.message
+From: {}  To: {}
+
+Interface: {}  Operation: {}
+Message: {}
+Response: {}"#,
+            inv.published,
+            inv.origin.as_deref().unwrap_or("<unknown>"),
+            inv.target,
+            inv.interface,
+            inv.operation,
+            message,
+            response,
         );
+
+        if let Some(redrive) = &redrive {
+            let (status, differences) = match &inv.response {
+                None => {
+                    skipped += 1;
+                    println!("Replay: skipped, the response was not captured");
+                    ("skipped", Vec::new())
+                }
+                Some(captured) => match redrive.redrive(inv, captured).await {
+                    Ok(differences) => {
+                        if differences.is_empty() {
+                            matched += 1;
+                            println!("Replay: matched");
+                            ("matched", differences)
+                        } else {
+                            differed += 1;
+                            println!("Replay: differs");
+                            for difference in &differences {
+                                let show = |value: &Option<serde_json::Value>| {
+                                    value.as_ref().map_or_else(
+                                        || "<missing>".to_string(),
+                                        ToString::to_string,
+                                    )
+                                };
+                                println!(
+                                    "  {}: {} -> {}",
+                                    difference.path,
+                                    show(&difference.captured),
+                                    show(&difference.replayed)
+                                );
+                            }
+                            ("differs", differences)
+                        }
+                    }
+                    Err(err) => {
+                        failed += 1;
+                        println!("Replay: failed: {err:#}");
+                        ("failed", Vec::new())
+                    }
+                },
+            };
+            results.push(serde_json::json!({
+                "subject": inv.subject,
+                "interface": inv.interface,
+                "operation": inv.operation,
+                "status": status,
+                "differences": differences,
+            }));
+        }
+
         if cmd.interactive {
-            out.write_all(b"Press Enter to continue...").await.unwrap();
-            out.flush().await.unwrap();
-            stdin().read_exact(&mut [0]).await.unwrap();
+            out.write_all(b"Press Enter to continue...").await?;
+            out.flush().await?;
+            stdin().read_exact(&mut [0]).await?;
         }
     }
-    Ok(CommandOutput::default())
+
+    if redrive.is_none() {
+        return Ok(CommandOutput::default());
+    }
+    let summary = format!(
+        "Replayed {} invocations: {matched} matched, {differed} differed, {failed} failed, {skipped} skipped",
+        invocations.len()
+    );
+    if differed > 0 || failed > 0 {
+        bail!("{summary}");
+    }
+    Ok(CommandOutput::new(
+        summary,
+        [
+            ("matched".to_string(), matched.into()),
+            ("skipped".to_string(), skipped.into()),
+            ("results".to_string(), results.into()),
+        ]
+        .into(),
+    ))
 }
 
 /// Handles the spy command, printing all output to stdout until the command is interrupted
@@ -153,6 +493,7 @@ pub async fn handle_command(cmd: CaptureCommand) -> Result<CommandOutput> {
             js_context,
             wco.lattice.as_deref().unwrap_or("default"),
             window_size,
+            cmd.capture_responses,
         )
         .await;
     } else if cmd.disable {
@@ -171,6 +512,7 @@ pub async fn enable(
     ctx: async_nats::jetstream::Context,
     lattice_id: &str,
     window_size: Duration,
+    capture_responses: bool,
 ) -> Result<CommandOutput> {
     // Until we get concrete errors, we should check for the stream and if it exists return a nice message that we're already enabled
     if ctx.get_stream(CAPTURE_STREAM_NAME).await.is_ok() {
@@ -179,13 +521,20 @@ pub async fn enable(
             format!("Capture is already enabled for lattice {lattice_id}"),
         ));
     }
+    let mut subjects = vec![
+        format!("wasmbus.rpc.{lattice_id}.>"),
+        format!("{lattice_id}.*.wrpc.>"),
+    ];
+    if capture_responses {
+        subjects.push(format!("{}.>", rpc_inbox_prefix(lattice_id)));
+    }
     ctx.create_stream(Config {
         name: stream_name(lattice_id),
         storage: async_nats::jetstream::stream::StorageType::File,
         max_age: window_size,
         // This needs to be set or it breaks invocations
         no_ack: true,
-        subjects: vec![format!("wasmbus.rpc.{}.>", lattice_id)],
+        subjects,
         ..Default::default()
     })
     .await
@@ -251,7 +600,7 @@ pub async fn capture(
         chrono::Local::now().to_rfc3339(),
         lattice_id
     );
-    let mut capture = WriteCapture::start(inventory, &filename).await?;
+    let mut capture = WriteCapture::start(lattice_id, inventory, &filename).await?;
 
     loop {
         tokio::select! {
@@ -311,13 +660,15 @@ async fn get_all_inventory(
         .await
         .map_err(|e| anyhow::anyhow!("{e:?}"))?
         .into_iter()
+        .filter_map(|host| host.response)
         .map(|host| (ctl_client.clone(), host.id))
         .map(|(client, host_id)| async move {
-            let inventory = client
+            client
                 .get_host_inventory(&host_id)
                 .await
-                .map_err(|e| anyhow::anyhow!("{e:?}"))?;
-            Ok(inventory)
+                .map_err(|e| anyhow::anyhow!("{e:?}"))?
+                .response
+                .with_context(|| format!("host {host_id} did not return an inventory"))
         });
     futures::future::join_all(futs).await.into_iter().collect()
 }
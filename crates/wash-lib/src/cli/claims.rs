@@ -823,7 +823,9 @@ pub async fn get_claims(
                 "Was able to connect to NATS, but failed to get claims: {:?}",
                 client
             )
-        })
+        })?
+        .response
+        .context("Host did not return any claims")
 }
 
 #[cfg(test)]
@@ -1573,6 +1575,7 @@ mod test {
                 wit_world: Some("wasmcloud:httpserver".to_string()),
                 rust_target: None,
                 bin_name: None,
+                targets: vec![],
             })
         );
 
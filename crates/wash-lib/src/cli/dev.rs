@@ -1,20 +1,45 @@
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
+use cloudevents::event::Event;
 use console::style;
+use tokio::sync::mpsc::Receiver;
+use tokio::time::Duration;
 use wasmcloud_control_interface::Client;
 
 use crate::{
     actor::update_actor,
     build::{build_project, SignConfig},
+    common::boxed_err_to_anyhow,
+    config::DEFAULT_START_PROVIDER_TIMEOUT_MS,
     generate::emoji,
-    id::{ModuleId, ServerId},
+    id::ServerId,
     parser::{ProjectConfig, TypeConfig},
+    wait::{
+        wait_for_provider_health_check_event, wait_for_provider_start_event,
+        wait_for_provider_stop_event, FindEventOutcome,
+    },
 };
 
+/// A provider run by the dev loop, which is restarted with the same settings whenever it is rebuilt
+#[derive(Clone, Debug)]
+pub struct DevProvider {
+    pub provider_id: String,
+    /// Reference of the provider archive built by the dev loop
+    pub provider_ref: String,
+    /// Names of the configs the provider is started with
+    pub config: Vec<String>,
+}
+
+/// The artifact run by the dev loop
+#[derive(Clone, Debug)]
+pub enum DevArtifact {
+    Actor { actor_id: String, actor_ref: String },
+    Provider(DevProvider),
+}
+
 /// Perform a single execution of the dev loop for an artifact
 pub async fn run_dev_loop(
     project_cfg: &ProjectConfig,
-    actor_id: ModuleId,
-    actor_ref: &str,
+    artifact: &DevArtifact,
     host_id: ServerId,
     ctl_client: &Client,
     sign_cfg: Option<SignConfig>,
@@ -24,15 +49,36 @@ pub async fn run_dev_loop(
         .canonicalize()?;
 
     // Restart the artifact so that changes can be observed
-    match project_cfg.project_type {
-        TypeConfig::Provider(_) => {
+    match (&project_cfg.project_type, artifact) {
+        (TypeConfig::Provider(_), DevArtifact::Provider(provider)) => {
+            eprintln!(
+                "{} {}",
+                emoji::RECYCLE,
+                style(format!(
+                    "restarting provider @ [{}]...",
+                    built_artifact_path.display()
+                ))
+                .bold(),
+            );
+
+            restart_dev_provider(ctl_client, &host_id, provider).await?;
             eprintln!(
                 "{} {}",
-                emoji::WARN,
-                style("`wash build` providers are not yet supported for dev, skipping...").bold(),
+                emoji::GREEN_CHECK,
+                style(format!(
+                    "provider [{}] restarted and healthy",
+                    provider.provider_id
+                ))
+                .bold(),
             );
         }
-        TypeConfig::Actor(_) => {
+        (
+            TypeConfig::Actor(_),
+            DevArtifact::Actor {
+                actor_id,
+                actor_ref,
+            },
+        ) => {
             eprintln!(
                 "{} {}",
                 emoji::RECYCLE,
@@ -43,9 +89,319 @@ pub async fn run_dev_loop(
                 .bold(),
             );
 
-            update_actor(ctl_client, &host_id, &actor_id, actor_ref).await?;
+            let ack = update_actor(ctl_client, &host_id, actor_id, actor_ref).await?;
+            if !ack.success {
+                bail!("Update actor ack not accepted: {}", ack.message);
+            }
         }
+        _ => bail!("dev artifact does not match the project type"),
+    }
+
+    Ok(())
+}
+
+/// Start a provider for the dev loop, waiting until it has started and passed a health check
+pub async fn start_dev_provider(
+    ctl_client: &Client,
+    host_id: &str,
+    provider_ref: &str,
+    provider_id: &str,
+    config: Vec<String>,
+) -> Result<DevProvider> {
+    let mut receiver = provider_events_receiver(ctl_client).await?;
+    start_provider_and_wait(
+        ctl_client,
+        &mut receiver,
+        host_id,
+        provider_ref,
+        provider_id,
+        config.clone(),
+    )
+    .await?;
+    Ok(DevProvider {
+        provider_id: provider_id.to_string(),
+        provider_ref: provider_ref.to_string(),
+        config,
+    })
+}
+
+/// Restart a provider after it was rebuilt.
+///
+/// The running provider is stopped and the new build is started with the same provider ID and
+/// configuration. Once the provider has started and passed a health check, the links of the
+/// provider are put again, so that it receives them.
+pub async fn restart_dev_provider(
+    ctl_client: &Client,
+    host_id: &str,
+    provider: &DevProvider,
+) -> Result<()> {
+    let timeout = Duration::from_millis(DEFAULT_START_PROVIDER_TIMEOUT_MS);
+    let links = ctl_client
+        .get_links()
+        .await
+        .map_err(boxed_err_to_anyhow)
+        .context("failed to query links")?;
+    if !links.success {
+        bail!("Failed to query links: {}", links.message);
+    }
+    let links = links
+        .response
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|ld| ld.source_id == provider.provider_id || ld.target == provider.provider_id)
+        .collect::<Vec<_>>();
+
+    let mut receiver = provider_events_receiver(ctl_client).await?;
+
+    let ack = ctl_client
+        .stop_provider(host_id, &provider.provider_id)
+        .await
+        .map_err(boxed_err_to_anyhow)
+        .with_context(|| format!("failed to stop provider [{}]", provider.provider_id))?;
+    if !ack.success {
+        bail!("Stop provider ack not accepted: {}", ack.message);
     }
+    wait_for_provider_stopped(&mut receiver, timeout, host_id, &provider.provider_id).await?;
+
+    start_provider_and_wait(
+        ctl_client,
+        &mut receiver,
+        host_id,
+        &provider.provider_ref,
+        &provider.provider_id,
+        provider.config.clone(),
+    )
+    .await?;
 
+    for ld in links {
+        let source_id = ld.source_id.clone();
+        let ack = ctl_client
+            .put_link(ld)
+            .await
+            .map_err(boxed_err_to_anyhow)
+            .with_context(|| format!("failed to put link of [{source_id}]"))?;
+        if !ack.success {
+            bail!(
+                "Put link of [{source_id}] ack not accepted: {}",
+                ack.message
+            );
+        }
+    }
     Ok(())
 }
+
+async fn provider_events_receiver(ctl_client: &Client) -> Result<Receiver<Event>> {
+    ctl_client
+        .events_receiver(vec![
+            "provider_started".to_string(),
+            "provider_start_failed".to_string(),
+            "provider_stopped".to_string(),
+            "provider_stop_failed".to_string(),
+            "health_check_passed".to_string(),
+            "health_check_failed".to_string(),
+        ])
+        .await
+        .map_err(boxed_err_to_anyhow)
+        .context("Failed to get lattice event channel")
+}
+
+/// Start a provider and wait for it to start and pass a health check
+async fn start_provider_and_wait(
+    ctl_client: &Client,
+    receiver: &mut Receiver<Event>,
+    host_id: &str,
+    provider_ref: &str,
+    provider_id: &str,
+    config: Vec<String>,
+) -> Result<()> {
+    let timeout = Duration::from_millis(DEFAULT_START_PROVIDER_TIMEOUT_MS);
+    let ack = ctl_client
+        .start_provider(host_id, provider_ref, provider_id, None, config)
+        .await
+        .map_err(boxed_err_to_anyhow)
+        .with_context(|| format!("failed to start provider [{provider_ref}]"))?;
+    if !ack.success {
+        bail!("Start provider ack not accepted: {}", ack.message);
+    }
+
+    wait_for_provider_started_and_healthy(receiver, timeout, host_id, provider_ref).await?;
+    Ok(())
+}
+
+/// Wait for the provider to stop, skipping events of the provider before it stopped
+async fn wait_for_provider_stopped(
+    receiver: &mut Receiver<Event>,
+    timeout: Duration,
+    host_id: &str,
+    provider_id: &str,
+) -> Result<()> {
+    match wait_for_provider_stop_event(
+        receiver,
+        timeout,
+        host_id.to_string(),
+        provider_id.to_string(),
+    )
+    .await?
+    {
+        FindEventOutcome::Success(_) => Ok(()),
+        FindEventOutcome::Failure(err) => {
+            Err(err).with_context(|| format!("failed to stop provider [{provider_id}]"))
+        }
+    }
+}
+
+/// Wait for the provider to start and then pass a health check. Health checks received before
+/// the provider started belong to a previous instance of it and are skipped. Returns the ID of
+/// the started provider
+async fn wait_for_provider_started_and_healthy(
+    receiver: &mut Receiver<Event>,
+    timeout: Duration,
+    host_id: &str,
+    provider_ref: &str,
+) -> Result<String> {
+    let started = match wait_for_provider_start_event(
+        receiver,
+        timeout,
+        host_id.to_string(),
+        provider_ref.to_string(),
+    )
+    .await?
+    {
+        FindEventOutcome::Success(info) => info,
+        FindEventOutcome::Failure(err) => {
+            return Err(err).with_context(|| format!("failed to start provider [{provider_ref}]"))
+        }
+    };
+
+    eprintln!(
+        "{} {}",
+        emoji::HOURGLASS_DRAINING,
+        style(format!(
+            "waiting for provider [{}] to pass a health check...",
+            started.provider_id
+        ))
+        .bold(),
+    );
+    match wait_for_provider_health_check_event(
+        receiver,
+        timeout,
+        host_id.to_string(),
+        started.provider_id.clone(),
+    )
+    .await?
+    {
+        FindEventOutcome::Success(_) => Ok(started.provider_id),
+        FindEventOutcome::Failure(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use cloudevents::{Event, EventBuilder, EventBuilderV10};
+    use serde_json::json;
+    use tokio::sync::mpsc;
+    use tokio::time::Duration;
+
+    use super::{wait_for_provider_started_and_healthy, wait_for_provider_stopped};
+
+    const HOST_ID: &str = "NHOST";
+    const PROVIDER_ID: &str = "VPROVIDER";
+    const PROVIDER_REF: &str = "file:///provider.par.gz";
+    const TIMEOUT: Duration = Duration::from_millis(100);
+
+    fn event(host_id: &str, name: &str, data: serde_json::Value) -> Result<Event> {
+        Ok(EventBuilderV10::new()
+            .id(name)
+            .source(host_id)
+            .ty(format!("com.wasmcloud.lattice.{name}"))
+            .data("application/json", data)
+            .build()?)
+    }
+
+    fn provider_event(name: &str) -> Result<Event> {
+        event(
+            HOST_ID,
+            name,
+            json!({
+                "provider_id": PROVIDER_ID,
+                "image_ref": PROVIDER_REF,
+                "provider_ref": PROVIDER_REF,
+                "error": "failure",
+            }),
+        )
+    }
+
+    fn events(events: Vec<Event>) -> mpsc::Receiver<Event> {
+        let (tx, rx) = mpsc::channel(events.len().max(1));
+        for event in events {
+            tx.try_send(event).expect("failed to send event");
+        }
+        rx
+    }
+
+    #[tokio::test]
+    async fn restart_waits_for_stop_start_and_health_check() -> Result<()> {
+        let mut receiver = events(vec![
+            // health check of the instance being stopped
+            provider_event("health_check_passed")?,
+            provider_event("provider_stopped")?,
+            // events of other hosts are ignored
+            event(
+                "NOTHER",
+                "provider_started",
+                json!({ "provider_id": PROVIDER_ID, "image_ref": PROVIDER_REF }),
+            )?,
+            provider_event("provider_started")?,
+            provider_event("health_check_passed")?,
+        ]);
+        wait_for_provider_stopped(&mut receiver, TIMEOUT, HOST_ID, PROVIDER_ID).await?;
+        let provider_id =
+            wait_for_provider_started_and_healthy(&mut receiver, TIMEOUT, HOST_ID, PROVIDER_REF)
+                .await?;
+        assert_eq!(provider_id, PROVIDER_ID);
+        assert!(
+            receiver.try_recv().is_err(),
+            "all events should be consumed"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn health_check_before_start_is_skipped() -> Result<()> {
+        let mut receiver = events(vec![
+            provider_event("health_check_passed")?,
+            provider_event("provider_started")?,
+        ]);
+        wait_for_provider_started_and_healthy(&mut receiver, TIMEOUT, HOST_ID, PROVIDER_REF)
+            .await
+            .expect_err("the provider should not be healthy before a health check after start");
+
+        let mut receiver = events(vec![
+            provider_event("provider_started")?,
+            provider_event("health_check_failed")?,
+        ]);
+        wait_for_provider_started_and_healthy(&mut receiver, TIMEOUT, HOST_ID, PROVIDER_REF)
+            .await
+            .expect_err("a failed health check should fail the start");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn failures_are_reported() -> Result<()> {
+        let mut receiver = events(vec![provider_event("provider_stop_failed")?]);
+        wait_for_provider_stopped(&mut receiver, TIMEOUT, HOST_ID, PROVIDER_ID)
+            .await
+            .expect_err("a failed stop should be reported");
+
+        let mut receiver = events(vec![
+            provider_event("provider_start_failed")?,
+            provider_event("provider_started")?,
+            provider_event("health_check_passed")?,
+        ]);
+        wait_for_provider_started_and_healthy(&mut receiver, TIMEOUT, HOST_ID, PROVIDER_REF)
+            .await
+            .expect_err("a failed start should be reported");
+        Ok(())
+    }
+}
@@ -63,10 +63,19 @@ pub async fn get_host_inventories(cmd: GetHostInventoriesCommand) -> Result<Vec<
     let client = wco.into_ctl_client(None).await?;
 
     if let Some(host_id) = cmd.host_id {
-        Ok(vec![client
+        let inventory = client
             .get_host_inventory(&host_id)
             .await
-            .map_err(boxed_err_to_anyhow)?])
+            .map_err(boxed_err_to_anyhow)?;
+        if !inventory.success {
+            anyhow::bail!(
+                "Failed to get inventory of host {host_id}: {}",
+                inventory.message
+            );
+        }
+        Ok(vec![inventory.response.with_context(|| {
+            format!("Host {host_id} did not return an inventory")
+        })?])
     } else {
         let hosts = get_all_inventories(&client)
             .await
@@ -84,9 +93,12 @@ pub async fn get_host_inventories(cmd: GetHostInventoriesCommand) -> Result<Vec<
 pub async fn get_hosts(cmd: GetHostsCommand) -> Result<Vec<Host>> {
     let wco: WashConnectionOptions = cmd.opts.try_into()?;
     let client = wco.into_ctl_client(None).await?;
-    client
+    Ok(client
         .get_hosts()
         .await
         .map_err(boxed_err_to_anyhow)
-        .context("Was able to connect to NATS, but failed to get hosts.")
+        .context("Was able to connect to NATS, but failed to get hosts.")?
+        .into_iter()
+        .filter_map(|host| host.response)
+        .collect())
 }
@@ -1,11 +1,12 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use clap::Parser;
-use wasmcloud_control_interface::LinkDefinition;
+use wasmcloud_control_interface::{Client as CtlClient, InterfaceLinkDefinition, LinkLimits};
 
 use crate::{
-    cli::{labels_vec_to_hashmap, CliConnectionOpts},
-    common::{boxed_err_to_anyhow, find_actor_id, find_provider_id},
+    cli::CliConnectionOpts,
+    common::{boxed_err_to_anyhow, find_component_id},
     config::WashConnectionOptions,
+    id::split_contract_id,
 };
 
 #[derive(Parser, Debug, Clone)]
@@ -13,12 +14,13 @@ pub struct LinkDelCommand {
     #[clap(flatten)]
     pub opts: CliConnectionOpts,
 
-    /// Public key ID or name of actor to match on. If an actor name is given and matches multiple
-    /// actors, an error will be returned with a list of matching actors and their IDs.
-    #[clap(name = "actor-id")]
-    pub actor_id: String,
+    /// ID or name of the source of the link. If a name is given and matches multiple actors or
+    /// providers, an error will be returned with a list of matching components and their IDs.
+    #[clap(name = "source-id")]
+    pub source_id: String,
 
-    /// Capability contract ID between actor and provider
+    /// Capability contract ID of the link, the WIT namespace and package of its interfaces (e.g.
+    /// `wasi:keyvalue`)
     #[clap(name = "contract-id")]
     pub contract_id: String,
 
@@ -29,33 +31,96 @@ pub struct LinkDelCommand {
 
 #[derive(Parser, Debug, Clone)]
 #[clap(
-    override_usage = "wash ctl link put --link-name <LINK_NAME> [OPTIONS] <actor-id-or-name> <provider-id-or-name> <contract-id> [values]..."
+    override_usage = "wash ctl link put --interface <INTERFACE> [OPTIONS] <source-id-or-name> <target-id-or-name> <contract-id>"
 )]
 pub struct LinkPutCommand {
     #[clap(flatten)]
     pub opts: CliConnectionOpts,
 
-    /// Public key ID or name of actor to match on. If an actor name is given and matches multiple
-    /// actors, an error will be returned with a list of matching actors and their IDs.
-    #[clap(name = "actor-id")]
-    pub actor_id: String,
+    /// ID or name of the source of the link. If a name is given and matches multiple actors or
+    /// providers, an error will be returned with a list of matching components and their IDs.
+    #[clap(name = "source-id")]
+    pub source_id: String,
 
-    /// Public key ID or name of provider to match on. If an provider name is given and matches
-    /// multiple providers, an error will be returned with a list of matching actors and their IDs.
-    #[clap(name = "provider-id")]
-    pub provider_id: String,
+    /// ID or name of the target of the link. If a name is given and matches multiple actors or
+    /// providers, an error will be returned with a list of matching components and their IDs.
+    #[clap(name = "target")]
+    pub target: String,
 
-    /// Capability contract ID between actor and provider
+    /// Capability contract ID of the link, the WIT namespace and package of its interfaces (e.g.
+    /// `wasi:keyvalue`)
     #[clap(name = "contract-id")]
     pub contract_id: String,
 
+    /// WIT interfaces of the link, e.g. `readwrite` in `wasi:keyvalue/readwrite`. Can be passed
+    /// more than once
+    #[clap(long = "interface", required = true)]
+    pub interfaces: Vec<String>,
+
+    /// Names of the configs to supply to the source of the link. Can be passed more than once
+    #[clap(long = "source-config")]
+    pub source_config: Vec<String>,
+
+    /// Names of the configs to supply to the target of the link. Can be passed more than once
+    #[clap(long = "target-config")]
+    pub target_config: Vec<String>,
+
     /// Link name, defaults to "default"
     #[clap(short = 'l', long = "link-name")]
     pub link_name: Option<String>,
 
-    /// Environment values to provide alongside link
-    #[clap(name = "values")]
-    pub values: Vec<String>,
+    /// Maximum sustained number of invocations per second the source may make over the link
+    #[clap(long = "rate-per-second", value_parser = clap::value_parser!(u32).range(1..))]
+    pub rate_per_second: Option<u32>,
+
+    /// Maximum number of invocations the source may make over the link in a burst, defaults to
+    /// `--rate-per-second`
+    #[clap(long = "burst", value_parser = clap::value_parser!(u32).range(1..))]
+    pub burst: Option<u32>,
+
+    /// Maximum number of invocations over the link that may be in flight at the same time
+    #[clap(long = "max-in-flight", value_parser = clap::value_parser!(u32).range(1..))]
+    pub max_in_flight: Option<u32>,
+
+    /// Validate the link config against the config schema of the target without putting the link
+    #[clap(long = "dry-run")]
+    pub dry_run: bool,
+}
+
+impl TryFrom<LinkPutCommand> for InterfaceLinkDefinition {
+    type Error = anyhow::Error;
+
+    fn try_from(cmd: LinkPutCommand) -> Result<Self> {
+        let (wit_namespace, wit_package) = split_contract_id(&cmd.contract_id)?;
+        Ok(InterfaceLinkDefinition {
+            source_id: cmd.source_id,
+            target: cmd.target,
+            name: cmd.link_name.unwrap_or_else(|| "default".to_string()),
+            wit_namespace,
+            wit_package,
+            interfaces: cmd.interfaces,
+            source_config: cmd.source_config,
+            target_config: cmd.target_config,
+            limits: (cmd.rate_per_second.is_some()
+                || cmd.burst.is_some()
+                || cmd.max_in_flight.is_some())
+            .then_some(LinkLimits {
+                rate_per_second: cmd.rate_per_second,
+                burst: cmd.burst,
+                max_in_flight: cmd.max_in_flight,
+            }),
+        })
+    }
+}
+
+/// Resolves the source and target of `link`, which may be given by name, to component IDs
+async fn resolve_link_ids(
+    ctl_client: &CtlClient,
+    mut link: InterfaceLinkDefinition,
+) -> Result<InterfaceLinkDefinition> {
+    link.source_id = find_component_id(&link.source_id, ctl_client).await?.0;
+    link.target = find_component_id(&link.target, ctl_client).await?.0;
+    Ok(link)
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -88,15 +153,19 @@ pub enum LinkCommand {
 /// # Examples
 ///
 /// ```no_run
-/// let ack = query_links(WashConnectionOptions::default()).await?;
-/// assert_eq!(ack.accepted, true);
+/// let links = query_links(WashConnectionOptions::default()).await?;
 /// ```
-pub async fn query_links(wco: WashConnectionOptions) -> Result<Vec<LinkDefinition>> {
-    wco.into_ctl_client(None)
+pub async fn query_links(wco: WashConnectionOptions) -> Result<Vec<InterfaceLinkDefinition>> {
+    let response = wco
+        .into_ctl_client(None)
         .await?
-        .query_links()
+        .get_links()
         .await
-        .map_err(boxed_err_to_anyhow)
+        .map_err(boxed_err_to_anyhow)?;
+    if !response.success {
+        bail!("Failed to query links: {}", response.message);
+    }
+    Ok(response.response.unwrap_or_default())
 }
 
 /// Delete a single link
@@ -104,43 +173,42 @@ pub async fn query_links(wco: WashConnectionOptions) -> Result<Vec<LinkDefinitio
 /// # Arguments
 ///
 /// * `wco` - Options for connecting to wash
-/// * `actor_id` - The ID of the actor attached to the link
+/// * `source_id` - The ID or name of the source of the link
 /// * `contract_id` - The contract ID of the link
 /// * `link_name` - The link name of the link ('default')
 ///
 /// # Examples
 ///
 /// ```no_run
-/// let ack = delete_link(
+/// delete_link(
 ///   WashConnectionOptions::default(),
-///   "wasmcloud:httpserver",
-///   "MBCFOPM6JW2APJLXJD3Z5O4CN7CPYJ2B4FTKLJUR5YR5MITIU7HD3WD5", // wasmcloud.azurecr.io/echo:0.3.8
+///   "echo",
+///   "wasi:http",
 ///   "default",
 /// ).await?;
-/// assert_eq!(ack.accepted, true);
 /// ```
 pub async fn delete_link(
     wco: WashConnectionOptions,
+    source_id: &str,
     contract_id: &str,
-    actor_id: &str,
     link_name: &str,
 ) -> Result<()> {
+    let (wit_namespace, wit_package) = split_contract_id(contract_id)?;
     let ctl_client = wco.into_ctl_client(None).await?;
-    ctl_client
-        .remove_link(
-            &find_actor_id(actor_id, &ctl_client).await?.0,
-            contract_id,
-            link_name,
-        )
+    let source_id = find_component_id(source_id, &ctl_client).await?.0;
+    let ack = ctl_client
+        .delete_link(&source_id, link_name, &wit_namespace, &wit_package)
         .await
-        .map(|_| ())
         .map_err(boxed_err_to_anyhow)
         .with_context(|| {
             format!(
-                "Failed to remove link between {} and {} with link name {}",
-                actor_id, contract_id, link_name
+                "Failed to remove link from {source_id} on {wit_namespace}:{wit_package} with link name {link_name}"
             )
-        })
+        })?;
+    if !ack.success {
+        bail!("Delete link not accepted: {}", ack.message);
+    }
+    Ok(())
 }
 
 /// Create ("put") a new link
@@ -148,48 +216,97 @@ pub async fn delete_link(
 /// # Arguments
 ///
 /// * `wco` - Options for connecting to wash
-/// * `contract_id` - The contract ID of the link
-/// * `actor_id` - The ID of the actor attached to the link
-/// * `provider_id` - The ID of the provider attached to the link
-/// * `link_name` - The link name of the link ('default')
+/// * `link` - The link to put, whose source and target may be given by name
 ///
 /// # Examples
 ///
 /// ```no_run
-/// let ack = delete_link(
+/// create_link(
 ///   WashConnectionOptions::default(),
-///   "wasmcloud:httpserver",
-///   "MBCFOPM6JW2APJLXJD3Z5O4CN7CPYJ2B4FTKLJUR5YR5MITIU7HD3WD5", // wasmcloud.azurecr.io/echo:0.3.8
-///   "VAG3QITQQ2ODAOWB5TTQSDJ53XK3SHBEIFNK4AYJ5RKAX2UNSCAPHA5M", // wasmcloud.azurecr.io/httpserver:0.17.0
-///   "default",
-///   vec!["KEY", "value"],
+///   InterfaceLinkDefinition {
+///     source_id: "http-server".to_string(),
+///     target: "echo".to_string(),
+///     name: "default".to_string(),
+///     wit_namespace: "wasi".to_string(),
+///     wit_package: "http".to_string(),
+///     interfaces: vec!["incoming-handler".to_string()],
+///     ..Default::default()
+///   },
+/// ).await?;
+/// ```
+pub async fn create_link(wco: WashConnectionOptions, link: InterfaceLinkDefinition) -> Result<()> {
+    let description = format!(
+        "link from {} to {} on {}:{} with link name {}",
+        link.source_id, link.target, link.wit_namespace, link.wit_package, link.name
+    );
+    let ctl_client = wco.into_ctl_client(None).await?;
+    let link = resolve_link_ids(&ctl_client, link).await?;
+    let ack = ctl_client
+        .put_link(link)
+        .await
+        .map_err(boxed_err_to_anyhow)
+        .with_context(|| format!("Failed to create {description}"))?;
+    if !ack.success {
+        bail!("Put {description} not accepted: {}", ack.message);
+    }
+    Ok(())
+}
+
+/// Result of validating a link with [`validate_link`]
+#[derive(Debug, Default)]
+pub struct LinkValidation {
+    /// Description of every violation, if the link config is invalid
+    pub violations: Option<String>,
+    /// Description of the config schemas which are invalid, and were therefore not used to
+    /// validate the link config. Invalid schemas do not cause a link to be rejected.
+    pub invalid_schemas: Option<String>,
+}
+
+/// Validate a new link against the config schemas of its source and target without putting it
+///
+/// # Arguments
+///
+/// * `wco` - Options for connecting to wash
+/// * `link` - The link to validate, whose source and target may be given by name
+///
+/// # Examples
+///
+/// ```no_run
+/// let validation = validate_link(
+///   WashConnectionOptions::default(),
+///   InterfaceLinkDefinition {
+///     source_id: "echo".to_string(),
+///     target: "kvredis".to_string(),
+///     name: "default".to_string(),
+///     wit_namespace: "wasi".to_string(),
+///     wit_package: "keyvalue".to_string(),
+///     interfaces: vec!["readwrite".to_string()],
+///     target_config: vec!["redis-url".to_string()],
+///     ..Default::default()
+///   },
 /// ).await?;
-/// assert_eq!(ack.accepted, true);
+/// assert!(validation.violations.is_none());
 /// ```
-pub async fn create_link(
+pub async fn validate_link(
     wco: WashConnectionOptions,
-    contract_id: &str,
-    actor_id: &str,
-    provider_id: &str,
-    link_name: &str,
-    link_values: &Vec<String>,
-) -> Result<()> {
+    link: InterfaceLinkDefinition,
+) -> Result<LinkValidation> {
     let ctl_client = wco.into_ctl_client(None).await?;
-    ctl_client
-        .advertise_link(
-            &find_actor_id(actor_id, &ctl_client).await?.0,
-            &find_provider_id(provider_id, &ctl_client).await?.0,
-            contract_id,
-            link_name,
-            labels_vec_to_hashmap(link_values.clone())?,
-        )
+    let link = resolve_link_ids(&ctl_client, link).await?;
+    let response = ctl_client
+        .validate_link(link)
         .await
-        .map(|_| ())
         .map_err(boxed_err_to_anyhow)
-        .with_context(|| {
-            format!(
-                "Failed to create link between {} and {} with contract {}. Link name: {}, values: {:?}",
-                actor_id, provider_id, contract_id, link_name, &link_values
-            )
-        })
+        .context("Failed to validate link")?;
+    Ok(if response.success {
+        LinkValidation {
+            violations: None,
+            invalid_schemas: (!response.message.is_empty()).then_some(response.message),
+        }
+    } else {
+        LinkValidation {
+            violations: Some(response.message),
+            invalid_schemas: None,
+        }
+    })
 }
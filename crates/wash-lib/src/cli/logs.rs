@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use futures::StreamExt;
+use serde_json::json;
+
+use wasmcloud_control_interface::CtlResponse;
+pub use wasmcloud_control_interface::ProviderLogLine;
+
+use crate::{
+    cli::{stop::find_host_with_provider, CliConnectionOpts, CommandOutput},
+    common::{boxed_err_to_anyhow, find_host_id, find_provider_id},
+    config::WashConnectionOptions,
+};
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum LogsCommand {
+    /// Show the output of a capability provider running in a host
+    #[clap(name = "provider")]
+    Provider(ProviderLogsCommand),
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct ProviderLogsCommand {
+    #[clap(flatten)]
+    pub opts: CliConnectionOpts,
+
+    /// Id of host the provider is running on. If a non-ID is provided, the host will be selected
+    /// based on matching the prefix of the ID or the friendly name and will return an error if more
+    /// than one host matches. If omitted, the host running the provider is looked up
+    #[clap(long = "host-id")]
+    pub host_id: Option<String>,
+
+    /// Provider Id (e.g. the public key for the provider) or a string to match on the friendly name
+    /// of the provider
+    #[clap(name = "provider-id")]
+    pub provider_id: String,
+
+    /// Number of most recent lines of output to show. All lines retained by the host are shown
+    /// if omitted
+    #[clap(long = "tail")]
+    pub tail: Option<usize>,
+
+    /// Keep streaming output of the provider until interrupted. Requires the host to be started
+    /// with `--publish-provider-logs`
+    #[clap(short = 'f', long = "follow")]
+    pub follow: bool,
+}
+
+pub async fn handle_command(command: LogsCommand) -> Result<CommandOutput> {
+    match command {
+        LogsCommand::Provider(cmd) => handle_provider_logs(cmd).await,
+    }
+}
+
+/// Handles the provider logs command. When following, new output is printed to stdout until the
+/// command is interrupted
+pub async fn handle_provider_logs(cmd: ProviderLogsCommand) -> Result<CommandOutput> {
+    let wco: WashConnectionOptions = cmd.opts.try_into()?;
+    let lattice = wco.get_lattice();
+    let ctl_client = wco.clone().into_ctl_client(None).await?;
+    let nats_client = wco.into_nats_client().await?;
+
+    let (provider_id, _) = find_provider_id(&cmd.provider_id, &ctl_client).await?;
+    let host_id = if let Some(host_id) = cmd.host_id {
+        find_host_id(&host_id, &ctl_client).await?.0
+    } else {
+        find_host_with_provider(&provider_id, &ctl_client).await?
+    };
+
+    // Subscribe before requesting the retained lines, so no output is missed in between
+    let mut subscriber = if cmd.follow {
+        Some(
+            nats_client
+                .subscribe(format!("wasmbus.logs.{lattice}.{provider_id}"))
+                .await
+                .context("failed to subscribe to provider logs")?,
+        )
+    } else {
+        None
+    };
+
+    let CtlResponse {
+        success,
+        message,
+        response,
+    } = ctl_client
+        .get_provider_logs(&host_id, &provider_id, cmd.tail)
+        .await
+        .map_err(boxed_err_to_anyhow)
+        .context("failed to request provider logs from host")?;
+    if !success {
+        bail!("Operation failed: {message}");
+    }
+    let lines = response.unwrap_or_default();
+
+    let Some(subscriber) = subscriber.as_mut() else {
+        let mut map = HashMap::new();
+        map.insert("lines".to_string(), json!(lines));
+        return Ok(CommandOutput::new(
+            lines
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n"),
+            map,
+        ));
+    };
+
+    for line in &lines {
+        println!("{line}");
+    }
+    // Lines written while the history was requested are received on the subscription as well
+    let mut printed = lines;
+    while let Some(msg) = subscriber.next().await {
+        let Ok(line) = serde_json::from_slice::<ProviderLogLine>(&msg.payload) else {
+            continue;
+        };
+        // Skip output of the provider on other hosts and lines already printed from the history
+        if line.host_id != host_id.to_string() {
+            continue;
+        }
+        if let Some(i) = printed.iter().position(|printed| *printed == line) {
+            printed.swap_remove(i);
+            continue;
+        }
+        println!("{line}");
+    }
+
+    println!("Log subscriber closed");
+
+    Ok(CommandOutput::default())
+}
@@ -39,6 +39,7 @@ pub mod get;
 pub mod inspect;
 pub mod label;
 pub mod link;
+pub mod logs;
 pub mod output;
 pub mod par;
 pub mod registry;
@@ -46,6 +47,7 @@ pub mod scale;
 pub mod spy;
 pub mod start;
 pub mod stop;
+pub mod testing;
 pub mod update;
 
 /// Used for displaying human-readable output vs JSON format
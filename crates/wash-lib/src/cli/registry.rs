@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use clap::{Parser, Subcommand};
 
 #[derive(Parser, Debug, Clone)]
@@ -67,12 +69,30 @@ pub struct RegistryPullCommand {
 #[derive(Parser, Debug, Clone)]
 pub struct RegistryPushCommand {
     /// URL to push artifact to
-    #[clap(name = "url")]
-    pub url: String,
+    #[clap(name = "url", required_unless_present = "workspace")]
+    pub url: Option<String>,
 
     /// Path to artifact to push
-    #[clap(name = "artifact")]
-    pub artifact: String,
+    #[clap(name = "artifact", required_unless_present = "workspace")]
+    pub artifact: Option<String>,
+
+    /// Build and push the members of the workspace configured in the wasmcloud.toml file at this
+    /// path or in this folder, tagged as `<registry>/<name>:<version>`
+    #[clap(
+        long = "workspace",
+        num_args = 0..=1,
+        default_missing_value = ".",
+        conflicts_with_all = ["url", "artifact", "config"]
+    )]
+    pub workspace: Option<PathBuf>,
+
+    /// Push only the workspace members with these names
+    #[clap(long = "member", name = "member", requires = "workspace")]
+    pub members: Vec<String>,
+
+    /// Number of workspace members to build and push at once. Defaults to the number of CPUs
+    #[clap(short = 'j', long = "jobs", requires = "workspace")]
+    pub jobs: Option<usize>,
 
     /// Registry of artifact. This is only needed if the URL is not a full (OCI) artifact URL (ie, missing the registry fragment)
     #[clap(short = 'r', long = "registry", env = "WASH_REG_URL")]
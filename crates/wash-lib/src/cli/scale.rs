@@ -1,10 +1,11 @@
 use anyhow::Result;
 use clap::Parser;
+use wasmcloud_control_interface::ComponentLimits;
 
 use crate::{
     actor::scale_actor,
     cli::{labels_vec_to_hashmap, CliConnectionOpts, CommandOutput},
-    common::find_host_id,
+    common::{component_id_from_ref, find_host_id},
     config::WashConnectionOptions,
 };
 
@@ -29,6 +30,11 @@ pub struct ScaleActorCommand {
     #[clap(name = "actor-ref")]
     pub actor_ref: String,
 
+    /// Unique ID of the actor to scale. Defaults to the name of the actor reference without its
+    /// tag, e.g. `echo` for `wasmcloud.azurecr.io/echo:0.3.8`
+    #[clap(long = "actor-id")]
+    pub actor_id: Option<String>,
+
     /// Maximum number of actor instances allowed to run concurrently. Setting this value to `0` will stop the actor.
     #[clap(short = 'c', long = "max-instances", alias = "max-concurrent", alias = "max", alias = "count", default_value_t = u32::MAX)]
     pub max_instances: u32,
@@ -37,6 +43,14 @@ pub struct ScaleActorCommand {
     /// For example, autonomous agents may wish to “tag” scale requests as part of a given deployment
     #[clap(short = 'a', long = "annotations")]
     pub annotations: Vec<String>,
+
+    /// Maximum size, in bytes, that any single linear memory of an actor instance may grow to
+    #[clap(long = "max-memory-bytes")]
+    pub max_memory_bytes: Option<u64>,
+
+    /// Maximum number of elements that any single table of an actor instance may grow to
+    #[clap(long = "max-table-elements")]
+    pub max_table_elements: Option<u32>,
 }
 
 pub async fn handle_scale_actor(cmd: ScaleActorCommand) -> Result<CommandOutput> {
@@ -44,6 +58,15 @@ pub async fn handle_scale_actor(cmd: ScaleActorCommand) -> Result<CommandOutput>
     let client = wco.into_ctl_client(None).await?;
 
     let annotations = labels_vec_to_hashmap(cmd.annotations)?;
+    let actor_id = cmd
+        .actor_id
+        .unwrap_or_else(|| component_id_from_ref(&cmd.actor_ref));
+    let limits = (cmd.max_memory_bytes.is_some() || cmd.max_table_elements.is_some()).then_some(
+        ComponentLimits {
+            max_memory_bytes: cmd.max_memory_bytes,
+            max_table_elements: cmd.max_table_elements,
+        },
+    );
 
     scale_actor(
         &client,
@@ -51,8 +74,10 @@ pub async fn handle_scale_actor(cmd: ScaleActorCommand) -> Result<CommandOutput>
         // prompt the user to choose if more than one thing matches
         &find_host_id(&cmd.host_id, &client).await?.0,
         &cmd.actor_ref,
+        &actor_id,
         cmd.max_instances,
         Some(annotations),
+        limits,
     )
     .await?;
 
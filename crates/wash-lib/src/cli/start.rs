@@ -8,7 +8,7 @@ use tokio::time::Duration;
 use crate::{
     actor::{start_actor, ActorStartedInfo, StartActorArgs},
     cli::{labels_vec_to_hashmap, CliConnectionOpts, CommandOutput},
-    common::{boxed_err_to_anyhow, find_host_id},
+    common::{boxed_err_to_anyhow, component_id_from_ref, find_host_id},
     config::{
         WashConnectionOptions, DEFAULT_NATS_TIMEOUT_MS, DEFAULT_START_ACTOR_TIMEOUT_MS,
         DEFAULT_START_PROVIDER_TIMEOUT_MS,
@@ -44,6 +44,11 @@ pub struct StartActorCommand {
     #[clap(name = "actor-ref")]
     pub actor_ref: String,
 
+    /// Unique ID to run the actor under. Defaults to the name of the actor reference without its
+    /// tag, e.g. `echo` for `wasmcloud.azurecr.io/echo:0.3.8`
+    #[clap(long = "actor-id")]
+    pub actor_id: Option<String>,
+
     /// Maximum number of instances this actor can run concurrently.
     #[clap(
         long = "max-instances",
@@ -86,12 +91,17 @@ pub async fn handle_start_actor(cmd: StartActorCommand) -> Result<CommandOutput>
         cmd.actor_ref.to_string()
     };
 
+    let actor_id = cmd
+        .actor_id
+        .unwrap_or_else(|| component_id_from_ref(&actor_ref));
+
     let host = match cmd.host_id {
         Some(host) => find_host_id(&host, &client).await?.0,
         None => {
             let suitable_hosts = client
                 .perform_actor_auction(
                     &actor_ref,
+                    &actor_id,
                     labels_vec_to_hashmap(cmd.constraints.unwrap_or_default())?,
                 )
                 .await
@@ -99,13 +109,15 @@ pub async fn handle_start_actor(cmd: StartActorCommand) -> Result<CommandOutput>
                 .with_context(|| {
                     format!("Failed to auction actor {} to hosts in lattice", &actor_ref)
                 })?;
-            if suitable_hosts.is_empty() {
+            let Some(host_id) = suitable_hosts
+                .into_iter()
+                .find_map(|ack| ack.response.map(|ack| ack.host_id))
+            else {
                 bail!("No suitable hosts found for actor {}", actor_ref);
-            } else {
-                suitable_hosts[0].host_id.parse().with_context(|| {
-                    format!("Failed to parse host id: {}", suitable_hosts[0].host_id)
-                })?
-            }
+            };
+            host_id
+                .parse()
+                .with_context(|| format!("Failed to parse host id: {}", host_id))?
         }
     };
 
@@ -117,7 +129,8 @@ pub async fn handle_start_actor(cmd: StartActorCommand) -> Result<CommandOutput>
     } = start_actor(StartActorArgs {
         ctl_client: &client,
         host_id: &host,
-        actor_ref: &cmd.actor_ref,
+        actor_ref: &actor_ref,
+        actor_id: &actor_id,
         count: cmd.max_instances,
         skip_wait: cmd.skip_wait,
         timeout_ms: Some(timeout_ms),
@@ -165,9 +178,14 @@ pub struct StartProviderCommand {
     #[clap(name = "provider-ref")]
     pub provider_ref: String,
 
-    /// Link name of provider
-    #[clap(short = 'l', long = "link-name", default_value = "default")]
-    pub link_name: String,
+    /// Unique ID to run the provider under. Defaults to the name of the provider reference without
+    /// its tag, e.g. `kvredis` for `wasmcloud.azurecr.io/kvredis:0.22.0`
+    #[clap(long = "provider-id")]
+    pub provider_id: Option<String>,
+
+    /// Names of the configs to supply to the provider. Can be passed more than once
+    #[clap(long = "config")]
+    pub config: Vec<String>,
 
     /// Constraints for provider auction in the form of "label=value". If host-id is supplied, this list is ignored
     #[clap(short = 'c', long = "constraint", name = "constraints")]
@@ -177,7 +195,8 @@ pub struct StartProviderCommand {
     #[clap(long = "auction-timeout-ms", default_value_t = default_timeout_ms())]
     pub auction_timeout_ms: u64,
 
-    /// Path to provider configuration JSON file
+    /// Path to provider configuration JSON file. The JSON object is stored as a named config
+    /// called `<provider-id>-config-json`, which is supplied to the provider after any `--config`
     #[clap(long = "config-json")]
     pub config_json: Option<PathBuf>,
 
@@ -205,48 +224,56 @@ pub async fn handle_start_provider(cmd: StartProviderCommand) -> Result<CommandO
         cmd.provider_ref.to_string()
     };
 
+    let provider_id = cmd
+        .provider_id
+        .unwrap_or_else(|| component_id_from_ref(&provider_ref));
+
     let host = match cmd.host_id {
         Some(host) => find_host_id(&host, &client).await?.0,
         None => {
             let suitable_hosts = client
                 .perform_provider_auction(
                     &provider_ref,
-                    &cmd.link_name,
+                    &provider_id,
                     labels_vec_to_hashmap(cmd.constraints.unwrap_or_default())?,
                 )
                 .await
                 .map_err(boxed_err_to_anyhow)
                 .with_context(|| {
                     format!(
-                        "Failed to auction provider {} with link name {} to hosts in lattice",
-                        &provider_ref, &cmd.link_name
+                        "Failed to auction provider {} to hosts in lattice",
+                        &provider_ref
                     )
                 })?;
-            if suitable_hosts.is_empty() {
+            let Some(host_id) = suitable_hosts
+                .into_iter()
+                .find_map(|ack| ack.response.map(|ack| ack.host_id))
+            else {
                 bail!("No suitable hosts found for provider {}", provider_ref);
-            } else {
-                suitable_hosts[0].host_id.parse().with_context(|| {
-                    format!("Failed to parse host id: {}", suitable_hosts[0].host_id)
-                })?
-            }
+            };
+            host_id
+                .parse()
+                .with_context(|| format!("Failed to parse host id: {}", host_id))?
         }
     };
 
-    let config_json = if let Some(config_path) = cmd.config_json {
-        let config_str = match std::fs::read_to_string(&config_path) {
-            Ok(s) => s,
-            Err(e) => bail!("Error reading provider configuration: {}", e),
-        };
-        match serde_json::from_str::<serde_json::Value>(&config_str) {
-            Ok(_v) => Some(config_str),
-            _ => bail!(
-                "Configuration path provided but was invalid JSON: {}",
-                config_path.display()
-            ),
+    let mut config = cmd.config;
+    if let Some(config_path) = cmd.config_json {
+        let config_name = format!("{provider_id}-config-json");
+        let values = read_config_json(&config_path)?;
+        let ack = client
+            .put_config(&config_name, values)
+            .await
+            .map_err(boxed_err_to_anyhow)
+            .with_context(|| format!("Failed to put provider configuration {config_name}"))?;
+        if !ack.success {
+            bail!(
+                "Put provider configuration {config_name} not accepted: {}",
+                ack.message
+            );
         }
-    } else {
-        None
-    };
+        config.push(config_name);
+    }
 
     let mut receiver = client
         .events_receiver(vec![
@@ -258,24 +285,18 @@ pub async fn handle_start_provider(cmd: StartProviderCommand) -> Result<CommandO
         .context("Failed to get lattice event channel")?;
 
     let ack = client
-        .start_provider(
-            &host,
-            &provider_ref,
-            Some(cmd.link_name.clone()),
-            None,
-            config_json.clone(),
-        )
+        .start_provider(&host, &provider_ref, &provider_id, None, config.clone())
         .await
         .map_err(boxed_err_to_anyhow)
         .with_context(|| {
             format!(
-                "Failed to start provider {} on host {:?} with link name {} and configuration {:?}",
-                &provider_ref, &host, &cmd.link_name, &config_json
+                "Failed to start provider {} on host {:?} with configuration {:?}",
+                &provider_ref, &host, &config
             )
         })?;
 
-    if !ack.accepted {
-        bail!("Start provider ack not accepted: {}", ack.error);
+    if !ack.success {
+        bail!("Start provider ack not accepted: {}", ack.message);
     }
 
     if cmd.skip_wait {
@@ -285,7 +306,7 @@ pub async fn handle_start_provider(cmd: StartProviderCommand) -> Result<CommandO
             HashMap::from([
                 ("result".into(), text.into()),
                 ("provider_ref".into(), provider_ref.into()),
-                ("link_name".into(), cmd.link_name.into()),
+                ("provider_id".into(), provider_id.into()),
                 ("host_id".into(), host.to_string().into()),
             ]),
         ));
@@ -337,3 +358,27 @@ pub async fn handle_start_provider(cmd: StartProviderCommand) -> Result<CommandO
         }),
     }
 }
+
+/// Reads a provider configuration JSON file as a config map. The file must contain a JSON object;
+/// string values are used as-is and any other values are stored as their JSON text
+pub fn read_config_json(config_path: &std::path::Path) -> Result<HashMap<String, String>> {
+    let config_str = std::fs::read_to_string(config_path).with_context(|| {
+        format!(
+            "Error reading provider configuration {}",
+            config_path.display()
+        )
+    })?;
+    let Ok(serde_json::Value::Object(values)) = serde_json::from_str(&config_str) else {
+        bail!(
+            "Configuration path provided but was not a JSON object: {}",
+            config_path.display()
+        );
+    };
+    Ok(values
+        .into_iter()
+        .map(|(key, value)| match value {
+            serde_json::Value::String(value) => (key, value),
+            value => (key, value.to_string()),
+        })
+        .collect())
+}
@@ -134,18 +134,12 @@ pub async fn stop_provider(cmd: StopProviderCommand) -> Result<CommandOutput> {
     };
 
     let ack = client
-        .stop_provider(
-            &host_id,
-            &provider_id,
-            &cmd.link_name,
-            &cmd.contract_id,
-            None,
-        )
+        .stop_provider(&host_id, &provider_id)
         .await
         .map_err(boxed_err_to_anyhow)?;
 
-    if !ack.accepted {
-        bail!("Operation failed: {}", ack.error);
+    if !ack.success {
+        bail!("Operation failed: {}", ack.message);
     }
     if cmd.skip_wait {
         let text = format!(
@@ -157,8 +151,6 @@ pub async fn stop_provider(cmd: StopProviderCommand) -> Result<CommandOutput> {
             HashMap::from([
                 ("result".into(), text.into()),
                 ("provider_id".into(), provider_id.to_string().into()),
-                ("link_name".into(), cmd.link_name.into()),
-                ("contract_id".into(), cmd.contract_id.into()),
                 ("host_id".into(), host_id.to_string().into()),
             ]),
         ));
@@ -251,8 +243,8 @@ pub async fn stop_host(cmd: StopHostCommand) -> Result<CommandOutput> {
         .await
         .map_err(boxed_err_to_anyhow)?;
 
-    if !ack.accepted {
-        bail!("Operation failed: {}", ack.error);
+    if !ack.success {
+        bail!("Operation failed: {}", ack.message);
     }
 
     Ok(CommandOutput::from_key_and_text(
@@ -261,7 +253,7 @@ pub async fn stop_host(cmd: StopHostCommand) -> Result<CommandOutput> {
     ))
 }
 
-async fn find_host_with_provider(
+pub(crate) async fn find_host_with_provider(
     provider_id: &str,
     ctl_client: &wasmcloud_control_interface::Client,
 ) -> Result<ServerId, FindIdError> {
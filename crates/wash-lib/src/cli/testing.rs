@@ -0,0 +1,1494 @@
+//! Run the tests of a component in-process, described by a declarative test file.
+//!
+//! Every test runs against a fresh instance of the component with in-memory keyvalue and blobstore
+//! providers, which are seeded with the fixtures of the test file, and scripted responses to
+//! messaging and outgoing HTTP requests. Published messages and outgoing HTTP requests are
+//! recorded, so that tests can check the side effects of the component along with its output.
+//!
+//! ```toml
+//! # Path to the component, relative to the test file
+//! component = "build/http_hello_world_s.wasm"
+//!
+//! [config]
+//! greeting = "Hello"
+//!
+//! [keyvalue.default]
+//! counter = 41
+//!
+//! [[outgoing_http]]
+//! url = "https://api.example.com/weather"
+//! body = "sunny"
+//!
+//! [[test]]
+//! name = "counts requests"
+//! timeout_ms = 5000
+//! incoming_http = { method = "GET", path = "/" }
+//! expect = { status = 200, body = "Hello, sunny", keyvalue = { default = { counter = 42 } } }
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicU64;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, ensure, Context, Result};
+use bytes::Bytes;
+use clap::Parser;
+use futures::TryStreamExt;
+use http_body_util::{BodyExt, Full};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::sync::Mutex;
+use wasmcloud_runtime::capability::logging::logging;
+use wasmcloud_runtime::capability::provider::{
+    MemoryBlobstore, MemoryKeyValue, MemoryKeyValueEntry,
+};
+use wasmcloud_runtime::capability::{
+    self, guest_config, messaging, Blobstore as _, CallTargetInterface, IncomingHttp as _,
+    KeyValueEventual as _, TargetEntity,
+};
+use wasmcloud_runtime::{async_trait, Component, Runtime};
+use wasmtime_wasi_http::body::HyperIncomingBody;
+use wrpc_transport::Value;
+use wrpc_types::{DynamicFunction, Type};
+
+use super::capture::value_to_json;
+use super::CommandOutput;
+
+/// Name of the test file used if none is given
+pub const DEFAULT_TEST_FILE: &str = "wasmcloud-test.toml";
+
+/// Time a test may run for if neither the test nor its test file set `timeout_ms`
+const DEFAULT_TEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Parser, Clone)]
+pub struct TestCommand {
+    /// Paths to the test files to run. Each file is reported as a separate test suite
+    #[clap(name = "test_files", default_value = DEFAULT_TEST_FILE)]
+    pub test_files: Vec<PathBuf>,
+
+    /// Path to the component to test, instead of the `component` set in the test files
+    #[clap(name = "component", long = "component")]
+    pub component: Option<PathBuf>,
+
+    /// Only run the tests whose name contains this string
+    #[clap(name = "filter", long = "filter")]
+    pub filter: Option<String>,
+
+    /// Write a JUnit XML report of the test results to this path
+    #[clap(name = "junit", long = "junit")]
+    pub junit: Option<PathBuf>,
+}
+
+/// A test file, containing the fixtures shared by its tests and the tests themselves
+#[derive(Debug, Clone, Default, Deserialize)]
+struct TestFile {
+    /// Path to the component to test, relative to the test file
+    component: Option<PathBuf>,
+    /// Configuration returned by `wasmcloud:bus/guest-config`
+    #[serde(default)]
+    config: BTreeMap<String, String>,
+    /// Time in milliseconds each test may run for, unless the test sets its own
+    #[serde(default)]
+    timeout_ms: Option<u64>,
+    #[serde(flatten)]
+    fixtures: Fixtures,
+    #[serde(default, rename = "test")]
+    tests: Vec<TestCase>,
+}
+
+/// State of the mocked capabilities before a test runs
+#[derive(Debug, Clone, Default, Deserialize)]
+struct Fixtures {
+    /// Entries of the keyvalue buckets, keyed by bucket and key
+    #[serde(default)]
+    keyvalue: BTreeMap<String, BTreeMap<String, KeyValueData>>,
+    /// Objects of the blobstore containers, keyed by container and object name
+    #[serde(default)]
+    blobstore: BTreeMap<String, BTreeMap<String, String>>,
+    /// Responses to messaging requests
+    #[serde(default)]
+    messaging: Vec<MessagingResponse>,
+    /// Responses to outgoing HTTP requests
+    #[serde(default)]
+    outgoing_http: Vec<OutgoingHttpResponse>,
+}
+
+impl Fixtures {
+    /// Combine the fixtures of a test file with the fixtures of one of its tests, which take
+    /// precedence
+    fn merge(&self, test: &Fixtures) -> Fixtures {
+        let mut keyvalue = self.keyvalue.clone();
+        for (bucket, entries) in &test.keyvalue {
+            keyvalue
+                .entry(bucket.clone())
+                .or_default()
+                .extend(entries.clone());
+        }
+        let mut blobstore = self.blobstore.clone();
+        for (container, objects) in &test.blobstore {
+            blobstore
+                .entry(container.clone())
+                .or_default()
+                .extend(objects.clone());
+        }
+        Fixtures {
+            keyvalue,
+            blobstore,
+            // Responses are matched in order, so the responses of the test come first
+            messaging: test
+                .messaging
+                .iter()
+                .chain(&self.messaging)
+                .cloned()
+                .collect(),
+            outgoing_http: test
+                .outgoing_http
+                .iter()
+                .chain(&self.outgoing_http)
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// A keyvalue entry, either an atomic counter or a string
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+enum KeyValueData {
+    Atomic(u64),
+    Blob(String),
+}
+
+impl KeyValueData {
+    /// Value of the entry as returned by `wasi:keyvalue/eventual.get`
+    fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::Atomic(n) => n.to_string().into_bytes(),
+            Self::Blob(s) => s.clone().into_bytes(),
+        }
+    }
+}
+
+/// Scripted response to messaging requests on a subject
+#[derive(Debug, Clone, Deserialize)]
+struct MessagingResponse {
+    subject: String,
+    #[serde(default)]
+    body: Option<String>,
+}
+
+/// Scripted response to outgoing HTTP requests to a URL
+#[derive(Debug, Clone, Deserialize)]
+struct OutgoingHttpResponse {
+    /// URL of the request, including the query string if any
+    url: String,
+    /// Method of the request. Requests with any method match if unset
+    #[serde(default)]
+    method: Option<String>,
+    #[serde(default = "default_status")]
+    status: u16,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    #[serde(default)]
+    body: String,
+}
+
+fn default_status() -> u16 {
+    200
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct TestCase {
+    name: String,
+    /// Invoke a function exported by the component
+    #[serde(default)]
+    call: Option<Call>,
+    /// Send a request to the `wasi:http/incoming-handler` export of the component
+    #[serde(default)]
+    incoming_http: Option<IncomingHttpRequest>,
+    /// Time in milliseconds the test may run for
+    #[serde(default)]
+    timeout_ms: Option<u64>,
+    /// Fixtures of this test, in addition to the fixtures of the test file
+    #[serde(flatten)]
+    fixtures: Fixtures,
+    #[serde(default)]
+    expect: Expectations,
+}
+
+impl TestCase {
+    fn validate(&self) -> Result<()> {
+        let expect = &self.expect;
+        match (&self.call, &self.incoming_http) {
+            (Some(_), None) => ensure!(
+                expect.status.is_none() && expect.headers.is_empty() && expect.body.is_none(),
+                "`expect.status`, `expect.headers` and `expect.body` can only be used with `incoming_http`"
+            ),
+            (None, Some(_)) => ensure!(
+                expect.results.is_none(),
+                "`expect.results` can only be used with `call`"
+            ),
+            _ => bail!("exactly one of `call` or `incoming_http` must be set"),
+        }
+        ensure!(
+            expect.error.is_none() || expect.results.is_none() && expect.status.is_none(),
+            "`expect.error` cannot be combined with expected results or a status"
+        );
+        Ok(())
+    }
+}
+
+/// Invocation of a function exported by the component
+#[derive(Debug, Clone, Deserialize)]
+struct Call {
+    /// Exported interface, e.g. `wasi:cli/run@0.2.0`. Functions exported at the root of the
+    /// component are used if unset
+    #[serde(default)]
+    interface: String,
+    function: String,
+    /// Parameters of the function, in the JSON representation used by `wash capture replay`
+    #[serde(default)]
+    params: Vec<serde_json::Value>,
+}
+
+/// Request sent to the `wasi:http/incoming-handler` export of the component
+#[derive(Debug, Clone, Deserialize)]
+struct IncomingHttpRequest {
+    #[serde(default = "default_method")]
+    method: String,
+    /// Path of the request, including the query string if any
+    #[serde(default = "default_path")]
+    path: String,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    #[serde(default)]
+    body: String,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+fn default_path() -> String {
+    "/".to_string()
+}
+
+/// Expected output and side effects of a test. Unset expectations are not checked
+#[derive(Debug, Clone, Default, Deserialize)]
+struct Expectations {
+    /// Results of the called function
+    #[serde(default)]
+    results: Option<Vec<serde_json::Value>>,
+    /// Part of the error the invocation is expected to fail with
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    status: Option<u16>,
+    /// Headers the HTTP response must contain
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    #[serde(default)]
+    body: Option<String>,
+    /// Entries the keyvalue buckets must contain after the test
+    #[serde(default)]
+    keyvalue: BTreeMap<String, BTreeMap<String, KeyValueData>>,
+    /// Objects the blobstore containers must contain after the test
+    #[serde(default)]
+    blobstore: BTreeMap<String, BTreeMap<String, String>>,
+    /// All messages published by the component, in order
+    #[serde(default)]
+    published: Option<Vec<ExpectedMessage>>,
+    /// All outgoing HTTP requests sent by the component, in order
+    #[serde(default)]
+    outgoing_http: Option<Vec<ExpectedRequest>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ExpectedMessage {
+    subject: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    reply_to: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ExpectedRequest {
+    url: String,
+    #[serde(default)]
+    method: Option<String>,
+    #[serde(default)]
+    body: Option<String>,
+}
+
+impl TestFile {
+    async fn load(path: &Path) -> Result<Self> {
+        let contents = tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("failed to read test file {}", path.display()))?;
+        let file: TestFile = toml::from_str(&contents)
+            .with_context(|| format!("failed to parse test file {}", path.display()))?;
+        for test in &file.tests {
+            test.validate()
+                .with_context(|| format!("invalid test [{}] in {}", test.name, path.display()))?;
+        }
+        Ok(file)
+    }
+
+    /// Time `test` may run for
+    fn timeout(&self, test: &TestCase) -> Duration {
+        test.timeout_ms
+            .or(self.timeout_ms)
+            .map_or(DEFAULT_TEST_TIMEOUT, Duration::from_millis)
+    }
+}
+
+/// `wasmcloud:bus` implementation returning the configuration of the test file. Components are
+/// not linked to any other components
+struct MockBus {
+    config: BTreeMap<String, String>,
+    link_name: Mutex<String>,
+}
+
+#[async_trait]
+impl capability::Bus for MockBus {
+    async fn identify_interface_target(
+        &self,
+        _interface: &CallTargetInterface,
+    ) -> Option<TargetEntity> {
+        None
+    }
+
+    async fn set_link_name(
+        &self,
+        target: String,
+        _interfaces: Vec<CallTargetInterface>,
+    ) -> anyhow::Result<()> {
+        *self.link_name.lock().await = target;
+        Ok(())
+    }
+
+    async fn get_link_name(&self) -> anyhow::Result<String> {
+        Ok(self.link_name.lock().await.clone())
+    }
+
+    async fn get(
+        &self,
+        key: &str,
+    ) -> anyhow::Result<Result<Option<Vec<u8>>, guest_config::ConfigError>> {
+        Ok(Ok(self
+            .config
+            .get(key)
+            .map(|value| value.clone().into_bytes())))
+    }
+
+    async fn get_all(
+        &self,
+    ) -> anyhow::Result<Result<Vec<(String, Vec<u8>)>, guest_config::ConfigError>> {
+        Ok(Ok(self
+            .config
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone().into_bytes()))
+            .collect()))
+    }
+
+    async fn call(
+        &self,
+        _target: TargetEntity,
+        instance: &str,
+        name: &str,
+        _params: Vec<Value>,
+    ) -> anyhow::Result<Vec<Value>> {
+        bail!("`{instance}.{name}` cannot be called, since the component is tested in isolation")
+    }
+}
+
+/// `wasmcloud:messaging` implementation answering requests with the scripted responses and
+/// recording published messages
+struct MockMessaging {
+    responses: Vec<MessagingResponse>,
+    published: Mutex<Vec<messaging::types::BrokerMessage>>,
+}
+
+impl MockMessaging {
+    fn responses<'a>(
+        &'a self,
+        subject: &'a str,
+    ) -> impl Iterator<Item = messaging::types::BrokerMessage> + 'a {
+        self.responses
+            .iter()
+            .filter(move |response| response.subject == subject)
+            .map(|response| messaging::types::BrokerMessage {
+                subject: response.subject.clone(),
+                body: response.body.clone().map(String::into_bytes),
+                reply_to: None,
+            })
+    }
+}
+
+#[async_trait]
+impl capability::Messaging for MockMessaging {
+    async fn request(
+        &self,
+        subject: String,
+        _body: Option<Vec<u8>>,
+        _timeout: Duration,
+    ) -> anyhow::Result<messaging::types::BrokerMessage> {
+        self.responses(&subject)
+            .next()
+            .with_context(|| format!("no response to requests on [{subject}] is scripted"))
+    }
+
+    async fn request_multi(
+        &self,
+        subject: String,
+        _body: Option<Vec<u8>>,
+        _timeout: Duration,
+        max_results: u32,
+    ) -> anyhow::Result<Vec<messaging::types::BrokerMessage>> {
+        Ok(self
+            .responses(&subject)
+            .take(max_results.try_into().unwrap_or(usize::MAX))
+            .collect())
+    }
+
+    async fn publish(&self, msg: messaging::types::BrokerMessage) -> anyhow::Result<()> {
+        self.published.lock().await.push(msg);
+        Ok(())
+    }
+}
+
+/// An outgoing HTTP request sent by the component
+#[derive(Debug, Clone)]
+struct SentRequest {
+    method: String,
+    url: String,
+    body: Bytes,
+}
+
+/// `wasi:http/outgoing-handler` implementation answering requests with the scripted responses
+/// and recording them. Requests without a scripted response are denied
+struct MockOutgoingHttp {
+    responses: Vec<OutgoingHttpResponse>,
+    sent: Mutex<Vec<SentRequest>>,
+}
+
+#[async_trait]
+impl capability::OutgoingHttp for MockOutgoingHttp {
+    async fn handle(
+        &self,
+        request: wasmtime_wasi_http::types::OutgoingRequest,
+    ) -> anyhow::Result<Result<http::Response<HyperIncomingBody>, capability::http::types::ErrorCode>>
+    {
+        let (parts, body) = request.request.into_parts();
+        let body = match body.collect().await {
+            Ok(body) => body.to_bytes(),
+            Err(err) => return Ok(Err(err)),
+        };
+        // The authority always includes the port, which is omitted from the URL if it is the
+        // default one
+        let (scheme, default_port) = if request.use_tls {
+            ("https", ":443")
+        } else {
+            ("http", ":80")
+        };
+        let authority = request
+            .authority
+            .strip_suffix(default_port)
+            .unwrap_or(&request.authority);
+        let path = parts.uri.path_and_query().map_or("/", |path| path.as_str());
+        let url = format!("{scheme}://{authority}{path}");
+        let method = parts.method.to_string();
+
+        let response = self.responses.iter().find(|response| {
+            response.url == url
+                && response
+                    .method
+                    .as_ref()
+                    .map_or(true, |m| m.eq_ignore_ascii_case(&method))
+        });
+        self.sent
+            .lock()
+            .await
+            .push(SentRequest { method, url, body });
+        let Some(response) = response else {
+            return Ok(Err(capability::http::types::ErrorCode::HttpRequestDenied));
+        };
+
+        let mut builder = http::Response::builder().status(response.status);
+        for (name, value) in &response.headers {
+            builder = builder.header(name, value);
+        }
+        let body = Full::new(Bytes::from(response.body.clone()))
+            .map_err(|never| match never {})
+            .boxed();
+        let response = builder
+            .body(body)
+            .context("failed to build scripted HTTP response")?;
+        Ok(Ok(response))
+    }
+}
+
+/// `wasi:logging` implementation collecting the logs of a test
+#[derive(Default)]
+struct MockLogging {
+    logs: Mutex<String>,
+}
+
+#[async_trait]
+impl capability::Logging for MockLogging {
+    async fn log(
+        &self,
+        level: logging::Level,
+        context: String,
+        message: String,
+    ) -> anyhow::Result<()> {
+        let level = match level {
+            logging::Level::Trace => "TRACE",
+            logging::Level::Debug => "DEBUG",
+            logging::Level::Info => "INFO",
+            logging::Level::Warn => "WARN",
+            logging::Level::Error => "ERROR",
+            logging::Level::Critical => "CRITICAL",
+        };
+        let mut logs = self.logs.lock().await;
+        let _ = writeln!(logs, "{level} {context}: {message}");
+        Ok(())
+    }
+}
+
+/// Outcome of a test
+#[derive(Debug, Clone, PartialEq)]
+enum TestOutcome {
+    Passed,
+    /// The test ran, but did not meet the listed expectations
+    Failed(Vec<String>),
+    /// The test could not be run
+    Error(String),
+}
+
+#[derive(Debug, Clone)]
+struct TestResult {
+    name: String,
+    outcome: TestOutcome,
+    elapsed: Duration,
+    logs: String,
+}
+
+#[derive(Debug, Clone)]
+struct SuiteResult {
+    name: String,
+    tests: Vec<TestResult>,
+}
+
+/// Output of the component under test
+enum Output {
+    Results(serde_json::Value),
+    Http {
+        status: u16,
+        headers: http::HeaderMap,
+        body: Bytes,
+    },
+}
+
+/// Mocked capabilities of a single test
+struct Mocks {
+    bus: Arc<MockBus>,
+    keyvalue: Arc<MemoryKeyValue>,
+    blobstore: Arc<MemoryBlobstore>,
+    messaging: Arc<MockMessaging>,
+    outgoing_http: Arc<MockOutgoingHttp>,
+    logging: Arc<MockLogging>,
+}
+
+impl Mocks {
+    async fn new(config: &BTreeMap<String, String>, fixtures: Fixtures) -> Result<Self> {
+        let keyvalue = fixtures
+            .keyvalue
+            .into_iter()
+            .map(|(bucket, entries)| {
+                let entries = entries
+                    .into_iter()
+                    .map(|(key, value)| {
+                        let entry = match value {
+                            KeyValueData::Atomic(n) => {
+                                MemoryKeyValueEntry::Atomic(AtomicU64::new(n))
+                            }
+                            KeyValueData::Blob(s) => MemoryKeyValueEntry::Blob(s.into_bytes()),
+                        };
+                        (key, entry)
+                    })
+                    .collect::<HashMap<_, _>>();
+                (bucket, entries)
+            })
+            .collect::<HashMap<_, _>>();
+
+        let blobstore = MemoryBlobstore::default();
+        for (container, objects) in fixtures.blobstore {
+            blobstore
+                .create_container(&container)
+                .await
+                .with_context(|| format!("failed to create container [{container}]"))?;
+            for (name, data) in objects {
+                blobstore
+                    .write_data(&container, name, Box::new(Cursor::new(data.into_bytes())))
+                    .await
+                    .with_context(|| format!("failed to write object to [{container}]"))?;
+            }
+        }
+
+        Ok(Self {
+            bus: Arc::new(MockBus {
+                config: config.clone(),
+                link_name: Mutex::new("default".to_string()),
+            }),
+            keyvalue: Arc::new(keyvalue.into()),
+            blobstore: Arc::new(blobstore),
+            messaging: Arc::new(MockMessaging {
+                responses: fixtures.messaging,
+                published: Mutex::default(),
+            }),
+            outgoing_http: Arc::new(MockOutgoingHttp {
+                responses: fixtures.outgoing_http,
+                sent: Mutex::default(),
+            }),
+            logging: Arc::default(),
+        })
+    }
+}
+
+/// Run a test against a fresh instance of `component`
+async fn run_test(component: &Component, file: &TestFile, test: &TestCase) -> TestResult {
+    let start = Instant::now();
+    let timeout = file.timeout(test);
+    let (outcome, logs) = match Mocks::new(&file.config, file.fixtures.merge(&test.fixtures)).await
+    {
+        Ok(mocks) => {
+            let outcome = match tokio::time::timeout(timeout, invoke(component, &mocks, test)).await
+            {
+                Ok(Ok(output)) => match check(&mocks, &test.expect, output).await {
+                    failures if failures.is_empty() => TestOutcome::Passed,
+                    failures => TestOutcome::Failed(failures),
+                },
+                Ok(Err(err)) => TestOutcome::Error(format!("{err:#}")),
+                Err(_) => TestOutcome::Error(format!("test timed out after {timeout:?}")),
+            };
+            (outcome, mocks.logging.logs.lock().await.clone())
+        }
+        Err(err) => (TestOutcome::Error(format!("{err:#}")), String::new()),
+    };
+    TestResult {
+        name: test.name.clone(),
+        outcome,
+        elapsed: start.elapsed(),
+        logs,
+    }
+}
+
+/// Invoke the component as described by the test. The inner result contains the error of the
+/// invocation, which may be expected by the test
+async fn invoke(
+    component: &Component,
+    mocks: &Mocks,
+    test: &TestCase,
+) -> Result<Result<Output, String>> {
+    let mut instance = component
+        .instantiate()
+        .context("failed to instantiate component")?;
+    instance
+        .bus(mocks.bus.clone())
+        .keyvalue_atomic(mocks.keyvalue.clone())
+        .keyvalue_eventual(mocks.keyvalue.clone())
+        .blobstore(mocks.blobstore.clone())
+        .messaging(mocks.messaging.clone())
+        .outgoing_http(mocks.outgoing_http.clone())
+        .logging(mocks.logging.clone());
+    // Show panics and other output of the component
+    instance
+        .stderr(tokio::io::stderr())
+        .await
+        .context("failed to set stderr of component")?;
+
+    if let Some(call) = &test.call {
+        let Some(DynamicFunction::Static { params, .. }) = component
+            .exports()
+            .get(&call.interface)
+            .and_then(|functions| functions.get(&call.function))
+        else {
+            bail!(
+                "component does not export `{}.{}`",
+                call.interface,
+                call.function
+            )
+        };
+        ensure!(
+            params.len() == call.params.len(),
+            "`{}.{}` takes {} parameters, but {} are given",
+            call.interface,
+            call.function,
+            params.len(),
+            call.params.len()
+        );
+        let params = params
+            .iter()
+            .zip(&call.params)
+            .enumerate()
+            .map(|(i, (ty, param))| {
+                json_to_value(ty, param).with_context(|| format!("invalid parameter {i}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        return Ok(
+            match instance.call(&call.interface, &call.function, params).await {
+                Ok(results) => Ok(Output::Results(
+                    results.into_iter().map(value_to_json).collect(),
+                )),
+                Err(err) => Err(format!("{err:#}")),
+            },
+        );
+    }
+
+    let Some(request) = &test.incoming_http else {
+        bail!("exactly one of `call` or `incoming_http` must be set")
+    };
+    let mut builder = http::Request::builder()
+        .method(request.method.as_str())
+        .uri(request.path.as_str());
+    for (name, value) in &request.headers {
+        builder = builder.header(name, value);
+    }
+    let body = Full::new(Bytes::from(request.body.clone()))
+        .map_err(|never| match never {})
+        .boxed();
+    let request = builder.body(body).context("invalid HTTP request")?;
+    let response = instance
+        .into_incoming_http()
+        .await
+        .context("component does not export `wasi:http/incoming-handler`")?
+        .handle(request)
+        .await;
+    let response = match response {
+        Ok(Ok(response)) => response,
+        Ok(Err(code)) => return Ok(Err(format!("{code:?}"))),
+        Err(err) => return Ok(Err(format!("{err:#}"))),
+    };
+    let (parts, body) = response.into_parts();
+    let body = match body.collect().await {
+        Ok(body) => body.to_bytes(),
+        Err(code) => return Ok(Err(format!("failed to read response body: {code:?}"))),
+    };
+    Ok(Ok(Output::Http {
+        status: parts.status.as_u16(),
+        headers: parts.headers,
+        body,
+    }))
+}
+
+/// Check the output and side effects of a test, returning all unmet expectations
+async fn check(
+    mocks: &Mocks,
+    expect: &Expectations,
+    output: Result<Output, String>,
+) -> Vec<String> {
+    let mut failures = Vec::new();
+    match (output, &expect.error) {
+        (Ok(_), Some(expected)) => failures.push(format!(
+            "expected the invocation to fail with `{expected}`, but it succeeded"
+        )),
+        (Err(err), Some(expected)) if !err.contains(expected.as_str()) => failures.push(format!(
+            "expected the invocation to fail with `{expected}`, but it failed with `{err}`"
+        )),
+        (Err(_), Some(_)) => {}
+        (Err(err), None) => failures.push(format!("invocation failed: {err}")),
+        (Ok(Output::Results(results)), None) => {
+            if let Some(expected) = &expect.results {
+                let expected = json!(expected);
+                if results != expected {
+                    failures.push(format!("expected results {expected}, got {results}"));
+                }
+            }
+        }
+        (
+            Ok(Output::Http {
+                status,
+                headers,
+                body,
+            }),
+            None,
+        ) => {
+            if let Some(expected) = expect.status {
+                if status != expected {
+                    failures.push(format!("expected status {expected}, got {status}"));
+                }
+            }
+            for (name, expected) in &expect.headers {
+                match headers.get(name).map(|value| value.to_str()) {
+                    Some(Ok(value)) if value == expected => {}
+                    Some(Ok(value)) => failures.push(format!(
+                        "expected header [{name}] to be `{expected}`, got `{value}`"
+                    )),
+                    Some(Err(_)) => failures.push(format!("header [{name}] is not valid UTF-8")),
+                    None => failures.push(format!("expected header [{name}] to be set")),
+                }
+            }
+            if let Some(expected) = &expect.body {
+                if body != expected.as_bytes() {
+                    failures.push(format!(
+                        "expected body `{expected}`, got `{}`",
+                        String::from_utf8_lossy(&body)
+                    ));
+                }
+            }
+        }
+    }
+
+    for (bucket, entries) in &expect.keyvalue {
+        for (key, expected) in entries {
+            let value = match mocks.keyvalue.get(bucket, key.clone()).await {
+                Ok(Some(stream)) => stream.map_ok(Vec::from).try_concat().await.map(Some),
+                Ok(None) => Ok(None),
+                Err(err) => Err(err),
+            };
+            match value {
+                Ok(Some(value)) if value == expected.to_bytes() => {}
+                Ok(Some(value)) => failures.push(format!(
+                    "expected keyvalue entry [{bucket}/{key}] to be `{}`, got `{}`",
+                    String::from_utf8_lossy(&expected.to_bytes()),
+                    String::from_utf8_lossy(&value)
+                )),
+                Ok(None) => failures.push(format!(
+                    "expected keyvalue entry [{bucket}/{key}] to be set"
+                )),
+                Err(err) => failures.push(format!(
+                    "failed to read keyvalue entry [{bucket}/{key}]: {err:#}"
+                )),
+            }
+        }
+    }
+
+    for (container, objects) in &expect.blobstore {
+        for (name, expected) in objects {
+            let data = match mocks
+                .blobstore
+                .get_data(container, name.clone(), 0..=u64::MAX)
+                .await
+            {
+                Ok(stream) => stream.map_ok(Vec::from).try_concat().await,
+                Err(err) => Err(err),
+            };
+            match data {
+                Ok(data) if data == expected.as_bytes() => {}
+                Ok(data) => failures.push(format!(
+                    "expected blobstore object [{container}/{name}] to be `{expected}`, got `{}`",
+                    String::from_utf8_lossy(&data)
+                )),
+                Err(err) => failures.push(format!(
+                    "failed to read blobstore object [{container}/{name}]: {err:#}"
+                )),
+            }
+        }
+    }
+
+    if let Some(expected) = &expect.published {
+        let published = mocks.messaging.published.lock().await;
+        if published.len() != expected.len() {
+            failures.push(format!(
+                "expected {} published messages, got {}",
+                expected.len(),
+                published.len()
+            ));
+        }
+        for (i, (msg, expected)) in published.iter().zip(expected).enumerate() {
+            if msg.subject != expected.subject {
+                failures.push(format!(
+                    "expected published message {i} to have subject [{}], got [{}]",
+                    expected.subject, msg.subject
+                ));
+            }
+            if let Some(body) = &expected.body {
+                if msg.body.as_deref() != Some(body.as_bytes()) {
+                    failures.push(format!(
+                        "expected published message {i} to have body `{body}`, got `{}`",
+                        String::from_utf8_lossy(msg.body.as_deref().unwrap_or_default())
+                    ));
+                }
+            }
+            if expected.reply_to.is_some() && msg.reply_to != expected.reply_to {
+                failures.push(format!(
+                    "expected published message {i} to have reply subject {:?}, got {:?}",
+                    expected.reply_to, msg.reply_to
+                ));
+            }
+        }
+    }
+
+    if let Some(expected) = &expect.outgoing_http {
+        let sent = mocks.outgoing_http.sent.lock().await;
+        if sent.len() != expected.len() {
+            failures.push(format!(
+                "expected {} outgoing HTTP requests, got {}",
+                expected.len(),
+                sent.len()
+            ));
+        }
+        for (i, (request, expected)) in sent.iter().zip(expected).enumerate() {
+            if request.url != expected.url {
+                failures.push(format!(
+                    "expected outgoing HTTP request {i} to be sent to {}, got {}",
+                    expected.url, request.url
+                ));
+            }
+            if let Some(method) = &expected.method {
+                if !request.method.eq_ignore_ascii_case(method) {
+                    failures.push(format!(
+                        "expected outgoing HTTP request {i} to use method {method}, got {}",
+                        request.method
+                    ));
+                }
+            }
+            if let Some(body) = &expected.body {
+                if request.body != body.as_bytes() {
+                    failures.push(format!(
+                        "expected outgoing HTTP request {i} to have body `{body}`, got `{}`",
+                        String::from_utf8_lossy(&request.body)
+                    ));
+                }
+            }
+        }
+    }
+
+    failures
+}
+
+/// Converts JSON into a wRPC value of type `ty`, using the representation of [value_to_json].
+/// Records and tuples are arrays of their fields, variants are `{ "case": <index>, "value": .. }`
+/// objects and results are `{ "ok": .. }` or `{ "err": .. }` objects
+fn json_to_value(ty: &Type, json: &serde_json::Value) -> Result<Value> {
+    let unsigned = || {
+        json.as_u64()
+            .with_context(|| format!("expected an unsigned integer, got `{json}`"))
+    };
+    let signed = || {
+        json.as_i64()
+            .with_context(|| format!("expected an integer, got `{json}`"))
+    };
+    let float = || {
+        json.as_f64()
+            .with_context(|| format!("expected a number, got `{json}`"))
+    };
+    let string = || {
+        json.as_str()
+            .with_context(|| format!("expected a string, got `{json}`"))
+    };
+    let array = |len: Option<usize>| {
+        let values = json
+            .as_array()
+            .with_context(|| format!("expected an array, got `{json}`"))?;
+        if let Some(len) = len {
+            ensure!(
+                values.len() == len,
+                "expected an array of {len} values, got `{json}`"
+            );
+        }
+        Ok(values)
+    };
+    let out_of_range = || format!("`{json}` is out of range");
+
+    Ok(match ty {
+        Type::Bool => Value::Bool(
+            json.as_bool()
+                .with_context(|| format!("expected a boolean, got `{json}`"))?,
+        ),
+        Type::U8 => Value::U8(unsigned()?.try_into().with_context(out_of_range)?),
+        Type::U16 => Value::U16(unsigned()?.try_into().with_context(out_of_range)?),
+        Type::U32 => Value::U32(unsigned()?.try_into().with_context(out_of_range)?),
+        Type::U64 => Value::U64(unsigned()?),
+        Type::S8 => Value::S8(signed()?.try_into().with_context(out_of_range)?),
+        Type::S16 => Value::S16(signed()?.try_into().with_context(out_of_range)?),
+        Type::S32 => Value::S32(signed()?.try_into().with_context(out_of_range)?),
+        Type::S64 => Value::S64(signed()?),
+        Type::Float32 => Value::Float32(float()? as f32),
+        Type::Float64 => Value::Float64(float()?),
+        Type::Char => {
+            let mut chars = string()?.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Value::Char(c),
+                _ => bail!("expected a single character, got `{json}`"),
+            }
+        }
+        Type::String => Value::String(string()?.to_string()),
+        Type::List(ty) => Value::List(
+            array(None)?
+                .iter()
+                .map(|json| json_to_value(ty, json))
+                .collect::<Result<_>>()?,
+        ),
+        Type::Record(types) => Value::Record(
+            types
+                .iter()
+                .zip(array(Some(types.len()))?)
+                .map(|(ty, json)| json_to_value(ty, json))
+                .collect::<Result<_>>()?,
+        ),
+        Type::Tuple(types) => Value::Tuple(
+            types
+                .iter()
+                .zip(array(Some(types.len()))?)
+                .map(|(ty, json)| json_to_value(ty, json))
+                .collect::<Result<_>>()?,
+        ),
+        Type::Variant(cases) => {
+            let discriminant = json
+                .get("case")
+                .and_then(serde_json::Value::as_u64)
+                .with_context(|| format!("expected a `case` index, got `{json}`"))?;
+            let case = usize::try_from(discriminant)
+                .ok()
+                .and_then(|i| cases.get(i))
+                .with_context(|| format!("variant has no case {discriminant}"))?;
+            let nested = match (case, json.get("value")) {
+                (Some(ty), Some(json)) => Some(Box::new(json_to_value(ty, json)?)),
+                (Some(_), None) => bail!("variant case {discriminant} requires a `value`"),
+                (None, _) => None,
+            };
+            Value::Variant {
+                discriminant: discriminant.try_into().with_context(out_of_range)?,
+                nested,
+            }
+        }
+        Type::Enum => Value::Enum(unsigned()?.try_into().with_context(out_of_range)?),
+        Type::Option(_) if json.is_null() => Value::Option(None),
+        Type::Option(ty) => Value::Option(Some(Box::new(json_to_value(ty, json)?))),
+        Type::Result { ok, err } => {
+            let nested = |ty: &Option<Arc<Type>>, json: &serde_json::Value| match ty {
+                Some(ty) => json_to_value(ty, json).map(|value| Some(Box::new(value))),
+                None => Ok(None),
+            };
+            match (json.get("ok"), json.get("err")) {
+                (Some(json), None) => Value::Result(Ok(nested(ok, json)?)),
+                (None, Some(json)) => Value::Result(Err(nested(err, json)?)),
+                _ => bail!("expected an object with either `ok` or `err`, got `{json}`"),
+            }
+        }
+        Type::Flags => Value::Flags(unsigned()?),
+        Type::Future(_) | Type::Stream(_) | Type::Resource(_) => {
+            bail!("futures, streams and resources cannot be passed as parameters")
+        }
+    })
+}
+
+pub async fn handle_command(cmd: TestCommand) -> Result<CommandOutput> {
+    let mut suites = Vec::with_capacity(cmd.test_files.len());
+    for path in &cmd.test_files {
+        let file = TestFile::load(path).await?;
+        // Components that never yield to the host cannot be timed out by the test itself, so
+        // interrupt their execution once the longest timeout of the file has passed
+        let max_execution_time = file
+            .tests
+            .iter()
+            .map(|test| file.timeout(test))
+            .max()
+            .unwrap_or(DEFAULT_TEST_TIMEOUT);
+        let rt = Runtime::builder()
+            .max_execution_time(max_execution_time)
+            .build()
+            .context("failed to construct runtime")?;
+        let component_path = match (&cmd.component, &file.component) {
+            (Some(component), _) => component.clone(),
+            (None, Some(component)) => path
+                .parent()
+                .unwrap_or_else(|| Path::new("."))
+                .join(component),
+            (None, None) => bail!(
+                "no component to test is set in {}, please set `component` or use --component",
+                path.display()
+            ),
+        };
+        let wasm = tokio::fs::read(&component_path).await.with_context(|| {
+            format!("failed to read component from {}", component_path.display())
+        })?;
+        let component = Component::new(&rt, wasm)
+            .with_context(|| format!("failed to compile component {}", component_path.display()))?;
+
+        let mut tests = Vec::with_capacity(file.tests.len());
+        for test in file.tests.iter().filter(|test| {
+            cmd.filter
+                .as_ref()
+                .map_or(true, |filter| test.name.contains(filter.as_str()))
+        }) {
+            tests.push(run_test(&component, &file, test).await);
+        }
+        suites.push(SuiteResult {
+            name: path.display().to_string(),
+            tests,
+        });
+    }
+
+    if let Some(junit) = &cmd.junit {
+        tokio::fs::write(junit, junit_report(&suites))
+            .await
+            .with_context(|| format!("failed to write JUnit report to {}", junit.display()))?;
+    }
+
+    let results = suites
+        .iter()
+        .flat_map(|suite| suite.tests.iter())
+        .collect::<Vec<_>>();
+    let passed = results
+        .iter()
+        .filter(|test| test.outcome == TestOutcome::Passed)
+        .count();
+    let failed = results.len() - passed;
+    let text = text_report(&suites, passed, failed);
+    if failed > 0 {
+        // the report ends with the number of failed tests, and is printed by wash as the error
+        bail!("{text}");
+    }
+
+    let suites = suites
+        .iter()
+        .map(|suite| {
+            json!({
+                "name": suite.name,
+                "tests": suite.tests.iter().map(|test| json!({
+                    "name": test.name,
+                    "duration_ms": test.elapsed.as_millis(),
+                })).collect::<Vec<_>>(),
+            })
+        })
+        .collect::<Vec<_>>();
+    Ok(CommandOutput::new(
+        text,
+        HashMap::from([
+            ("suites".to_string(), json!(suites)),
+            ("passed".to_string(), json!(passed)),
+        ]),
+    ))
+}
+
+fn text_report(suites: &[SuiteResult], passed: usize, failed: usize) -> String {
+    let mut text = String::new();
+    let mut failures = String::new();
+    for suite in suites {
+        for test in &suite.tests {
+            let status = match &test.outcome {
+                TestOutcome::Passed => "ok",
+                TestOutcome::Failed(messages) => {
+                    let _ = writeln!(failures, "\n---- {} [{}] ----", suite.name, test.name);
+                    for message in messages {
+                        let _ = writeln!(failures, "{message}");
+                    }
+                    "FAILED"
+                }
+                TestOutcome::Error(err) => {
+                    let _ = writeln!(
+                        failures,
+                        "\n---- {} [{}] ----\n{err}",
+                        suite.name, test.name
+                    );
+                    "ERROR"
+                }
+            };
+            let _ = writeln!(text, "test {} [{}] ... {status}", suite.name, test.name);
+        }
+    }
+    if !failures.is_empty() {
+        let _ = write!(text, "\nfailures:\n{failures}");
+    }
+    let _ = write!(
+        text,
+        "\ntest result: {}. {passed} passed; {failed} failed",
+        if failed == 0 { "ok" } else { "FAILED" }
+    );
+    text
+}
+
+/// Render the results as a JUnit XML report, with a test suite for every test file
+fn junit_report(suites: &[SuiteResult]) -> String {
+    let count = |tests: &[TestResult], f: fn(&TestOutcome) -> bool| {
+        tests.iter().filter(|test| f(&test.outcome)).count()
+    };
+    let is_failure = |outcome: &TestOutcome| matches!(outcome, TestOutcome::Failed(_));
+    let is_error = |outcome: &TestOutcome| matches!(outcome, TestOutcome::Error(_));
+    let seconds = |tests: &[TestResult]| {
+        tests
+            .iter()
+            .map(|test| test.elapsed)
+            .sum::<Duration>()
+            .as_secs_f64()
+    };
+
+    let all = suites
+        .iter()
+        .flat_map(|suite| suite.tests.iter().cloned())
+        .collect::<Vec<_>>();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"wash test\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">",
+        all.len(),
+        count(&all, is_failure),
+        count(&all, is_error),
+        seconds(&all)
+    );
+    for suite in suites {
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">",
+            xml_escape(&suite.name),
+            suite.tests.len(),
+            count(&suite.tests, is_failure),
+            count(&suite.tests, is_error),
+            seconds(&suite.tests)
+        );
+        for test in &suite.tests {
+            let _ = write!(
+                xml,
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+                xml_escape(&test.name),
+                xml_escape(&suite.name),
+                test.elapsed.as_secs_f64()
+            );
+            if test.outcome == TestOutcome::Passed && test.logs.is_empty() {
+                xml.push_str("/>\n");
+                continue;
+            }
+            xml.push_str(">\n");
+            match &test.outcome {
+                TestOutcome::Passed => {}
+                TestOutcome::Failed(messages) => {
+                    let _ = writeln!(
+                        xml,
+                        "      <failure message=\"{}\">{}</failure>",
+                        xml_escape(
+                            messages
+                                .first()
+                                .and_then(|message| message.lines().next())
+                                .unwrap_or_default()
+                        ),
+                        xml_escape(&messages.join("\n"))
+                    );
+                }
+                TestOutcome::Error(err) => {
+                    let _ = writeln!(
+                        xml,
+                        "      <error message=\"{}\">{}</error>",
+                        xml_escape(err.lines().next().unwrap_or_default()),
+                        xml_escape(err)
+                    );
+                }
+            }
+            if !test.logs.is_empty() {
+                let _ = writeln!(
+                    xml,
+                    "      <system-out>{}</system-out>",
+                    xml_escape(&test.logs)
+                );
+            }
+            xml.push_str("    </testcase>\n");
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+/// Escape text for use in XML attributes and elements, dropping characters XML cannot contain
+fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use wasmcloud_runtime::{Component, Runtime};
+    use wrpc_transport::Value;
+    use wrpc_types::Type;
+
+    use super::{
+        json_to_value, junit_report, run_test, value_to_json, SuiteResult, TestFile, TestOutcome,
+        TestResult, DEFAULT_TEST_TIMEOUT,
+    };
+
+    #[test]
+    fn test_json_to_value_round_trip() {
+        let ty = Type::Tuple(Arc::from([
+            Type::U8,
+            Type::S32,
+            Type::String,
+            Type::Char,
+            Type::List(Arc::new(Type::Bool)),
+            Type::Option(Arc::new(Type::U64)),
+            Type::Option(Arc::new(Type::U64)),
+            Type::Result {
+                ok: None,
+                err: Some(Arc::new(Type::String)),
+            },
+            Type::Variant(Arc::from([None, Some(Type::Float64)])),
+        ]));
+        let json = serde_json::json!([
+            7,
+            -3,
+            "hello",
+            "x",
+            [true, false],
+            null,
+            42,
+            { "err": "oops" },
+            { "case": 1, "value": 1.5 },
+        ]);
+        let value = json_to_value(&ty, &json).expect("failed to convert JSON");
+        assert_eq!(value_to_json(value), json);
+
+        assert!(json_to_value(&Type::U8, &serde_json::json!(256)).is_err());
+        assert!(json_to_value(&Type::U32, &serde_json::json!(-1)).is_err());
+        assert!(json_to_value(&Type::Char, &serde_json::json!("ab")).is_err());
+        assert!(matches!(
+            json_to_value(
+                &Type::Record(Arc::from([Type::U8])),
+                &serde_json::json!([1])
+            ),
+            Ok(Value::Record(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_test_file() {
+        let file: TestFile = toml::from_str(
+            r#"
+            component = "build/echo_s.wasm"
+            timeout_ms = 1000
+
+            [keyvalue.default]
+            counter = 1
+            name = "echo"
+
+            [[outgoing_http]]
+            url = "https://example.com/"
+
+            [[test]]
+            name = "echo"
+            incoming_http = { method = "POST", path = "/echo", body = "hi" }
+            expect = { status = 200, body = "hi", keyvalue = { default = { counter = 2 } } }
+
+            [[test]]
+            name = "add"
+            timeout_ms = 10
+            call = { interface = "example:math/ops", function = "add", params = [1, 2] }
+            expect = { results = [3] }
+
+            [[test.outgoing_http]]
+            url = "https://example.com/"
+            status = 500
+            "#,
+        )
+        .expect("failed to parse test file");
+        assert_eq!(file.tests.len(), 2);
+        for test in &file.tests {
+            test.validate().expect("test should be valid");
+        }
+        let fixtures = file.fixtures.merge(&file.tests[1].fixtures);
+        assert_eq!(fixtures.keyvalue["default"].len(), 2);
+        assert_eq!(fixtures.outgoing_http.len(), 2);
+        assert_eq!(fixtures.outgoing_http[0].status, 500);
+        assert_eq!(file.timeout(&file.tests[0]), Duration::from_secs(1));
+        assert_eq!(file.timeout(&file.tests[1]), Duration::from_millis(10));
+
+        let file: TestFile = toml::from_str(
+            r#"
+            [[test]]
+            name = "no invocation"
+            "#,
+        )
+        .expect("failed to parse test file");
+        assert!(file.tests[0].validate().is_err());
+        assert_eq!(file.timeout(&file.tests[0]), DEFAULT_TEST_TIMEOUT);
+    }
+
+    #[tokio::test]
+    async fn test_run_fixture_component() {
+        let file: TestFile = toml::from_str(
+            r#"
+            [config]
+            pong = "pong"
+
+            [[test]]
+            name = "returns config"
+            call = { interface = "test-actors:testing/pingpong", function = "ping" }
+            expect = { results = ["pong"] }
+
+            [[test]]
+            name = "unexpected result"
+            call = { interface = "test-actors:testing/pingpong", function = "ping" }
+            expect = { results = ["ping"] }
+
+            [[test]]
+            name = "missing export"
+            call = { interface = "test-actors:testing/pingpong", function = "pong" }
+            "#,
+        )
+        .expect("failed to parse test file");
+        let rt = Runtime::new().expect("failed to construct runtime");
+        let wasm = tokio::fs::read(test_actors::RUST_PONGER_CONFIG_COMPONENT_PREVIEW2)
+            .await
+            .expect("failed to read component");
+        let component = Component::new(&rt, wasm).expect("failed to compile component");
+
+        let result = run_test(&component, &file, &file.tests[0]).await;
+        assert_eq!(result.outcome, TestOutcome::Passed);
+        let result = run_test(&component, &file, &file.tests[1]).await;
+        assert!(matches!(result.outcome, TestOutcome::Failed(failures) if failures.len() == 1));
+        let result = run_test(&component, &file, &file.tests[2]).await;
+        assert!(
+            matches!(result.outcome, TestOutcome::Error(err) if err.contains("does not export"))
+        );
+    }
+
+    #[test]
+    fn test_junit_report() {
+        let report = junit_report(&[SuiteResult {
+            name: "tests.toml".to_string(),
+            tests: vec![
+                TestResult {
+                    name: "passes".to_string(),
+                    outcome: TestOutcome::Passed,
+                    elapsed: Duration::from_millis(1500),
+                    logs: String::new(),
+                },
+                TestResult {
+                    name: "fails <badly>".to_string(),
+                    outcome: TestOutcome::Failed(vec!["expected `a` & got \"b\"".to_string()]),
+                    elapsed: Duration::ZERO,
+                    logs: "INFO test: hello\n".to_string(),
+                },
+            ],
+        }]);
+        assert!(report.contains(
+            r#"<testsuites name="wash test" tests="2" failures="1" errors="0" time="1.500">"#
+        ));
+        assert!(report.contains(r#"<testcase name="passes" classname="tests.toml" time="1.500"/>"#));
+        assert!(report.contains(r#"<testcase name="fails &lt;badly&gt;""#));
+        assert!(report.contains(
+            r#"<failure message="expected `a` &amp; got &quot;b&quot;">expected `a` &amp; got &quot;b&quot;</failure>"#
+        ));
+        assert!(report.contains("<system-out>INFO test: hello\n</system-out>"));
+    }
+}
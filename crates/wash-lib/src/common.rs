@@ -6,11 +6,7 @@ use std::{
 use anyhow::Context;
 use wasmcloud_control_interface::HostInventory;
 
-use crate::id::{ModuleId, ServerId, ServiceId};
-
-const CLAIMS_CALL_ALIAS: &str = "call_alias";
-pub(crate) const CLAIMS_NAME: &str = "name";
-pub(crate) const CLAIMS_SUBJECT: &str = "sub";
+use crate::id::ServerId;
 
 /// Converts error from Send + Sync error to standard anyhow error
 pub(crate) fn boxed_err_to_anyhow(e: Box<dyn ::std::error::Error + Send + Sync>) -> anyhow::Error {
@@ -56,26 +52,34 @@ impl Debug for Match {
 /// Given a string, attempts to resolve an actor ID. Returning the actor ID and an optional friendly
 /// name
 ///
-/// If the string is a valid actor ID, it will be returned unchanged. If it is not an ID, it will
+/// If the string is the ID of a running actor, it will be returned unchanged. If it is not, it will
 /// attempt to resolve an ID in the following order:
 ///
 /// 1. The value matches the prefix of the ID of an actor
-/// 2. The value is contained in the call alias of an actor
-/// 3. The value is contained in the name field of an actor
+/// 2. The value is contained in the name field of an actor
 ///
 /// If more than one matches, then an error will be returned indicating the options to choose from
 pub async fn find_actor_id(
     value: &str,
     ctl_client: &wasmcloud_control_interface::Client,
-) -> Result<(ModuleId, Option<String>), FindIdError> {
-    find_id_matches(value, ctl_client).await
+) -> Result<(String, Option<String>), FindIdError> {
+    let inventories = get_all_inventories(ctl_client)
+        .await
+        .context("unable to fetch inventories for lookup")?;
+    find_id_matches(
+        value,
+        inventories
+            .into_iter()
+            .flat_map(|inv| inv.actors)
+            .map(|actor| (actor.id, actor.name)),
+    )
 }
 
 /// Given a string, attempts to resolve an provider ID. Returning the provider ID and an optional
 /// friendly name
 ///
-/// If the string is a valid provider ID, it will be returned unchanged. If it is not an ID, it will
-/// attempt to resolve an ID in the following order:
+/// If the string is the ID of a running provider, it will be returned unchanged. If it is not, it
+/// will attempt to resolve an ID in the following order:
 ///
 /// 1. The value matches the prefix of the ID of a provider
 /// 2. The value is contained in the name field of a provider
@@ -84,49 +88,73 @@ pub async fn find_actor_id(
 pub async fn find_provider_id(
     value: &str,
     ctl_client: &wasmcloud_control_interface::Client,
-) -> Result<(ServiceId, Option<String>), FindIdError> {
-    find_id_matches(value, ctl_client).await
+) -> Result<(String, Option<String>), FindIdError> {
+    let inventories = get_all_inventories(ctl_client)
+        .await
+        .context("unable to fetch inventories for lookup")?;
+    find_id_matches(
+        value,
+        inventories
+            .into_iter()
+            .flat_map(|inv| inv.providers)
+            .map(|provider| (provider.id, provider.name)),
+    )
 }
 
-async fn find_id_matches<T: FromStr + ToString + Display>(
+/// Given a string, attempts to resolve the ID of an actor or provider, to be used as one end of a
+/// link. Returning the component ID and an optional friendly name
+///
+/// The ID is resolved like [`find_actor_id`] and [`find_provider_id`] do, across both actors and
+/// providers. Links may be put before their components run, so if nothing matches, the value is
+/// returned unchanged as the component ID
+pub async fn find_component_id(
     value: &str,
     ctl_client: &wasmcloud_control_interface::Client,
-) -> Result<(T, Option<String>), FindIdError> {
-    if let Ok(id) = T::from_str(value) {
-        return Ok((id, None));
+) -> Result<(String, Option<String>), FindIdError> {
+    let inventories = get_all_inventories(ctl_client)
+        .await
+        .context("unable to fetch inventories for lookup")?;
+    match find_id_matches(
+        value,
+        inventories.into_iter().flat_map(|inv| {
+            inv.actors
+                .into_iter()
+                .map(|actor| (actor.id, actor.name))
+                .chain(
+                    inv.providers
+                        .into_iter()
+                        .map(|provider| (provider.id, provider.name)),
+                )
+        }),
+    ) {
+        Err(FindIdError::NoMatches) => Ok((value.to_string(), None)),
+        res => res,
     }
+}
+
+fn find_id_matches(
+    value: &str,
+    components: impl IntoIterator<Item = (String, Option<String>)>,
+) -> Result<(String, Option<String>), FindIdError> {
     // Case insensitive searching here to make things nicer
-    let value = value.to_lowercase();
-    // If it wasn't an ID, get the claims
-    let claims = ctl_client
-        .get_claims()
-        .await
-        .map_err(boxed_err_to_anyhow)
-        .context("unable to get claims for lookup")?;
-    let all_matches = claims
-        .iter()
-        .filter_map(|v| {
-            let id_str = v
-                .get(CLAIMS_SUBJECT)
-                .map(|s| s.as_str())
-                .unwrap_or_default();
-            // If it doesn't parse to our type, just skip
-            let id = match T::from_str(id_str) {
-                Ok(id) => id,
-                Err(_) => return None,
-            };
-            (id_str.to_lowercase().starts_with(&value)
-                || v.get(CLAIMS_CALL_ALIAS)
-                    .map(|s| s.to_lowercase())
-                    .unwrap_or_default()
-                    .contains(&value)
-                || v.get(CLAIMS_NAME)
-                    .map(|s| s.to_ascii_lowercase())
-                    .unwrap_or_default()
-                    .contains(&value))
-            .then(|| (id, v.get(CLAIMS_NAME).map(|s| s.to_string())))
-        })
-        .collect::<Vec<_>>();
+    let lowercase = value.to_lowercase();
+    let mut all_matches: Vec<(String, Option<String>)> = Vec::new();
+    for (id, name) in components {
+        // The same component can run on more than one host
+        if all_matches.iter().any(|(matched, _)| *matched == id) {
+            continue;
+        }
+        if id == value {
+            return Ok((id, name));
+        }
+        if id.to_lowercase().starts_with(&lowercase)
+            || name
+                .as_ref()
+                .is_some_and(|name| name.to_lowercase().contains(&lowercase))
+        {
+            all_matches.push((id, name));
+        }
+    }
 
     if all_matches.is_empty() {
         Err(FindIdError::NoMatches)
@@ -134,10 +162,7 @@ async fn find_id_matches<T: FromStr + ToString + Display>(
         Err(FindIdError::MultipleMatches(
             all_matches
                 .into_iter()
-                .map(|(id, friendly_name)| Match {
-                    id: id.to_string(),
-                    friendly_name,
-                })
+                .map(|(id, friendly_name)| Match { id, friendly_name })
                 .collect(),
         ))
     } else {
@@ -174,6 +199,7 @@ pub async fn find_host_id(
 
     let all_matches = hosts
         .into_iter()
+        .filter_map(|h| h.response)
         .filter_map(|h| {
             if h.id.to_lowercase().starts_with(&value)
                 || h.friendly_name.to_lowercase().contains(&value)
@@ -211,20 +237,77 @@ pub async fn get_all_inventories(
     let hosts = client.get_hosts().await.map_err(boxed_err_to_anyhow)?;
     let host_ids = match hosts.len() {
         0 => return Ok(Vec::with_capacity(0)),
-        _ => hosts.into_iter().map(|h| h.id),
+        _ => hosts.into_iter().filter_map(|h| h.response).map(|h| h.id),
     };
 
     let futs =
         host_ids
             .map(|host_id| (client.clone(), host_id))
             .map(|(client, host_id)| async move {
-                client
+                let inventory = client
                     .get_host_inventory(&host_id)
                     .await
-                    .map_err(boxed_err_to_anyhow)
+                    .map_err(boxed_err_to_anyhow)?;
+                inventory.response.with_context(|| {
+                    format!(
+                        "failed to get inventory of host {host_id}: {}",
+                        inventory.message
+                    )
+                })
             });
     futures::future::join_all(futs)
         .await
         .into_iter()
         .collect::<anyhow::Result<Vec<HostInventory>>>()
 }
+
+/// Derives a component ID from the reference of an actor or provider, for use when no ID is given.
+/// The ID is the file or repository name of the reference without its tag or extension, with any
+/// characters that can't appear in a NATS subject token replaced by `_`
+pub fn component_id_from_ref(reference: &str) -> String {
+    let name = reference
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or(reference);
+    let name = name.split_once('@').map_or(name, |(name, _digest)| name);
+    let name = name.split_once(':').map_or(name, |(name, _tag)| name);
+    let name = name
+        .strip_suffix(".par.gz")
+        .or_else(|| name.strip_suffix(".wasm"))
+        .unwrap_or(name);
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::component_id_from_ref;
+
+    #[test]
+    fn derives_component_ids_from_refs() {
+        assert_eq!(
+            component_id_from_ref("wasmcloud.azurecr.io/echo:0.3.8"),
+            "echo"
+        );
+        assert_eq!(
+            component_id_from_ref("ghcr.io/wasmcloud/http-server@sha256:1234"),
+            "http-server"
+        );
+        assert_eq!(
+            component_id_from_ref("file:///tmp/build/hello_world_s.wasm"),
+            "hello_world_s"
+        );
+        assert_eq!(
+            component_id_from_ref("file:///tmp/build/kv.redis.par.gz"),
+            "kv_redis"
+        );
+    }
+}
@@ -164,6 +164,20 @@ pub fn validate_contract_id(contract_id: &str) -> Result<()> {
     }
 }
 
+/// Splits a capability contract ID, e.g. `wasi:keyvalue`, into the WIT namespace and package of
+/// the interfaces it covers
+pub fn split_contract_id(contract_id: &str) -> Result<(String, String)> {
+    validate_contract_id(contract_id)?;
+    match contract_id.split_once(':') {
+        Some((namespace, package)) if !namespace.is_empty() && !package.is_empty() => {
+            Ok((namespace.to_string(), package.to_string()))
+        }
+        _ => bail!(
+            "Contract ID `{contract_id}` must be a WIT namespace and package separated by a colon (e.g. wasi:keyvalue)"
+        ),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -230,4 +244,17 @@ mod tests {
         let b = a.parse::<ClusterSeed>().unwrap();
         assert_eq!(a.to_string(), b.to_string());
     }
+
+    #[test]
+    fn split_contract_ids() {
+        assert_eq!(
+            split_contract_id("wasi:keyvalue").unwrap(),
+            ("wasi".to_string(), "keyvalue".to_string())
+        );
+        assert!(split_contract_id("keyvalue").is_err());
+        assert!(split_contract_id("wasi:").is_err());
+        assert!(
+            split_contract_id("VAG3QITQQ2ODAOWB5TTQSDJ53XK3SHBEIFNK4AYJ5RKAX2UNSCAPHA5M").is_err()
+        );
+    }
 }
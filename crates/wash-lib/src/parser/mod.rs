@@ -1,14 +1,22 @@
 //! Parse wasmcloud.toml files which specify key information for building and signing
 //! WebAssembly modules and native capability provider binaries
 
-use std::{collections::HashSet, fmt::Display, fs, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+};
 
 use anyhow::{anyhow, bail, Context, Result};
 use cargo_toml::{Manifest, Product};
 use config::Config;
 use semver::Version;
 use serde::Deserialize;
-use wasmcloud_control_interface::{RegistryCredential, RegistryCredentialMap};
+use wasmcloud_control_interface::RegistryCredential;
+
+mod workspace;
+pub use workspace::*;
 
 #[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
 #[serde(rename_all = "snake_case")]
@@ -148,6 +156,101 @@ pub struct ProviderConfig {
     pub bin_name: Option<String>,
     /// The directory to store the private signing keys in.
     pub key_directory: PathBuf,
+    /// Targets to build the provider for, all of which are added to a single provider archive.
+    /// If empty, the provider is built for `rust_target` or the default rust toolchain.
+    pub targets: Vec<ProviderTarget>,
+}
+
+/// A target a provider is built for and added to its provider archive
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct ProviderTarget {
+    /// The Rust target triple to build for, e.g. `aarch64-unknown-linux-musl`
+    pub rust_target: String,
+    /// The target of the binary in the provider archive, e.g. `aarch64-linux`
+    pub par_target: String,
+    /// The tool used to build the target
+    pub builder: ProviderBuilder,
+    /// The linker used for the target, passed to cargo as `CARGO_TARGET_<TRIPLE>_LINKER`
+    pub linker: Option<PathBuf>,
+}
+
+/// Tool used to build a provider target
+#[derive(Deserialize, Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderBuilder {
+    /// Build with cargo, using the `cargo_path` of the rust configuration if set
+    #[default]
+    Cargo,
+    /// Build with [cross](https://github.com/cross-rs/cross), which cross-compiles in containers
+    Cross,
+}
+
+/// A provider target, either just a Rust target triple or a table of target settings
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(untagged)]
+enum RawProviderTarget {
+    RustTarget(String),
+    Target {
+        rust_target: String,
+        par_target: Option<String>,
+        builder: Option<ProviderBuilder>,
+        linker: Option<PathBuf>,
+    },
+}
+
+impl TryFrom<RawProviderTarget> for ProviderTarget {
+    type Error = anyhow::Error;
+
+    fn try_from(raw_target: RawProviderTarget) -> Result<Self> {
+        let (rust_target, par_target, builder, linker) = match raw_target {
+            RawProviderTarget::RustTarget(rust_target) => (rust_target, None, None, None),
+            RawProviderTarget::Target {
+                rust_target,
+                par_target,
+                builder,
+                linker,
+            } => (rust_target, par_target, builder, linker),
+        };
+        let par_target = match par_target {
+            Some(par_target) => par_target,
+            None => par_target_for_rust_target(&rust_target)?,
+        };
+        Ok(Self {
+            rust_target,
+            par_target,
+            builder: builder.unwrap_or_default(),
+            linker,
+        })
+    }
+}
+
+/// Returns the provider archive target of a Rust target triple, which is the architecture and
+/// operating system in the form used by hosts to select the binary to run, e.g. `x86_64-linux`
+pub fn par_target_for_rust_target(rust_target: &str) -> Result<String> {
+    let (arch, rest) = rust_target
+        .split_once('-')
+        .with_context(|| format!("invalid Rust target triple [{rust_target}]"))?;
+    let arch = match arch {
+        "i586" | "i686" => "x86",
+        arch if arch.starts_with("armv") || arch.starts_with("thumbv") => "arm",
+        arch => arch,
+    };
+    let os = if rest.contains("android") {
+        "android"
+    } else if rest.contains("linux") {
+        "linux"
+    } else if rest.contains("darwin") {
+        "macos"
+    } else if rest.contains("ios") {
+        "ios"
+    } else if rest.contains("windows") {
+        "windows"
+    } else if rest.contains("freebsd") {
+        "freebsd"
+    } else {
+        bail!("unable to determine the operating system of Rust target [{rust_target}], please specify its par_target")
+    };
+    Ok(format!("{arch}-{os}"))
 }
 
 #[derive(Deserialize, Debug, PartialEq)]
@@ -166,12 +269,33 @@ struct RawProviderConfig {
     pub bin_name: Option<String>,
     /// The directory to store the private signing keys in.
     pub key_directory: Option<PathBuf>,
+    /// Targets to build the provider for, all of which are added to a single provider archive.
+    pub targets: Option<Vec<RawProviderTarget>>,
 }
 
 impl TryFrom<RawProviderConfig> for ProviderConfig {
     type Error = anyhow::Error;
 
     fn try_from(raw_config: RawProviderConfig) -> Result<Self> {
+        let targets = raw_config
+            .targets
+            .unwrap_or_default()
+            .into_iter()
+            .map(ProviderTarget::try_from)
+            .collect::<Result<Vec<_>>>()?;
+        if !targets.is_empty() && raw_config.rust_target.is_some() {
+            bail!("provider config may specify either rust_target or targets, not both");
+        }
+        let mut par_targets = HashSet::new();
+        if let Some(target) = targets
+            .iter()
+            .find(|target| !par_targets.insert(target.par_target.as_str()))
+        {
+            bail!(
+                "multiple provider targets are added to the provider archive as [{}], please specify a distinct par_target for each of them",
+                target.par_target
+            );
+        }
         Ok(Self {
             vendor: raw_config.vendor.unwrap_or_else(|| "NoVendor".to_string()),
             os: raw_config
@@ -187,6 +311,7 @@ impl TryFrom<RawProviderConfig> for ProviderConfig {
             key_directory: raw_config
                 .key_directory
                 .unwrap_or_else(|| PathBuf::from("./keys")),
+            targets,
         })
     }
 }
@@ -374,6 +499,30 @@ impl TryFrom<RawTinyGoConfig> for TinyGoConfig {
 /// * `opt_path` - The path to the config file. If None, it will look for a wasmcloud.toml file in the current directory.
 /// * `use_env` - Whether to use the environment variables or not. If false, it will not attempt to use environment variables. Defaults to true.
 pub fn get_config(opt_path: Option<PathBuf>, use_env: Option<bool>) -> Result<ProjectConfig> {
+    let (project_path, wasmcloud_path) = resolve_config_path(opt_path)?;
+    let json_value = read_config_file(&wasmcloud_path, use_env.unwrap_or(true))?;
+    if json_value.get("workspace").is_some() && json_value.get("type").is_none() {
+        bail!(
+            "{} configures a workspace, not a project",
+            wasmcloud_path.display()
+        );
+    }
+
+    let mut raw_project_config: RawProjectConfig = serde_json::from_value(json_value)?;
+
+    // Projects that are members of a workspace use its defaults for unset values
+    if let Some((workspace_path, defaults)) = find_workspace_defaults(&project_path)? {
+        raw_project_config.apply_workspace_defaults(&workspace_path, defaults);
+    }
+
+    raw_project_config
+        .convert(project_path)
+        .map_err(|e: anyhow::Error| anyhow!("{} in {}", e, wasmcloud_path.display()))
+}
+
+/// Resolve the path of a project directory, or of a wasmcloud.toml file, to the project directory
+/// and the path of its wasmcloud.toml file
+fn resolve_config_path(opt_path: Option<PathBuf>) -> Result<(PathBuf, PathBuf)> {
     let mut path = opt_path.unwrap_or_else(|| PathBuf::from("."));
 
     if !path.exists() {
@@ -381,30 +530,33 @@ pub fn get_config(opt_path: Option<PathBuf>, use_env: Option<bool>) -> Result<Pr
     }
 
     path = fs::canonicalize(path)?;
-    let (project_path, wasmcloud_path) = if path.is_dir() {
+    if path.is_dir() {
         let wasmcloud_path = path.join("wasmcloud.toml");
         if !wasmcloud_path.is_file() {
             bail!("no wasmcloud.toml file found in {}", path.display());
         }
-        (path, wasmcloud_path)
+        Ok((path, wasmcloud_path))
     } else if path.is_file() {
-        (
+        Ok((
             path.parent()
                 .ok_or_else(|| anyhow!("Could not get parent path of wasmcloud.toml file"))?
                 .to_path_buf(),
             path,
-        )
+        ))
     } else {
         bail!("no wasmcloud.toml file found in {}", path.display());
-    };
+    }
+}
 
-    let mut config = Config::builder().add_source(config::File::from(wasmcloud_path.clone()));
+/// Read a wasmcloud.toml file, optionally overridden by `WASMCLOUD_` environment variables
+fn read_config_file(wasmcloud_path: &Path, use_env: bool) -> Result<serde_json::Value> {
+    let mut config = Config::builder().add_source(config::File::from(wasmcloud_path.to_path_buf()));
 
-    if use_env.unwrap_or(true) {
+    if use_env {
         config = config.add_source(config::Environment::with_prefix("WASMCLOUD"));
     }
 
-    let json_value = config
+    Ok(config
         .build()
         .map_err(|e| {
             if e.to_string().contains("is not of a registered file format") {
@@ -413,13 +565,7 @@ pub fn get_config(opt_path: Option<PathBuf>, use_env: Option<bool>) -> Result<Pr
 
             anyhow!("{}", e)
         })?
-        .try_deserialize::<serde_json::Value>()?;
-
-    let raw_project_config: RawProjectConfig = serde_json::from_value(json_value)?;
-
-    raw_project_config
-        .convert(project_path)
-        .map_err(|e: anyhow::Error| anyhow!("{} in {}", e, wasmcloud_path.display()))
+        .try_deserialize::<serde_json::Value>()?)
 }
 
 impl RawProjectConfig {
@@ -583,8 +729,8 @@ impl ProjectConfig {
                 credentials_file.display()
             ))?;
 
-        let credentials =
-            serde_json::from_str::<RegistryCredentialMap>(&credentials).context(format!(
+        let credentials = serde_json::from_str::<HashMap<String, RegistryCredential>>(&credentials)
+            .context(format!(
                 "Failed to parse registry credentials from file {}",
                 credentials_file.display()
             ))?;
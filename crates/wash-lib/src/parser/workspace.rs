@@ -0,0 +1,280 @@
+//! Parse the `[workspace]` table of wasmcloud.toml files, which lists the projects of a
+//! repository containing multiple actors and providers along with defaults shared by them
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+use super::{
+    get_config, read_config_file, resolve_config_path, ProjectConfig, RawProjectConfig,
+    RawRegistryConfig,
+};
+
+/// Workspace configuration, specified in the `[workspace]` table of a wasmcloud.toml file
+#[derive(Debug, Clone)]
+pub struct WorkspaceConfig {
+    /// Path to the workspace directory
+    pub path: PathBuf,
+    /// Projects of the workspace, in the order they are listed in
+    pub members: Vec<WorkspaceMember>,
+}
+
+/// A project of a workspace
+#[derive(Debug, Clone)]
+pub struct WorkspaceMember {
+    /// Configuration of the project, using the workspace defaults for unset values
+    pub config: ProjectConfig,
+    /// Names of the workspace members that have to be built before this project
+    pub depends_on: Vec<String>,
+}
+
+impl WorkspaceMember {
+    /// Name of the project, which identifies it in the workspace
+    pub fn name(&self) -> &str {
+        &self.config.common.name
+    }
+}
+
+impl WorkspaceConfig {
+    /// Group the members with the given names, or all members if no names are given, into stages
+    /// in dependency order. Members only depend on members of earlier stages, so the members of a
+    /// stage can be built in parallel.
+    ///
+    /// Dependencies that are not selected are not included and expected to be built already.
+    pub fn build_stages(&self, names: &[String]) -> Result<Vec<Vec<&WorkspaceMember>>> {
+        let mut by_name = HashMap::with_capacity(self.members.len());
+        for member in &self.members {
+            if by_name.insert(member.name(), member).is_some() {
+                bail!("multiple workspace members are named [{}]", member.name());
+            }
+        }
+        for member in &self.members {
+            if let Some(dep) = member
+                .depends_on
+                .iter()
+                .find(|dep| !by_name.contains_key(dep.as_str()))
+            {
+                bail!(
+                    "workspace member [{}] depends on unknown member [{dep}]",
+                    member.name()
+                );
+            }
+        }
+        if let Some(name) = names
+            .iter()
+            .find(|name| !by_name.contains_key(name.as_str()))
+        {
+            bail!("no workspace member is named [{name}]");
+        }
+
+        let mut remaining: Vec<&WorkspaceMember> = self
+            .members
+            .iter()
+            .filter(|member| names.is_empty() || names.iter().any(|name| name == member.name()))
+            .collect();
+        let selected: HashSet<&str> = remaining.iter().map(|member| member.name()).collect();
+        let mut built = HashSet::with_capacity(remaining.len());
+        let mut stages = Vec::new();
+        while !remaining.is_empty() {
+            let (ready, blocked): (Vec<_>, Vec<_>) = remaining.into_iter().partition(|member| {
+                member
+                    .depends_on
+                    .iter()
+                    .all(|dep| built.contains(dep.as_str()) || !selected.contains(dep.as_str()))
+            });
+            if ready.is_empty() {
+                bail!(
+                    "workspace members [{}] have cyclic dependencies",
+                    blocked
+                        .iter()
+                        .map(|member| member.name())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            }
+            built.extend(ready.iter().map(|member| member.name()));
+            stages.push(ready);
+            remaining = blocked;
+        }
+        Ok(stages)
+    }
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+#[serde(untagged)]
+enum RawWorkspaceMember {
+    /// Path of the project directory, relative to the workspace directory
+    Path(PathBuf),
+    Member {
+        /// Path of the project directory, relative to the workspace directory
+        path: PathBuf,
+        /// Names of the workspace members that have to be built before this project
+        #[serde(default)]
+        depends_on: Vec<String>,
+    },
+}
+
+impl RawWorkspaceMember {
+    fn path(&self) -> &Path {
+        match self {
+            Self::Path(path) | Self::Member { path, .. } => path,
+        }
+    }
+}
+
+/// Defaults for values that are not set in the wasmcloud.toml files of the workspace members.
+/// Paths are relative to the workspace directory.
+#[derive(Deserialize, Debug, PartialEq, Clone, Default)]
+pub(super) struct RawWorkspaceDefaults {
+    /// Artifact OCI registry configuration
+    pub registry: Option<RawRegistryConfig>,
+    /// The directory to store the private signing keys in
+    pub key_directory: Option<PathBuf>,
+    /// Tags that should be applied during the actor signing process
+    pub tags: Option<HashSet<String>>,
+}
+
+#[derive(Deserialize, Debug)]
+struct RawWorkspaceConfig {
+    /// Paths of the workspace projects, optionally with their dependencies
+    pub members: Vec<RawWorkspaceMember>,
+    #[serde(default)]
+    pub defaults: RawWorkspaceDefaults,
+}
+
+/// Gets the workspace config from a wasmcloud.toml file, or `None` if the file does not
+/// contain a `[workspace]` table.
+///
+/// The config of each member is loaded using [get_config], which applies the workspace defaults.
+/// Members must be located in the workspace directory or one of its subdirectories.
+///
+/// # Arguments
+/// * `opt_path` - The path to the config file. If None, it will look for a wasmcloud.toml file in the current directory.
+/// * `use_env` - Whether to use the environment variables or not for the member configs. Defaults to true.
+pub fn get_workspace_config(
+    opt_path: Option<PathBuf>,
+    use_env: Option<bool>,
+) -> Result<Option<WorkspaceConfig>> {
+    let (workspace_path, wasmcloud_path) = resolve_config_path(opt_path)?;
+    let Some(raw_config) = parse_workspace_config(read_config_file(&wasmcloud_path, false)?)
+        .with_context(|| format!("invalid workspace in {}", wasmcloud_path.display()))?
+    else {
+        return Ok(None);
+    };
+
+    let mut members = Vec::with_capacity(raw_config.members.len());
+    for member in raw_config.members {
+        let member_path =
+            fs::canonicalize(workspace_path.join(member.path())).with_context(|| {
+                format!(
+                    "workspace member {} does not exist",
+                    member.path().display()
+                )
+            })?;
+        if !member_path.starts_with(&workspace_path) {
+            bail!(
+                "workspace member {} is not located in the workspace directory {}",
+                member.path().display(),
+                workspace_path.display()
+            );
+        }
+        let config = get_config(Some(member_path), use_env).with_context(|| {
+            format!(
+                "failed to load workspace member {}",
+                member.path().display()
+            )
+        })?;
+        let depends_on = match member {
+            RawWorkspaceMember::Path(_) => Vec::new(),
+            RawWorkspaceMember::Member { depends_on, .. } => depends_on,
+        };
+        members.push(WorkspaceMember { config, depends_on });
+    }
+
+    let workspace = WorkspaceConfig {
+        path: workspace_path,
+        members,
+    };
+    // Check member names and dependencies up front
+    workspace
+        .build_stages(&[])
+        .with_context(|| format!("invalid workspace in {}", wasmcloud_path.display()))?;
+    Ok(Some(workspace))
+}
+
+fn parse_workspace_config(mut value: serde_json::Value) -> Result<Option<RawWorkspaceConfig>> {
+    match value.get_mut("workspace").map(serde_json::Value::take) {
+        Some(workspace) => Ok(Some(serde_json::from_value(workspace)?)),
+        None => Ok(None),
+    }
+}
+
+/// Find the closest workspace that lists the project at `project_path` as a member, returning
+/// the workspace directory and its defaults
+pub(super) fn find_workspace_defaults(
+    project_path: &Path,
+) -> Result<Option<(PathBuf, RawWorkspaceDefaults)>> {
+    for dir in project_path.ancestors() {
+        let wasmcloud_path = dir.join("wasmcloud.toml");
+        if !wasmcloud_path.is_file() {
+            continue;
+        }
+        // Files that cannot be read are reported when they are used as a project or workspace
+        let Ok(value) = read_config_file(&wasmcloud_path, false) else {
+            continue;
+        };
+        let Some(workspace) = parse_workspace_config(value)
+            .with_context(|| format!("invalid workspace in {}", wasmcloud_path.display()))?
+        else {
+            continue;
+        };
+        if workspace.members.iter().any(|member| {
+            fs::canonicalize(dir.join(member.path())).is_ok_and(|path| path == project_path)
+        }) {
+            return Ok(Some((dir.to_path_buf(), workspace.defaults)));
+        }
+    }
+    Ok(None)
+}
+
+impl RawProjectConfig {
+    /// Use the defaults of the workspace at `workspace_path` for values that are not set
+    pub(super) fn apply_workspace_defaults(
+        &mut self,
+        workspace_path: &Path,
+        defaults: RawWorkspaceDefaults,
+    ) {
+        if let Some(default_registry) = defaults.registry {
+            let registry = self.registry.get_or_insert_with(Default::default);
+            if registry.url.is_none() {
+                registry.url = default_registry.url;
+            }
+            if registry.credentials.is_none() {
+                registry.credentials = default_registry
+                    .credentials
+                    .map(|path| workspace_path.join(path));
+            }
+        }
+        if let Some(key_directory) = defaults.key_directory {
+            let key_directory = workspace_path.join(key_directory);
+            if let Some(actor) = self.actor.as_mut() {
+                actor
+                    .key_directory
+                    .get_or_insert_with(|| key_directory.clone());
+            }
+            if let Some(provider) = self.provider.as_mut() {
+                provider.key_directory.get_or_insert(key_directory);
+            }
+        }
+        if let Some(tags) = defaults.tags {
+            if let Some(actor) = self.actor.as_mut() {
+                actor.tags.get_or_insert(tags);
+            }
+        }
+    }
+}
@@ -167,17 +167,25 @@ pub async fn push_oci_artifact(
     url: String,
     artifact: impl AsRef<Path>,
     options: OciPushOptions,
+) -> Result<()> {
+    let mut artifact_buf = vec![];
+    let mut f = File::open(artifact).await?;
+    f.read_to_end(&mut artifact_buf).await?;
+    push_oci_artifact_bytes(url, artifact_buf, options).await
+}
+
+/// Push the given artifact contents to the given url with additional options
+pub async fn push_oci_artifact_bytes(
+    url: String,
+    artifact_buf: Vec<u8>,
+    options: OciPushOptions,
 ) -> Result<()> {
     let image: Reference = url.to_lowercase().parse()?;
 
-    if image.tag().unwrap() == "latest" && !options.allow_latest {
+    if image.tag() == Some("latest") && !options.allow_latest {
         bail!("Pushing artifacts with tag 'latest' is prohibited");
     };
 
-    let mut artifact_buf = vec![];
-    let mut f = File::open(artifact).await?;
-    f.read_to_end(&mut artifact_buf).await?;
-
     let (artifact_media_type, config_media_type) = match validate_artifact(&artifact_buf).await? {
         SupportedArtifacts::Wasm => (WASM_MEDIA_TYPE, WASM_CONFIG_MEDIA_TYPE),
         SupportedArtifacts::Par => (
@@ -1,14 +1,11 @@
-use std::{collections::HashMap, str::FromStr, task::Poll};
+use std::{collections::HashMap, task::Poll};
 
 use anyhow::Result;
 use chrono::{DateTime, Local};
 use futures::{Stream, StreamExt};
 use wasmcloud_core::Invocation;
 
-use crate::{
-    common::{find_actor_id, CLAIMS_NAME, CLAIMS_SUBJECT},
-    id::{ModuleId, ServiceId},
-};
+use crate::common::{find_actor_id, get_all_inventories};
 
 /// A struct that represents an invocation that was observed by the spier.
 #[derive(Debug)]
@@ -80,7 +77,7 @@ impl ObservedMessage {
 /// A struct that can spy on the RPC messages sent to and from an actor, consumable as a stream
 pub struct Spier {
     stream: futures::stream::SelectAll<async_nats::Subscriber>,
-    actor_id: ModuleId,
+    actor_id: String,
     friendly_name: Option<String>,
     provider_info: HashMap<String, ProviderDetails>,
 }
@@ -98,16 +95,11 @@ impl Spier {
 
         let rpc_topic_prefix = format!("wasmbus.rpc.{}", ctl_client.lattice);
         let actor_stream = nats_client
-            .subscribe(format!("{}.{}", rpc_topic_prefix, actor_id.as_ref()))
+            .subscribe(format!("{}.{}", rpc_topic_prefix, actor_id))
             .await?;
 
         let mut subs = futures::future::join_all(linked_providers.iter().map(|prov| {
-            let topic = format!(
-                "{}.{}.{}",
-                rpc_topic_prefix,
-                prov.id.as_ref(),
-                &prov.link_name
-            );
+            let topic = format!("{}.{}.{}", rpc_topic_prefix, prov.id, &prov.link_name);
             nats_client.subscribe(topic)
         }))
         .await
@@ -123,16 +115,14 @@ impl Spier {
             friendly_name,
             provider_info: linked_providers
                 .into_iter()
-                .map(|prov| (prov.id.clone().into_string(), prov))
+                .map(|prov| (prov.id.clone(), prov))
                 .collect(),
         })
     }
 
     /// Returns the actor name, or id if no name is set, that this spier is spying on
     pub fn actor_id(&self) -> &str {
-        self.friendly_name
-            .as_deref()
-            .unwrap_or_else(|| self.actor_id.as_ref())
+        self.friendly_name.as_deref().unwrap_or(&self.actor_id)
     }
 }
 
@@ -161,7 +151,7 @@ impl Stream for Spier {
                 // todo(vados-cosmonic): In the wRPC future, `target.public_key` (i.e. the target ID)
                 // may include the current actor ID, despite note being exactly equal to it
                 // (ex. actor id '1234' may also be addressable under the opaque string 'frontends')
-                if inv.origin.is_provider() && inv.target.public_key != self.actor_id.as_ref() {
+                if inv.origin.is_provider() && inv.target.public_key != self.actor_id {
                     // This is a provider invocation that isn't for us, so we should skip it
                     cx.waker().wake_by_ref();
                     return Poll::Pending;
@@ -199,54 +189,36 @@ impl Stream for Spier {
 
 #[derive(Debug)]
 struct ProviderDetails {
-    id: ServiceId,
+    id: String,
     link_name: String,
     friendly_name: Option<String>,
 }
 
 /// Fetches all providers linked to the given actor, along with their link names
 async fn get_linked_providers(
-    actor_id: &ModuleId,
+    actor_id: &str,
     ctl_client: &wasmcloud_control_interface::Client,
 ) -> Result<Vec<ProviderDetails>> {
-    let mut details = ctl_client
-        .query_links()
+    let links = ctl_client
+        .get_links()
         .await
-        .map_err(|e| anyhow::anyhow!("Unable to get linkdefs: {e:?}"))
-        .map(|linkdefs| {
-            linkdefs
-                .into_iter()
-                .filter_map(|link| {
-                    if link.actor_id == actor_id.as_ref() {
-                        let provider_id = ServiceId::from_str(&link.provider_id).ok()?;
-                        Some(ProviderDetails {
-                            id: provider_id,
-                            link_name: link.link_name,
-                            friendly_name: None,
-                        })
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<_>>()
-        })?;
-    let mut claim_names: HashMap<String, String> = ctl_client
-        .get_claims()
-        .await
-        .map_err(|e| anyhow::anyhow!("Unable to get claims: {e:?}"))?
+        .map_err(|e| anyhow::anyhow!("Unable to get links: {e:?}"))?;
+    let links = links
+        .response
+        .ok_or_else(|| anyhow::anyhow!("Unable to get links: {}", links.message))?;
+    let mut provider_names: HashMap<String, String> = get_all_inventories(ctl_client)
+        .await?
         .into_iter()
-        .filter_map(|mut claims| {
-            let id = claims.remove(CLAIMS_SUBJECT).unwrap_or_default();
-            // If it isn't a provider, skip
-            if !id.starts_with(ServiceId::prefix()) {
-                return None;
-            }
-            // Only return it if it has a name
-            claims.remove(CLAIMS_NAME).map(|name| (id, name))
-        })
+        .flat_map(|inv| inv.providers)
+        .filter_map(|provider| provider.name.map(|name| (provider.id, name)))
         .collect();
-    details.iter_mut().for_each(|detail| {
-        detail.friendly_name = claim_names.remove(detail.id.as_ref());
-    });
-    Ok(details)
+    Ok(links
+        .into_iter()
+        .filter(|link| link.source_id == actor_id)
+        .map(|link| ProviderDetails {
+            friendly_name: provider_names.remove(&link.target),
+            id: link.target,
+            link_name: link.name,
+        })
+        .collect())
 }
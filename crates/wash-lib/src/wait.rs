@@ -128,7 +128,7 @@ pub async fn wait_for_actor_start_event(
                 let image_ref = get_string_data_from_json(&cloud_event.data, "image_ref")?;
 
                 if image_ref == actor_ref {
-                    let actor_id = get_string_data_from_json(&cloud_event.data, "public_key")?;
+                    let actor_id = get_string_data_from_json(&cloud_event.data, "actor_id")?;
                     return Ok(EventCheckOutcome::Success(ActorStartedInfo {
                         host_id: host_id.as_str().into(),
                         actor_ref: actor_ref.as_str().into(),
@@ -138,7 +138,7 @@ pub async fn wait_for_actor_start_event(
             }
             "com.wasmcloud.lattice.actor_start_failed"
             | "com.wasmcloud.lattice.actor_scale_failed" => {
-                let returned_actor_ref = get_string_data_from_json(&cloud_event.data, "actor_ref")?;
+                let returned_actor_ref = get_string_data_from_json(&cloud_event.data, "image_ref")?;
 
                 if returned_actor_ref == actor_ref {
                     let error = anyhow!(
@@ -197,9 +197,12 @@ pub async fn wait_for_provider_start_event(
                 let image_ref = get_string_data_from_json(&cloud_event.data, "image_ref")?;
 
                 if image_ref == provider_ref {
-                    let provider_id = get_string_data_from_json(&cloud_event.data, "public_key")?;
-                    let contract_id = get_string_data_from_json(&cloud_event.data, "contract_id")?;
-                    let link_name = get_string_data_from_json(&cloud_event.data, "link_name")?;
+                    let provider_id = get_string_data_from_json(&cloud_event.data, "provider_id")?;
+                    // Providers without claims don't report a contract ID or link name
+                    let contract_id = get_string_data_from_json(&cloud_event.data, "contract_id")
+                        .unwrap_or_default();
+                    let link_name = get_string_data_from_json(&cloud_event.data, "link_name")
+                        .unwrap_or_default();
 
                     return Ok(EventCheckOutcome::Success(ProviderStartedInfo {
                         host_id: host_id.as_str().into(),
@@ -268,11 +271,14 @@ pub async fn wait_for_provider_stop_event(
         match cloud_event.event_type.as_str() {
             "com.wasmcloud.lattice.provider_stopped" => {
                 let returned_provider_id =
-                    get_string_data_from_json(&cloud_event.data, "public_key")?;
+                    get_string_data_from_json(&cloud_event.data, "provider_id")?;
 
                 if returned_provider_id == provider_id {
-                    let contract_id = get_string_data_from_json(&cloud_event.data, "contract_id")?;
-                    let link_name = get_string_data_from_json(&cloud_event.data, "link_name")?;
+                    // Providers without claims don't report a contract ID or link name
+                    let contract_id = get_string_data_from_json(&cloud_event.data, "contract_id")
+                        .unwrap_or_default();
+                    let link_name = get_string_data_from_json(&cloud_event.data, "link_name")
+                        .unwrap_or_default();
 
                     return Ok(EventCheckOutcome::Success(ProviderStoppedInfo {
                         host_id: host_id.as_str().into(),
@@ -284,7 +290,7 @@ pub async fn wait_for_provider_stop_event(
             }
             "com.wasmcloud.lattice.provider_stop_failed" => {
                 let returned_provider_id =
-                    get_string_data_from_json(&cloud_event.data, "public_key")?;
+                    get_string_data_from_json(&cloud_event.data, "provider_id")?;
 
                 if returned_provider_id == provider_id {
                     let error = anyhow!(
@@ -310,6 +316,64 @@ pub async fn wait_for_provider_stop_event(
     Ok(event)
 }
 
+/// Information related to a passed provider health check
+pub struct ProviderHealthyInfo {
+    pub host_id: String,
+    pub provider_id: String,
+}
+
+/// Uses the NATS reciever to read events being published to the wasmCloud lattice event subject, up until the given timeout duration.
+///
+/// If the applicable provider health check event is found (either passed or failed), the `Ok` variant of the `Result` will be returned,
+/// with the `FindEventOutcome` enum containing the success or failure state of the event.
+///
+/// If the timeout is reached or another error occurs, the `Err` variant of the `Result` will be returned.
+pub async fn wait_for_provider_health_check_event(
+    receiver: &mut Receiver<Event>,
+    timeout: Duration,
+    host_id: String,
+    provider_id: String,
+) -> Result<FindEventOutcome<ProviderHealthyInfo>> {
+    let check_function = move |event: Event| {
+        let cloud_event = get_wasmbus_event_info(event)?;
+
+        if cloud_event.source != host_id.as_str() {
+            return Ok(EventCheckOutcome::NotApplicable);
+        }
+
+        match cloud_event.event_type.as_str() {
+            "com.wasmcloud.lattice.health_check_passed" => {
+                let returned_provider_id =
+                    get_string_data_from_json(&cloud_event.data, "provider_id")?;
+
+                if returned_provider_id == provider_id {
+                    return Ok(EventCheckOutcome::Success(ProviderHealthyInfo {
+                        host_id: host_id.as_str().into(),
+                        provider_id: returned_provider_id,
+                    }));
+                }
+            }
+            "com.wasmcloud.lattice.health_check_failed" => {
+                let returned_provider_id =
+                    get_string_data_from_json(&cloud_event.data, "provider_id")?;
+
+                if returned_provider_id == provider_id {
+                    return Ok(EventCheckOutcome::Failure(anyhow!(
+                        "Provider [{}] failed its health check",
+                        provider_id
+                    )));
+                }
+            }
+            _ => {}
+        }
+
+        Ok(EventCheckOutcome::NotApplicable)
+    };
+
+    let event = find_event(receiver, timeout, check_function).await?;
+    Ok(event)
+}
+
 /// Information related to an actor stop
 pub struct ActorStoppedInfo {
     pub host_id: String,
@@ -337,7 +401,7 @@ pub async fn wait_for_actor_stop_event(
 
         match cloud_event.event_type.as_str() {
             "com.wasmcloud.lattice.actor_stopped" | "com.wasmcloud.lattice.actor_scaled" => {
-                let returned_actor_id = get_string_data_from_json(&cloud_event.data, "public_key")?;
+                let returned_actor_id = get_string_data_from_json(&cloud_event.data, "actor_id")?;
                 if returned_actor_id == actor_id {
                     return Ok(EventCheckOutcome::Success(ActorStoppedInfo {
                         host_id: host_id.as_str().into(),
@@ -347,7 +411,7 @@ pub async fn wait_for_actor_stop_event(
             }
             "com.wasmcloud.lattice.actor_stop_failed"
             | "com.wasmcloud.lattice.actor_scale_failed" => {
-                let returned_actor_id = get_string_data_from_json(&cloud_event.data, "public_key")?;
+                let returned_actor_id = get_string_data_from_json(&cloud_event.data, "actor_id")?;
 
                 if returned_actor_id == actor_id {
                     let error = anyhow!(
@@ -3,8 +3,9 @@ use std::{collections::HashSet, fs, path::PathBuf};
 use claims::{assert_err, assert_ok};
 use semver::Version;
 use wash_lib::parser::{
-    get_config, ActorConfig, CommonConfig, LanguageConfig, RegistryConfig, RustConfig,
-    TinyGoConfig, TypeConfig, WasmTarget,
+    get_config, get_workspace_config, ActorConfig, CommonConfig, LanguageConfig, ProviderBuilder,
+    ProviderConfig, ProviderTarget, RegistryConfig, RustConfig, TinyGoConfig, TypeConfig,
+    WasmTarget,
 };
 
 #[test]
@@ -496,3 +497,131 @@ fn tags() {
         }) if tags == Some(HashSet::from(["test".into(), "wasmcloud.com/experimental".into()])),
     ));
 }
+
+/// Provider targets are parsed from plain rust targets and tables, deriving the par target
+#[test]
+fn rust_provider_targets() {
+    let result = get_config(
+        Some(PathBuf::from(
+            "./tests/parser/files/rust_provider_targets.toml",
+        )),
+        None,
+    );
+
+    let config = assert_ok!(result);
+    let TypeConfig::Provider(ProviderConfig { targets, .. }) = config.project_type else {
+        panic!("expected provider config");
+    };
+    assert_eq!(
+        targets,
+        vec![
+            ProviderTarget {
+                rust_target: "x86_64-unknown-linux-gnu".to_string(),
+                par_target: "x86_64-linux".to_string(),
+                builder: ProviderBuilder::Cargo,
+                linker: None,
+            },
+            ProviderTarget {
+                rust_target: "aarch64-unknown-linux-musl".to_string(),
+                par_target: "aarch64-linux".to_string(),
+                builder: ProviderBuilder::Cross,
+                linker: None,
+            },
+            ProviderTarget {
+                rust_target: "armv7-unknown-linux-gnueabihf".to_string(),
+                par_target: "arm-linux".to_string(),
+                builder: ProviderBuilder::Cargo,
+                linker: Some(PathBuf::from("arm-linux-gnueabihf-gcc")),
+            },
+            ProviderTarget {
+                rust_target: "x86_64-pc-windows-gnu".to_string(),
+                par_target: "x86_64-windows-gnu".to_string(),
+                builder: ProviderBuilder::Cargo,
+                linker: None,
+            },
+        ]
+    );
+}
+
+/// Targets that would overwrite each other in the provider archive are rejected
+#[test]
+fn rust_provider_duplicate_targets() {
+    let result = get_config(
+        Some(PathBuf::from(
+            "./tests/parser/files/rust_provider_duplicate_targets.toml",
+        )),
+        None,
+    );
+
+    assert_err!(result);
+}
+
+/// Workspace members are loaded with the workspace defaults and grouped in dependency order
+#[test]
+fn workspace() {
+    let result = get_workspace_config(Some(PathBuf::from("./tests/parser/files/workspace")), None);
+
+    let workspace = assert_ok!(result).expect("workspace config");
+    let workspace_path = PathBuf::from("./tests/parser/files/workspace")
+        .canonicalize()
+        .unwrap();
+    assert_eq!(workspace.path, workspace_path);
+
+    let stages = assert_ok!(workspace.build_stages(&[]));
+    let names = stages
+        .iter()
+        .map(|stage| stage.iter().map(|member| member.name()).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec![vec!["kv"], vec!["echo"], vec!["ui"]]);
+
+    // Dependencies that are not selected are skipped
+    let stages = assert_ok!(workspace.build_stages(&["ui".to_string(), "kv".to_string()]));
+    let names = stages
+        .iter()
+        .map(|stage| stage.iter().map(|member| member.name()).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec![vec!["kv"], vec!["ui"]]);
+    assert_err!(workspace.build_stages(&["nonexistent".to_string()]));
+
+    let kv = &stages[0][0].config;
+    assert!(matches!(
+        &kv.project_type,
+        TypeConfig::Provider(ProviderConfig { key_directory, .. }) if *key_directory == workspace_path.join("keys")
+    ));
+    assert_eq!(
+        kv.common.registry,
+        RegistryConfig {
+            url: Some("registry.example.com".to_string()),
+            credentials: Some(workspace_path.join("credentials.json")),
+        }
+    );
+
+    // Values set by a member take precedence over the defaults
+    let ui = &stages[1][0].config;
+    assert!(matches!(
+        &ui.project_type,
+        TypeConfig::Actor(ActorConfig { key_directory, tags, .. })
+            if *key_directory == PathBuf::from("./ui-keys") && *tags == Some(HashSet::from(["frontend".into()]))
+    ));
+
+    let echo = assert_ok!(get_config(
+        Some(PathBuf::from("./tests/parser/files/workspace/echo")),
+        None,
+    ));
+    assert!(matches!(
+        echo.project_type,
+        TypeConfig::Actor(ActorConfig { tags, .. }) if tags == Some(HashSet::from(["wayne-industries".into()]))
+    ));
+    assert_eq!(echo.common.registry.url, Some("localhost:5000".to_string()));
+
+    // A workspace is not a project, and a project is not a workspace
+    assert_err!(get_config(
+        Some(PathBuf::from("./tests/parser/files/workspace")),
+        None
+    ));
+    assert!(assert_ok!(get_workspace_config(
+        Some(PathBuf::from("./tests/parser/files/workspace/echo")),
+        None
+    ))
+    .is_none());
+}
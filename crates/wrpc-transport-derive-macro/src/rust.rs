@@ -36,13 +36,33 @@ pub(crate) fn has_vec_type(ty: syn::Type) -> Result<Option<syn::Type>> {
     has_wrapped_type(ty, "Vec")
 }
 
+/// Check if a given [`syn::Type`] is a map type (i.e. HashMap<K, V>), returning the key and value types
+pub(crate) fn has_hashmap_type(ty: syn::Type) -> Option<(syn::Type, syn::Type)> {
+    let syn::Type::Path(syn::TypePath { qself: None, path }) = ty else {
+        return None;
+    };
+    let segment = path.segments.last()?;
+    if segment.ident != "HashMap" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.iter().collect::<Vec<_>>()[..] {
+        [syn::GenericArgument::Type(k), syn::GenericArgument::Type(v)] => {
+            Some((k.clone(), v.clone()))
+        }
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::{anyhow, Context as _, Result};
     use quote::quote;
     use syn::Type;
 
-    use super::{has_option_type, has_vec_type};
+    use super::{has_hashmap_type, has_option_type, has_vec_type};
 
     /// Detecting a Option should work for a appropriately wrapped type (ex. Option<String>)
     #[test]
@@ -103,4 +123,23 @@ mod tests {
         assert!(matches!(has_vec_type(ty), Ok(None)));
         Ok(())
     }
+
+    /// Detecting a HashMap should work with and without a path (ex. ::std::collections::HashMap<K, V>)
+    #[test]
+    fn hashmap_type_detection() -> Result<()> {
+        let key_ty: Type = syn::parse2(quote!(String)).map_err(|e| anyhow!(e))?;
+        let value_ty: Type = syn::parse2(quote!(Vec<String>)).map_err(|e| anyhow!(e))?;
+        for ty in [
+            quote!(HashMap<String, Vec<String>>),
+            quote!(::std::collections::HashMap<String, Vec<String>>),
+        ] {
+            let ty: Type = syn::parse2(ty).map_err(|e| anyhow!(e))?;
+            let (k, v) = has_hashmap_type(ty).context("missing types")?;
+            assert_eq!(k, key_ty);
+            assert_eq!(v, value_ty);
+        }
+        let ty: Type = syn::parse2(quote!(Vec<(String, String)>)).map_err(|e| anyhow!(e))?;
+        assert!(has_hashmap_type(ty).is_none());
+        Ok(())
+    }
 }
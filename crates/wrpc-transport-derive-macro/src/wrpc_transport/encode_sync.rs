@@ -5,7 +5,7 @@ use syn::{Fields, FieldsNamed, FieldsUnnamed, Ident, LitInt};
 use tracing::warn;
 
 use crate::config::AttrOptions;
-use crate::rust::{has_option_type, has_vec_type};
+use crate::rust::{has_hashmap_type, has_option_type, has_vec_type};
 
 /// Derive [`wrpc_transport::EncodeSync`] for a given input stream
 pub fn derive_encode_sync_inner(input: TokenStream) -> Result<TokenStream> {
@@ -54,18 +54,22 @@ fn derive_encode_sync_inner_for_struct(item: syn::Item) -> Result<TokenStream> {
             .clone()
             .context("unexpectedly missing field name in struct")?;
         members.push(member_name.clone());
-        if let Ok(Some(_)) = has_option_type(member.ty.clone()) {
+        if let Some((key_ty, value_ty)) = has_hashmap_type(member.ty.clone()) {
+            // Maps are encoded as a list of (key, value) tuples
+            let key_ident = format_ident!("key");
+            let value_ident = format_ident!("value");
+            let encode_key = encode_sync_value(&crate_path, &key_ident, &key_ty);
+            let encode_value = encode_sync_value(&crate_path, &value_ident, &value_ty);
             encode_lines.push(quote::quote!(
-                #crate_path::deps::wrpc_transport::EncodeSync::encode_sync_option(#member_name, &mut payload).context("failed to encode member (option) `#member_name`")?;
-            ));
-        } else if let Ok(Some(_)) = has_vec_type(member.ty.clone()) {
-            encode_lines.push(quote::quote!(
-                #crate_path::deps::wrpc_transport::EncodeSync::encode_sync_list(#member_name, &mut payload).context("failed to encode member (list) `#member_name`")?;
+                let len = #member_name.len().try_into().context("map length does not fit in u64")?;
+                #crate_path::deps::leb128::write::unsigned(&mut #crate_path::deps::bytes::BufMut::writer(&mut payload), len).context("failed to encode member (map) `#member_name`")?;
+                for (#key_ident, #value_ident) in #member_name {
+                    #encode_key
+                    #encode_value
+                }
             ));
         } else {
-            encode_lines.push(quote::quote!(
-                #member_name.encode_sync(&mut payload).context("failed to encode member `#member_name`")?;
-            ));
+            encode_lines.push(encode_sync_value(&crate_path, &member_name, &member.ty));
         }
     }
 
@@ -87,6 +91,23 @@ fn derive_encode_sync_inner_for_struct(item: syn::Item) -> Result<TokenStream> {
     ))
 }
 
+/// Generate a line that encodes the value bound to `name` of type `ty`
+fn encode_sync_value(crate_path: &TokenStream, name: &Ident, ty: &syn::Type) -> TokenStream {
+    if let Ok(Some(_)) = has_option_type(ty.clone()) {
+        quote::quote!(
+            #crate_path::deps::wrpc_transport::EncodeSync::encode_sync_option(#name, &mut payload).context("failed to encode member (option) `#name`")?;
+        )
+    } else if let Ok(Some(_)) = has_vec_type(ty.clone()) {
+        quote::quote!(
+            #crate_path::deps::wrpc_transport::EncodeSync::encode_sync_list(#name, &mut payload).context("failed to encode member (list) `#name`")?;
+        )
+    } else {
+        quote::quote!(
+            #name.encode_sync(&mut payload).context("failed to encode member `#name`")?;
+        )
+    }
+}
+
 /// Derive `EncodeSync` for a Rust struct
 fn derive_encode_sync_inner_for_enum(item: syn::Item) -> Result<TokenStream> {
     let syn::Item::Enum(e) = item else {
@@ -248,6 +269,19 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn encode_struct_with_hashmap() -> Result<()> {
+        let derived = derive_encode_sync_inner(quote::quote!(
+            struct Test {
+                byte: u8,
+                headers: ::std::collections::HashMap<String, Vec<String>>,
+            }
+        ))?;
+        let parsed_item = syn::parse2::<syn::Item>(derived);
+        assert!(matches!(parsed_item, Ok(syn::Item::Impl(_))));
+        Ok(())
+    }
+
     #[test]
     fn encode_enum_simple() -> Result<()> {
         let derived = derive_encode_sync_inner(quote::quote!(
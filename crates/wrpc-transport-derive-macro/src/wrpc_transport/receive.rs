@@ -5,6 +5,7 @@ use syn::{Fields, FieldsNamed, FieldsUnnamed, Ident, LitInt, LitStr};
 use tracing::warn;
 
 use crate::config::AttrOptions;
+use crate::rust::has_hashmap_type;
 
 /// Derive [`wrpc_transport::Receive`] for a given input stream
 pub fn derive_receive_inner(input: TokenStream) -> Result<TokenStream> {
@@ -54,12 +55,22 @@ fn derive_receive_inner_for_struct(item: syn::Item) -> Result<TokenStream> {
             .context("unexpectedly missing field name in struct")?;
         let member_name_lit_str = LitStr::new(member_name.to_string().as_ref(), Span::call_site());
         members.push(member_name.clone());
-        // Add a line that receives this member
-        receive_lines.push(quote::quote!(
-            let (#member_name, payload) = #crate_path::deps::wrpc_transport::Receive::receive_sync(payload, rx)
-                .await
-                .with_context(|| format!("failed to receive member `{}`", #member_name_lit_str))?;
-        ));
+        if let Some((key_ty, value_ty)) = has_hashmap_type(member.ty.clone()) {
+            // Maps are received as a list of (key, value) tuples
+            receive_lines.push(quote::quote!(
+                let (#member_name, payload) = <::std::vec::Vec<(#key_ty, #value_ty)> as #crate_path::deps::wrpc_transport::Receive>::receive_sync(payload, rx)
+                    .await
+                    .with_context(|| format!("failed to receive member `{}`", #member_name_lit_str))?;
+                let #member_name = #member_name.into_iter().collect();
+            ));
+        } else {
+            // Add a line that receives this member
+            receive_lines.push(quote::quote!(
+                let (#member_name, payload) = #crate_path::deps::wrpc_transport::Receive::receive_sync(payload, rx)
+                    .await
+                    .with_context(|| format!("failed to receive member `{}`", #member_name_lit_str))?;
+            ));
+        }
     }
 
     // Build the generated impl
@@ -236,4 +247,16 @@ mod tests {
         assert!(matches!(parsed_item, Ok(syn::Item::Impl(_))));
         Ok(())
     }
+
+    #[test]
+    fn derive_receive_inner_with_hashmap() -> Result<()> {
+        let tokens = quote::quote!(
+            struct Test {
+                headers: ::std::collections::HashMap<String, Vec<String>>,
+            }
+        );
+        let parsed_item = syn::parse2::<syn::Item>(derive_receive_inner(tokens)?);
+        assert!(matches!(parsed_item, Ok(syn::Item::Impl(_))));
+        Ok(())
+    }
 }
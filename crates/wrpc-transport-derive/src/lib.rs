@@ -37,6 +37,7 @@ pub mod deps {
     pub use async_trait;
     pub use bytes;
     pub use futures;
+    pub use leb128;
     pub use wrpc_transport;
 }
 
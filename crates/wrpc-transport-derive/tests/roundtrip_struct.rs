@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use anyhow::{Context as _, Result};
 use bytes::Bytes;
 use futures::stream::empty;
@@ -111,3 +113,61 @@ async fn roundtrip_struct_with_vec() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn roundtrip_struct_with_hashmap() -> Result<()> {
+    #[derive(Debug, Clone, PartialEq, Eq, EncodeSync, Receive, Default)]
+    struct TestStruct {
+        byte: u8,
+        headers: HashMap<String, Vec<String>>,
+    }
+
+    test_roundtrip_value!(
+        TestStruct,
+        TestStruct {
+            byte: 1,
+            headers: HashMap::new(),
+        }
+    );
+
+    test_roundtrip_value!(
+        TestStruct,
+        TestStruct {
+            byte: 1,
+            headers: HashMap::from([
+                ("accept".into(), Vec::from(["text/plain".into()])),
+                ("x-many".into(), Vec::from(["a".into(), "b".into()])),
+            ]),
+        }
+    );
+
+    Ok(())
+}
+
+/// Maps must be encoded like a WIT-ified map, i.e. `list<tuple<K, V>>`
+#[tokio::test]
+async fn encode_hashmap_as_tuple_list() -> Result<()> {
+    #[derive(Debug, Clone, PartialEq, Eq, EncodeSync, Receive, Default)]
+    struct TestStruct {
+        headers: HashMap<String, Vec<String>>,
+    }
+
+    let mut buffer: Vec<u8> = Vec::new();
+    TestStruct {
+        headers: HashMap::from([("accept".into(), Vec::from(["text/plain".into()]))]),
+    }
+    .encode_sync(&mut buffer)
+    .context("failed to perform encode")?;
+
+    let (received, leftover): (Vec<(String, Vec<String>)>, _) =
+        Receive::receive_sync(Bytes::from(buffer), &mut empty())
+            .await
+            .context("failed to receive")?;
+    assert_eq!(
+        received,
+        Vec::from([("accept".into(), Vec::from(["text/plain".into()]))])
+    );
+    assert_eq!(leftover.remaining(), 0, "payload was completely consumed");
+
+    Ok(())
+}
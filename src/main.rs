@@ -1,10 +1,13 @@
 use std::collections::{HashMap, HashSet};
 use std::env;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::{self, bail, Context};
-use clap::Parser;
+use clap::{ArgGroup, Parser};
 use nkeys::KeyPair;
 use tokio::time::{timeout, timeout_at};
 use tokio::{select, signal};
@@ -13,13 +16,16 @@ use wasmcloud_core::logging::Level as WasmcloudLogLevel;
 use wasmcloud_core::OtelConfig;
 use wasmcloud_host::oci::Config as OciConfig;
 use wasmcloud_host::url::Url;
-use wasmcloud_host::wasmbus::host_config::PolicyService as PolicyServiceConfig;
+use wasmcloud_host::wasmbus::host_config::{
+    CircuitBreakerConfig, PolicyService as PolicyServiceConfig, ProviderRestartPolicy, RetryPolicy,
+};
 use wasmcloud_host::WasmbusHostConfig;
 use wasmcloud_tracing::configure_observability;
 
 #[derive(Debug, Parser)]
 #[allow(clippy::struct_excessive_bools)]
 #[command(version, about, long_about = None)]
+#[command(group(ArgGroup::new("policy").args(["policy_topic", "policy_rules_file"])))]
 struct Args {
     /// Controls the verbosity of logs from the wasmCloud host
     #[clap(long = "log-level", alias = "structured-log-level", default_value_t = TracingLogLevel::INFO, env = "WASMCLOUD_LOG_LEVEL")]
@@ -69,6 +75,35 @@ struct Args {
     /// Delay, in milliseconds, between requesting a provider shut down and forcibly terminating its process
     #[clap(long = "provider-shutdown-delay", default_value = "300", env = "WASMCLOUD_PROV_SHUTDOWN_DELAY_MS", value_parser = parse_duration)]
     provider_shutdown_delay: Duration,
+    /// Whether to restart capability providers that exit without being stopped, one of `never`, `on-failure` or `always`
+    #[clap(
+        long = "provider-restart-policy",
+        default_value = "on-failure",
+        env = "WASMCLOUD_PROVIDER_RESTART_POLICY",
+        value_parser = ProviderRestartPolicy::from_str
+    )]
+    provider_restart_policy: ProviderRestartPolicy,
+    /// Maximum number of times a capability provider may be restarted within the restart window before it is considered to be crash looping and is no longer restarted
+    #[clap(
+        long = "provider-max-restarts",
+        default_value_t = 5,
+        env = "WASMCLOUD_PROVIDER_MAX_RESTARTS"
+    )]
+    provider_max_restarts: usize,
+    /// Window, in milliseconds, in which capability provider restarts are counted towards the maximum number of restarts
+    #[clap(
+        long = "provider-restart-window-ms",
+        default_value = "300000",
+        env = "WASMCLOUD_PROVIDER_RESTART_WINDOW_MS",
+        value_parser = parse_duration
+    )]
+    provider_restart_window_ms: Duration,
+    /// Publishes each line of output of capability providers on `wasmbus.logs.{lattice}.{provider_id}`, in addition to logging it
+    #[clap(
+        long = "publish-provider-logs",
+        env = "WASMCLOUD_PUBLISH_PROVIDER_LOGS"
+    )]
+    publish_provider_logs: bool,
     /// Determines whether OCI images tagged latest are allowed to be pulled from OCI registries and started
     #[clap(long = "allow-latest", env = "WASMCLOUD_OCI_ALLOW_LATEST")]
     allow_latest: bool,
@@ -96,6 +131,22 @@ struct Args {
         env = "WASMCLOUD_ALLOW_FILE_LOAD"
     )]
     allow_file_load: bool,
+    /// A comma-delimited list of signed offline bundles to preload, artifacts in them are resolved by OCI reference without network access
+    #[clap(long = "bundles", env = "WASMCLOUD_BUNDLES", value_delimiter = ',')]
+    bundles: Vec<PathBuf>,
+    /// A comma-delimited list of account public keys that offline bundles must be signed by
+    #[clap(
+        long = "bundle-issuers",
+        env = "WASMCLOUD_BUNDLE_ISSUERS",
+        value_delimiter = ','
+    )]
+    bundle_issuers: Vec<String>,
+    /// Accept offline bundles signed by any key if no bundle issuers are set. Bundles are rejected in that case otherwise
+    #[clap(
+        long = "allow-any-bundle-issuer",
+        env = "WASMCLOUD_ALLOW_ANY_BUNDLE_ISSUER"
+    )]
+    allow_any_bundle_issuer: bool,
     /// Enable JSON structured logging from the wasmCloud host
     #[clap(
         long = "enable-structured-logging",
@@ -166,15 +217,21 @@ struct Args {
     /// Optional flag to require host communication over TLS with a NATS server for RPC messages
     #[clap(long = "rpc-tls", env = "WASMCLOUD_RPC_TLS", hide = true)]
     rpc_tls: bool,
+    /// If set, the host and its providers receive RPC responses on inboxes scoped to the lattice (`_INBOX.{lattice}`) instead of `_INBOX`, so that `wash capture` can capture them
+    #[clap(long = "rpc-lattice-inbox", env = "WASMCLOUD_RPC_LATTICE_INBOX")]
+    rpc_lattice_inbox: bool,
 
     /// If provided, enables policy checks on start actions and actor invocations
     #[clap(long = "policy-topic", env = "WASMCLOUD_POLICY_TOPIC")]
     policy_topic: Option<String>,
-    /// If provided, allows the host to subscribe to updates on past policy decisions. Requires `policy_topic` to be set.
+    /// If provided, enables policy checks on start actions and actor invocations, evaluated by the host using the rules in this JSON file. Cannot be combined with `policy_topic`.
+    #[clap(long = "policy-rules-file", env = "WASMCLOUD_POLICY_RULES_FILE")]
+    policy_rules_file: Option<PathBuf>,
+    /// If provided, allows the host to subscribe to updates on past policy decisions. Requires `policy_topic` or `policy_rules_file` to be set.
     #[clap(
         long = "policy-changes-topic",
         env = "WASMCLOUD_POLICY_CHANGES_TOPIC",
-        requires = "policy_topic"
+        requires = "policy"
     )]
     policy_changes_topic: Option<String>,
     /// If provided, allows setting a custom timeout for requesting policy decisions. Defaults to one second. Requires `policy_topic` to be set.
@@ -186,6 +243,73 @@ struct Args {
     )]
     policy_timeout_ms: Option<Duration>,
 
+    /// If provided, limits the wall-clock time in milliseconds a single component invocation may execute for
+    #[clap(
+        long = "max-execution-time-ms",
+        env = "WASMCLOUD_MAX_EXECUTION_TIME_MS",
+        value_parser = parse_duration,
+    )]
+    max_execution_time_ms: Option<Duration>,
+    /// If provided, limits the amount of fuel (roughly, the number of WebAssembly instructions) a single component invocation may consume
+    #[clap(long = "max-execution-fuel", env = "WASMCLOUD_MAX_EXECUTION_FUEL")]
+    max_execution_fuel: Option<u64>,
+    /// Enables the built-in `wasi:keyvalue` capability backed by NATS JetStream KV, which components can be linked to using the target `wasmcloud:builtin:keyvalue`
+    #[clap(
+        long = "enable-builtin-keyvalue",
+        env = "WASMCLOUD_BUILTIN_KEYVALUE_ENABLED"
+    )]
+    enable_builtin_keyvalue: bool,
+    /// If provided, serves host metrics in the Prometheus text format at `/metrics` on the given address, e.g. `127.0.0.1:9090`
+    #[clap(
+        long = "metrics-listen-address",
+        env = "WASMCLOUD_METRICS_LISTEN_ADDRESS"
+    )]
+    metrics_listen_address: Option<SocketAddr>,
+    /// If provided, opens the circuit breaker of a lattice target after this many consecutive failed invocations, failing further invocations of it immediately
+    #[clap(
+        long = "circuit-breaker-failure-threshold",
+        env = "WASMCLOUD_CIRCUIT_BREAKER_FAILURE_THRESHOLD"
+    )]
+    circuit_breaker_failure_threshold: Option<u32>,
+    /// Time, in milliseconds, an open circuit breaker rejects invocations for before letting a trial invocation through
+    #[clap(
+        long = "circuit-breaker-open-timeout-ms",
+        default_value = "30000",
+        env = "WASMCLOUD_CIRCUIT_BREAKER_OPEN_TIMEOUT_MS",
+        value_parser = parse_duration
+    )]
+    circuit_breaker_open_timeout_ms: Duration,
+    /// A comma-separated list of idempotent operations to retry on failure, either interfaces (e.g. `wasi:keyvalue/eventual`) or single functions (e.g. `wasi:keyvalue/eventual.get`)
+    #[clap(
+        long = "retry-operations",
+        env = "WASMCLOUD_RETRY_OPERATIONS",
+        value_delimiter = ','
+    )]
+    retry_operations: Vec<String>,
+    /// Maximum number of attempts made to invoke a retried operation, including the first one
+    #[clap(
+        long = "retry-max-attempts",
+        default_value_t = 3,
+        env = "WASMCLOUD_RETRY_MAX_ATTEMPTS"
+    )]
+    retry_max_attempts: u32,
+    /// Backoff, in milliseconds, before the first retry of an operation, doubled for every following retry and jittered
+    #[clap(
+        long = "retry-initial-backoff-ms",
+        default_value = "100",
+        env = "WASMCLOUD_RETRY_INITIAL_BACKOFF_MS",
+        value_parser = parse_duration
+    )]
+    retry_initial_backoff_ms: Duration,
+    /// Maximum backoff, in milliseconds, between retries of an operation
+    #[clap(
+        long = "retry-max-backoff-ms",
+        default_value = "2000",
+        env = "WASMCLOUD_RETRY_MAX_BACKOFF_MS",
+        value_parser = parse_duration
+    )]
+    retry_max_backoff_ms: Duration,
+
     /// Used in tandem with `oci_user` and `oci_password` to override credentials for a specific OCI registry.
     #[clap(
         long = "oci-registry",
@@ -345,6 +469,7 @@ async fn main() -> anyhow::Result<()> {
     };
     let policy_service_config = PolicyServiceConfig {
         policy_topic: args.policy_topic,
+        policy_rules_file: args.policy_rules_file,
         policy_changes_topic: args.policy_changes_topic,
         policy_timeout_ms: args.policy_timeout_ms,
     };
@@ -381,6 +506,10 @@ async fn main() -> anyhow::Result<()> {
         js_domain: args.js_domain,
         labels,
         provider_shutdown_delay: Some(args.provider_shutdown_delay),
+        provider_restart_policy: args.provider_restart_policy,
+        provider_max_restarts: args.provider_max_restarts,
+        provider_restart_window: args.provider_restart_window_ms,
+        publish_provider_logs: args.publish_provider_logs,
         oci_opts,
         ctl_jwt: args.ctl_jwt.or_else(|| args.nats_jwt.clone()),
         ctl_key: ctl_key.or_else(|| nats_key.clone()),
@@ -391,11 +520,29 @@ async fn main() -> anyhow::Result<()> {
         rpc_jwt: args.rpc_jwt.or_else(|| args.nats_jwt.clone()),
         rpc_key: rpc_key.or_else(|| nats_key.clone()),
         rpc_tls: args.rpc_tls,
+        rpc_lattice_inbox: args.rpc_lattice_inbox,
         allow_file_load: args.allow_file_load,
+        bundles: args.bundles,
+        bundle_issuers: args.bundle_issuers,
+        allow_any_bundle_issuer: args.allow_any_bundle_issuer,
         log_level,
         enable_structured_logging: args.enable_structured_logging,
         otel_config,
         policy_service_config,
+        max_execution_time: args.max_execution_time_ms,
+        max_execution_fuel: args.max_execution_fuel,
+        builtin_keyvalue_enabled: args.enable_builtin_keyvalue,
+        metrics_listen_address: args.metrics_listen_address,
+        circuit_breaker: CircuitBreakerConfig {
+            failure_threshold: args.circuit_breaker_failure_threshold,
+            open_timeout: args.circuit_breaker_open_timeout_ms,
+        },
+        retry_policy: RetryPolicy {
+            operations: args.retry_operations,
+            max_attempts: args.retry_max_attempts,
+            initial_backoff: args.retry_initial_backoff_ms,
+            max_backoff: args.retry_max_backoff_ms,
+        },
     }))
     .await
     .context("failed to initialize host")?;
@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use futures::future::try_join_all;
+use wasmcloud_host::wasmbus::keyvalue::JetStreamKeyValue;
+use wasmcloud_runtime::capability::{KeyValueAtomic, KeyValueEventual};
+
+pub mod common;
+use common::nats::start_nats;
+
+const LATTICE: &str = "keyvalue";
+const BUCKET: &str = "test";
+
+async fn set(kv: &JetStreamKeyValue, key: &str, value: &str) -> Result<()> {
+    kv.set(
+        BUCKET,
+        key.into(),
+        Box::new(std::io::Cursor::new(value.to_string())),
+    )
+    .await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn builtin_keyvalue_increment() -> Result<()> {
+    let (nats_server, _, nats_client) = start_nats()
+        .await
+        .context("failed to start backing services")?;
+    let kv = Arc::new(JetStreamKeyValue::new(
+        async_nats::jetstream::new(nats_client),
+        LATTICE,
+    ));
+
+    // Missing keys count as 0
+    assert_eq!(kv.increment(BUCKET, "counter".into(), 5).await?, 5);
+    assert_eq!(kv.increment(BUCKET, "counter".into(), 2).await?, 7);
+
+    // Concurrent increments of the same key race on its revision and must all be retried
+    try_join_all((0..16).map(|_| {
+        let kv = Arc::clone(&kv);
+        async move { kv.increment(BUCKET, "counter".into(), 1).await }
+    }))
+    .await?;
+    assert_eq!(kv.increment(BUCKET, "counter".into(), 0).await?, 23);
+
+    // Deleted keys start over, non-numeric values are errors rather than conflicts
+    kv.delete(BUCKET, "counter".into()).await?;
+    assert_eq!(kv.increment(BUCKET, "counter".into(), 1).await?, 1);
+    set(&kv, "text", "forty-two").await?;
+    kv.increment(BUCKET, "text".into(), 1)
+        .await
+        .expect_err("incrementing a non-numeric value should fail");
+
+    nats_server.stop().await?;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn builtin_keyvalue_compare_and_swap() -> Result<()> {
+    let (nats_server, _, nats_client) = start_nats()
+        .await
+        .context("failed to start backing services")?;
+    let kv = Arc::new(JetStreamKeyValue::new(
+        async_nats::jetstream::new(nats_client),
+        LATTICE,
+    ));
+
+    set(&kv, "value", "1").await?;
+    assert!(!kv.compare_and_swap(BUCKET, "value".into(), 2, 3).await?);
+    assert!(kv.compare_and_swap(BUCKET, "value".into(), 1, 3).await?);
+    assert_eq!(kv.increment(BUCKET, "value".into(), 0).await?, 3);
+    kv.compare_and_swap(BUCKET, "missing".into(), 0, 1)
+        .await
+        .expect_err("swapping a missing key should fail");
+
+    // Of concurrent swaps from the same value exactly one wins, the others observe its write
+    // after retrying and report a mismatch
+    let swapped = try_join_all((0..8).map(|i| {
+        let kv = Arc::clone(&kv);
+        async move { kv.compare_and_swap(BUCKET, "value".into(), 3, 10 + i).await }
+    }))
+    .await?;
+    assert_eq!(swapped.iter().filter(|swapped| **swapped).count(), 1);
+    let winner = swapped.iter().position(|swapped| *swapped).unwrap() as u64;
+    assert_eq!(kv.increment(BUCKET, "value".into(), 0).await?, 10 + winner);
+
+    nats_server.stop().await?;
+    Ok(())
+}